@@ -0,0 +1,303 @@
+//! Representative datagrams for the benchmarks
+//!
+//! The datagrams are built field by field after the API specification, with values as they appear
+//! during a race: twenty cars spread over a lap, on different tyres, with plausible temperatures,
+//! speeds, and names. Captures of real sessions cannot be shipped with the crate, but the decoders
+//! do the same work for these datagrams, since they read every field of every car.
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+/// Datagram of a packet type in an API specification
+pub struct Datagram {
+    pub name: &'static str,
+    pub data: Bytes,
+}
+
+/// Returns a datagram for each packet type of each supported API specification.
+pub fn all() -> Vec<Datagram> {
+    let datagram = |name, data: BytesMut| Datagram {
+        name,
+        data: data.freeze(),
+    };
+
+    vec![
+        datagram("2019/event", event()),
+        datagram("2019/lap", lap()),
+        datagram("2019/motion", motion()),
+        datagram("2019/participants", participants()),
+        datagram("2019/session", session()),
+        datagram("2019/setup", setup()),
+        datagram("2019/status", status()),
+        datagram("2019/telemetry", telemetry()),
+    ]
+}
+
+fn put_header(size: usize, packet_id: u8) -> BytesMut {
+    let mut bytes = BytesMut::with_capacity(size);
+
+    bytes.put_u16_le(2019);
+    bytes.put_u8(1);
+    bytes.put_u8(22);
+    bytes.put_u8(1);
+    bytes.put_u8(packet_id);
+    bytes.put_u64_le(0x2bad_f00d_dead_beef);
+    bytes.put_f32_le(1834.25);
+    bytes.put_u32_le(98_765);
+    bytes.put_u8(7);
+
+    bytes
+}
+
+fn event() -> BytesMut {
+    let mut bytes = put_header(32, 3);
+
+    bytes.put(&b"FTLP"[..]);
+    bytes.put_u8(4);
+    bytes.put_f32_le(81.632);
+    bytes.put(&[0u8; 4][..]);
+
+    bytes
+}
+
+fn lap() -> BytesMut {
+    let mut bytes = put_header(843, 2);
+
+    for car in 0..20u8 {
+        let offset = f32::from(car);
+
+        bytes.put_f32_le(83.4 + offset * 0.2);
+        bytes.put_f32_le(41.7 - offset);
+        bytes.put_f32_le(82.9 + offset * 0.2);
+        bytes.put_f32_le(27.3 + offset * 0.05);
+        bytes.put_f32_le(29.8 + offset * 0.05);
+        bytes.put_f32_le(2650.0 - offset * 80.0);
+        bytes.put_f32_le(84_120.0 - offset * 80.0);
+        bytes.put_f32_le(0.0);
+        bytes.put_u8(car + 1);
+        bytes.put_u8(17);
+        bytes.put_u8(if car == 12 { 1 } else { 0 });
+        bytes.put_u8(car % 3);
+        bytes.put_u8(0);
+        bytes.put_u8(if car == 5 { 5 } else { 0 });
+        bytes.put_u8((car * 7) % 20 + 1);
+        bytes.put_u8(4);
+        bytes.put_u8(2);
+    }
+
+    bytes
+}
+
+fn motion() -> BytesMut {
+    let mut bytes = put_header(1343, 0);
+
+    for car in 0..20u8 {
+        let offset = f32::from(car);
+
+        bytes.put_f32_le(-312.5 + offset * 14.0);
+        bytes.put_f32_le(4.2);
+        bytes.put_f32_le(188.0 - offset * 9.5);
+        bytes.put_f32_le(72.1 - offset);
+        bytes.put_f32_le(0.3);
+        bytes.put_f32_le(-21.6 + offset * 0.5);
+        bytes.put_i16_le(29_000);
+        bytes.put_i16_le(120);
+        bytes.put_i16_le(-8_400);
+        bytes.put_i16_le(8_400);
+        bytes.put_i16_le(-40);
+        bytes.put_i16_le(29_000);
+        bytes.put_f32_le(1.8 - offset * 0.1);
+        bytes.put_f32_le(-0.4);
+        bytes.put_f32_le(1.1);
+        bytes.put_f32_le(-1.29 + offset * 0.01);
+        bytes.put_f32_le(0.012);
+        bytes.put_f32_le(-0.004);
+    }
+
+    for value in 0..30u8 {
+        bytes.put_f32_le(f32::from(value) * 0.75 - 8.0);
+    }
+
+    bytes
+}
+
+fn participants() -> BytesMut {
+    const NAMES: [&str; 20] = [
+        "HAMILTON",
+        "BOTTAS",
+        "VETTEL",
+        "LECLERC",
+        "VERSTAPPEN",
+        "GASLY",
+        "SAINZ",
+        "NORRIS",
+        "RICCIARDO",
+        "HÜLKENBERG",
+        "PÉREZ",
+        "STROLL",
+        "RÄIKKÖNEN",
+        "GIOVINAZZI",
+        "GROSJEAN",
+        "MAGNUSSEN",
+        "KVYAT",
+        "ALBON",
+        "RUSSELL",
+        "KUBICA",
+    ];
+
+    let mut bytes = put_header(1104, 4);
+    bytes.put_u8(20);
+
+    for (car, name) in NAMES.iter().enumerate() {
+        let car = car as u8;
+
+        bytes.put_u8(if car == 7 { 0 } else { 1 });
+        bytes.put_u8(car);
+        bytes.put_u8(car / 2);
+        bytes.put_u8(car * 3 + 2);
+        bytes.put_u8(car + 10);
+
+        let mut padded = [0u8; 48];
+        padded[..name.len()].copy_from_slice(name.as_bytes());
+        bytes.put(&padded[..]);
+
+        bytes.put_u8(1);
+    }
+
+    bytes
+}
+
+fn session() -> BytesMut {
+    let mut bytes = put_header(149, 1);
+
+    bytes.put_u8(1);
+    bytes.put_i8(38);
+    bytes.put_i8(27);
+    bytes.put_u8(53);
+    bytes.put_u16_le(5793);
+    bytes.put_u8(10);
+    bytes.put_i8(11);
+    bytes.put_u8(0);
+    bytes.put_u16_le(3120);
+    bytes.put_u16_le(7200);
+    bytes.put_u8(80);
+    bytes.put_u8(0);
+    bytes.put_u8(0);
+    bytes.put_u8(0);
+    bytes.put_u8(0);
+    bytes.put_u8(21);
+
+    for zone in 0..21u8 {
+        bytes.put_f32_le(f32::from(zone) / 21.0);
+        bytes.put_i8(if zone == 9 { 3 } else { 0 });
+    }
+
+    bytes.put_u8(0);
+    bytes.put_u8(0);
+
+    bytes
+}
+
+fn setup() -> BytesMut {
+    let mut bytes = put_header(843, 5);
+
+    for car in 0..20u8 {
+        bytes.put_u8(5 + car % 3);
+        bytes.put_u8(6 + car % 2);
+        bytes.put_u8(75);
+        bytes.put_u8(60);
+        bytes.put_f32_le(-3.0);
+        bytes.put_f32_le(-1.5);
+        bytes.put_f32_le(0.1);
+        bytes.put_f32_le(0.3);
+        bytes.put_u8(6);
+        bytes.put_u8(4);
+        bytes.put_u8(7);
+        bytes.put_u8(5);
+        bytes.put_u8(3);
+        bytes.put_u8(4);
+        bytes.put_u8(57);
+        bytes.put_u8(100);
+        bytes.put_f32_le(23.0);
+        bytes.put_f32_le(21.5);
+        bytes.put_u8(0);
+        bytes.put_f32_le(28.4 - f32::from(car) * 0.1);
+    }
+
+    bytes
+}
+
+fn status() -> BytesMut {
+    let mut bytes = put_header(1143, 7);
+
+    for car in 0..20u8 {
+        bytes.put_u8(0);
+        bytes.put_u8(0);
+        bytes.put_u8(car % 4);
+        bytes.put_u8(57);
+        bytes.put_u8(0);
+        bytes.put_f32_le(28.4 - f32::from(car) * 0.1);
+        bytes.put_f32_le(110.0);
+        bytes.put_f32_le(2.3);
+        bytes.put_u16_le(12_000);
+        bytes.put_u16_le(4_000);
+        bytes.put_u8(8);
+        bytes.put_i8(if car > 0 { 1 } else { 0 });
+        bytes.put(&[12, 12, 9, 9][..]);
+        bytes.put_u8(18 - car % 3);
+        bytes.put_u8(16 + car % 3);
+        bytes.put(&[12, 12, 9, 9][..]);
+        bytes.put_u8(0);
+        bytes.put_u8(if car == 15 { 20 } else { 0 });
+        bytes.put_u8(0);
+        bytes.put_u8(3);
+        bytes.put_u8(2);
+        bytes.put_i8(if car == 19 { 2 } else { 0 });
+        bytes.put_f32_le(2_800_000.0);
+        bytes.put_u8(1);
+        bytes.put_f32_le(120_000.0);
+        bytes.put_f32_le(90_000.0);
+        bytes.put_f32_le(180_000.0);
+    }
+
+    bytes
+}
+
+fn telemetry() -> BytesMut {
+    let mut bytes = put_header(1347, 6);
+
+    for car in 0..20u8 {
+        let offset = u16::from(car);
+
+        bytes.put_u16_le(287 - offset * 3);
+        bytes.put_f32_le(1.0);
+        bytes.put_f32_le(-0.05);
+        bytes.put_f32_le(0.0);
+        bytes.put_u8(0);
+        bytes.put_i8(7);
+        bytes.put_u16_le(11_200 - offset * 40);
+        bytes.put_u8(if car % 4 == 1 { 1 } else { 0 });
+        bytes.put_u8(80);
+
+        for temperature in &[612, 598, 455, 461] {
+            bytes.put_u16_le(*temperature);
+        }
+        for temperature in &[98, 97, 91, 92] {
+            bytes.put_u16_le(*temperature);
+        }
+        for temperature in &[104, 103, 99, 99] {
+            bytes.put_u16_le(*temperature);
+        }
+
+        bytes.put_u16_le(118);
+
+        for pressure in &[23.4, 23.3, 21.8, 21.8] {
+            bytes.put_f32_le(*pressure);
+        }
+
+        bytes.put(&[0u8; 4][..]);
+    }
+
+    bytes.put_u32_le(0);
+
+    bytes
+}
@@ -0,0 +1,178 @@
+//! Benchmarks of the decode path
+//!
+//! Each datagram is decoded into owned packets by the `F1Codec`, into lazy packets by the
+//! `LazyCodec`, through a `PacketView` that decodes the data of every car, and into an existing
+//! packet with `decode_into` if its packet type supports it. The throughput is
+//! reported in bytes per second. Before the timings are measured, the number of allocations that
+//! each way of decoding makes per datagram is printed, counted by a global allocator.
+//!
+//! Run the benchmarks with `cargo bench`, or `cargo bench -- lap` for a single packet type.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use bytes::BytesMut;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use tokio_util::codec::Decoder;
+
+use f1_api::codec::F1Codec;
+use f1_api::lazy::LazyCodec;
+use f1_api::nineteen::decode_into;
+use f1_api::nineteen::view::PacketView;
+use f1_api::packet::header::ApiSpec;
+use f1_api::packet::Packet;
+use f1_api::types::VehicleIndex;
+
+mod datagrams;
+
+/// Global allocator that counts the allocations it makes
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns the number of allocations that the function makes.
+fn count_allocations<F: FnMut()>(mut function: F) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    function();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+/// Decode the data of every car that the view gives access to.
+fn visit_cars(view: &PacketView<'_>) -> usize {
+    let mut cars = 0;
+
+    if let Some(laps) = view.laps() {
+        cars += laps.iter().filter(|lap| lap.is_ok()).count();
+    }
+    if let Some(motion) = view.motion() {
+        cars += motion.iter().filter(|motion| motion.is_ok()).count();
+    }
+    if let Some(participants) = view.participants() {
+        cars += VehicleIndex::all(ApiSpec::Nineteen)
+            .filter_map(|index| view.participant_name(index))
+            .count();
+        cars += participants.len();
+    }
+    if let Some(setups) = view.setups() {
+        cars += setups.iter().filter(|setup| setup.is_ok()).count();
+    }
+    if let Some(statuses) = view.statuses() {
+        cars += statuses.iter().filter(|status| status.is_ok()).count();
+    }
+    if let Some(telemetry) = view.telemetry() {
+        cars += telemetry
+            .iter()
+            .filter(|telemetry| telemetry.is_ok())
+            .count();
+    }
+
+    cars
+}
+
+/// Decode the datagram into the packet, if packets of its type can be decoded into storage.
+fn decode_into_packet(packet: &mut Packet, data: &[u8]) -> Option<()> {
+    match packet {
+        Packet::Lap(packet) => decode_into(packet, data).ok(),
+        Packet::Motion(packet) => decode_into(packet, data).ok(),
+        Packet::Participants(packet) => decode_into(packet, data).ok(),
+        Packet::Setup(packet) => decode_into(packet, data).ok(),
+        Packet::Status(packet) => decode_into(packet, data).ok(),
+        Packet::Telemetry(packet) => decode_into(packet, data).ok(),
+        Packet::Event(_) | Packet::Session(_) => None,
+    }
+}
+
+fn report_allocations(datagrams: &[datagrams::Datagram]) {
+    println!(
+        "{:<20} {:>8} {:>8} {:>8} {:>8}",
+        "allocations", "codec", "lazy", "view", "into"
+    );
+
+    for datagram in datagrams {
+        let mut buffer = BytesMut::from(&datagram.data[..]);
+        let mut codec = F1Codec::default();
+        let mut lazy = LazyCodec::default();
+
+        let owned = count_allocations(|| {
+            black_box(codec.decode(&mut buffer).unwrap().unwrap());
+        });
+        // The lazy codec takes the bytes of the datagram out of its buffer.
+        let mut lazy_buffer = buffer.clone();
+        let lazily = count_allocations(|| {
+            black_box(lazy.decode(&mut lazy_buffer).unwrap().unwrap());
+        });
+        let viewed = count_allocations(|| {
+            black_box(visit_cars(&PacketView::new(&datagram.data).unwrap()));
+        });
+
+        // The first decode into the packet sizes its vectors, the second one reuses them.
+        let mut packet = codec.decode(&mut buffer).unwrap().unwrap();
+        let into = match decode_into_packet(&mut packet, &datagram.data) {
+            Some(()) => count_allocations(|| {
+                black_box(decode_into_packet(&mut packet, &datagram.data));
+            })
+            .to_string(),
+            None => String::from("-"),
+        };
+
+        println!(
+            "{:<20} {:>8} {:>8} {:>8} {:>8}",
+            datagram.name, owned, lazily, viewed, into
+        );
+    }
+}
+
+fn decode(c: &mut Criterion) {
+    let datagrams = datagrams::all();
+    report_allocations(&datagrams);
+
+    for datagram in &datagrams {
+        let mut group = c.benchmark_group(datagram.name);
+        group.throughput(Throughput::Bytes(datagram.data.len() as u64));
+
+        let mut buffer = BytesMut::from(&datagram.data[..]);
+        let mut codec = F1Codec::default();
+        group.bench_function("codec", |b| {
+            b.iter(|| codec.decode(black_box(&mut buffer)).unwrap())
+        });
+
+        let mut lazy = LazyCodec::default();
+        group.bench_function("lazy", |b| {
+            b.iter_batched_ref(
+                || buffer.clone(),
+                |buffer| lazy.decode(black_box(buffer)).unwrap(),
+                BatchSize::SmallInput,
+            )
+        });
+
+        group.bench_function("view", |b| {
+            b.iter(|| visit_cars(&PacketView::new(black_box(&datagram.data)).unwrap()))
+        });
+
+        let mut packet = codec.decode(&mut buffer).unwrap().unwrap();
+        if decode_into_packet(&mut packet, &datagram.data).is_some() {
+            group.bench_function("into", |b| {
+                b.iter(|| decode_into_packet(&mut packet, black_box(&datagram.data)))
+            });
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, decode);
+criterion_main!(benches);
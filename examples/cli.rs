@@ -39,14 +39,15 @@ async fn main() {
 
     while let Some(packet) = stream.next().await {
         match packet {
-            Event(_) => println!("Received Event packet"),
-            Lap(_) => println!("Received Lap packet"),
-            Motion(_) => println!("Received Motion packet"),
-            Participants(_) => println!("Received Participants packet"),
-            Session(_) => println!("Received Session packet"),
-            Setup(_) => println!("Received Setup packet"),
-            Status(_) => println!("Received Status packet"),
-            Telemetry(_) => println!("Received Telemetry packet"),
+            Ok(Event(_)) => println!("Received Event packet"),
+            Ok(Lap(_)) => println!("Received Lap packet"),
+            Ok(Motion(_)) => println!("Received Motion packet"),
+            Ok(Participants(_)) => println!("Received Participants packet"),
+            Ok(Session(_)) => println!("Received Session packet"),
+            Ok(Setup(_)) => println!("Received Setup packet"),
+            Ok(Status(_)) => println!("Received Status packet"),
+            Ok(Telemetry(_)) => println!("Received Telemetry packet"),
+            Err(error) => eprintln!("Failed to decode a packet: {}", error),
         }
     }
 }
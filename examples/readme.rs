@@ -15,14 +15,15 @@ async fn main() {
 
     while let Some(packet) = stream.next().await {
         match packet {
-            Event(_) => println!("Received an Event packet"),
-            Lap(_) => println!("Received a Lap packet"),
-            Motion(_) => println!("Received a Motion packet"),
-            Participants(_) => println!("Received a Participants packet"),
-            Session(_) => println!("Received a Session packet"),
-            Setup(_) => println!("Received aaSetup packet"),
-            Status(_) => println!("Received a Status packet"),
-            Telemetry(_) => println!("Received a Telemetry packet"),
+            Ok(Event(_)) => println!("Received an Event packet"),
+            Ok(Lap(_)) => println!("Received a Lap packet"),
+            Ok(Motion(_)) => println!("Received a Motion packet"),
+            Ok(Participants(_)) => println!("Received a Participants packet"),
+            Ok(Session(_)) => println!("Received a Session packet"),
+            Ok(Setup(_)) => println!("Received aaSetup packet"),
+            Ok(Status(_)) => println!("Received a Status packet"),
+            Ok(Telemetry(_)) => println!("Received a Telemetry packet"),
+            Err(error) => eprintln!("Failed to decode a packet: {}", error),
         }
     }
 }
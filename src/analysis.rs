@@ -0,0 +1,57 @@
+//! Analysis of the data published by modern F1 games
+//!
+//! The packets sent by the F1 games describe the state of a session at a single point in time. Many
+//! interesting questions, for example how long a pit stop took or which tyres a driver used in a
+//! stint, can only be answered by observing the packets over time. The modules in `analysis`
+//! consume the decoded packets and derive these higher-level insights from them.
+
+use crate::packet::lap::{Lap, ResultStatus};
+
+pub mod battle;
+pub mod braking;
+pub mod championship;
+pub mod consistency;
+pub mod corner;
+pub mod damage;
+pub mod delta;
+pub mod drs;
+pub mod ers;
+pub mod excursion;
+pub mod filter;
+pub mod fuel;
+pub mod gap;
+pub mod gear;
+pub mod lap_kind;
+pub mod launch;
+pub mod mini_sector;
+pub mod penalty;
+pub mod performance;
+pub mod pit_lane;
+pub mod position;
+pub mod privacy;
+pub mod qualifying;
+pub mod racing_line;
+pub mod ranking;
+pub mod report;
+pub mod result;
+pub mod safety_car;
+pub mod sector;
+pub mod speed_trap;
+pub mod stint;
+pub mod strategy;
+pub mod summary;
+pub mod timing_tower;
+pub mod trace;
+pub mod track_limits;
+pub mod track_map;
+pub mod tyre_wear;
+pub mod weather;
+pub mod window;
+
+#[cfg(test)]
+pub(crate) mod fixtures;
+
+/// Returns whether the car is actively racing and has a position.
+pub(crate) fn is_racing(lap: &Lap) -> bool {
+    lap.position() > 0 && lap.result_status() == ResultStatus::Active
+}
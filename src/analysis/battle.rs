@@ -0,0 +1,235 @@
+//! Battles between cars on track
+//!
+//! Two cars that run close together for a while are fighting for position, and these battles are
+//! what broadcast directors want to show. The battle detector measures the time gap between cars on
+//! the same lap, and reports a battle when two cars have been within a configurable gap of each
+//! other for a configurable amount of time.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::analysis::gap::GapTracker;
+use crate::analysis::is_racing;
+use crate::packet::lap::LapPacket;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// A battle between two cars
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+pub struct Battle {
+    /// Returns the vehicle index of the car that was ahead when the battle started.
+    #[getset(get_copy = "pub")]
+    ahead: VehicleIndex,
+
+    /// Returns the vehicle index of the car that was behind when the battle started.
+    #[getset(get_copy = "pub")]
+    behind: VehicleIndex,
+
+    /// Returns the session time at which the cars came within the battle gap.
+    #[getset(get = "pub")]
+    start_time: Duration,
+}
+
+/// Events emitted by the battle detector
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+pub enum BattleEvent {
+    /// Two cars have been close to each other for long enough to be in a battle.
+    Started(Battle),
+
+    /// Two cars that were in a battle have been apart for long enough to end it.
+    Ended(Battle),
+}
+
+/// Internal state that is tracked for each pair of cars
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+struct PairState {
+    close_since: Option<Duration>,
+    apart_since: Option<Duration>,
+    battle: Option<Battle>,
+}
+
+/// Detector for battles between cars
+///
+/// The battle detector consumes lap data packets. A battle starts when two cars on the same lap are
+/// within the maximum gap for at least the minimum duration, and ends when they have been further
+/// apart for the same duration. Cars that are not actively racing are never part of a battle.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BattleDetector {
+    max_gap: Duration,
+    min_duration: Duration,
+    gaps: GapTracker,
+    pairs: BTreeMap<(VehicleIndex, VehicleIndex), PairState>,
+}
+
+impl BattleDetector {
+    /// Create a new battle detector with the maximum gap and the minimum duration of a battle.
+    pub fn new(max_gap: Duration, min_duration: Duration) -> Self {
+        BattleDetector {
+            max_gap,
+            min_duration,
+            gaps: GapTracker::new(),
+            pairs: BTreeMap::new(),
+        }
+    }
+
+    /// Update the detector and return the battle events detected in the packet.
+    pub fn update(&mut self, packet: &Packet) -> Vec<BattleEvent> {
+        match packet {
+            Packet::Lap(lap_packet) => {
+                self.gaps.update(packet);
+                self.update_laps(lap_packet)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) -> Vec<BattleEvent> {
+        let session_time = *packet.header().session_time();
+        let laps = packet.laps();
+        let mut events = Vec::new();
+
+        for (position, (first, first_lap)) in enumerate_cars(laps).enumerate() {
+            for (second, second_lap) in enumerate_cars(laps).skip(position + 1) {
+                let (ahead, behind) = if first_lap.position() < second_lap.position() {
+                    (first, second)
+                } else {
+                    (second, first)
+                };
+
+                let is_close = is_racing(first_lap)
+                    && is_racing(second_lap)
+                    && first_lap.current_lap_number() == second_lap.current_lap_number()
+                    && self
+                        .gaps
+                        .gap(ahead, behind)
+                        .is_some_and(|gap| gap <= self.max_gap);
+
+                let state = self.pairs.entry((first, second)).or_default();
+
+                if let Some(event) =
+                    state.update(is_close, ahead, behind, session_time, self.min_duration)
+                {
+                    events.push(event);
+                }
+            }
+        }
+
+        events
+    }
+
+    /// Returns the battles that are currently ongoing.
+    pub fn battles(&self) -> Vec<Battle> {
+        self.pairs
+            .values()
+            .filter_map(|state| state.battle)
+            .collect()
+    }
+}
+
+impl PairState {
+    fn update(
+        &mut self,
+        is_close: bool,
+        ahead: VehicleIndex,
+        behind: VehicleIndex,
+        session_time: Duration,
+        min_duration: Duration,
+    ) -> Option<BattleEvent> {
+        if is_close {
+            self.apart_since = None;
+            let close_since = *self.close_since.get_or_insert(session_time);
+
+            if self.battle.is_none() && session_time.saturating_sub(close_since) >= min_duration {
+                let battle = Battle::new(ahead, behind, close_since);
+                self.battle = Some(battle);
+                return Some(BattleEvent::Started(battle));
+            }
+        } else {
+            self.close_since = None;
+
+            if let Some(battle) = self.battle {
+                let apart_since = *self.apart_since.get_or_insert(session_time);
+
+                if session_time.saturating_sub(apart_since) >= min_duration {
+                    self.battle = None;
+                    self.apart_since = None;
+                    return Some(BattleEvent::Ended(battle));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::battle::{Battle, BattleDetector, BattleEvent};
+    use crate::analysis::fixtures::{lap_packet, vehicle, LapFixture};
+
+    fn lap(position: u8, total_distance: f32) -> LapFixture {
+        LapFixture {
+            position,
+            total_distance,
+            ..LapFixture::default()
+        }
+    }
+
+    fn tick(detector: &mut BattleDetector, second: u32, gap: f32) -> Vec<BattleEvent> {
+        let distance = second as f32 * 100.0;
+
+        detector.update(&lap_packet(
+            second as f32,
+            vec![lap(1, distance), lap(2, distance - gap * 100.0)],
+        ))
+    }
+
+    #[test]
+    fn update_with_battle() {
+        let mut detector = BattleDetector::new(Duration::from_secs(1), Duration::from_secs(2));
+
+        for second in 0..3 {
+            assert!(tick(&mut detector, second, 0.5).is_empty());
+        }
+
+        let battle = Battle::new(vehicle(0), vehicle(1), Duration::from_secs(1));
+        assert_eq!(
+            vec![BattleEvent::Started(battle)],
+            tick(&mut detector, 3, 0.5)
+        );
+        assert_eq!(vec![battle], detector.battles());
+
+        assert!(tick(&mut detector, 4, 3.0).is_empty());
+        assert!(tick(&mut detector, 5, 3.0).is_empty());
+        assert_eq!(
+            vec![BattleEvent::Ended(battle)],
+            tick(&mut detector, 6, 3.0)
+        );
+        assert!(detector.battles().is_empty());
+    }
+
+    #[test]
+    fn update_ignores_cars_on_different_laps() {
+        let mut detector = BattleDetector::new(Duration::from_secs(1), Duration::from_secs(0));
+
+        let events = detector.update(&lap_packet(
+            0.0,
+            vec![
+                lap(1, 0.0),
+                LapFixture {
+                    current_lap_number: 2,
+                    ..lap(2, 0.0)
+                },
+            ],
+        ));
+
+        assert!(events.is_empty());
+    }
+}
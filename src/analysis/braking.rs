@@ -0,0 +1,281 @@
+//! Braking zones and braking performance
+//!
+//! Braking is where drivers gain or lose the most time in a corner. Braking too early costs time
+//! on the straight, while braking too late ruins the exit of the corner. The braking detector finds
+//! the braking zones in the telemetry trace of a lap, and measures the braking point, the peak
+//! deceleration, and how long the driver kept braking while turning into the corner. The braking
+//! zones of two laps can be compared corner by corner.
+
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::analysis::corner::{SegmentKind, TrackSegmentation};
+use crate::analysis::trace::{LapTrace, TraceSample};
+
+/// Factor to convert kilometers per hour into meters per second
+const KPH_TO_MPS: f32 = 1.0 / 3.6;
+
+/// A braking zone on a lap
+#[derive(Debug, Getters, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct BrakingZone {
+    /// Returns the number of the corner that the car braked for, if it is known.
+    #[getset(get_copy = "pub")]
+    corner: Option<u8>,
+
+    /// Returns the lap distance at which the driver started to brake.
+    #[getset(get_copy = "pub")]
+    braking_point: f32,
+
+    /// Returns the lap distance at which the driver released the brake.
+    #[getset(get_copy = "pub")]
+    release_point: f32,
+
+    /// Returns the speed at the braking point in kilometers per hour.
+    #[getset(get_copy = "pub")]
+    entry_speed: f32,
+
+    /// Returns the lowest speed in the braking zone in kilometers per hour.
+    #[getset(get_copy = "pub")]
+    min_speed: f32,
+
+    /// Returns the highest deceleration in meters per second squared.
+    #[getset(get_copy = "pub")]
+    peak_deceleration: f32,
+
+    /// Returns how long the driver was braking.
+    #[getset(get = "pub")]
+    duration: Duration,
+
+    /// Returns how long the driver was braking and steering at the same time.
+    #[getset(get = "pub")]
+    trail_braking_duration: Duration,
+}
+
+/// Comparison of the braking for a corner on two laps
+#[derive(new, Debug, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct BrakingComparison {
+    /// Returns the number of the corner.
+    #[getset(get_copy = "pub")]
+    corner: u8,
+
+    /// Returns how much later the driver braked than on the reference lap in meters.
+    #[getset(get_copy = "pub")]
+    braking_point_delta: f32,
+
+    /// Returns the difference of the minimum speed to the reference lap in kilometers per hour.
+    #[getset(get_copy = "pub")]
+    min_speed_delta: f32,
+
+    /// Returns the difference of the peak deceleration to the reference lap.
+    #[getset(get_copy = "pub")]
+    peak_deceleration_delta: f32,
+}
+
+/// Detector for braking zones in lap traces
+///
+/// A braking zone starts when the brake is pressed harder than the brake threshold, and ends when
+/// it is released again. Braking while the steering input is larger than the steering threshold
+/// counts as trail braking.
+#[derive(new, Debug, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct BrakingDetector {
+    /// Returns the ratio of the brake above which the car is braking.
+    #[getset(get_copy = "pub")]
+    brake_threshold: f32,
+
+    /// Returns the ratio of the steering above which the car is turning.
+    #[getset(get_copy = "pub")]
+    steering_threshold: f32,
+}
+
+impl BrakingDetector {
+    /// Detect the braking zones on a lap.
+    ///
+    /// Each braking zone is assigned to the first corner of the segmentation that ends after the
+    /// point of the lowest speed in the zone.
+    pub fn detect(&self, trace: &LapTrace, segmentation: &TrackSegmentation) -> Vec<BrakingZone> {
+        let mut zones = Vec::new();
+        let mut zone: Option<BrakingZone> = None;
+        let mut min_speed_distance = 0.0;
+        let mut previous: Option<&TraceSample> = None;
+
+        for sample in trace.samples().iter() {
+            let is_braking = sample.brake() > self.brake_threshold;
+
+            match zone.as_mut() {
+                Some(current) if is_braking => {
+                    if let Some(previous) = previous {
+                        let elapsed = sample.lap_time().saturating_sub(*previous.lap_time());
+                        current.duration += elapsed;
+
+                        if previous.steering().abs() > self.steering_threshold {
+                            current.trail_braking_duration += elapsed;
+                        }
+
+                        if elapsed > Duration::default() {
+                            let deceleration = (previous.speed() - sample.speed()) * KPH_TO_MPS
+                                / elapsed.as_secs_f32();
+                            current.peak_deceleration = current.peak_deceleration.max(deceleration);
+                        }
+                    }
+
+                    if sample.speed() < current.min_speed {
+                        current.min_speed = sample.speed();
+                        min_speed_distance = sample.lap_distance();
+                    }
+
+                    current.release_point = sample.lap_distance();
+                }
+                Some(_) => {
+                    if let Some(mut finished) = zone.take() {
+                        finished.corner = corner_after(segmentation, min_speed_distance);
+                        zones.push(finished);
+                    }
+                }
+                None if is_braking => {
+                    zone = Some(BrakingZone {
+                        braking_point: sample.lap_distance(),
+                        release_point: sample.lap_distance(),
+                        entry_speed: sample.speed(),
+                        min_speed: sample.speed(),
+                        ..BrakingZone::default()
+                    });
+                    min_speed_distance = sample.lap_distance();
+                }
+                None => {}
+            }
+
+            previous = Some(sample);
+        }
+
+        if let Some(mut finished) = zone {
+            finished.corner = corner_after(segmentation, min_speed_distance);
+            zones.push(finished);
+        }
+
+        zones
+    }
+
+    /// Compare the braking on a lap with the braking on a reference lap, corner by corner.
+    ///
+    /// Only corners for which a braking zone was detected on both laps are compared.
+    pub fn compare(
+        &self,
+        trace: &LapTrace,
+        reference: &LapTrace,
+        segmentation: &TrackSegmentation,
+    ) -> Vec<BrakingComparison> {
+        let reference_zones = self.detect(reference, segmentation);
+
+        self.detect(trace, segmentation)
+            .iter()
+            .filter_map(|zone| {
+                let corner = zone.corner?;
+                let reference = reference_zones
+                    .iter()
+                    .find(|reference| reference.corner == Some(corner))?;
+
+                Some(BrakingComparison::new(
+                    corner,
+                    zone.braking_point - reference.braking_point,
+                    zone.min_speed - reference.min_speed,
+                    zone.peak_deceleration - reference.peak_deceleration,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Returns the number of the first corner that ends after the lap distance.
+fn corner_after(segmentation: &TrackSegmentation, lap_distance: f32) -> Option<u8> {
+    segmentation
+        .segments()
+        .iter()
+        .find(|segment| {
+            segment.kind() == SegmentKind::Corner && segment.end_distance() > lap_distance
+        })
+        .map(|segment| segment.number())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::fixtures::vehicle;
+    use std::time::Duration;
+
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::braking::BrakingDetector;
+    use crate::analysis::corner::{SegmentKind, TrackSegment, TrackSegmentation};
+    use crate::analysis::trace::{LapTrace, TraceSample};
+    use crate::packet::telemetry::Gear;
+
+    fn sample(
+        lap_distance: f32,
+        lap_time: f32,
+        speed: f32,
+        brake: f32,
+        steering: f32,
+    ) -> TraceSample {
+        TraceSample::new(
+            lap_distance,
+            Duration::from_secs_f32(lap_time),
+            speed,
+            1.0 - brake,
+            brake,
+            steering,
+            Gear::Fifth,
+            10000.0,
+        )
+    }
+
+    fn segmentation() -> TrackSegmentation {
+        TrackSegmentation::new(vec![
+            TrackSegment::new(SegmentKind::Straight, 1, 0.0, 400.0),
+            TrackSegment::new(SegmentKind::Corner, 1, 400.0, 500.0),
+        ])
+    }
+
+    fn trace(braking_point: f32) -> LapTrace {
+        LapTrace::new(
+            vehicle(0),
+            1,
+            Duration::from_secs(10),
+            vec![
+                sample(braking_point - 50.0, 0.0, 288.0, 0.0, 0.0),
+                sample(braking_point, 1.0, 288.0, 1.0, 0.0),
+                sample(braking_point + 50.0, 2.0, 216.0, 1.0, 0.3),
+                sample(braking_point + 100.0, 3.0, 180.0, 0.5, 0.3),
+                sample(braking_point + 150.0, 4.0, 144.0, 0.0, 0.3),
+            ],
+        )
+    }
+
+    #[test]
+    fn detect_with_trail_braking() {
+        let detector = BrakingDetector::new(0.1, 0.1);
+        let zones = detector.detect(&trace(300.0), &segmentation());
+
+        assert_eq!(1, zones.len());
+
+        let zone = zones[0];
+        assert_eq!(Some(1), zone.corner());
+        assert_approx_eq!(300.0, zone.braking_point());
+        assert_approx_eq!(400.0, zone.release_point());
+        assert_approx_eq!(180.0, zone.min_speed());
+        assert_approx_eq!(20.0, zone.peak_deceleration());
+        assert_eq!(Duration::from_secs(2), *zone.duration());
+        assert_eq!(Duration::from_secs(1), *zone.trail_braking_duration());
+    }
+
+    #[test]
+    fn compare_with_reference_lap() {
+        let detector = BrakingDetector::new(0.1, 0.1);
+        let comparison = detector.compare(&trace(320.0), &trace(300.0), &segmentation());
+
+        assert_eq!(1, comparison.len());
+        assert_eq!(1, comparison[0].corner());
+        assert_approx_eq!(20.0, comparison[0].braking_point_delta());
+        assert_approx_eq!(0.0, comparison[0].min_speed_delta());
+    }
+}
@@ -0,0 +1,264 @@
+//! Championship standings over multiple sessions
+//!
+//! Leagues run championships over a season of races, and award points for the result of every
+//! race. The championship collects the results of many sessions, awards points with a configurable
+//! points system, and calculates the standings of the drivers and teams. Drivers are identified by
+//! their name, since the index of their car can change from session to session.
+//!
+//! F1 2019 has no dedicated sprint sessions. The second race of a weekend, which is used for the
+//! sprint race in the F2 championship, is scored with the points system for sprint races.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BTreeMap;
+
+use getset::{CopyGetters, Getters};
+
+use crate::analysis::result::{PointsSystem, SessionResult};
+use crate::packet::participants::Team;
+use crate::packet::session::Session;
+
+/// Standing of a driver in the championship
+#[derive(Debug, Getters, CopyGetters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DriverStanding {
+    /// Returns the position of the driver in the championship.
+    #[getset(get_copy = "pub")]
+    position: u8,
+
+    /// Returns the name of the driver.
+    #[getset(get = "pub")]
+    name: String,
+
+    /// Returns the team the driver drove for in their latest race, if it is known.
+    #[getset(get_copy = "pub")]
+    team: Option<Team>,
+
+    /// Returns the points of the driver.
+    #[getset(get_copy = "pub")]
+    points: u16,
+
+    /// Returns the number of races the driver won.
+    #[getset(get_copy = "pub")]
+    wins: u8,
+
+    /// Returns the number of races the driver finished in the top three.
+    #[getset(get_copy = "pub")]
+    podiums: u8,
+}
+
+/// Standing of a team in the championship
+#[derive(
+    Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TeamStanding {
+    /// Returns the position of the team in the championship.
+    #[getset(get_copy = "pub")]
+    position: u8,
+
+    /// Returns the team.
+    #[getset(get_copy = "pub")]
+    team: Team,
+
+    /// Returns the points of the team.
+    #[getset(get_copy = "pub")]
+    points: u16,
+}
+
+/// Internal state that is tracked for each driver
+#[derive(Debug, Clone, PartialEq, Default)]
+struct DriverRecord {
+    team: Option<Team>,
+    points: u16,
+    finishes: Vec<usize>,
+}
+
+/// Championship over multiple sessions
+///
+/// Results of races are scored with the points system for races, results of second races with the
+/// points system for sprint races, and results of all other sessions are ignored. Drivers with the
+/// same number of points are ranked by their number of wins, then by their number of second places,
+/// and so on.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Championship {
+    race_points: PointsSystem,
+    sprint_points: PointsSystem,
+    rounds: usize,
+    drivers: BTreeMap<String, DriverRecord>,
+}
+
+impl Championship {
+    /// Create a new championship with the points systems for races and sprint races.
+    pub fn new(race_points: PointsSystem, sprint_points: PointsSystem) -> Self {
+        Championship {
+            race_points,
+            sprint_points,
+            rounds: 0,
+            drivers: BTreeMap::new(),
+        }
+    }
+
+    /// Add the result of a session to the championship.
+    ///
+    /// Returns whether the result was scored.
+    pub fn add(&mut self, result: &SessionResult) -> bool {
+        let points_system = match result.session_type() {
+            Session::Race => &self.race_points,
+            Session::Race2 => &self.sprint_points,
+            _ => return false,
+        };
+
+        let fastest_lap = result
+            .fastest_lap()
+            .map(|fastest_lap| fastest_lap.vehicle_index());
+
+        for car in result.classification() {
+            let driver = self.drivers.entry(car.display_name()).or_default();
+
+            if car.team().is_some() {
+                driver.team = car.team();
+            }
+
+            if car.is_classified() {
+                let has_fastest_lap = fastest_lap == Some(car.vehicle_index());
+                let points = points_system.points(car.position(), has_fastest_lap);
+
+                driver.points += points as u16;
+                driver.finishes.push(car.position() as usize);
+            }
+        }
+
+        self.rounds += 1;
+        true
+    }
+
+    /// Returns the number of sessions that have been scored.
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    /// Returns the standings of the drivers, ordered by their position.
+    pub fn driver_standings(&self) -> Vec<DriverStanding> {
+        let mut drivers: Vec<(&String, &DriverRecord)> = self.drivers.iter().collect();
+        drivers.sort_by(|(_, a), (_, b)| compare(a, b));
+
+        drivers
+            .into_iter()
+            .enumerate()
+            .map(|(index, (name, driver))| DriverStanding {
+                position: index as u8 + 1,
+                name: name.clone(),
+                team: driver.team,
+                points: driver.points,
+                wins: count(&driver.finishes, |position| position == 1),
+                podiums: count(&driver.finishes, |position| position <= 3),
+            })
+            .collect()
+    }
+
+    /// Returns the standings of the teams, ordered by their position.
+    pub fn team_standings(&self) -> Vec<TeamStanding> {
+        let mut teams: BTreeMap<Team, u16> = BTreeMap::new();
+
+        for driver in self.drivers.values() {
+            if let Some(team) = driver.team {
+                *teams.entry(team).or_default() += driver.points;
+            }
+        }
+
+        let mut teams: Vec<(Team, u16)> = teams.into_iter().collect();
+        teams.sort_by_key(|(_, points)| Reverse(*points));
+
+        teams
+            .into_iter()
+            .enumerate()
+            .map(|(index, (team, points))| TeamStanding {
+                position: index as u8 + 1,
+                team,
+                points,
+            })
+            .collect()
+    }
+}
+
+/// Compare two drivers by their points, and break ties by the count of their best finishes.
+fn compare(a: &DriverRecord, b: &DriverRecord) -> Ordering {
+    b.points
+        .cmp(&a.points)
+        .then_with(|| finish_counts(b).cmp(&finish_counts(a)))
+}
+
+/// Returns how often a driver finished in each position, starting with the wins.
+fn finish_counts(driver: &DriverRecord) -> Vec<usize> {
+    let mut counts = vec![0; driver.finishes.iter().max().copied().unwrap_or_default()];
+
+    for position in driver.finishes.iter() {
+        counts[position - 1] += 1;
+    }
+
+    counts
+}
+
+/// Returns the number of finishes that match the predicate.
+fn count<F>(finishes: &[usize], predicate: F) -> u8
+where
+    F: Fn(usize) -> bool,
+{
+    finishes
+        .iter()
+        .filter(|position| predicate(**position))
+        .count() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::championship::Championship;
+    use crate::analysis::fixtures::{
+        lap_packet, participants_packet, session_packet, LapFixture, SessionFixture,
+    };
+    use crate::analysis::result::{PointsSystem, ResultBuilder, SessionResult};
+    use crate::packet::session::Session;
+
+    fn race(session_type: Session, names: Vec<&str>) -> SessionResult {
+        let mut builder = ResultBuilder::new(PointsSystem::default());
+
+        builder.update(&session_packet(
+            0.0,
+            SessionFixture {
+                session_type,
+                ..SessionFixture::default()
+            },
+        ));
+        builder.update(&participants_packet(0.0, names.clone()));
+        builder.update(&lap_packet(
+            0.0,
+            (1..=names.len())
+                .map(|position| LapFixture {
+                    position: position as u8,
+                    ..LapFixture::default()
+                })
+                .collect(),
+        ));
+
+        builder.build()
+    }
+
+    #[test]
+    fn driver_standings_with_sprint() {
+        let mut championship = Championship::new(PointsSystem::default(), PointsSystem::sprint());
+
+        assert!(championship.add(&race(Session::Race, vec!["Alice", "Bob", "Carol"])));
+        assert!(championship.add(&race(Session::Race2, vec!["Bob", "Carol", "Alice"])));
+        assert!(championship.add(&race(Session::Race, vec!["Bob", "Alice", "Carol"])));
+        assert!(!championship.add(&race(Session::Q1, vec!["Carol", "Alice", "Bob"])));
+
+        let standings = championship.driver_standings();
+        assert_eq!(3, championship.rounds());
+        assert_eq!("Bob", standings[0].name());
+        assert_eq!(51, standings[0].points());
+        assert_eq!(2, standings[0].wins());
+        assert_eq!("Alice", standings[1].name());
+        assert_eq!(49, standings[1].points());
+        assert_eq!(3, standings[2].podiums());
+    }
+}
@@ -0,0 +1,312 @@
+//! Consistency metrics for the lap times of each driver
+//!
+//! A fast lap wins qualifying, but races are won by drivers who can lap at a high pace over and
+//! over again. The consistency tracker records the lap times of every car and calculates how much
+//! they vary, both over the whole session and for each stint, so that league statistics and driver
+//! coaching tools can compare drivers by their consistency and not only by their best lap.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::{LapPacket, PitStatus};
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// A completed lap and its lap time
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+pub struct TimedLap {
+    /// Returns the number of the lap.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the stint in which the lap was driven, starting at zero.
+    #[getset(get_copy = "pub")]
+    stint: usize,
+
+    /// Returns the time it took to complete the lap.
+    #[getset(get = "pub")]
+    lap_time: Duration,
+
+    /// Returns whether the car entered or left the pit lane during the lap.
+    #[getset(get_copy = "pub")]
+    is_pit_lap: bool,
+}
+
+/// Consistency metrics for a series of lap times
+#[derive(
+    Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+pub struct Consistency {
+    /// Returns the number of laps that the metrics are based on.
+    #[getset(get_copy = "pub")]
+    lap_count: usize,
+
+    /// Returns the fastest lap time.
+    #[getset(get = "pub")]
+    best: Duration,
+
+    /// Returns the slowest lap time.
+    #[getset(get = "pub")]
+    worst: Duration,
+
+    /// Returns the mean lap time.
+    #[getset(get = "pub")]
+    mean: Duration,
+
+    /// Returns the mean lap time without the fastest and slowest laps.
+    #[getset(get = "pub")]
+    trimmed_mean: Duration,
+
+    /// Returns the sample standard deviation of the lap times.
+    #[getset(get = "pub")]
+    standard_deviation: Duration,
+}
+
+impl Consistency {
+    /// Calculate the consistency metrics for the lap times.
+    ///
+    /// The trim is the share of the laps that is removed from each end of the sorted lap times
+    /// before the trimmed mean is calculated. A trim of 0.1 ignores the fastest and the slowest
+    /// 10% of the laps. Returns `None` if there are no lap times.
+    pub fn from_lap_times(lap_times: &[Duration], trim: f32) -> Option<Self> {
+        if lap_times.is_empty() {
+            return None;
+        }
+
+        let mut sorted = lap_times.to_vec();
+        sorted.sort();
+
+        let seconds: Vec<f64> = sorted.iter().map(|time| time.as_secs_f64()).collect();
+        let mean = average(&seconds);
+
+        let trimmed = (sorted.len() as f32 * trim.clamp(0.0, 0.5)) as usize;
+        let trimmed_mean = if trimmed * 2 < seconds.len() {
+            average(&seconds[trimmed..seconds.len() - trimmed])
+        } else {
+            mean
+        };
+
+        let variance = if seconds.len() > 1 {
+            seconds
+                .iter()
+                .map(|time| (time - mean).powi(2))
+                .sum::<f64>()
+                / (seconds.len() - 1) as f64
+        } else {
+            0.0
+        };
+
+        Some(Consistency {
+            lap_count: sorted.len(),
+            best: sorted[0],
+            worst: sorted[sorted.len() - 1],
+            mean: Duration::from_secs_f64(mean),
+            trimmed_mean: Duration::from_secs_f64(trimmed_mean),
+            standard_deviation: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarLaps {
+    lap: Option<u8>,
+    stint: usize,
+    pit_status: PitStatus,
+    is_pit_lap: bool,
+    laps: Vec<TimedLap>,
+}
+
+/// Tracker for the consistency of all drivers in the session
+///
+/// The consistency tracker consumes lap data packets. A lap is recorded when a car crosses the
+/// line, and a new stint starts every time a car leaves the pit lane. Laps on which a car entered
+/// or left the pit lane are recorded, but are not included in the consistency metrics.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ConsistencyTracker {
+    trim: f32,
+    rolling_window: usize,
+    cars: BTreeMap<VehicleIndex, CarLaps>,
+}
+
+impl ConsistencyTracker {
+    /// Create a new consistency tracker.
+    ///
+    /// The trim is the share of the laps that is ignored at each end when the trimmed mean is
+    /// calculated, and the rolling window is the number of recent laps that the rolling pace is
+    /// calculated from.
+    pub fn new(trim: f32, rolling_window: usize) -> Self {
+        ConsistencyTracker {
+            trim,
+            rolling_window,
+            cars: BTreeMap::new(),
+        }
+    }
+
+    /// Update the lap times with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        if let Packet::Lap(packet) = packet {
+            self.update_laps(packet);
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(index).or_default();
+            let lap_number = lap.current_lap_number();
+            let pit_status = lap.pit_status();
+
+            if let Some(previous) = car.lap.filter(|previous| lap_number > *previous) {
+                if *lap.last_lap_time() > Duration::default() {
+                    car.laps.push(TimedLap::new(
+                        previous,
+                        car.stint,
+                        *lap.last_lap_time(),
+                        car.is_pit_lap,
+                    ));
+                }
+
+                car.is_pit_lap = false;
+            }
+
+            if car.pit_status != PitStatus::None && pit_status == PitStatus::None {
+                car.stint += 1;
+            }
+
+            car.is_pit_lap |= pit_status != PitStatus::None;
+            car.pit_status = pit_status;
+            car.lap = Some(lap_number);
+        }
+    }
+
+    /// Returns the completed laps of a car.
+    pub fn laps(&self, vehicle_index: VehicleIndex) -> &[TimedLap] {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.laps.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns the consistency metrics of a car for the whole session.
+    pub fn session(&self, vehicle_index: VehicleIndex) -> Option<Consistency> {
+        self.consistency(vehicle_index, |_| true)
+    }
+
+    /// Returns the consistency metrics of a car for a stint.
+    pub fn stint(&self, vehicle_index: VehicleIndex, stint: usize) -> Option<Consistency> {
+        self.consistency(vehicle_index, |lap| lap.stint == stint)
+    }
+
+    /// Returns the mean lap time of the most recent laps of a car.
+    pub fn rolling_pace(&self, vehicle_index: VehicleIndex) -> Option<Duration> {
+        let lap_times: Vec<Duration> = self
+            .laps(vehicle_index)
+            .iter()
+            .rev()
+            .filter(|lap| !lap.is_pit_lap)
+            .take(self.rolling_window)
+            .map(|lap| lap.lap_time)
+            .collect();
+
+        Consistency::from_lap_times(&lap_times, 0.0).map(|consistency| consistency.mean)
+    }
+
+    fn consistency<F>(&self, vehicle_index: VehicleIndex, filter: F) -> Option<Consistency>
+    where
+        F: Fn(&TimedLap) -> bool,
+    {
+        let lap_times: Vec<Duration> = self
+            .laps(vehicle_index)
+            .iter()
+            .filter(|lap| !lap.is_pit_lap && filter(lap))
+            .map(|lap| lap.lap_time)
+            .collect();
+
+        Consistency::from_lap_times(&lap_times, self.trim)
+    }
+}
+
+/// Returns the average of the values.
+fn average(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::consistency::{Consistency, ConsistencyTracker};
+    use crate::analysis::fixtures::{lap_packet, vehicle, LapFixture};
+    use crate::packet::lap::PitStatus;
+
+    fn lap(current_lap_number: u8, last_lap_time: f32, pit_status: PitStatus) -> LapFixture {
+        LapFixture {
+            current_lap_number,
+            last_lap_time,
+            pit_status,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn consistency_from_lap_times() {
+        let lap_times: Vec<Duration> = [90.0, 91.0, 92.0, 93.0, 120.0]
+            .iter()
+            .map(|time| Duration::from_secs_f32(*time))
+            .collect();
+
+        let consistency = Consistency::from_lap_times(&lap_times, 0.2).unwrap();
+
+        assert_eq!(5, consistency.lap_count());
+        assert_approx_eq!(97.2, consistency.mean().as_secs_f32(), 0.001);
+        assert_approx_eq!(92.0, consistency.trimmed_mean().as_secs_f32(), 0.001);
+        assert_approx_eq!(
+            12.794,
+            consistency.standard_deviation().as_secs_f32(),
+            0.001
+        );
+        assert!(Consistency::from_lap_times(&[], 0.2).is_none());
+    }
+
+    #[test]
+    fn update_with_stints() {
+        let mut tracker = ConsistencyTracker::new(0.0, 2);
+        let laps = [
+            lap(1, 0.0, PitStatus::None),
+            lap(2, 90.0, PitStatus::None),
+            lap(3, 92.0, PitStatus::None),
+            lap(3, 92.0, PitStatus::Pitting),
+            lap(4, 110.0, PitStatus::InPits),
+            lap(4, 110.0, PitStatus::None),
+            lap(5, 115.0, PitStatus::None),
+            lap(6, 89.0, PitStatus::None),
+            lap(7, 88.0, PitStatus::None),
+        ];
+
+        for lap in laps.iter() {
+            tracker.update(&lap_packet(0.0, vec![*lap]));
+        }
+
+        assert_eq!(6, tracker.laps(vehicle(0)).len());
+        assert_eq!(4, tracker.session(vehicle(0)).unwrap().lap_count());
+
+        let first = tracker.stint(vehicle(0), 0).unwrap();
+        assert_eq!(Duration::from_secs(91), *first.mean());
+
+        let second = tracker.stint(vehicle(0), 1).unwrap();
+        assert_eq!(2, second.lap_count());
+        assert_eq!(Duration::from_secs(88), *second.best());
+
+        assert_eq!(
+            Some(Duration::from_millis(88500)),
+            tracker.rolling_pace(vehicle(0))
+        );
+    }
+}
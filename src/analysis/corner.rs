@@ -0,0 +1,415 @@
+//! Corner-by-corner analysis of laps
+//!
+//! Lap times alone do not tell drivers where on the track they lose time. The corner analyzer
+//! divides the track into corners and straights, and measures how long each car takes through each
+//! section, how fast it enters and exits it, and its minimum speed. Comparing these results between
+//! laps or drivers shows where time is lost.
+//!
+//! The sections of a track can either be defined by hand, or be derived from a track map by looking
+//! for the parts of the track where its direction changes quickly.
+
+use std::collections::BTreeMap;
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::analysis::track_map::TrackMap;
+use crate::packet::lap::{Lap, LapPacket};
+use crate::packet::telemetry::TelemetryPacket;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Types of sections a track is divided into
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+pub enum SegmentKind {
+    Corner,
+    #[default]
+    Straight,
+}
+
+/// A section of a track
+#[derive(new, Debug, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct TrackSegment {
+    /// Returns whether the segment is a corner or a straight.
+    #[getset(get_copy = "pub")]
+    kind: SegmentKind,
+
+    /// Returns the number of the corner or straight, counted from the start line.
+    #[getset(get_copy = "pub")]
+    number: u8,
+
+    /// Returns the lap distance at which the segment starts.
+    #[getset(get_copy = "pub")]
+    start_distance: f32,
+
+    /// Returns the lap distance at which the segment ends.
+    #[getset(get_copy = "pub")]
+    end_distance: f32,
+}
+
+/// Division of a track into corners and straights
+#[derive(Debug, Getters, PartialEq, Clone, PartialOrd, Default)]
+pub struct TrackSegmentation {
+    /// Returns the segments of the track, ordered by their lap distance.
+    #[getset(get = "pub")]
+    segments: Vec<TrackSegment>,
+}
+
+impl TrackSegmentation {
+    /// Create a segmentation from segments that have been defined by hand.
+    pub fn new(mut segments: Vec<TrackSegment>) -> Self {
+        segments.sort_by(|a, b| a.start_distance.total_cmp(&b.start_distance));
+        TrackSegmentation { segments }
+    }
+
+    /// Derive a segmentation from a track map.
+    ///
+    /// Points of the map where the direction of the track changes by more than `min_curvature`
+    /// radians per meter are considered part of a corner. Everything between two corners is a
+    /// straight.
+    pub fn from_track_map(map: &TrackMap, min_curvature: f32) -> Self {
+        let points = map.points();
+
+        if points.len() < 3 {
+            return TrackSegmentation::default();
+        }
+
+        let headings: Vec<f32> = points
+            .windows(2)
+            .map(|pair| {
+                let (from, to) = (pair[0].position(), pair[1].position());
+                (to.z() - from.z()).atan2(to.x() - from.x())
+            })
+            .collect();
+
+        let is_corner: Vec<bool> = (1..points.len() - 1)
+            .map(|index| {
+                let mut turn = (headings[index] - headings[index - 1]).abs();
+
+                if turn > PI {
+                    turn = 2.0 * PI - turn;
+                }
+
+                let length =
+                    (points[index + 1].lap_distance() - points[index - 1].lap_distance()) / 2.0;
+                length > 0.0 && turn / length > min_curvature
+            })
+            .collect();
+
+        let mut corners: Vec<(f32, f32)> = Vec::new();
+
+        for (offset, is_corner) in is_corner.iter().enumerate() {
+            if !is_corner {
+                continue;
+            }
+
+            // The corner extends to the points before and after the change of direction.
+            let start = points[offset].lap_distance();
+            let end = points[offset + 2].lap_distance();
+
+            match corners.last_mut() {
+                Some(corner) if start <= corner.1 => corner.1 = end,
+                _ => corners.push((start, end)),
+            }
+        }
+
+        let mut segments = Vec::new();
+        let mut straight_start = points[0].lap_distance();
+        let end_distance = points[points.len() - 1].lap_distance();
+
+        for (number, (start, end)) in corners.iter().enumerate() {
+            if *start > straight_start {
+                segments.push(TrackSegment::new(
+                    SegmentKind::Straight,
+                    count(&segments, SegmentKind::Straight) + 1,
+                    straight_start,
+                    *start,
+                ));
+            }
+
+            segments.push(TrackSegment::new(
+                SegmentKind::Corner,
+                number as u8 + 1,
+                *start,
+                *end,
+            ));
+            straight_start = *end;
+        }
+
+        if end_distance > straight_start {
+            segments.push(TrackSegment::new(
+                SegmentKind::Straight,
+                count(&segments, SegmentKind::Straight) + 1,
+                straight_start,
+                end_distance,
+            ));
+        }
+
+        TrackSegmentation { segments }
+    }
+
+    /// Returns the index of the segment that contains the lap distance.
+    pub fn segment_at(&self, lap_distance: f32) -> Option<usize> {
+        self.segments.iter().position(|segment| {
+            lap_distance >= segment.start_distance && lap_distance < segment.end_distance
+        })
+    }
+}
+
+/// Returns the number of segments of the given kind.
+fn count(segments: &[TrackSegment], kind: SegmentKind) -> u8 {
+    segments
+        .iter()
+        .filter(|segment| segment.kind == kind)
+        .count() as u8
+}
+
+/// Result of a car driving through a segment of the track
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct SegmentResult {
+    /// Returns the lap on which the car drove through the segment.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the segment of the track.
+    #[getset(get = "pub")]
+    segment: TrackSegment,
+
+    /// Returns the speed of the car when it entered the segment in kilometers per hour.
+    #[getset(get_copy = "pub")]
+    entry_speed: u16,
+
+    /// Returns the lowest speed of the car in the segment in kilometers per hour.
+    #[getset(get_copy = "pub")]
+    min_speed: u16,
+
+    /// Returns the speed of the car when it exited the segment in kilometers per hour.
+    #[getset(get_copy = "pub")]
+    exit_speed: u16,
+
+    /// Returns the time the car took to drive through the segment.
+    #[getset(get = "pub")]
+    time: Duration,
+}
+
+/// Segment a car is currently driving through
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+struct OpenSegment {
+    index: usize,
+    lap: u8,
+    entry_time: Duration,
+    entry_speed: u16,
+    min_speed: u16,
+    is_complete: bool,
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarSegments {
+    speed: u16,
+    open: Option<OpenSegment>,
+    results: Vec<SegmentResult>,
+}
+
+/// Analyzer for the performance of all cars in each segment of the track
+///
+/// The corner analyzer consumes lap data and telemetry packets. A segment result is only recorded
+/// if the car has been observed entering the segment from the segment before it, so that partial
+/// segments at the start of tracking or after a flashback are discarded.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CornerAnalyzer {
+    segmentation: TrackSegmentation,
+    cars: BTreeMap<VehicleIndex, CarSegments>,
+}
+
+impl CornerAnalyzer {
+    /// Create a new corner analyzer for the segmentation of the track.
+    pub fn new(segmentation: TrackSegmentation) -> Self {
+        CornerAnalyzer {
+            segmentation,
+            cars: BTreeMap::new(),
+        }
+    }
+
+    /// Update the analysis with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Lap(packet) => self.update_laps(packet),
+            Packet::Telemetry(packet) => self.update_telemetry(packet),
+            _ => {}
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        let segment_count = self.segmentation.segments.len();
+
+        for (index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(index).or_default();
+            let segment_index = match self.segmentation.segment_at(lap.lap_distance()) {
+                Some(segment_index) => segment_index,
+                None => continue,
+            };
+
+            let open = match car.open {
+                Some(open) if open.index == segment_index => continue,
+                open => open,
+            };
+
+            let is_next = open
+                .map(|open| (open.index + 1) % segment_count == segment_index)
+                .unwrap_or(false);
+
+            if let Some(open) = open.filter(|open| open.is_complete && is_next) {
+                let time = exit_time(&open, lap).saturating_sub(open.entry_time);
+
+                car.results.push(SegmentResult::new(
+                    open.lap,
+                    self.segmentation.segments[open.index],
+                    open.entry_speed,
+                    open.min_speed,
+                    car.speed,
+                    time,
+                ));
+            }
+
+            car.open = Some(OpenSegment {
+                index: segment_index,
+                lap: lap.current_lap_number(),
+                entry_time: *lap.current_lap_time(),
+                entry_speed: car.speed,
+                min_speed: car.speed,
+                is_complete: is_next,
+            });
+        }
+    }
+
+    fn update_telemetry(&mut self, packet: &TelemetryPacket) {
+        for (index, telemetry) in enumerate_cars(packet.telemetry()) {
+            let car = self.cars.entry(index).or_default();
+            car.speed = telemetry.speed().as_kph();
+
+            if let Some(open) = car.open.as_mut() {
+                open.min_speed = open.min_speed.min(telemetry.speed().as_kph());
+            }
+        }
+    }
+
+    /// Returns the segmentation of the track.
+    pub fn segmentation(&self) -> &TrackSegmentation {
+        &self.segmentation
+    }
+
+    /// Returns the results of a car in all segments it completed.
+    pub fn results(&self, vehicle_index: VehicleIndex) -> &[SegmentResult] {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.results.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns the results of a car in the corners of a lap.
+    pub fn corners(
+        &self,
+        vehicle_index: VehicleIndex,
+        lap: u8,
+    ) -> impl Iterator<Item = &SegmentResult> {
+        self.results(vehicle_index)
+            .iter()
+            .filter(move |result| result.lap == lap && result.segment.kind == SegmentKind::Corner)
+    }
+}
+
+/// Returns the lap time at which the car left the open segment.
+fn exit_time(open: &OpenSegment, lap: &Lap) -> Duration {
+    if lap.current_lap_number() != open.lap {
+        // The car crossed the line, and the lap timer has been reset.
+        *lap.last_lap_time()
+    } else {
+        *lap.current_lap_time()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::corner::{CornerAnalyzer, SegmentKind, TrackSegment, TrackSegmentation};
+    use crate::analysis::fixtures::{
+        lap_packet, telemetry_packet, vehicle, LapFixture, TelemetryFixture,
+    };
+    use crate::analysis::track_map::{TrackMap, TrackPoint};
+    use crate::types::Property3D;
+
+    fn drive(analyzer: &mut CornerAnalyzer, lap_number: u8, distance: f32, time: f32, speed: u16) {
+        analyzer.update(&telemetry_packet(
+            0.0,
+            vec![TelemetryFixture {
+                speed,
+                ..TelemetryFixture::default()
+            }],
+        ));
+        analyzer.update(&lap_packet(
+            0.0,
+            vec![LapFixture {
+                current_lap_number: lap_number,
+                lap_distance: distance,
+                current_lap_time: time,
+                last_lap_time: 30.0,
+                ..LapFixture::default()
+            }],
+        ));
+    }
+
+    #[test]
+    fn from_track_map_with_corner() {
+        let mut points: Vec<TrackPoint> = (0..=10)
+            .map(|step| {
+                let distance = step as f32 * 10.0;
+                TrackPoint::new(distance, Property3D::new(distance, 0.0, 0.0))
+            })
+            .collect();
+        points.extend((1..=5).map(|step| {
+            let offset = step as f32 * 10.0;
+            TrackPoint::new(100.0 + offset, Property3D::new(100.0, 0.0, offset))
+        }));
+
+        let segmentation = TrackSegmentation::from_track_map(&TrackMap::new(points), 0.05);
+        let segments = segmentation.segments();
+
+        assert_eq!(3, segments.len());
+        assert_eq!(SegmentKind::Straight, segments[0].kind());
+        assert_eq!(SegmentKind::Corner, segments[1].kind());
+        assert_eq!(90.0, segments[1].start_distance());
+        assert_eq!(110.0, segments[1].end_distance());
+        assert_eq!(2, segments[2].number());
+    }
+
+    #[test]
+    fn update_with_corner_results() {
+        let segmentation = TrackSegmentation::new(vec![
+            TrackSegment::new(SegmentKind::Straight, 1, 0.0, 100.0),
+            TrackSegment::new(SegmentKind::Corner, 1, 100.0, 200.0),
+            TrackSegment::new(SegmentKind::Straight, 2, 200.0, 300.0),
+        ]);
+        let mut analyzer = CornerAnalyzer::new(segmentation);
+
+        drive(&mut analyzer, 1, 50.0, 5.0, 300);
+        drive(&mut analyzer, 1, 110.0, 11.0, 250);
+        drive(&mut analyzer, 1, 150.0, 15.0, 120);
+        drive(&mut analyzer, 1, 210.0, 21.0, 200);
+        drive(&mut analyzer, 2, 10.0, 1.0, 310);
+
+        let corners: Vec<_> = analyzer.corners(vehicle(0), 1).collect();
+        assert_eq!(1, corners.len());
+        assert_eq!(250, corners[0].entry_speed());
+        assert_eq!(120, corners[0].min_speed());
+        assert_eq!(200, corners[0].exit_speed());
+        assert_eq!(Duration::from_secs(10), *corners[0].time());
+
+        let results = analyzer.results(vehicle(0));
+        assert_eq!(2, results.len());
+        assert_eq!(Duration::from_secs(9), *results[1].time());
+    }
+}
@@ -0,0 +1,215 @@
+//! Timeline of the damage of each car
+//!
+//! Damage to a car, for example a broken front wing after contact in the first corner, often
+//! explains why a driver lost pace during a race. The damage tracker records every change to the
+//! damage of a car, together with the session time and the lap on which it happened, and builds a
+//! timeline that can be queried after the race.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::LapPacket;
+use crate::packet::status::{CarStatus, CarStatusPacket};
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Components of a car that can be damaged
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+pub enum DamageComponent {
+    #[default]
+    FrontLeftWing,
+    FrontRightWing,
+    RearWing,
+    Engine,
+    GearBox,
+    FrontLeftTyre,
+    FrontRightTyre,
+    RearLeftTyre,
+    RearRightTyre,
+}
+
+/// A change to the damage of a component
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+pub struct DamageChange {
+    /// Returns the session time at which the damage changed.
+    #[getset(get = "pub")]
+    session_time: Duration,
+
+    /// Returns the lap on which the damage changed.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the component whose damage changed.
+    #[getset(get_copy = "pub")]
+    component: DamageComponent,
+
+    /// Returns the previous damage of the component in percent.
+    #[getset(get_copy = "pub")]
+    from: u8,
+
+    /// Returns the new damage of the component in percent.
+    #[getset(get_copy = "pub")]
+    to: u8,
+}
+
+/// Timeline of the damage of a car
+#[derive(Debug, Getters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+pub struct DamageTimeline {
+    /// Returns the changes to the damage of the car in chronological order.
+    #[getset(get = "pub")]
+    changes: Vec<DamageChange>,
+
+    current: BTreeMap<DamageComponent, u8>,
+}
+
+impl DamageTimeline {
+    /// Returns the current damage of a component in percent.
+    pub fn damage(&self, component: DamageComponent) -> u8 {
+        self.current.get(&component).copied().unwrap_or_default()
+    }
+
+    /// Returns the changes to the damage of a component in chronological order.
+    pub fn changes_of(&self, component: DamageComponent) -> impl Iterator<Item = &DamageChange> {
+        self.changes
+            .iter()
+            .filter(move |change| change.component == component)
+    }
+}
+
+/// Tracker for the damage of all cars in the session
+///
+/// The damage tracker consumes lap data and car status packets. The damage of each component is
+/// compared with the previous car status, and every difference is recorded as a change.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DamageTracker {
+    laps: BTreeMap<VehicleIndex, u8>,
+    timelines: BTreeMap<VehicleIndex, DamageTimeline>,
+}
+
+impl DamageTracker {
+    /// Create a new damage tracker without any damage.
+    pub fn new() -> Self {
+        DamageTracker::default()
+    }
+
+    /// Update the damage with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Lap(packet) => self.update_laps(packet),
+            Packet::Status(packet) => self.update_statuses(packet),
+            _ => {}
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (index, lap) in enumerate_cars(packet.laps()) {
+            self.laps.insert(index, lap.current_lap_number());
+        }
+    }
+
+    fn update_statuses(&mut self, packet: &CarStatusPacket) {
+        let session_time = *packet.header().session_time();
+
+        for (vehicle_index, status) in enumerate_cars(packet.statuses()) {
+            let lap = self.laps.get(&vehicle_index).copied().unwrap_or_default();
+            let timeline = self.timelines.entry(vehicle_index).or_default();
+
+            for (component, damage) in component_damage(status).iter() {
+                let previous = timeline.damage(*component);
+
+                if previous != *damage {
+                    timeline.changes.push(DamageChange::new(
+                        session_time,
+                        lap,
+                        *component,
+                        previous,
+                        *damage,
+                    ));
+                    timeline.current.insert(*component, *damage);
+                }
+            }
+        }
+    }
+
+    /// Returns the damage timeline of a car.
+    pub fn timeline(&self, vehicle_index: VehicleIndex) -> Option<&DamageTimeline> {
+        self.timelines.get(&vehicle_index)
+    }
+}
+
+/// Returns the damage of each component of the car.
+fn component_damage(status: &CarStatus) -> [(DamageComponent, u8); 9] {
+    let tyre_damage = status.tyre_damage();
+
+    [
+        (
+            DamageComponent::FrontLeftWing,
+            status.front_left_wing_damage(),
+        ),
+        (
+            DamageComponent::FrontRightWing,
+            status.front_right_wing_damage(),
+        ),
+        (DamageComponent::RearWing, status.rear_wing_damage()),
+        (DamageComponent::Engine, status.engine_damage()),
+        (DamageComponent::GearBox, status.gear_box_damage()),
+        (DamageComponent::FrontLeftTyre, tyre_damage.front_left()),
+        (DamageComponent::FrontRightTyre, tyre_damage.front_right()),
+        (DamageComponent::RearLeftTyre, tyre_damage.rear_left()),
+        (DamageComponent::RearRightTyre, tyre_damage.rear_right()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::damage::{DamageComponent, DamageTracker};
+    use crate::analysis::fixtures::{
+        lap_packet, status_packet, vehicle, LapFixture, StatusFixture,
+    };
+
+    fn lap(current_lap_number: u8) -> LapFixture {
+        LapFixture {
+            current_lap_number,
+            ..LapFixture::default()
+        }
+    }
+
+    fn status(front_left_wing_damage: u8) -> StatusFixture {
+        StatusFixture {
+            front_left_wing_damage,
+            ..StatusFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_damage_changes() {
+        let mut tracker = DamageTracker::new();
+
+        tracker.update(&lap_packet(0.0, vec![lap(1)]));
+        tracker.update(&status_packet(0.0, vec![status(0)]));
+        assert!(tracker.timeline(vehicle(0)).unwrap().changes().is_empty());
+
+        tracker.update(&lap_packet(1.0, vec![lap(2)]));
+        tracker.update(&status_packet(1.0, vec![status(25)]));
+        tracker.update(&status_packet(2.0, vec![status(25)]));
+        tracker.update(&lap_packet(3.0, vec![lap(5)]));
+        tracker.update(&status_packet(3.0, vec![status(100)]));
+
+        let timeline = tracker.timeline(vehicle(0)).unwrap();
+        let changes: Vec<_> = timeline
+            .changes_of(DamageComponent::FrontLeftWing)
+            .collect();
+
+        assert_eq!(2, changes.len());
+        assert_eq!(2, changes[0].lap());
+        assert_eq!(25, changes[1].from());
+        assert_eq!(100, changes[1].to());
+        assert_eq!(100, timeline.damage(DamageComponent::FrontLeftWing));
+        assert_eq!(0, timeline.damage(DamageComponent::Engine));
+    }
+}
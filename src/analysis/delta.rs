@@ -0,0 +1,305 @@
+//! Live time delta to a reference lap
+//!
+//! The in-game delta bar shows drivers whether they are faster or slower than their best lap at the
+//! current point of the track. The delta tracker recreates it for overlays and for any car in the
+//! session. It records the lap distance and the lap time of every car during each lap, keeps the
+//! fastest valid laps as reference laps, and compares the current lap against them.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use getset::{CopyGetters, Getters};
+
+use crate::analysis::gap::time_at_distance;
+use crate::packet::lap::{Lap, LapPacket};
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Reference lap for the delta
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub enum DeltaReference {
+    /// The personal best lap of the car.
+    #[default]
+    PersonalBest,
+
+    /// The fastest lap of any car in the session.
+    SessionBest,
+}
+
+/// A lap that is indexed by the distance around the track
+///
+/// A reference lap stores the lap time at which the car passed each sampled lap distance, and can
+/// be used to look up how long it took the car to reach any point of the track.
+#[derive(Debug, Getters, CopyGetters, PartialEq, Clone, PartialOrd, Default)]
+pub struct ReferenceLap {
+    /// Returns the vehicle index of the car that drove the lap.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the number of the lap.
+    #[getset(get_copy = "pub")]
+    lap_number: u8,
+
+    /// Returns the lap time.
+    #[getset(get = "pub")]
+    lap_time: Duration,
+
+    /// Returns the lap distance and the lap time of each sample.
+    #[getset(get = "pub")]
+    samples: Vec<(f32, Duration)>,
+}
+
+impl ReferenceLap {
+    /// Returns the interpolated lap time at which the car passed the lap distance.
+    pub fn time_at(&self, lap_distance: f32) -> Option<Duration> {
+        time_at_distance(&self.samples, lap_distance)
+    }
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarDelta {
+    lap: Option<u8>,
+    is_complete: bool,
+    is_valid: bool,
+    samples: Vec<(f32, Duration)>,
+    personal_best: Option<ReferenceLap>,
+}
+
+/// Tracker for the live delta of all cars in the session
+///
+/// The delta tracker consumes lap data packets. Only laps that were observed from start to finish
+/// and that were not invalidated become reference laps. The lap time of a reference lap is taken
+/// from the last lap time that the game publishes after the car crosses the line.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DeltaTracker {
+    cars: BTreeMap<VehicleIndex, CarDelta>,
+    session_best: Option<ReferenceLap>,
+}
+
+impl DeltaTracker {
+    /// Create a new delta tracker without any reference laps.
+    pub fn new() -> Self {
+        DeltaTracker::default()
+    }
+
+    /// Update the delta with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        if let Packet::Lap(packet) = packet {
+            self.update_laps(packet);
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (vehicle_index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(vehicle_index).or_default();
+
+            if let Some(reference) = car.update(vehicle_index, lap) {
+                let is_faster = self
+                    .session_best
+                    .as_ref()
+                    .map(|best| reference.lap_time < best.lap_time)
+                    .unwrap_or(true);
+
+                if is_faster {
+                    self.session_best = Some(reference);
+                }
+            }
+        }
+    }
+
+    /// Returns the reference lap of a car.
+    pub fn reference(
+        &self,
+        vehicle_index: VehicleIndex,
+        reference: DeltaReference,
+    ) -> Option<&ReferenceLap> {
+        match reference {
+            DeltaReference::PersonalBest => self.cars.get(&vehicle_index)?.personal_best.as_ref(),
+            DeltaReference::SessionBest => self.session_best.as_ref(),
+        }
+    }
+
+    /// Returns the live delta of a car to the reference lap in seconds.
+    ///
+    /// A negative delta means that the car is faster than the reference lap at its current lap
+    /// distance. No delta is returned while the car is not on a timed lap, or if no reference lap
+    /// exists yet.
+    pub fn delta(&self, vehicle_index: VehicleIndex, reference: DeltaReference) -> Option<f32> {
+        let car = self.cars.get(&vehicle_index)?;
+        let (lap_distance, lap_time) = *car.samples.last()?;
+        let reference_time = self
+            .reference(vehicle_index, reference)?
+            .time_at(lap_distance)?;
+
+        Some(lap_time.as_secs_f32() - reference_time.as_secs_f32())
+    }
+}
+
+impl CarDelta {
+    /// Update the car with its lap data, and return the lap it completed if it is a new best.
+    fn update(&mut self, vehicle_index: VehicleIndex, lap: &Lap) -> Option<ReferenceLap> {
+        let lap_number = lap.current_lap_number();
+        let mut personal_best = None;
+
+        match self.lap {
+            Some(previous) if lap_number > previous => {
+                let samples = std::mem::take(&mut self.samples);
+                let lap_time = *lap.last_lap_time();
+
+                if self.is_complete && self.is_valid && !samples.is_empty() {
+                    let is_faster = self
+                        .personal_best
+                        .as_ref()
+                        .map(|best| lap_time < best.lap_time)
+                        .unwrap_or(true);
+
+                    if is_faster {
+                        let reference = ReferenceLap {
+                            vehicle_index,
+                            lap_number: previous,
+                            lap_time,
+                            samples,
+                        };
+
+                        self.personal_best = Some(reference.clone());
+                        personal_best = Some(reference);
+                    }
+                }
+
+                self.is_complete = true;
+                self.is_valid = true;
+            }
+            Some(previous) if lap_number < previous => {
+                // The lap number only decreases when a session is restarted.
+                *self = CarDelta::default();
+            }
+            Some(_) => {}
+            None => {
+                // A lap counts as observed from its start if tracking began before the lap timer.
+                self.is_complete = *lap.current_lap_time() == Duration::ZERO;
+                self.is_valid = true;
+            }
+        }
+
+        self.lap = Some(lap_number);
+        self.is_valid &= lap.is_valid_lap();
+
+        let lap_distance = lap.lap_distance();
+        let is_ahead = self
+            .samples
+            .last()
+            .map(|(last_distance, _)| lap_distance > *last_distance)
+            .unwrap_or(true);
+
+        if lap_distance >= 0.0 && is_ahead {
+            self.samples.push((lap_distance, *lap.current_lap_time()));
+        }
+
+        personal_best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::delta::{DeltaReference, DeltaTracker};
+    use crate::analysis::fixtures::{lap_packet, vehicle, LapFixture};
+
+    fn lap(current_lap_number: u8, lap_distance: f32, current_lap_time: f32) -> LapFixture {
+        LapFixture {
+            current_lap_number,
+            lap_distance,
+            current_lap_time,
+            ..LapFixture::default()
+        }
+    }
+
+    fn drive_lap(tracker: &mut DeltaTracker, lap_number: u8, pace: f32) {
+        for step in 0..10 {
+            let distance = step as f32 * 500.0;
+            tracker.update(&lap_packet(
+                0.0,
+                vec![lap(lap_number, distance, distance / pace)],
+            ));
+        }
+    }
+
+    fn cross_line(tracker: &mut DeltaTracker, lap_number: u8, last_lap_time: f32) {
+        tracker.update(&lap_packet(
+            0.0,
+            vec![LapFixture {
+                last_lap_time,
+                ..lap(lap_number, 0.0, 0.0)
+            }],
+        ));
+    }
+
+    #[test]
+    fn delta_without_complete_lap() {
+        let mut tracker = DeltaTracker::new();
+
+        tracker.update(&lap_packet(0.0, vec![lap(1, 2000.0, 40.0)]));
+        cross_line(&mut tracker, 2, 100.0);
+
+        assert!(tracker
+            .reference(vehicle(0), DeltaReference::PersonalBest)
+            .is_none());
+        assert!(tracker
+            .delta(vehicle(0), DeltaReference::SessionBest)
+            .is_none());
+    }
+
+    #[test]
+    fn delta_to_personal_best() {
+        let mut tracker = DeltaTracker::new();
+
+        cross_line(&mut tracker, 1, 0.0);
+        drive_lap(&mut tracker, 1, 50.0);
+        cross_line(&mut tracker, 2, 100.0);
+
+        let reference = tracker
+            .reference(vehicle(0), DeltaReference::PersonalBest)
+            .unwrap();
+        assert_eq!(1, reference.lap_number());
+        assert_eq!(Duration::from_secs(100), *reference.lap_time());
+
+        tracker.update(&lap_packet(0.0, vec![lap(2, 2250.0, 40.0)]));
+
+        assert_approx_eq!(
+            -5.0,
+            tracker
+                .delta(vehicle(0), DeltaReference::PersonalBest)
+                .unwrap()
+        );
+        assert_approx_eq!(
+            -5.0,
+            tracker
+                .delta(vehicle(0), DeltaReference::SessionBest)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn delta_ignores_invalid_laps() {
+        let mut tracker = DeltaTracker::new();
+
+        cross_line(&mut tracker, 1, 0.0);
+        drive_lap(&mut tracker, 1, 50.0);
+        tracker.update(&lap_packet(
+            0.0,
+            vec![LapFixture {
+                is_valid_lap: false,
+                ..lap(1, 4800.0, 96.0)
+            }],
+        ));
+        cross_line(&mut tracker, 2, 100.0);
+
+        assert!(tracker
+            .reference(vehicle(0), DeltaReference::PersonalBest)
+            .is_none());
+    }
+}
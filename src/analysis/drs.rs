@@ -0,0 +1,252 @@
+//! Usage of the Drag Reduction System
+//!
+//! The Drag Reduction System, or DRS, opens a flap in the rear wing to reduce drag and increase the
+//! top speed of a car. Drivers may only open it in designated zones, and only when race control
+//! allows it. The DRS tracker records when each car was allowed to use DRS and when it opened the
+//! flap, and summarizes the usage per lap.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::LapPacket;
+use crate::packet::status::{CarStatusPacket, DrsSetting};
+use crate::packet::telemetry::TelemetryPacket;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Summary of the DRS usage of a car during a lap
+#[derive(Debug, Getters, CopyGetters, PartialEq, Clone, PartialOrd, Default)]
+pub struct DrsLap {
+    /// Returns the lap that is summarized.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns whether the car was allowed to use DRS at any point during the lap.
+    #[getset(get_copy = "pub")]
+    was_allowed: bool,
+
+    /// Returns how often the car opened DRS during the lap.
+    #[getset(get_copy = "pub")]
+    activations: u8,
+
+    /// Returns for how long DRS was open during the lap.
+    #[getset(get = "pub")]
+    open_time: Duration,
+
+    /// Returns the lap distances at which DRS was opened, which identify the zones that were used.
+    #[getset(get = "pub")]
+    activation_distances: Vec<f32>,
+}
+
+impl DrsLap {
+    fn new(lap: u8) -> Self {
+        DrsLap {
+            lap,
+            ..DrsLap::default()
+        }
+    }
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarDrs {
+    lap_distance: f32,
+    is_allowed: bool,
+    is_open: bool,
+    last_update: Option<Duration>,
+    current: Option<DrsLap>,
+    laps: Vec<DrsLap>,
+}
+
+/// Tracker for the DRS usage of all cars in the session
+///
+/// The DRS tracker consumes lap data, car status, and telemetry packets. Whether DRS is allowed is
+/// taken from the car status, while the telemetry shows whether the flap is open.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DrsTracker {
+    cars: BTreeMap<VehicleIndex, CarDrs>,
+}
+
+impl DrsTracker {
+    /// Create a new DRS tracker without any data.
+    pub fn new() -> Self {
+        DrsTracker::default()
+    }
+
+    /// Update the DRS usage with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Lap(packet) => self.update_laps(packet),
+            Packet::Status(packet) => self.update_statuses(packet),
+            Packet::Telemetry(packet) => self.update_telemetry(packet),
+            _ => {}
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(index).or_default();
+            let lap_number = lap.current_lap_number();
+
+            match car.current.as_ref().map(|current| current.lap) {
+                Some(previous) if previous == lap_number => {}
+                _ => {
+                    if let Some(current) = car.current.take() {
+                        car.laps.push(current);
+                    }
+
+                    let mut current = DrsLap::new(lap_number);
+                    current.was_allowed = car.is_allowed;
+                    car.current = Some(current);
+                }
+            }
+
+            car.lap_distance = lap.lap_distance();
+        }
+    }
+
+    fn update_statuses(&mut self, packet: &CarStatusPacket) {
+        for (index, status) in enumerate_cars(packet.statuses()) {
+            let car = self.cars.entry(index).or_default();
+            car.is_allowed = status.drs() == DrsSetting::Allowed;
+
+            if let Some(current) = car.current.as_mut() {
+                current.was_allowed |= car.is_allowed;
+            }
+        }
+    }
+
+    fn update_telemetry(&mut self, packet: &TelemetryPacket) {
+        let session_time = *packet.header().session_time();
+
+        for (index, telemetry) in enumerate_cars(packet.telemetry()) {
+            let car = self.cars.entry(index).or_default();
+            let is_open = telemetry.drs();
+
+            if let Some(current) = car.current.as_mut() {
+                if car.is_open {
+                    let elapsed = car
+                        .last_update
+                        .map(|last_update| session_time.saturating_sub(last_update))
+                        .unwrap_or_default();
+                    current.open_time += elapsed;
+                }
+
+                if is_open && !car.is_open {
+                    current.activations = current.activations.saturating_add(1);
+                    current.activation_distances.push(car.lap_distance);
+                }
+            }
+
+            car.is_open = is_open;
+            car.last_update = Some(session_time);
+        }
+    }
+
+    /// Returns the DRS summaries of the completed laps of a car.
+    pub fn laps(&self, vehicle_index: VehicleIndex) -> &[DrsLap] {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.laps.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns the DRS summary of the lap a car is currently on.
+    pub fn current_lap(&self, vehicle_index: VehicleIndex) -> Option<&DrsLap> {
+        self.cars.get(&vehicle_index)?.current.as_ref()
+    }
+
+    /// Returns the total time that a car had DRS open in the session.
+    pub fn total_open_time(&self, vehicle_index: VehicleIndex) -> Duration {
+        self.laps(vehicle_index)
+            .iter()
+            .chain(self.current_lap(vehicle_index))
+            .map(|lap| lap.open_time)
+            .sum()
+    }
+
+    /// Returns whether a car is allowed to use DRS right now.
+    pub fn is_allowed(&self, vehicle_index: VehicleIndex) -> bool {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.is_allowed)
+            .unwrap_or(false)
+    }
+
+    /// Returns whether a car has DRS open right now.
+    pub fn is_open(&self, vehicle_index: VehicleIndex) -> bool {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.is_open)
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::drs::DrsTracker;
+    use crate::analysis::fixtures::{
+        lap_packet, status_packet, telemetry_packet, vehicle, LapFixture, StatusFixture,
+        TelemetryFixture,
+    };
+    use crate::packet::status::DrsSetting;
+
+    fn lap(current_lap_number: u8, lap_distance: f32) -> LapFixture {
+        LapFixture {
+            current_lap_number,
+            lap_distance,
+            ..LapFixture::default()
+        }
+    }
+
+    fn drs(tracker: &mut DrsTracker, session_time: f32, drs: bool) {
+        tracker.update(&telemetry_packet(
+            session_time,
+            vec![TelemetryFixture {
+                drs,
+                ..TelemetryFixture::default()
+            }],
+        ));
+    }
+
+    #[test]
+    fn update_with_drs_activations() {
+        let mut tracker = DrsTracker::new();
+
+        tracker.update(&status_packet(
+            0.0,
+            vec![StatusFixture {
+                drs: DrsSetting::Allowed,
+                ..StatusFixture::default()
+            }],
+        ));
+        tracker.update(&lap_packet(0.0, vec![lap(3, 100.0)]));
+        drs(&mut tracker, 0.0, true);
+        assert!(tracker.is_open(vehicle(0)));
+        drs(&mut tracker, 2.0, false);
+
+        tracker.update(&lap_packet(3.0, vec![lap(3, 2000.0)]));
+        drs(&mut tracker, 3.0, true);
+        drs(&mut tracker, 4.5, false);
+
+        tracker.update(&lap_packet(5.0, vec![lap(4, 0.0)]));
+
+        let summary = &tracker.laps(vehicle(0))[0];
+        assert_eq!(3, summary.lap());
+        assert!(summary.was_allowed());
+        assert_eq!(2, summary.activations());
+        assert_eq!(Duration::from_millis(3500), *summary.open_time());
+        assert_eq!(&vec![100.0, 2000.0], summary.activation_distances());
+
+        assert_eq!(
+            Duration::from_millis(3500),
+            tracker.total_open_time(vehicle(0))
+        );
+        assert!(tracker.is_allowed(vehicle(0)));
+        assert!(!tracker.is_open(vehicle(0)));
+    }
+}
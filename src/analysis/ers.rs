@@ -0,0 +1,328 @@
+//! Deployment and harvesting of the Energy Recovery System
+//!
+//! The Energy Recovery System, or ERS, harvests energy under braking and from the exhaust gases, and
+//! allows drivers to deploy it for additional power. How much energy a driver deploys and harvests
+//! each lap is an important part of managing a race. The ERS tracker summarizes the energy store,
+//! deployment, and harvesting of each car per lap, and records every change of the deploy mode.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::analysis::privacy::TelemetryRestrictions;
+use crate::packet::lap::LapPacket;
+use crate::packet::status::{CarStatusPacket, ErsDeployMode};
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Summary of the ERS usage of a car during a lap
+///
+/// The F1 games publish the energy that has been deployed and harvested in the current lap. The
+/// summary captures these values at the end of the lap, together with the state of the energy store
+/// at the start and the end of the lap. All energy values are provided in Joules.
+#[derive(new, Debug, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct ErsLapSummary {
+    /// Returns the lap that is summarized.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the energy in the store at the start of the lap.
+    #[getset(get_copy = "pub")]
+    store_at_start: f32,
+
+    /// Returns the energy in the store at the end of the lap.
+    #[getset(get_copy = "pub")]
+    store_at_end: f32,
+
+    /// Returns the energy deployed during the lap.
+    #[getset(get_copy = "pub")]
+    deployed: f32,
+
+    /// Returns the energy harvested by the MGU-K during the lap.
+    #[getset(get_copy = "pub")]
+    harvested_mgu_k: f32,
+
+    /// Returns the energy harvested by the MGU-H during the lap.
+    #[getset(get_copy = "pub")]
+    harvested_mgu_h: f32,
+
+    /// Returns how often the deploy mode was changed during the lap.
+    #[getset(get_copy = "pub")]
+    deploy_mode_changes: u8,
+}
+
+impl ErsLapSummary {
+    /// Returns the total energy harvested during the lap.
+    pub fn harvested(&self) -> f32 {
+        self.harvested_mgu_k + self.harvested_mgu_h
+    }
+
+    /// Returns the difference between the harvested and the deployed energy.
+    pub fn net_energy(&self) -> f32 {
+        self.harvested() - self.deployed
+    }
+}
+
+/// Change of the ERS deploy mode of a car
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+pub struct ErsModeChange {
+    /// Returns the session time at which the deploy mode was changed.
+    #[getset(get = "pub")]
+    session_time: Duration,
+
+    /// Returns the lap on which the deploy mode was changed.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the previous deploy mode.
+    #[getset(get_copy = "pub")]
+    from: ErsDeployMode,
+
+    /// Returns the new deploy mode.
+    #[getset(get_copy = "pub")]
+    to: ErsDeployMode,
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarErs {
+    lap: Option<u8>,
+    store: Option<f32>,
+    deploy_mode: Option<ErsDeployMode>,
+    current: Option<ErsLapSummary>,
+    laps: Vec<ErsLapSummary>,
+    mode_changes: Vec<ErsModeChange>,
+}
+
+/// Tracker for the ERS usage of all cars in a session
+///
+/// The ERS tracker consumes lap data, car status, and participants packets. A lap is summarized
+/// once the car crosses the line, and only laps that were completely observed are reported. The ERS
+/// of cars with restricted telemetry is not published, and no laps are summarized for them.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ErsTracker {
+    cars: BTreeMap<VehicleIndex, CarErs>,
+    restrictions: TelemetryRestrictions,
+}
+
+impl ErsTracker {
+    /// Create a new ERS tracker without any data.
+    pub fn new() -> Self {
+        ErsTracker::default()
+    }
+
+    /// Update the ERS usage with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Lap(packet) => self.update_laps(packet),
+            Packet::Participants(packet) => {
+                // Laps that were summarized before the restriction became known are discarded.
+                for index in self.restrictions.update(packet) {
+                    self.cars.remove(&index);
+                }
+            }
+            Packet::Status(packet) => self.update_statuses(packet),
+            _ => {}
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(index).or_default();
+            let lap_number = lap.current_lap_number();
+
+            if car.lap.is_some_and(|previous| lap_number > previous) {
+                if let Some(current) = car.current.take() {
+                    car.laps.push(current);
+                }
+
+                // Laps that were only partially observed have no meaningful summary, so a lap is
+                // only summarized when the state of the store at its start is known.
+                car.current = car
+                    .store
+                    .map(|store| ErsLapSummary::new(lap_number, store, store, 0.0, 0.0, 0.0, 0));
+            }
+
+            car.lap = Some(lap_number);
+        }
+    }
+
+    fn update_statuses(&mut self, packet: &CarStatusPacket) {
+        let session_time = *packet.header().session_time();
+
+        for (index, status) in enumerate_cars(packet.statuses()) {
+            if self.restrictions.is_restricted(index) {
+                continue;
+            }
+
+            let car = self.cars.entry(index).or_default();
+            let deploy_mode = status.ers_deploy_mode();
+
+            let mode_changed = match (car.lap, car.deploy_mode) {
+                (Some(lap), Some(previous_mode)) if previous_mode != deploy_mode => {
+                    car.mode_changes.push(ErsModeChange::new(
+                        session_time,
+                        lap,
+                        previous_mode,
+                        deploy_mode,
+                    ));
+                    true
+                }
+                _ => false,
+            };
+
+            if let Some(current) = car.current.as_mut() {
+                if mode_changed {
+                    current.deploy_mode_changes = current.deploy_mode_changes.saturating_add(1);
+                }
+
+                current.store_at_end = status.ers_energy();
+                current.deployed = status.ers_deployed();
+                current.harvested_mgu_k = status.ers_harvest_mgu_k();
+                current.harvested_mgu_h = status.ers_harvest_mgu_h();
+            }
+
+            car.store = Some(status.ers_energy());
+            car.deploy_mode = Some(deploy_mode);
+        }
+    }
+
+    /// Returns the ERS summaries of the completed laps of a car.
+    pub fn laps(&self, vehicle_index: VehicleIndex) -> &[ErsLapSummary] {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.laps.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns the ERS summary of the lap a car is currently on.
+    ///
+    /// The summary of the current lap is only available when the lap has been observed since the
+    /// car crossed the line.
+    pub fn current_lap(&self, vehicle_index: VehicleIndex) -> Option<ErsLapSummary> {
+        self.cars.get(&vehicle_index).and_then(|car| car.current)
+    }
+
+    /// Returns the changes of the deploy mode of a car.
+    pub fn mode_changes(&self, vehicle_index: VehicleIndex) -> &[ErsModeChange] {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.mode_changes.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::ers::ErsTracker;
+    use crate::analysis::fixtures::{
+        lap_packet, privacy_packet, status_packet, vehicle, LapFixture, StatusFixture,
+    };
+    use crate::packet::participants::TelemetryPrivacy;
+    use crate::packet::status::ErsDeployMode;
+
+    fn lap(current_lap_number: u8) -> LapFixture {
+        LapFixture {
+            current_lap_number,
+            ..LapFixture::default()
+        }
+    }
+
+    fn status(ers_energy: f32, ers_deployed: f32, mode: ErsDeployMode) -> StatusFixture {
+        StatusFixture {
+            ers_energy,
+            ers_deployed,
+            ers_harvest_mgu_k: 100.0,
+            ers_harvest_mgu_h: 50.0,
+            ers_deploy_mode: mode,
+            ..StatusFixture::default()
+        }
+    }
+
+    #[test]
+    fn laps_skip_partial_first_lap() {
+        let mut tracker = ErsTracker::new();
+
+        tracker.update(&lap_packet(0.0, vec![lap(1)]));
+        tracker.update(&status_packet(
+            1.0,
+            vec![status(1000.0, 0.0, ErsDeployMode::Medium)],
+        ));
+
+        assert!(tracker.current_lap(vehicle(0)).is_none());
+
+        tracker.update(&lap_packet(2.0, vec![lap(2)]));
+
+        assert!(tracker.laps(vehicle(0)).is_empty());
+        assert_approx_eq!(
+            1000.0,
+            tracker.current_lap(vehicle(0)).unwrap().store_at_start()
+        );
+    }
+
+    #[test]
+    fn laps_with_mode_changes() {
+        let mut tracker = ErsTracker::new();
+
+        tracker.update(&lap_packet(0.0, vec![lap(1)]));
+        tracker.update(&status_packet(
+            1.0,
+            vec![status(1000.0, 0.0, ErsDeployMode::Medium)],
+        ));
+        tracker.update(&lap_packet(2.0, vec![lap(2)]));
+        tracker.update(&status_packet(
+            3.0,
+            vec![status(900.0, 200.0, ErsDeployMode::High)],
+        ));
+        tracker.update(&status_packet(
+            4.0,
+            vec![status(800.0, 350.0, ErsDeployMode::Medium)],
+        ));
+        tracker.update(&lap_packet(5.0, vec![lap(3)]));
+
+        let summary = tracker.laps(vehicle(0))[0];
+        assert_eq!(2, summary.lap());
+        assert_approx_eq!(1000.0, summary.store_at_start());
+        assert_approx_eq!(800.0, summary.store_at_end());
+        assert_approx_eq!(350.0, summary.deployed());
+        assert_approx_eq!(150.0, summary.harvested());
+        assert_approx_eq!(-200.0, summary.net_energy());
+        assert_eq!(2, summary.deploy_mode_changes());
+
+        let changes = tracker.mode_changes(vehicle(0));
+        assert_eq!(2, changes.len());
+        assert_eq!(ErsDeployMode::High, changes[0].to());
+    }
+
+    #[test]
+    fn skip_cars_with_restricted_telemetry() {
+        let mut tracker = ErsTracker::new();
+
+        // The game sends an empty store and no deployment for the restricted car.
+        let restricted = status(0.0, 0.0, ErsDeployMode::None);
+
+        for lap_number in 1..=3 {
+            tracker.update(&lap_packet(0.0, vec![lap(lap_number), lap(lap_number)]));
+            tracker.update(&status_packet(
+                1.0,
+                vec![status(1000.0, 200.0, ErsDeployMode::Medium), restricted],
+            ));
+
+            if lap_number == 2 {
+                tracker.update(&privacy_packet(
+                    1.0,
+                    vec![TelemetryPrivacy::Public, TelemetryPrivacy::Restricted],
+                ));
+            }
+        }
+        tracker.update(&lap_packet(2.0, vec![lap(4), lap(4)]));
+
+        assert_eq!(2, tracker.laps(vehicle(0)).len());
+        assert!(tracker.laps(vehicle(1)).is_empty());
+        assert!(tracker.current_lap(vehicle(1)).is_none());
+    }
+}
@@ -0,0 +1,262 @@
+//! Off-track excursions and kerb usage
+//!
+//! Leaving the track or riding the kerbs too hard can damage the car, cost time, or lead to a
+//! penalty. The telemetry of the F1 games includes the surface under each wheel of a car, which
+//! shows when a car leaves the track or hits a kerb. The excursion detector records these moments
+//! together with the lap and the lap distance at which they happened, so that incidents can be
+//! reviewed after a session.
+//!
+//! F1 2019 publishes the surface types in the telemetry packet, while newer games do so in the
+//! motion data. The detector only depends on the surface types, and not on the packet they were
+//! published in.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::LapPacket;
+use crate::packet::telemetry::{Surface, TelemetryPacket};
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, CornerProperty, VehicleIndex};
+
+/// Types of excursions
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+pub enum ExcursionKind {
+    /// The car left the track, for example onto grass or gravel.
+    OffTrack,
+
+    /// The car rode on a kerb.
+    #[default]
+    Kerb,
+}
+
+/// An excursion of a car from the track or onto a kerb
+#[derive(Debug, Getters, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct Excursion {
+    /// Returns the type of the excursion.
+    #[getset(get_copy = "pub")]
+    kind: ExcursionKind,
+
+    /// Returns the index of the car.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the lap on which the excursion started.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the lap distance at which the excursion started.
+    #[getset(get_copy = "pub")]
+    start_distance: f32,
+
+    /// Returns the lap distance at which the excursion ended.
+    #[getset(get_copy = "pub")]
+    end_distance: f32,
+
+    /// Returns the session time at which the excursion started.
+    #[getset(get = "pub")]
+    start_time: Duration,
+
+    /// Returns how long the excursion lasted.
+    #[getset(get = "pub")]
+    duration: Duration,
+
+    /// Returns the largest number of wheels that were off the track or on the kerb at once.
+    #[getset(get_copy = "pub")]
+    max_wheels: u8,
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarSurfaces {
+    lap: u8,
+    lap_distance: f32,
+    current: Option<Excursion>,
+    excursions: Vec<Excursion>,
+}
+
+/// Detector for the excursions of all cars in the session
+///
+/// The excursion detector consumes lap data and telemetry packets. A car is off the track when at
+/// least the configured number of wheels is on a surface next to the track, such as grass, gravel,
+/// or sand. Kerb usage is only recorded when enough wheels are on a kerb at the same time.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ExcursionDetector {
+    off_track_wheels: u8,
+    kerb_wheels: u8,
+    cars: BTreeMap<VehicleIndex, CarSurfaces>,
+}
+
+impl ExcursionDetector {
+    /// Create a new excursion detector.
+    ///
+    /// The wheel counts are the number of wheels that must be off the track or on a kerb for an
+    /// excursion to be recorded.
+    pub fn new(off_track_wheels: u8, kerb_wheels: u8) -> Self {
+        ExcursionDetector {
+            off_track_wheels,
+            kerb_wheels,
+            cars: BTreeMap::new(),
+        }
+    }
+
+    /// Update the excursions with the data from the packet.
+    ///
+    /// Excursions are returned once the car is back on the track.
+    pub fn update(&mut self, packet: &Packet) -> Vec<Excursion> {
+        match packet {
+            Packet::Lap(packet) => {
+                self.update_laps(packet);
+                Vec::new()
+            }
+            Packet::Telemetry(packet) => self.update_telemetry(packet),
+            _ => Vec::new(),
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(index).or_default();
+
+            car.lap = lap.current_lap_number();
+            car.lap_distance = lap.lap_distance();
+        }
+    }
+
+    fn update_telemetry(&mut self, packet: &TelemetryPacket) -> Vec<Excursion> {
+        let session_time = *packet.header().session_time();
+        let mut finished = Vec::new();
+
+        for (vehicle_index, telemetry) in enumerate_cars(packet.telemetry()) {
+            let surfaces = telemetry.surface_type();
+
+            let off_track = count(*surfaces, |surface| surface.is_off_track());
+            let on_kerb = count(*surfaces, |surface| surface.is_kerb());
+
+            let state = if self.off_track_wheels > 0 && off_track >= self.off_track_wheels {
+                Some((ExcursionKind::OffTrack, off_track))
+            } else if self.kerb_wheels > 0 && on_kerb >= self.kerb_wheels {
+                Some((ExcursionKind::Kerb, on_kerb))
+            } else {
+                None
+            };
+
+            let car = self.cars.entry(vehicle_index).or_default();
+
+            if let Some(current) = car.current.as_mut() {
+                current.end_distance = car.lap_distance;
+                current.duration = session_time.saturating_sub(current.start_time);
+
+                match state {
+                    Some((kind, wheels)) if kind == current.kind => {
+                        current.max_wheels = current.max_wheels.max(wheels);
+                        continue;
+                    }
+                    _ => {
+                        if let Some(excursion) = car.current.take() {
+                            car.excursions.push(excursion);
+                            finished.push(excursion);
+                        }
+                    }
+                }
+            }
+
+            if let Some((kind, wheels)) = state {
+                car.current = Some(Excursion {
+                    kind,
+                    vehicle_index,
+                    lap: car.lap,
+                    start_distance: car.lap_distance,
+                    end_distance: car.lap_distance,
+                    start_time: session_time,
+                    duration: Duration::default(),
+                    max_wheels: wheels,
+                });
+            }
+        }
+
+        finished
+    }
+
+    /// Returns the completed excursions of a car.
+    pub fn excursions(&self, vehicle_index: VehicleIndex) -> &[Excursion] {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.excursions.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns the excursion a car is currently on, if any.
+    pub fn current(&self, vehicle_index: VehicleIndex) -> Option<&Excursion> {
+        self.cars.get(&vehicle_index)?.current.as_ref()
+    }
+}
+
+/// Returns the number of wheels on a surface that matches the predicate.
+fn count<F>(surfaces: CornerProperty<Surface>, predicate: F) -> u8
+where
+    F: Fn(Surface) -> bool,
+{
+    surfaces
+        .iter()
+        .filter(|surface| predicate(*surface))
+        .count() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::excursion::{ExcursionDetector, ExcursionKind};
+    use crate::analysis::fixtures::{
+        lap_packet, telemetry_packet, vehicle, LapFixture, TelemetryFixture,
+    };
+    use crate::packet::telemetry::Surface;
+    use crate::types::CornerProperty;
+
+    fn surfaces(left: Surface, right: Surface) -> TelemetryFixture {
+        TelemetryFixture {
+            surface_type: CornerProperty::new(left, right, left, right),
+            ..TelemetryFixture::default()
+        }
+    }
+
+    fn lap(lap_distance: f32) -> LapFixture {
+        LapFixture {
+            current_lap_number: 4,
+            lap_distance,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_excursions() {
+        let mut detector = ExcursionDetector::new(4, 2);
+        let packets = [
+            (0.0, Surface::Tarmac, Surface::Tarmac),
+            (1.0, Surface::RumbleStrip, Surface::Tarmac),
+            (2.0, Surface::Grass, Surface::Grass),
+            (3.0, Surface::Gravel, Surface::Grass),
+            (4.0, Surface::Tarmac, Surface::Tarmac),
+        ];
+
+        let mut events = Vec::new();
+        for (time, left, right) in packets.iter() {
+            detector.update(&lap_packet(*time, vec![lap(*time * 100.0)]));
+            events.extend(detector.update(&telemetry_packet(*time, vec![surfaces(*left, *right)])));
+        }
+
+        assert_eq!(2, events.len());
+        assert_eq!(ExcursionKind::Kerb, events[0].kind());
+        assert_eq!(2, events[0].max_wheels());
+
+        let off_track = events[1];
+        assert_eq!(ExcursionKind::OffTrack, off_track.kind());
+        assert_eq!(4, off_track.lap());
+        assert_eq!(200.0, off_track.start_distance());
+        assert_eq!(400.0, off_track.end_distance());
+        assert_eq!(Duration::from_secs(2), *off_track.duration());
+        assert_eq!(2, detector.excursions(vehicle(0)).len());
+    }
+}
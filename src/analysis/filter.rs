@@ -0,0 +1,190 @@
+//! Smoothing filters for noisy signals
+//!
+//! Some telemetry channels are too noisy to be used directly, for example the wheel slip or the
+//! suspension position, which change rapidly from packet to packet. The signal filter smooths the
+//! values of any channel with a moving average, an exponential moving average, or a simple Kalman
+//! filter, before they are used to derive other metrics.
+
+use std::collections::VecDeque;
+
+/// Kind of a smoothing filter
+#[derive(Debug, PartialEq, Copy, Clone, PartialOrd)]
+pub enum FilterKind {
+    /// Mean of the given number of latest values.
+    MovingAverage(usize),
+
+    /// Exponential moving average with the given smoothing factor between 0 and 1. Higher factors
+    /// follow the signal more closely.
+    Exponential(f32),
+
+    /// One-dimensional Kalman filter for a signal that is expected to be constant, with the given
+    /// variances of the process noise and of the measurement noise.
+    Kalman {
+        process_noise: f32,
+        measurement_noise: f32,
+    },
+}
+
+impl Default for FilterKind {
+    fn default() -> Self {
+        FilterKind::Exponential(0.5)
+    }
+}
+
+/// Internal state of a filter
+#[derive(Debug, Clone, PartialEq)]
+enum FilterState {
+    MovingAverage(VecDeque<f32>),
+    Exponential(Option<f32>),
+    Kalman(Option<(f32, f32)>),
+}
+
+impl FilterState {
+    fn new(kind: FilterKind) -> Self {
+        match kind {
+            FilterKind::MovingAverage(_) => FilterState::MovingAverage(VecDeque::new()),
+            FilterKind::Exponential(_) => FilterState::Exponential(None),
+            FilterKind::Kalman { .. } => FilterState::Kalman(None),
+        }
+    }
+}
+
+/// Smoothing filter for a stream of values
+///
+/// Each filter keeps the state of a single channel. To smooth several channels, or the same channel
+/// of several cars, use one filter for each of them.
+///
+/// # Examples
+///
+/// ```
+/// use f1_api::analysis::filter::{FilterKind, SignalFilter};
+///
+/// let mut filter = SignalFilter::new(FilterKind::MovingAverage(2));
+///
+/// assert_eq!(1.0, filter.apply(1.0));
+/// assert_eq!(2.0, filter.apply(3.0));
+/// assert_eq!(4.0, filter.apply(5.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalFilter {
+    kind: FilterKind,
+    state: FilterState,
+}
+
+impl SignalFilter {
+    /// Create a new filter of the given kind.
+    pub fn new(kind: FilterKind) -> Self {
+        SignalFilter {
+            kind,
+            state: FilterState::new(kind),
+        }
+    }
+
+    /// Returns the kind of the filter.
+    pub fn kind(&self) -> FilterKind {
+        self.kind
+    }
+
+    /// Add a raw value to the filter, and return the smoothed value.
+    pub fn apply(&mut self, value: f32) -> f32 {
+        match (&mut self.state, self.kind) {
+            (FilterState::MovingAverage(values), FilterKind::MovingAverage(length)) => {
+                values.push_back(value);
+
+                while values.len() > length.max(1) {
+                    values.pop_front();
+                }
+
+                values.iter().sum::<f32>() / values.len() as f32
+            }
+            (FilterState::Exponential(smoothed), FilterKind::Exponential(factor)) => {
+                let factor = factor.clamp(0.0, 1.0);
+                let next =
+                    smoothed.map_or(value, |previous| previous + factor * (value - previous));
+
+                *smoothed = Some(next);
+                next
+            }
+            (
+                FilterState::Kalman(estimate),
+                FilterKind::Kalman {
+                    process_noise,
+                    measurement_noise,
+                },
+            ) => {
+                let (next, variance) = match *estimate {
+                    Some((previous, variance)) => {
+                        let variance = variance + process_noise;
+                        let gain = variance / (variance + measurement_noise);
+
+                        (
+                            previous + gain * (value - previous),
+                            (1.0 - gain) * variance,
+                        )
+                    }
+                    None => (value, measurement_noise),
+                };
+
+                *estimate = Some((next, variance));
+                next
+            }
+            _ => unreachable!("the state of a filter always matches its kind"),
+        }
+    }
+
+    /// Returns the latest smoothed value, if the filter has received any values.
+    pub fn value(&self) -> Option<f32> {
+        match &self.state {
+            FilterState::MovingAverage(values) if values.is_empty() => None,
+            FilterState::MovingAverage(values) => {
+                Some(values.iter().sum::<f32>() / values.len() as f32)
+            }
+            FilterState::Exponential(smoothed) => *smoothed,
+            FilterState::Kalman(estimate) => estimate.map(|(value, _)| value),
+        }
+    }
+
+    /// Reset the filter, and drop all values it has received.
+    pub fn reset(&mut self) {
+        self.state = FilterState::new(self.kind);
+    }
+}
+
+impl Default for SignalFilter {
+    fn default() -> Self {
+        SignalFilter::new(FilterKind::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::filter::{FilterKind, SignalFilter};
+
+    #[test]
+    fn apply_exponential() {
+        let mut filter = SignalFilter::new(FilterKind::Exponential(0.25));
+
+        assert_approx_eq!(8.0, filter.apply(8.0));
+        assert_approx_eq!(7.0, filter.apply(4.0));
+        assert_approx_eq!(7.0, filter.value().unwrap());
+
+        filter.reset();
+        assert_eq!(None, filter.value());
+    }
+
+    #[test]
+    fn apply_kalman() {
+        let mut filter = SignalFilter::new(FilterKind::Kalman {
+            process_noise: 0.0,
+            measurement_noise: 1.0,
+        });
+
+        for value in [10.0, 12.0, 8.0, 10.0].iter() {
+            filter.apply(*value);
+        }
+
+        assert_approx_eq!(10.0, filter.value().unwrap());
+    }
+}
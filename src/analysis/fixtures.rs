@@ -0,0 +1,480 @@
+//! Fixtures to build packets in the tests of the analysis modules
+//!
+//! The constructors of the packets take a long list of positional arguments, which makes tests hard
+//! to read. The fixtures in this module default every field to a sensible value, so that tests only
+//! have to specify the fields they actually care about.
+
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use bytes::{BufMut, BytesMut};
+use tokio::net::UdpSocket;
+use tokio_stream::Stream;
+
+use crate::codec::DecoderBuilder;
+use crate::error::F1Error;
+use crate::listener::Listener;
+use crate::nineteen::lap;
+use crate::packet::event::{Event, EventPacket};
+use crate::packet::header::{ApiSpec, Header, PacketType};
+use crate::packet::lap::{DriverStatus, Lap, LapPacket, PitStatus, ResultStatus, Sector};
+use crate::packet::motion::{Motion, MotionPacket};
+use crate::packet::participants::{
+    Controller, Driver, Nationality, Participant, ParticipantsPacket, Team, TelemetryPrivacy,
+};
+use crate::packet::session::{
+    Formula, MarshalZone, SafetyCar, Session, SessionPacket, Track, Weather,
+};
+use crate::packet::status::{
+    CarStatus, CarStatusPacket, DrsSetting, ErsDeployMode, FuelMix, PhysicalTyreCompound,
+    TractionControl, VisualTyreCompound,
+};
+use crate::packet::telemetry::{ButtonFlags, Gear, Surface, Telemetry, TelemetryPacket};
+use crate::packet::Packet;
+use crate::pool::BufferPool;
+use crate::types::{CornerProperty, Flag, Property3D, Speed, Temperature, VehicleIndex};
+
+/// Create the vehicle index of a car on the grid.
+pub fn vehicle(index: u8) -> VehicleIndex {
+    VehicleIndex::try_from(index).unwrap()
+}
+
+/// Create a packet header for the given packet type and session time in seconds.
+pub fn header(packet_type: PacketType, session_time: f32) -> Header {
+    framed_header(packet_type, session_time, 0)
+}
+
+/// Create a packet header for the given packet type, session time in seconds, and frame.
+pub fn framed_header(packet_type: PacketType, session_time: f32, frame_identifier: u32) -> Header {
+    Header::new(
+        ApiSpec::Nineteen,
+        None,
+        packet_type,
+        0,
+        Duration::from_secs_f32(session_time),
+        frame_identifier,
+        VehicleIndex::default(),
+        None,
+    )
+}
+
+/// Lap data with public fields and sensible defaults
+#[derive(Debug, Copy, Clone)]
+pub struct LapFixture {
+    pub last_lap_time: f32,
+    pub current_lap_time: f32,
+    pub best_lap_time: f32,
+    pub sector1_time: f32,
+    pub sector2_time: f32,
+    pub lap_distance: f32,
+    pub total_distance: f32,
+    pub position: u8,
+    pub current_lap_number: u8,
+    pub pit_status: PitStatus,
+    pub sector: Sector,
+    pub is_valid_lap: bool,
+    pub penalties: u8,
+    pub grid_position: u8,
+    pub driver_status: DriverStatus,
+    pub result_status: ResultStatus,
+}
+
+impl Default for LapFixture {
+    fn default() -> Self {
+        LapFixture {
+            last_lap_time: 0.0,
+            current_lap_time: 0.0,
+            best_lap_time: 0.0,
+            sector1_time: 0.0,
+            sector2_time: 0.0,
+            lap_distance: 0.0,
+            total_distance: 0.0,
+            position: 1,
+            current_lap_number: 1,
+            pit_status: PitStatus::None,
+            sector: Sector::First,
+            is_valid_lap: true,
+            penalties: 0,
+            grid_position: 1,
+            driver_status: DriverStatus::OnTrack,
+            result_status: ResultStatus::Active,
+        }
+    }
+}
+
+impl From<LapFixture> for Lap {
+    fn from(fixture: LapFixture) -> Self {
+        Lap::new(
+            Duration::from_secs_f32(fixture.last_lap_time),
+            Duration::from_secs_f32(fixture.current_lap_time),
+            Duration::from_secs_f32(fixture.best_lap_time),
+            Duration::from_secs_f32(fixture.sector1_time),
+            Duration::from_secs_f32(fixture.sector2_time),
+            fixture.lap_distance,
+            fixture.total_distance,
+            Duration::default(),
+            fixture.position,
+            fixture.current_lap_number,
+            fixture.pit_status,
+            fixture.sector,
+            fixture.is_valid_lap,
+            fixture.penalties,
+            fixture.grid_position,
+            fixture.driver_status,
+            fixture.result_status,
+        )
+    }
+}
+
+/// Create a lap packet with the given laps at the given session time in seconds.
+pub fn lap_packet(session_time: f32, laps: Vec<LapFixture>) -> Packet {
+    Packet::Lap(LapPacket::new(
+        header(PacketType::Lap, session_time),
+        laps.into_iter().map(Lap::from).collect(),
+    ))
+}
+
+/// Car status with public fields and sensible defaults
+#[derive(Debug, Copy, Clone)]
+pub struct StatusFixture {
+    pub fuel_remaining: f32,
+    pub fuel_capacity: f32,
+    pub fuel_remaining_laps: f32,
+    pub drs: DrsSetting,
+    pub tyre_wear: CornerProperty<u8>,
+    pub physical_tyre_compound: PhysicalTyreCompound,
+    pub visual_tyre_compound: VisualTyreCompound,
+    pub tyre_damage: CornerProperty<u8>,
+    pub front_left_wing_damage: u8,
+    pub front_right_wing_damage: u8,
+    pub rear_wing_damage: u8,
+    pub engine_damage: u8,
+    pub gear_box_damage: u8,
+    pub vehicle_flags: Flag,
+    pub ers_energy: f32,
+    pub ers_deploy_mode: ErsDeployMode,
+    pub ers_harvest_mgu_k: f32,
+    pub ers_harvest_mgu_h: f32,
+    pub ers_deployed: f32,
+}
+
+impl Default for StatusFixture {
+    fn default() -> Self {
+        StatusFixture {
+            fuel_remaining: 100.0,
+            fuel_capacity: 110.0,
+            fuel_remaining_laps: 0.0,
+            drs: DrsSetting::NotAllowed,
+            tyre_wear: CornerProperty::default(),
+            physical_tyre_compound: PhysicalTyreCompound::F1C3,
+            visual_tyre_compound: VisualTyreCompound::F1Medium,
+            tyre_damage: CornerProperty::default(),
+            front_left_wing_damage: 0,
+            front_right_wing_damage: 0,
+            rear_wing_damage: 0,
+            engine_damage: 0,
+            gear_box_damage: 0,
+            vehicle_flags: Flag::None,
+            ers_energy: 4_000_000.0,
+            ers_deploy_mode: ErsDeployMode::Medium,
+            ers_harvest_mgu_k: 0.0,
+            ers_harvest_mgu_h: 0.0,
+            ers_deployed: 0.0,
+        }
+    }
+}
+
+impl From<StatusFixture> for CarStatus {
+    fn from(fixture: StatusFixture) -> Self {
+        CarStatus::new(
+            TractionControl::Off,
+            false,
+            FuelMix::Standard,
+            56,
+            false,
+            fixture.fuel_remaining,
+            fixture.fuel_capacity,
+            fixture.fuel_remaining_laps,
+            12000,
+            4000,
+            8,
+            fixture.drs,
+            fixture.tyre_wear,
+            fixture.physical_tyre_compound,
+            fixture.visual_tyre_compound,
+            fixture.tyre_damage,
+            fixture.front_left_wing_damage,
+            fixture.front_right_wing_damage,
+            fixture.rear_wing_damage,
+            fixture.engine_damage,
+            fixture.gear_box_damage,
+            fixture.vehicle_flags,
+            fixture.ers_energy,
+            fixture.ers_deploy_mode,
+            fixture.ers_harvest_mgu_k,
+            fixture.ers_harvest_mgu_h,
+            fixture.ers_deployed,
+        )
+    }
+}
+
+/// Create a car status packet with the given statuses at the given session time in seconds.
+pub fn status_packet(session_time: f32, statuses: Vec<StatusFixture>) -> Packet {
+    Packet::Status(CarStatusPacket::new(
+        header(PacketType::Status, session_time),
+        statuses.into_iter().map(CarStatus::from).collect(),
+    ))
+}
+
+/// Session data with public fields and sensible defaults
+#[derive(Debug, Clone)]
+pub struct SessionFixture {
+    pub weather: Weather,
+    pub track_temperature: i8,
+    pub air_temperature: i8,
+    pub total_laps: u8,
+    pub track_length: u16,
+    pub session_type: Session,
+    pub track: Track,
+    pub time_left: u64,
+    pub duration: u64,
+    pub is_spectating: bool,
+    pub spectator_car_index: Option<VehicleIndex>,
+    pub marshal_zones: Vec<MarshalZone>,
+    pub safety_car: SafetyCar,
+    pub network_session: bool,
+}
+
+impl Default for SessionFixture {
+    fn default() -> Self {
+        SessionFixture {
+            weather: Weather::Clear,
+            track_temperature: 30,
+            air_temperature: 20,
+            total_laps: 50,
+            track_length: 5000,
+            session_type: Session::Race,
+            track: Track::Silverstone,
+            time_left: 7200,
+            duration: 7200,
+            is_spectating: false,
+            spectator_car_index: None,
+            marshal_zones: vec![],
+            safety_car: SafetyCar::None,
+            network_session: false,
+        }
+    }
+}
+
+/// Create a session packet from the fixture at the given session time in seconds.
+pub fn session_packet(session_time: f32, fixture: SessionFixture) -> Packet {
+    Packet::Session(SessionPacket::new(
+        header(PacketType::Session, session_time),
+        fixture.weather,
+        Temperature::from(fixture.track_temperature),
+        Temperature::from(fixture.air_temperature),
+        fixture.total_laps,
+        fixture.track_length,
+        fixture.session_type,
+        fixture.track,
+        Formula::ModernF1,
+        Duration::from_secs(fixture.time_left),
+        Duration::from_secs(fixture.duration),
+        Speed::from_kph(80),
+        false,
+        fixture.is_spectating,
+        fixture.spectator_car_index,
+        false,
+        fixture.marshal_zones,
+        fixture.safety_car,
+        fixture.network_session,
+    ))
+}
+
+/// Create a motion packet with cars at the given positions at the given session time in seconds.
+pub fn motion_packet(session_time: f32, positions: Vec<Property3D<f32>>) -> Packet {
+    let motion = positions
+        .into_iter()
+        .map(|position| (position, Property3D::default()))
+        .collect();
+
+    moving_motion_packet(session_time, motion)
+}
+
+/// Create a motion packet with cars at the given positions and velocities at the given session
+/// time in seconds.
+pub fn moving_motion_packet(
+    session_time: f32,
+    motion: Vec<(Property3D<f32>, Property3D<f32>)>,
+) -> Packet {
+    Packet::Motion(MotionPacket::new(
+        header(PacketType::Motion, session_time),
+        motion
+            .into_iter()
+            .map(|(position, velocity)| {
+                Motion::new(
+                    position,
+                    velocity,
+                    Property3D::default(),
+                    Property3D::default(),
+                    Property3D::default(),
+                    0.0,
+                    0.0,
+                    0.0,
+                )
+            })
+            .collect(),
+        CornerProperty::default(),
+        CornerProperty::default(),
+        CornerProperty::default(),
+        CornerProperty::default(),
+        CornerProperty::default(),
+        Property3D::default(),
+        Property3D::default(),
+        Property3D::default(),
+        0.0,
+    ))
+}
+
+/// Telemetry with public fields and sensible defaults
+#[derive(Debug, Copy, Clone)]
+pub struct TelemetryFixture {
+    pub speed: u16,
+    pub throttle: f32,
+    pub steering: f32,
+    pub brake: f32,
+    pub gear: Gear,
+    pub engine_rpm: u16,
+    pub drs: bool,
+    pub surface_type: CornerProperty<Surface>,
+}
+
+impl Default for TelemetryFixture {
+    fn default() -> Self {
+        TelemetryFixture {
+            speed: 200,
+            throttle: 1.0,
+            steering: 0.0,
+            brake: 0.0,
+            gear: Gear::Sixth,
+            engine_rpm: 11000,
+            drs: false,
+            surface_type: CornerProperty::default(),
+        }
+    }
+}
+
+impl From<TelemetryFixture> for Telemetry {
+    fn from(fixture: TelemetryFixture) -> Self {
+        Telemetry::new(
+            Speed::from_kph(fixture.speed),
+            fixture.throttle,
+            fixture.steering,
+            fixture.brake,
+            0,
+            fixture.gear,
+            fixture.engine_rpm,
+            fixture.drs,
+            0,
+            CornerProperty::default(),
+            CornerProperty::default(),
+            CornerProperty::default(),
+            Temperature::from_celsius(90),
+            CornerProperty::default(),
+            fixture.surface_type,
+        )
+    }
+}
+
+/// Create a telemetry packet with the given telemetry at the given session time in seconds.
+pub fn telemetry_packet(session_time: f32, telemetry: Vec<TelemetryFixture>) -> Packet {
+    Packet::Telemetry(TelemetryPacket::new(
+        header(PacketType::Telemetry, session_time),
+        telemetry.into_iter().map(Telemetry::from).collect(),
+        ButtonFlags::NONE,
+    ))
+}
+
+/// Create a participants packet with participants of the given names at the given session time in
+/// seconds.
+pub fn participants_packet(session_time: f32, names: Vec<&str>) -> Packet {
+    Packet::Participants(ParticipantsPacket::new(
+        header(PacketType::Participants, session_time),
+        names.len() as u8,
+        names
+            .into_iter()
+            .map(|name| {
+                Participant::new(
+                    Controller::AI,
+                    Driver::default(),
+                    Team::default(),
+                    0,
+                    Nationality::default(),
+                    name.to_string(),
+                    None,
+                )
+            })
+            .collect(),
+    ))
+}
+
+/// Create a participants packet with a participant for each of the given telemetry settings at the
+/// given session time in seconds.
+pub fn privacy_packet(session_time: f32, settings: Vec<TelemetryPrivacy>) -> Packet {
+    Packet::Participants(ParticipantsPacket::new(
+        header(PacketType::Participants, session_time),
+        settings.len() as u8,
+        settings
+            .into_iter()
+            .map(|setting| Participant::default().with_telemetry_privacy(Some(setting)))
+            .collect(),
+    ))
+}
+
+/// Create an event packet with the given event at the given session time in seconds.
+pub fn event_packet(session_time: f32, event: Event) -> Packet {
+    Packet::Event(EventPacket::new(
+        header(PacketType::Event, session_time),
+        event,
+    ))
+}
+
+/// Create the datagram of a lap packet at the given session time in seconds, in which each car is
+/// in the position after its index.
+pub fn lap_datagram(session_time: f32) -> BytesMut {
+    let mut bytes = BytesMut::with_capacity(lap::PACKET_SIZE);
+
+    bytes.put_u16_le(2019);
+    bytes.put_u8(1);
+    bytes.put_u8(2);
+    bytes.put_u8(1);
+    bytes.put_u8(2);
+    bytes.put_u64_le(0);
+    bytes.put_f32_le(session_time);
+    bytes.put_u32_le(0);
+    bytes.put_u8(0);
+
+    for position in 1..=20 {
+        bytes.put(&[0u8; 32][..]);
+        bytes.put_u8(position);
+        bytes.put(&[0u8; 8][..]);
+    }
+
+    bytes
+}
+
+/// Create a datagram that is not a packet of any supported API specification.
+pub fn invalid_datagram() -> BytesMut {
+    BytesMut::from(&[0u8; 32][..])
+}
+
+/// Create the stream that `F1::stream` returns for a local socket, and a socket that sends to it.
+pub async fn udp_stream() -> (UdpSocket, impl Stream<Item = Result<Packet, F1Error>>) {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let sender = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    sender.connect(socket.local_addr().unwrap()).await.unwrap();
+
+    let stream = Listener::new(socket, DecoderBuilder::new().build(), BufferPool::default());
+
+    (sender, stream)
+}
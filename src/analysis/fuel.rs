@@ -0,0 +1,294 @@
+//! Fuel usage and the fuel delta to the end of the race
+//!
+//! Cars start a race with a limited amount of fuel, and drivers often have to save fuel to make it
+//! to the end. The fuel tracker records the fuel load of every car at the start of each lap,
+//! computes the average fuel burn per lap, and combines it with the number of laps left in the race
+//! to calculate the fuel delta to the finish.
+
+use std::collections::BTreeMap;
+
+use derive_new::new;
+use getset::CopyGetters;
+
+use crate::analysis::privacy::TelemetryRestrictions;
+use crate::packet::lap::LapPacket;
+use crate::packet::status::CarStatusPacket;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Live fuel metric of a car
+///
+/// The fuel metric combines the fuel that is left in the tank with the average fuel burn of the car
+/// to predict how many more laps it can drive. When the length of the race is known, the fuel delta
+/// shows if the car has more (positive) or less (negative) fuel than it needs to finish the race.
+#[derive(new, Debug, CopyGetters, PartialEq, Copy, Clone, PartialOrd)]
+pub struct FuelMetric {
+    /// Returns the fuel that is left in the tank in kilograms.
+    #[getset(get_copy = "pub")]
+    fuel_remaining: f32,
+
+    /// Returns the average fuel burn per lap in kilograms.
+    #[getset(get_copy = "pub")]
+    average_burn: f32,
+
+    /// Returns the number of laps the remaining fuel lasts at the average fuel burn.
+    #[getset(get_copy = "pub")]
+    laps_of_fuel: f32,
+
+    /// Returns the number of laps left in the race, if the length of the race is known.
+    #[getset(get_copy = "pub")]
+    laps_to_go: Option<f32>,
+
+    /// Returns the fuel delta to the finish in laps, if the length of the race is known.
+    #[getset(get_copy = "pub")]
+    fuel_delta: Option<f32>,
+
+    /// Returns the remaining fuel in laps as reported by the game.
+    #[getset(get_copy = "pub")]
+    game_fuel_remaining_laps: f32,
+}
+
+impl FuelMetric {
+    /// Returns the fuel delta to the finish in kilograms, if the length of the race is known.
+    pub fn fuel_delta_mass(&self) -> Option<f32> {
+        self.fuel_delta.map(|delta| delta * self.average_burn)
+    }
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarFuel {
+    fuel_remaining: Option<f32>,
+    game_fuel_remaining_laps: f32,
+    lap: Option<u8>,
+    lap_distance: f32,
+    lap_start_fuel: Vec<f32>,
+}
+
+impl CarFuel {
+    fn average_burn(&self) -> Option<f32> {
+        let burns: Vec<f32> = self
+            .lap_start_fuel
+            .windows(2)
+            .map(|window| window[0] - window[1])
+            .filter(|burn| *burn > 0.0)
+            .collect();
+
+        if burns.is_empty() {
+            None
+        } else {
+            Some(burns.iter().sum::<f32>() / burns.len() as f32)
+        }
+    }
+}
+
+/// Tracker for the fuel usage of all cars in the session
+///
+/// The fuel tracker consumes session, lap data, car status, and participants packets. The fuel burn
+/// of a car is known after it has completed its first full lap while being tracked. The fuel of cars
+/// with restricted telemetry is not published, and no metric is calculated for them.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FuelTracker {
+    cars: BTreeMap<VehicleIndex, CarFuel>,
+    restrictions: TelemetryRestrictions,
+    total_laps: Option<u8>,
+    track_length: Option<u16>,
+}
+
+impl FuelTracker {
+    /// Create a new fuel tracker without any data.
+    pub fn new() -> Self {
+        FuelTracker::default()
+    }
+
+    /// Update the fuel usage with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Session(packet) => {
+                self.total_laps = Some(packet.total_laps());
+                self.track_length = Some(packet.track_length());
+            }
+            Packet::Lap(packet) => self.update_laps(packet),
+            Packet::Participants(packet) => {
+                // Fuel that was recorded before the restriction became known is discarded as well.
+                for index in self.restrictions.update(packet) {
+                    self.cars.remove(&index);
+                }
+            }
+            Packet::Status(packet) => self.update_statuses(packet),
+            _ => {}
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(index).or_default();
+            let lap_number = lap.current_lap_number();
+
+            if car.lap.is_some_and(|previous| lap_number > previous) {
+                if let Some(fuel) = car.fuel_remaining {
+                    car.lap_start_fuel.push(fuel);
+                }
+            }
+
+            car.lap = Some(lap_number);
+            car.lap_distance = lap.lap_distance().max(0.0);
+        }
+    }
+
+    fn update_statuses(&mut self, packet: &CarStatusPacket) {
+        for (index, status) in enumerate_cars(packet.statuses()) {
+            if self.restrictions.is_restricted(index) {
+                continue;
+            }
+
+            let car = self.cars.entry(index).or_default();
+
+            car.fuel_remaining = Some(status.fuel_remaining());
+            car.game_fuel_remaining_laps = status.fuel_remaining_laps();
+        }
+    }
+
+    /// Returns the live fuel metric of a car, if its fuel burn is known and its telemetry is not
+    /// restricted.
+    pub fn metric(&self, vehicle_index: VehicleIndex) -> Option<FuelMetric> {
+        let car = self.cars.get(&vehicle_index)?;
+        let fuel_remaining = car.fuel_remaining?;
+        let average_burn = car.average_burn()?;
+
+        let laps_of_fuel = fuel_remaining / average_burn;
+        let laps_to_go = self.laps_to_go(car);
+
+        Some(FuelMetric::new(
+            fuel_remaining,
+            average_burn,
+            laps_of_fuel,
+            laps_to_go,
+            laps_to_go.map(|laps_to_go| laps_of_fuel - laps_to_go),
+            car.game_fuel_remaining_laps,
+        ))
+    }
+
+    fn laps_to_go(&self, car: &CarFuel) -> Option<f32> {
+        let total_laps = self.total_laps.filter(|laps| *laps > 0)?;
+        let lap = car.lap?;
+
+        let lap_fraction = match self.track_length {
+            Some(length) if length > 0 => (car.lap_distance / length as f32).min(1.0),
+            _ => 0.0,
+        };
+
+        let completed = lap.saturating_sub(1) as f32 + lap_fraction;
+        Some((total_laps as f32 - completed).max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::fixtures::{
+        lap_packet, privacy_packet, session_packet, status_packet, vehicle, LapFixture,
+        SessionFixture, StatusFixture,
+    };
+    use crate::analysis::fuel::FuelTracker;
+    use crate::packet::participants::TelemetryPrivacy;
+
+    fn lap(current_lap_number: u8) -> LapFixture {
+        LapFixture {
+            current_lap_number,
+            ..LapFixture::default()
+        }
+    }
+
+    fn status(fuel_remaining: f32) -> StatusFixture {
+        StatusFixture {
+            fuel_remaining,
+            ..StatusFixture::default()
+        }
+    }
+
+    #[test]
+    fn metric_without_completed_lap() {
+        let mut tracker = FuelTracker::new();
+
+        tracker.update(&status_packet(0.0, vec![status(100.0)]));
+        tracker.update(&lap_packet(0.0, vec![lap(1)]));
+
+        assert!(tracker.metric(vehicle(0)).is_none());
+    }
+
+    #[test]
+    fn metric_with_average_burn() {
+        let mut tracker = FuelTracker::new();
+
+        tracker.update(&lap_packet(0.0, vec![lap(1)]));
+        tracker.update(&status_packet(0.0, vec![status(100.0)]));
+        tracker.update(&lap_packet(0.0, vec![lap(2)]));
+        tracker.update(&status_packet(0.0, vec![status(98.0)]));
+        tracker.update(&lap_packet(0.0, vec![lap(3)]));
+        tracker.update(&status_packet(0.0, vec![status(95.0)]));
+        tracker.update(&lap_packet(0.0, vec![lap(4)]));
+
+        let metric = tracker.metric(vehicle(0)).unwrap();
+
+        assert_approx_eq!(95.0, metric.fuel_remaining());
+        assert_approx_eq!(2.5, metric.average_burn());
+        assert_approx_eq!(38.0, metric.laps_of_fuel());
+        assert!(metric.fuel_delta().is_none());
+    }
+
+    #[test]
+    fn metric_with_fuel_delta() {
+        let mut tracker = FuelTracker::new();
+
+        tracker.update(&session_packet(
+            0.0,
+            SessionFixture {
+                total_laps: 10,
+                track_length: 5000,
+                ..SessionFixture::default()
+            },
+        ));
+        tracker.update(&lap_packet(0.0, vec![lap(1)]));
+        tracker.update(&status_packet(0.0, vec![status(20.0)]));
+        tracker.update(&lap_packet(0.0, vec![lap(2)]));
+        tracker.update(&status_packet(0.0, vec![status(18.0)]));
+        tracker.update(&lap_packet(
+            0.0,
+            vec![LapFixture {
+                current_lap_number: 3,
+                lap_distance: 2500.0,
+                ..LapFixture::default()
+            }],
+        ));
+
+        let metric = tracker.metric(vehicle(0)).unwrap();
+
+        assert_approx_eq!(7.5, metric.laps_to_go().unwrap());
+        assert_approx_eq!(1.5, metric.fuel_delta().unwrap());
+        assert_approx_eq!(3.0, metric.fuel_delta_mass().unwrap());
+    }
+
+    #[test]
+    fn skip_cars_with_restricted_telemetry() {
+        let mut tracker = FuelTracker::new();
+
+        tracker.update(&privacy_packet(
+            0.0,
+            vec![TelemetryPrivacy::Public, TelemetryPrivacy::Restricted],
+        ));
+
+        for lap_number in 1..=3 {
+            let fuel = 100.0 - f32::from(lap_number) * 2.0;
+
+            tracker.update(&lap_packet(0.0, vec![lap(lap_number), lap(lap_number)]));
+            tracker.update(&status_packet(0.0, vec![status(fuel), status(fuel)]));
+        }
+        tracker.update(&lap_packet(0.0, vec![lap(4), lap(4)]));
+
+        let metric = tracker.metric(vehicle(0)).unwrap();
+        assert_approx_eq!(2.0, metric.average_burn());
+        assert!(tracker.metric(vehicle(1)).is_none());
+    }
+}
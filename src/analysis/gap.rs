@@ -0,0 +1,151 @@
+//! Time gaps between cars on track
+//!
+//! The F1 games do not publish the time gap between two cars. A common way to measure it is to
+//! record when each car passes a point on the track, and to compare the times at which two cars
+//! passed the same point. The gap tracker samples the total distance of every car over time, and
+//! interpolates between these samples to calculate the gap between any two cars.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
+
+use crate::packet::lap::LapPacket;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Time for which the distance samples of a car are kept
+const SAMPLE_RETENTION: Duration = Duration::from_secs(300);
+
+/// Tracker for the time gaps between cars
+///
+/// The gap tracker consumes lap data packets, and keeps the distance samples of the last few
+/// minutes for each car. Gaps can be calculated between cars on different laps, as long as the car
+/// ahead passed the current position of the car behind within this time.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GapTracker {
+    samples: BTreeMap<VehicleIndex, VecDeque<(f32, Duration)>>,
+}
+
+impl GapTracker {
+    /// Create a new gap tracker without any samples.
+    pub fn new() -> Self {
+        GapTracker::default()
+    }
+
+    /// Update the distance samples with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        if let Packet::Lap(packet) = packet {
+            self.update_laps(packet);
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        let session_time = *packet.header().session_time();
+
+        for (index, lap) in enumerate_cars(packet.laps()) {
+            let samples = self.samples.entry(index).or_default();
+            let distance = lap.total_distance();
+
+            match samples.back() {
+                // A car that moves backwards has been reset, e.g. after a flashback, and its
+                // previous samples no longer describe its progress.
+                Some((last_distance, _)) if distance < *last_distance => samples.clear(),
+                Some((last_distance, _)) if distance == *last_distance => continue,
+                _ => {}
+            }
+
+            samples.push_back((distance, session_time));
+
+            while samples
+                .front()
+                .is_some_and(|(_, time)| session_time.saturating_sub(*time) > SAMPLE_RETENTION)
+            {
+                samples.pop_front();
+            }
+        }
+    }
+
+    /// Returns the time gap between a car and the car ahead of it.
+    ///
+    /// The gap is the time that passed since the car ahead was at the current position of the car
+    /// behind. No gap is returned if the car ahead has not yet reached this position, or if it did
+    /// so before the retained samples begin.
+    pub fn gap(&self, ahead: VehicleIndex, behind: VehicleIndex) -> Option<Duration> {
+        let (distance, time) = *self.samples.get(&behind)?.back()?;
+        let passed_at = self.time_at_distance(ahead, distance)?;
+
+        Some(time.saturating_sub(passed_at))
+    }
+
+    /// Returns the interpolated session time at which a car was at the given total distance.
+    pub fn time_at_distance(&self, vehicle_index: VehicleIndex, distance: f32) -> Option<Duration> {
+        time_at_distance(self.samples.get(&vehicle_index)?, distance)
+    }
+}
+
+/// Interpolate the time at a distance from samples that are ordered by distance.
+///
+/// No time is returned if the distance lies outside of the samples.
+pub(crate) fn time_at_distance<'a, I>(samples: I, distance: f32) -> Option<Duration>
+where
+    I: IntoIterator<Item = &'a (f32, Duration)>,
+{
+    let mut previous: Option<(f32, Duration)> = None;
+
+    for (sample_distance, sample_time) in samples {
+        if *sample_distance >= distance {
+            if *sample_distance == distance {
+                return Some(*sample_time);
+            }
+
+            let (before_distance, before_time) = previous?;
+            let fraction = (distance - before_distance) / (sample_distance - before_distance);
+            let elapsed = sample_time.saturating_sub(before_time).as_secs_f32() * fraction;
+
+            return Some(before_time + Duration::from_secs_f32(elapsed));
+        }
+
+        previous = Some((*sample_distance, *sample_time));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::fixtures::{lap_packet, vehicle, LapFixture};
+    use crate::analysis::gap::GapTracker;
+
+    fn lap(total_distance: f32) -> LapFixture {
+        LapFixture {
+            total_distance,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn gap_with_interpolation() {
+        let mut tracker = GapTracker::new();
+
+        tracker.update(&lap_packet(0.0, vec![lap(0.0), lap(-100.0)]));
+        tracker.update(&lap_packet(1.0, vec![lap(100.0), lap(0.0)]));
+        tracker.update(&lap_packet(2.0, vec![lap(200.0), lap(50.0)]));
+
+        assert_eq!(
+            Some(Duration::from_millis(1500)),
+            tracker.gap(vehicle(0), vehicle(1))
+        );
+        assert!(tracker.gap(vehicle(1), vehicle(0)).is_none());
+    }
+
+    #[test]
+    fn gap_without_samples() {
+        let mut tracker = GapTracker::new();
+
+        tracker.update(&lap_packet(0.0, vec![lap(200.0), lap(100.0)]));
+
+        assert!(tracker.gap(vehicle(0), vehicle(1)).is_none());
+        assert!(tracker.gap(vehicle(0), vehicle(2)).is_none());
+    }
+}
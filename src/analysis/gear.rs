@@ -0,0 +1,270 @@
+//! Gear usage and gear shifts
+//!
+//! How a driver uses the gearbox says a lot about their technique. Shifting up too late wastes
+//! time on the rev limiter, while shifting up early, or short-shifting, can help to keep the rear
+//! tyres under control on corner exit. The gear tracker records how long each car spends in each
+//! gear, and at which engine RPM it shifts up and down, for every lap.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::LapPacket;
+use crate::packet::status::CarStatusPacket;
+use crate::packet::telemetry::{Gear, Telemetry, TelemetryPacket};
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// A gear shift
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct Shift {
+    /// Returns the lap distance at which the car shifted.
+    #[getset(get_copy = "pub")]
+    lap_distance: f32,
+
+    /// Returns the gear before the shift.
+    #[getset(get_copy = "pub")]
+    from: Gear,
+
+    /// Returns the gear after the shift.
+    #[getset(get_copy = "pub")]
+    to: Gear,
+
+    /// Returns the engine RPM right before the shift.
+    #[getset(get_copy = "pub")]
+    engine_rpm: u16,
+
+    /// Returns whether the shift was an upshift below the short-shift threshold.
+    #[getset(get_copy = "pub")]
+    is_short_shift: bool,
+}
+
+impl Shift {
+    /// Returns whether the car shifted into a higher gear.
+    pub fn is_upshift(&self) -> bool {
+        self.to > self.from
+    }
+}
+
+/// Gear usage of a car during a lap
+#[derive(Debug, Getters, CopyGetters, PartialEq, Clone, PartialOrd, Default)]
+pub struct GearLap {
+    /// Returns the lap that is summarized.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the time spent in each gear.
+    #[getset(get = "pub")]
+    time_in_gear: BTreeMap<Gear, Duration>,
+
+    /// Returns the gear shifts in the order in which they happened.
+    #[getset(get = "pub")]
+    shifts: Vec<Shift>,
+}
+
+impl GearLap {
+    fn new(lap: u8) -> Self {
+        GearLap {
+            lap,
+            ..GearLap::default()
+        }
+    }
+
+    /// Returns the time spent in a gear.
+    pub fn time_in(&self, gear: Gear) -> Duration {
+        self.time_in_gear.get(&gear).copied().unwrap_or_default()
+    }
+
+    /// Returns the mean engine RPM at which the car shifted up.
+    pub fn mean_upshift_rpm(&self) -> Option<f32> {
+        self.mean_rpm(true)
+    }
+
+    /// Returns the mean engine RPM at which the car shifted down.
+    pub fn mean_downshift_rpm(&self) -> Option<f32> {
+        self.mean_rpm(false)
+    }
+
+    /// Returns the number of short-shifts during the lap.
+    pub fn short_shifts(&self) -> usize {
+        self.shifts
+            .iter()
+            .filter(|shift| shift.is_short_shift)
+            .count()
+    }
+
+    fn mean_rpm(&self, is_upshift: bool) -> Option<f32> {
+        let rpms: Vec<f32> = self
+            .shifts
+            .iter()
+            .filter(|shift| shift.is_upshift() == is_upshift)
+            .map(|shift| shift.engine_rpm as f32)
+            .collect();
+
+        if rpms.is_empty() {
+            None
+        } else {
+            Some(rpms.iter().sum::<f32>() / rpms.len() as f32)
+        }
+    }
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarGears {
+    lap_distance: f32,
+    max_rpm: Option<u16>,
+    previous: Option<(Duration, Telemetry)>,
+    current: Option<GearLap>,
+    laps: Vec<GearLap>,
+}
+
+/// Tracker for the gear usage of all cars in the session
+///
+/// The gear tracker consumes lap data, car status, and telemetry packets. An upshift counts as a
+/// short-shift if the engine RPM is below the short-shift ratio of the maximum RPM of the car,
+/// which is taken from the car status.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GearTracker {
+    short_shift_ratio: f32,
+    cars: BTreeMap<VehicleIndex, CarGears>,
+}
+
+impl GearTracker {
+    /// Create a new gear tracker with the given short-shift ratio.
+    pub fn new(short_shift_ratio: f32) -> Self {
+        GearTracker {
+            short_shift_ratio,
+            cars: BTreeMap::new(),
+        }
+    }
+
+    /// Update the gear usage with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Lap(packet) => self.update_laps(packet),
+            Packet::Status(packet) => self.update_statuses(packet),
+            Packet::Telemetry(packet) => self.update_telemetry(packet),
+            _ => {}
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(index).or_default();
+            let lap_number = lap.current_lap_number();
+
+            if car.current.as_ref().map(|current| current.lap) != Some(lap_number) {
+                if let Some(current) = car.current.take() {
+                    car.laps.push(current);
+                }
+
+                car.current = Some(GearLap::new(lap_number));
+            }
+
+            car.lap_distance = lap.lap_distance();
+        }
+    }
+
+    fn update_statuses(&mut self, packet: &CarStatusPacket) {
+        for (index, status) in enumerate_cars(packet.statuses()) {
+            self.cars.entry(index).or_default().max_rpm = Some(status.max_rpm());
+        }
+    }
+
+    fn update_telemetry(&mut self, packet: &TelemetryPacket) {
+        let session_time = *packet.header().session_time();
+        let short_shift_ratio = self.short_shift_ratio;
+
+        for (index, telemetry) in enumerate_cars(packet.telemetry()) {
+            let car = self.cars.entry(index).or_default();
+
+            if let (Some(current), Some((previous_time, previous))) =
+                (car.current.as_mut(), car.previous)
+            {
+                let elapsed = session_time.saturating_sub(previous_time);
+                *current.time_in_gear.entry(previous.gear()).or_default() += elapsed;
+
+                if telemetry.gear() != previous.gear() {
+                    let is_upshift = telemetry.gear() > previous.gear();
+                    let is_short_shift = is_upshift
+                        && car.max_rpm.is_some_and(|max_rpm| {
+                            (previous.engine_rpm() as f32) < max_rpm as f32 * short_shift_ratio
+                        });
+
+                    current.shifts.push(Shift::new(
+                        car.lap_distance,
+                        previous.gear(),
+                        telemetry.gear(),
+                        previous.engine_rpm(),
+                        is_short_shift,
+                    ));
+                }
+            }
+
+            car.previous = Some((session_time, *telemetry));
+        }
+    }
+
+    /// Returns the gear usage of the completed laps of a car.
+    pub fn laps(&self, vehicle_index: VehicleIndex) -> &[GearLap] {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.laps.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns the gear usage of the lap a car is currently on.
+    pub fn current_lap(&self, vehicle_index: VehicleIndex) -> Option<&GearLap> {
+        self.cars.get(&vehicle_index)?.current.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::fixtures::{
+        lap_packet, status_packet, telemetry_packet, vehicle, LapFixture, StatusFixture,
+        TelemetryFixture,
+    };
+    use crate::analysis::gear::GearTracker;
+    use crate::packet::telemetry::Gear;
+
+    fn telemetry(gear: Gear, engine_rpm: u16) -> TelemetryFixture {
+        TelemetryFixture {
+            gear,
+            engine_rpm,
+            ..TelemetryFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_shifts() {
+        let mut tracker = GearTracker::new(0.9);
+        let lap = |current_lap_number| LapFixture {
+            current_lap_number,
+            ..LapFixture::default()
+        };
+
+        tracker.update(&status_packet(0.0, vec![StatusFixture::default()]));
+        tracker.update(&lap_packet(0.0, vec![lap(1)]));
+        tracker.update(&telemetry_packet(0.0, vec![telemetry(Gear::Third, 11800)]));
+        tracker.update(&telemetry_packet(2.0, vec![telemetry(Gear::Fourth, 9000)]));
+        tracker.update(&telemetry_packet(3.0, vec![telemetry(Gear::Fifth, 11900)]));
+        tracker.update(&telemetry_packet(7.0, vec![telemetry(Gear::Third, 8000)]));
+        tracker.update(&lap_packet(7.0, vec![lap(2)]));
+
+        let lap = &tracker.laps(vehicle(0))[0];
+        assert_eq!(Duration::from_secs(2), lap.time_in(Gear::Third));
+        assert_eq!(Duration::from_secs(4), lap.time_in(Gear::Fifth));
+        assert_eq!(3, lap.shifts().len());
+        assert_eq!(1, lap.short_shifts());
+        assert_approx_eq!(10400.0, lap.mean_upshift_rpm().unwrap());
+        assert_approx_eq!(11900.0, lap.mean_downshift_rpm().unwrap());
+    }
+}
@@ -0,0 +1,276 @@
+//! Classification of laps
+//!
+//! Not every lap is representative of the pace of a driver. Out-laps and in-laps are slow because
+//! of the pit lane, and laps behind the safety car or while cooling the tyres are slow on purpose.
+//! The lap classifier assigns a kind to every completed lap, so that statistics such as the average
+//! pace can be computed from the representative laps only.
+//!
+//! F1 2019 does not flag the formation lap in the lap data. Instead, a formation lap is detected
+//! when a car completes a lap in a race before its lap timer has started.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::{DriverStatus, Lap, LapPacket, PitStatus};
+use crate::packet::session::Session;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Types of laps
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+pub enum LapKind {
+    /// The lap before the start of a race, on which the cars drive from the grid back to the grid.
+    Formation,
+
+    /// The lap after leaving the pits.
+    OutLap,
+
+    /// A lap at full pace.
+    #[default]
+    Flying,
+
+    /// The lap into the pits.
+    InLap,
+
+    /// A lap that was much slower than the best lap of the driver, for example behind the safety
+    /// car or to cool down the tyres.
+    Slow,
+}
+
+/// A completed lap and its kind
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+pub struct ClassifiedLap {
+    /// Returns the number of the lap.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the kind of the lap.
+    #[getset(get_copy = "pub")]
+    kind: LapKind,
+
+    /// Returns the time of the lap.
+    #[getset(get = "pub")]
+    lap_time: Duration,
+}
+
+impl ClassifiedLap {
+    /// Returns whether the lap is representative of the pace of the driver.
+    pub fn is_representative(&self) -> bool {
+        self.kind == LapKind::Flying
+    }
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarLaps {
+    previous: Option<Lap>,
+    started_in_pits: bool,
+    left_pits: bool,
+    entered_pits: bool,
+    laps: Vec<ClassifiedLap>,
+}
+
+impl CarLaps {
+    fn update(&mut self, lap: &Lap, is_race: bool, track_length: f32, slow_lap_ratio: f32) {
+        let previous = match self.previous.replace(*lap) {
+            Some(previous) => previous,
+            None => {
+                self.start_lap(lap);
+                return;
+            }
+        };
+
+        let is_in_pits = lap.pit_status() != PitStatus::None;
+
+        if lap.current_lap_number() != previous.current_lap_number() {
+            if *lap.last_lap_time() > Duration::default() {
+                let kind = self.kind(&previous, *lap.last_lap_time(), slow_lap_ratio);
+
+                self.laps.push(ClassifiedLap::new(
+                    previous.current_lap_number(),
+                    kind,
+                    *lap.last_lap_time(),
+                ));
+            }
+
+            self.start_lap(lap);
+        } else if is_race
+            && *lap.current_lap_time() == Duration::default()
+            && lap.lap_distance() < previous.lap_distance() - track_length / 2.0
+        {
+            self.laps.push(ClassifiedLap::new(
+                0,
+                LapKind::Formation,
+                Duration::default(),
+            ));
+        } else if is_in_pits && self.left_pits {
+            self.entered_pits = true;
+        } else if !is_in_pits {
+            self.left_pits = true;
+        }
+    }
+
+    fn start_lap(&mut self, lap: &Lap) {
+        self.started_in_pits = lap.pit_status() != PitStatus::None;
+        self.left_pits = !self.started_in_pits;
+        self.entered_pits = false;
+    }
+
+    fn kind(&self, previous: &Lap, lap_time: Duration, slow_lap_ratio: f32) -> LapKind {
+        let best_lap_time = *previous.best_lap_time();
+
+        if self.entered_pits || previous.driver_status() == DriverStatus::InLap {
+            LapKind::InLap
+        } else if self.started_in_pits || previous.driver_status() == DriverStatus::OutLap {
+            LapKind::OutLap
+        } else if best_lap_time > Duration::default()
+            && lap_time.as_secs_f32() > best_lap_time.as_secs_f32() * slow_lap_ratio
+        {
+            LapKind::Slow
+        } else {
+            LapKind::Flying
+        }
+    }
+}
+
+/// Classifier for the laps of all cars in the session
+///
+/// The lap classifier consumes session and lap data packets. In- and out-laps are detected from the
+/// pit status and the driver status of a car. A lap counts as slow if it is slower than the best lap
+/// of the driver by more than the slow-lap ratio, for example `1.07` for a lap that is more than 7%
+/// slower.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LapClassifier {
+    slow_lap_ratio: f32,
+    session_type: Session,
+    track_length: u16,
+    cars: BTreeMap<VehicleIndex, CarLaps>,
+}
+
+impl LapClassifier {
+    /// Create a new lap classifier with the given slow-lap ratio.
+    pub fn new(slow_lap_ratio: f32) -> Self {
+        LapClassifier {
+            slow_lap_ratio,
+            ..LapClassifier::default()
+        }
+    }
+
+    /// Update the classification with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Session(packet) => {
+                self.session_type = packet.session_type();
+                self.track_length = packet.track_length();
+            }
+            Packet::Lap(packet) => self.update_laps(packet),
+            _ => {}
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        let is_race = self.session_type.is_race();
+        let track_length = self.track_length as f32;
+
+        for (index, lap) in enumerate_cars(packet.laps()) {
+            self.cars.entry(index).or_default().update(
+                lap,
+                is_race,
+                track_length,
+                self.slow_lap_ratio,
+            );
+        }
+    }
+
+    /// Returns the completed laps of a car.
+    pub fn laps(&self, vehicle_index: VehicleIndex) -> &[ClassifiedLap] {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.laps.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns the completed laps of a car that are representative of its pace.
+    pub fn representative_laps(
+        &self,
+        vehicle_index: VehicleIndex,
+    ) -> impl Iterator<Item = &ClassifiedLap> {
+        self.laps(vehicle_index)
+            .iter()
+            .filter(|lap| lap.is_representative())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::fixtures::{
+        lap_packet, session_packet, vehicle, LapFixture, SessionFixture,
+    };
+    use crate::analysis::lap_kind::{LapClassifier, LapKind};
+    use crate::packet::lap::PitStatus;
+
+    fn lap(
+        current_lap_number: u8,
+        lap_distance: f32,
+        last_lap_time: f32,
+        pit_status: PitStatus,
+    ) -> LapFixture {
+        LapFixture {
+            current_lap_number,
+            lap_distance,
+            last_lap_time,
+            current_lap_time: if current_lap_number > 1 { 1.0 } else { 0.0 },
+            best_lap_time: if current_lap_number > 2 { 90.0 } else { 0.0 },
+            pit_status,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_race() {
+        let mut classifier = LapClassifier::new(1.07);
+        classifier.update(&session_packet(0.0, SessionFixture::default()));
+
+        for (time, fixture) in [
+            lap(1, 4900.0, 0.0, PitStatus::None),
+            lap(1, 10.0, 0.0, PitStatus::None),
+            lap(2, 10.0, 91.0, PitStatus::None),
+            lap(3, 10.0, 90.0, PitStatus::None),
+            lap(3, 4900.0, 0.0, PitStatus::Pitting),
+            lap(4, 10.0, 110.0, PitStatus::Pitting),
+            lap(4, 500.0, 0.0, PitStatus::None),
+            lap(5, 10.0, 120.0, PitStatus::None),
+            lap(6, 10.0, 99.0, PitStatus::None),
+            lap(7, 10.0, 91.0, PitStatus::None),
+        ]
+        .iter()
+        .enumerate()
+        {
+            classifier.update(&lap_packet(time as f32, vec![*fixture]));
+        }
+
+        let kinds: Vec<LapKind> = classifier
+            .laps(vehicle(0))
+            .iter()
+            .map(|lap| lap.kind())
+            .collect();
+        assert_eq!(
+            vec![
+                LapKind::Formation,
+                LapKind::Flying,
+                LapKind::Flying,
+                LapKind::InLap,
+                LapKind::OutLap,
+                LapKind::Slow,
+                LapKind::Flying,
+            ],
+            kinds
+        );
+        assert_eq!(3, classifier.representative_laps(vehicle(0)).count());
+    }
+}
@@ -0,0 +1,276 @@
+//! Race starts
+//!
+//! Races are often won or lost at the start. A good launch needs a quick reaction to the lights,
+//! little wheelspin, and a clean run into the first corner. The launch analyzer measures the
+//! reaction time and the acceleration of every car after the lights go out, and counts the
+//! positions each car gained or lost by the time it reaches the first corner.
+//!
+//! F1 2019 does not publish an event when the lights go out. Instead, the time of the start is
+//! derived from the current lap time of the first car that starts its first lap in a race. The wheel
+//! slip is only published for the player's car, so wheelspin is unknown for all other cars.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::{LapPacket, ResultStatus};
+use crate::packet::motion::MotionPacket;
+use crate::packet::session::Session;
+use crate::packet::telemetry::TelemetryPacket;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Speed in kilometers per hour above which a car is considered to be moving
+const MOVING_SPEED: u16 = 2;
+
+/// Start of a single car
+#[derive(Debug, Getters, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct CarStart {
+    /// Returns the index of the car.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the position the car started from.
+    #[getset(get_copy = "pub")]
+    grid_position: u8,
+
+    /// Returns the time between the lights going out and the car starting to move.
+    #[getset(get_copy = "pub")]
+    reaction_time: Option<Duration>,
+
+    /// Returns the time it took the car to reach 100 km/h.
+    #[getset(get_copy = "pub")]
+    time_to_100: Option<Duration>,
+
+    /// Returns the time it took the car to reach 200 km/h.
+    #[getset(get_copy = "pub")]
+    time_to_200: Option<Duration>,
+
+    /// Returns the highest wheel slip of the rear wheels before the first corner, if it is known.
+    #[getset(get_copy = "pub")]
+    peak_wheel_slip: Option<f32>,
+
+    /// Returns the position of the car when it reached the first corner.
+    #[getset(get_copy = "pub")]
+    turn_one_position: Option<u8>,
+}
+
+impl CarStart {
+    /// Returns the positions the car gained between the grid and the first corner.
+    ///
+    /// A negative number means that the car lost positions.
+    pub fn positions_gained(&self) -> Option<i8> {
+        let turn_one_position = self.turn_one_position?;
+        Some(self.grid_position as i8 - turn_one_position as i8)
+    }
+}
+
+/// Report of the start of a race
+#[derive(Debug, Getters, PartialEq, Clone, PartialOrd, Default)]
+pub struct StartReport {
+    /// Returns the session time at which the lights went out.
+    #[getset(get = "pub")]
+    lights_out: Duration,
+
+    /// Returns the starts of all cars, ordered by their grid position.
+    #[getset(get = "pub")]
+    cars: Vec<CarStart>,
+}
+
+/// Analyzer for the start of a race
+///
+/// The launch analyzer consumes session, lap data, motion, and telemetry packets. A car reaches the
+/// first corner when its lap distance on the first lap passes the given turn one distance. The
+/// times are measured with the resolution of the packets, which depends on the send rate that is
+/// configured in the game.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LaunchAnalyzer {
+    turn_one_distance: f32,
+    session_type: Session,
+    lights_out: Option<Duration>,
+    cars: BTreeMap<VehicleIndex, CarStart>,
+}
+
+impl LaunchAnalyzer {
+    /// Create a new launch analyzer with the lap distance of the first corner in meters.
+    pub fn new(turn_one_distance: f32) -> Self {
+        LaunchAnalyzer {
+            turn_one_distance,
+            ..LaunchAnalyzer::default()
+        }
+    }
+
+    /// Update the start with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Session(packet) => self.session_type = packet.session_type(),
+            Packet::Lap(packet) => self.update_laps(packet),
+            Packet::Motion(packet) => self.update_motion(packet),
+            Packet::Telemetry(packet) => self.update_telemetry(packet),
+            _ => {}
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        let session_time = *packet.header().session_time();
+        let is_race = self.session_type.is_race();
+
+        for (vehicle_index, lap) in enumerate_cars(packet.laps()) {
+            if matches!(
+                lap.result_status(),
+                ResultStatus::Invalid | ResultStatus::Inactive
+            ) {
+                continue;
+            }
+
+            let is_first_lap = lap.current_lap_number() == 1;
+
+            if self.lights_out.is_none()
+                && is_race
+                && is_first_lap
+                && *lap.current_lap_time() > Duration::default()
+            {
+                self.lights_out = Some(session_time.saturating_sub(*lap.current_lap_time()));
+            }
+
+            let car = self.cars.entry(vehicle_index).or_default();
+            car.vehicle_index = vehicle_index;
+
+            if self.lights_out.is_none() {
+                car.grid_position = lap.grid_position();
+            } else if car.turn_one_position.is_none()
+                && is_first_lap
+                && lap.lap_distance() >= self.turn_one_distance
+            {
+                car.turn_one_position = Some(lap.position());
+            }
+        }
+    }
+
+    fn update_motion(&mut self, packet: &MotionPacket) {
+        if self.lights_out.is_none() {
+            return;
+        }
+
+        let player_car_index = packet.header().player_car_index();
+        let wheel_slip = packet.wheel_slip();
+        let slip = wheel_slip
+            .rear_left()
+            .abs()
+            .max(wheel_slip.rear_right().abs());
+
+        if let Some(car) = self.cars.get_mut(&player_car_index) {
+            if car.turn_one_position.is_none() {
+                car.peak_wheel_slip = Some(car.peak_wheel_slip.unwrap_or_default().max(slip));
+            }
+        }
+    }
+
+    fn update_telemetry(&mut self, packet: &TelemetryPacket) {
+        let lights_out = match self.lights_out {
+            Some(lights_out) => lights_out,
+            None => return,
+        };
+
+        let elapsed = packet.header().session_time().saturating_sub(lights_out);
+
+        for (index, telemetry) in enumerate_cars(packet.telemetry()) {
+            if let Some(car) = self.cars.get_mut(&index) {
+                let speed = telemetry.speed().as_kph();
+
+                if speed >= MOVING_SPEED && car.reaction_time.is_none() {
+                    car.reaction_time = Some(elapsed);
+                }
+                if speed >= 100 && car.time_to_100.is_none() {
+                    car.time_to_100 = Some(elapsed);
+                }
+                if speed >= 200 && car.time_to_200.is_none() {
+                    car.time_to_200 = Some(elapsed);
+                }
+            }
+        }
+    }
+
+    /// Returns the report of the start, once the lights have gone out.
+    pub fn report(&self) -> Option<StartReport> {
+        let lights_out = self.lights_out?;
+
+        let mut cars: Vec<CarStart> = self.cars.values().copied().collect();
+        cars.sort_by_key(|car| car.grid_position);
+
+        Some(StartReport { lights_out, cars })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::fixtures::{
+        lap_packet, session_packet, telemetry_packet, LapFixture, SessionFixture, TelemetryFixture,
+    };
+    use crate::analysis::launch::LaunchAnalyzer;
+
+    fn lap(current_lap_time: f32, lap_distance: f32, position: u8, grid: u8) -> LapFixture {
+        LapFixture {
+            current_lap_time,
+            lap_distance,
+            position,
+            grid_position: grid,
+            current_lap_number: 1,
+            ..LapFixture::default()
+        }
+    }
+
+    fn speed(speed: u16) -> TelemetryFixture {
+        TelemetryFixture {
+            speed,
+            ..TelemetryFixture::default()
+        }
+    }
+
+    #[test]
+    fn report_with_start() {
+        let mut analyzer = LaunchAnalyzer::new(300.0);
+        analyzer.update(&session_packet(0.0, SessionFixture::default()));
+        assert!(analyzer.report().is_none());
+
+        analyzer.update(&lap_packet(
+            10.0,
+            vec![lap(0.0, -20.0, 1, 1), lap(0.0, -30.0, 2, 2)],
+        ));
+        analyzer.update(&lap_packet(
+            10.5,
+            vec![lap(0.5, -20.0, 1, 1), lap(0.5, -30.0, 2, 2)],
+        ));
+        analyzer.update(&telemetry_packet(10.5, vec![speed(0), speed(0)]));
+        analyzer.update(&telemetry_packet(10.7, vec![speed(5), speed(0)]));
+        analyzer.update(&telemetry_packet(10.9, vec![speed(40), speed(8)]));
+        analyzer.update(&telemetry_packet(12.6, vec![speed(100), speed(110)]));
+        analyzer.update(&telemetry_packet(14.8, vec![speed(190), speed(205)]));
+        analyzer.update(&lap_packet(
+            15.0,
+            vec![lap(5.0, 280.0, 2, 1), lap(5.0, 310.0, 1, 2)],
+        ));
+        analyzer.update(&lap_packet(
+            15.2,
+            vec![lap(5.2, 305.0, 2, 1), lap(5.2, 330.0, 1, 2)],
+        ));
+
+        let report = analyzer.report().unwrap();
+        assert_eq!(Duration::from_secs(10), *report.lights_out());
+
+        let pole = report.cars()[0];
+        assert_approx_eq!(0.7, pole.reaction_time().unwrap().as_secs_f32());
+        assert_approx_eq!(2.6, pole.time_to_100().unwrap().as_secs_f32());
+        assert_eq!(None, pole.time_to_200());
+        assert_eq!(Some(-1), pole.positions_gained());
+
+        let second = report.cars()[1];
+        assert_approx_eq!(4.8, second.time_to_200().unwrap().as_secs_f32());
+        assert_eq!(Some(1), second.positions_gained());
+    }
+}
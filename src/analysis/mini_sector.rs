@@ -0,0 +1,338 @@
+//! Mini-sector times
+//!
+//! The three sectors of a lap are too coarse to show where exactly a driver gains or loses time.
+//! Broadcasts split the lap into many small mini-sectors instead, and color each mini-sector by
+//! whether it was the fastest of the session, a personal best, or slower. The mini-sector tracker
+//! divides the lap into a configurable number of mini-sectors of equal length, and records the time
+//! of every car in each of them.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::{Lap, LapPacket};
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Comparison of a mini-sector time with the best times
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+pub enum MiniSectorStatus {
+    /// The time is the fastest time of the session.
+    OverallBest,
+
+    /// The time is the fastest time of the driver.
+    PersonalBest,
+
+    /// The time is slower than the personal best of the driver.
+    #[default]
+    Slower,
+}
+
+/// A mini-sector that a car has completed
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+pub struct MiniSectorEvent {
+    /// Returns the index of the car that completed the mini-sector.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the lap on which the mini-sector was completed.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the index of the mini-sector, starting at zero.
+    #[getset(get_copy = "pub")]
+    mini_sector: usize,
+
+    /// Returns the time of the mini-sector.
+    #[getset(get = "pub")]
+    time: Duration,
+
+    /// Returns how the time compares with the best times.
+    #[getset(get_copy = "pub")]
+    status: MiniSectorStatus,
+}
+
+/// Mini-sector times of a completed lap
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+pub struct MiniSectorLap {
+    /// Returns the number of the lap.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the time of each mini-sector, if it is known.
+    #[getset(get = "pub")]
+    times: Vec<Option<Duration>>,
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarMiniSectors {
+    previous: Option<Lap>,
+    mini_sector: usize,
+    mini_sector_start: Option<Duration>,
+    times: Vec<Option<Duration>>,
+    personal_best: Vec<Option<Duration>>,
+    laps: Vec<MiniSectorLap>,
+}
+
+/// Tracker for the mini-sector times of all cars in the session
+///
+/// The mini-sector tracker consumes session and lap data packets, and needs the track length from
+/// the session packet before it can record any times. The time at which a car crosses into a new
+/// mini-sector is interpolated between two lap data packets. Mini-sectors that a car was already in
+/// when the tracker first saw it are not recorded.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MiniSectorTracker {
+    count: usize,
+    track_length: f32,
+    session_best: Vec<Option<Duration>>,
+    cars: BTreeMap<VehicleIndex, CarMiniSectors>,
+}
+
+impl MiniSectorTracker {
+    /// Create a new mini-sector tracker that divides the lap into the given number of mini-sectors.
+    pub fn new(count: usize) -> Self {
+        MiniSectorTracker {
+            count,
+            track_length: 0.0,
+            session_best: vec![None; count],
+            cars: BTreeMap::new(),
+        }
+    }
+
+    /// Update the mini-sectors with the data from the packet, and return the completed
+    /// mini-sectors.
+    pub fn update(&mut self, packet: &Packet) -> Vec<MiniSectorEvent> {
+        match packet {
+            Packet::Session(packet) => {
+                self.track_length = packet.track_length() as f32;
+                Vec::new()
+            }
+            Packet::Lap(packet) => self.update_laps(packet),
+            _ => Vec::new(),
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) -> Vec<MiniSectorEvent> {
+        let mut events = Vec::new();
+
+        if self.count == 0 || self.track_length <= 0.0 {
+            return events;
+        }
+
+        let length = self.track_length / self.count as f32;
+
+        for (vehicle_index, lap) in enumerate_cars(packet.laps()) {
+            let count = self.count;
+            let car = self
+                .cars
+                .entry(vehicle_index)
+                .or_insert_with(|| CarMiniSectors {
+                    times: vec![None; count],
+                    personal_best: vec![None; count],
+                    ..CarMiniSectors::default()
+                });
+
+            let previous = match car.previous.replace(*lap) {
+                Some(previous) => previous,
+                None => {
+                    car.mini_sector = mini_sector_at(lap.lap_distance(), length, count);
+                    continue;
+                }
+            };
+
+            if lap.current_lap_number() != previous.current_lap_number() {
+                let last_time = car
+                    .mini_sector_start
+                    .filter(|_| car.mini_sector == count - 1)
+                    .map(|start| lap.last_lap_time().saturating_sub(start));
+
+                if let Some(time) = last_time {
+                    car.times[count - 1] = Some(time);
+                    events.push(compare_with_best(
+                        &mut self.session_best,
+                        &mut car.personal_best,
+                        vehicle_index,
+                        previous.current_lap_number(),
+                        count - 1,
+                        time,
+                    ));
+                }
+
+                car.laps.push(MiniSectorLap::new(
+                    previous.current_lap_number(),
+                    std::mem::replace(&mut car.times, vec![None; count]),
+                ));
+                car.mini_sector = 0;
+                car.mini_sector_start = Some(Duration::default());
+
+                continue;
+            }
+
+            while car.mini_sector + 1 < count {
+                let boundary = (car.mini_sector + 1) as f32 * length;
+
+                if lap.lap_distance() < boundary || previous.lap_distance() > boundary {
+                    break;
+                }
+
+                let crossing = interpolate(&previous, lap, boundary);
+
+                if let Some(start) = car.mini_sector_start {
+                    let time = crossing.saturating_sub(start);
+                    car.times[car.mini_sector] = Some(time);
+
+                    events.push(compare_with_best(
+                        &mut self.session_best,
+                        &mut car.personal_best,
+                        vehicle_index,
+                        lap.current_lap_number(),
+                        car.mini_sector,
+                        time,
+                    ));
+                }
+
+                car.mini_sector += 1;
+                car.mini_sector_start = Some(crossing);
+            }
+        }
+
+        events
+    }
+
+    /// Returns the mini-sector times of the completed laps of a car.
+    pub fn laps(&self, vehicle_index: VehicleIndex) -> &[MiniSectorLap] {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.laps.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns the best time of a car in each mini-sector.
+    pub fn personal_best(&self, vehicle_index: VehicleIndex) -> &[Option<Duration>] {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.personal_best.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns the best time of the session in each mini-sector.
+    pub fn session_best(&self) -> &[Option<Duration>] {
+        &self.session_best
+    }
+}
+
+/// Returns the index of the mini-sector that contains the lap distance.
+fn mini_sector_at(lap_distance: f32, length: f32, count: usize) -> usize {
+    ((lap_distance.max(0.0) / length) as usize).min(count - 1)
+}
+
+/// Returns the lap time at which a car passed the lap distance between two lap data samples.
+fn interpolate(before: &Lap, after: &Lap, lap_distance: f32) -> Duration {
+    let distance = after.lap_distance() - before.lap_distance();
+    let ratio = if distance > 0.0 {
+        (lap_distance - before.lap_distance()) / distance
+    } else {
+        1.0
+    };
+
+    let before_time = before.current_lap_time().as_secs_f32();
+    let after_time = after.current_lap_time().as_secs_f32();
+
+    Duration::from_secs_f32((before_time + (after_time - before_time) * ratio).max(0.0))
+}
+
+/// Compare a mini-sector time with the best times, and update the best times.
+fn compare_with_best(
+    session_best: &mut [Option<Duration>],
+    personal_best: &mut [Option<Duration>],
+    vehicle_index: VehicleIndex,
+    lap: u8,
+    mini_sector: usize,
+    time: Duration,
+) -> MiniSectorEvent {
+    let status = if session_best[mini_sector].is_none_or(|best| time < best) {
+        session_best[mini_sector] = Some(time);
+        personal_best[mini_sector] = Some(time);
+        MiniSectorStatus::OverallBest
+    } else if personal_best[mini_sector].is_none_or(|best| time < best) {
+        personal_best[mini_sector] = Some(time);
+        MiniSectorStatus::PersonalBest
+    } else {
+        MiniSectorStatus::Slower
+    };
+
+    MiniSectorEvent::new(vehicle_index, lap, mini_sector, time, status)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::fixtures::{
+        lap_packet, session_packet, vehicle, LapFixture, SessionFixture,
+    };
+    use crate::analysis::mini_sector::{MiniSectorStatus, MiniSectorTracker};
+
+    fn lap(current_lap_number: u8, lap_distance: f32, current_lap_time: f32) -> LapFixture {
+        LapFixture {
+            current_lap_number,
+            lap_distance,
+            current_lap_time,
+            last_lap_time: if current_lap_number > 1 { 60.0 } else { 0.0 },
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_mini_sectors() {
+        let mut tracker = MiniSectorTracker::new(4);
+        let session = SessionFixture {
+            track_length: 4000,
+            ..SessionFixture::default()
+        };
+        tracker.update(&session_packet(0.0, session));
+
+        let mut events = Vec::new();
+        for (first, second) in [
+            (lap(1, 3500.0, 35.0), lap(1, 3500.0, 35.0)),
+            (lap(2, 10.0, 0.1), lap(2, 10.0, 0.1)),
+            (lap(2, 900.0, 9.0), lap(2, 900.0, 10.0)),
+            (lap(2, 1100.0, 11.0), lap(2, 1100.0, 12.0)),
+            (lap(2, 2500.0, 25.0), lap(2, 2500.0, 26.0)),
+            (lap(2, 3500.0, 40.0), lap(2, 3500.0, 41.0)),
+            (lap(3, 10.0, 0.1), lap(3, 10.0, 0.1)),
+        ]
+        .iter()
+        {
+            events.extend(tracker.update(&lap_packet(0.0, vec![*first, *second])));
+        }
+
+        assert_eq!(8, events.len());
+        assert_eq!(MiniSectorStatus::OverallBest, events[0].status());
+        assert_eq!(MiniSectorStatus::PersonalBest, events[1].status());
+        assert_eq!(MiniSectorStatus::OverallBest, events[7].status());
+
+        let laps = tracker.laps(vehicle(0));
+        assert_eq!(vec![None; 4], *laps[0].times());
+
+        let times: Vec<f32> = laps[1]
+            .times()
+            .iter()
+            .map(|time| time.unwrap().as_secs_f32())
+            .collect();
+        assert_approx_eq!(10.0, times[0], 1e-3);
+        assert_approx_eq!(10.0, times[1], 1e-3);
+        assert_approx_eq!(12.5, times[2], 1e-3);
+        assert_approx_eq!(27.5, times[3], 1e-3);
+
+        let best = tracker.session_best()[3].unwrap();
+        assert_approx_eq!(26.5, best.as_secs_f32(), 1e-3);
+    }
+}
@@ -0,0 +1,160 @@
+//! Ledger of the penalties of each driver
+//!
+//! Stewards hand out time penalties for infringements such as cutting corners or causing
+//! collisions. The penalty ledger keeps a record of every time penalty a driver receives, including
+//! when and on which lap it was given, so that league stewards have a complete record of a session.
+//!
+//! F1 2019 only publishes the accumulated time penalties of each driver in the lap data. Newer games
+//! also publish penalty events with warnings and drive-through penalties, which are not available
+//! here.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::LapPacket;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, PenaltyType, VehicleIndex};
+
+/// A time penalty given to a driver
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimePenalty {
+    /// Returns the session time at which the penalty was given.
+    #[getset(get = "pub")]
+    session_time: Duration,
+
+    /// Returns the lap on which the penalty was given.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the length of the penalty in seconds.
+    #[getset(get_copy = "pub")]
+    seconds: u8,
+}
+
+impl TimePenalty {
+    /// Returns the type of the penalty, which is always a time penalty in F1 2019.
+    pub fn penalty_type(&self) -> PenaltyType {
+        PenaltyType::TimePenalty
+    }
+}
+
+/// Record of the penalties of a driver
+#[derive(Debug, Getters, CopyGetters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PenaltyRecord {
+    /// Returns the accumulated time penalties in seconds.
+    #[getset(get_copy = "pub")]
+    total_seconds: u8,
+
+    /// Returns the individual time penalties in the order they were given.
+    #[getset(get = "pub")]
+    penalties: Vec<TimePenalty>,
+}
+
+/// Ledger of the penalties of all drivers in the session
+///
+/// The penalty ledger consumes lap data packets. A penalty is recorded every time the accumulated
+/// time penalties of a driver increase.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PenaltyLedger {
+    records: BTreeMap<VehicleIndex, PenaltyRecord>,
+}
+
+impl PenaltyLedger {
+    /// Create a new penalty ledger without any penalties.
+    pub fn new() -> Self {
+        PenaltyLedger::default()
+    }
+
+    /// Update the ledger with the data from the packet, and return the newly recorded penalties.
+    pub fn update(&mut self, packet: &Packet) -> Vec<(VehicleIndex, TimePenalty)> {
+        match packet {
+            Packet::Lap(packet) => self.update_laps(packet),
+            _ => Vec::new(),
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) -> Vec<(VehicleIndex, TimePenalty)> {
+        let session_time = *packet.header().session_time();
+        let mut penalties = Vec::new();
+
+        for (vehicle_index, lap) in enumerate_cars(packet.laps()) {
+            let record = self.records.entry(vehicle_index).or_default();
+
+            if lap.penalties() > record.total_seconds {
+                let penalty = TimePenalty::new(
+                    session_time,
+                    lap.current_lap_number(),
+                    lap.penalties() - record.total_seconds,
+                );
+
+                record.penalties.push(penalty);
+                penalties.push((vehicle_index, penalty));
+            }
+
+            // The accumulated penalties only decrease if a session is restarted, or if stewards
+            // revoke a penalty. In both cases the new total is the one that counts.
+            record.total_seconds = lap.penalties();
+        }
+
+        penalties
+    }
+
+    /// Returns the penalty record of a driver.
+    pub fn record(&self, vehicle_index: VehicleIndex) -> Option<&PenaltyRecord> {
+        self.records.get(&vehicle_index)
+    }
+
+    /// Returns the penalty records of all drivers that have received a penalty.
+    pub fn records(&self) -> impl Iterator<Item = (VehicleIndex, &PenaltyRecord)> {
+        self.records
+            .iter()
+            .filter(|(_, record)| !record.penalties.is_empty())
+            .map(|(index, record)| (*index, record))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::fixtures::{lap_packet, vehicle, LapFixture};
+    use crate::analysis::penalty::{PenaltyLedger, TimePenalty};
+
+    fn lap(current_lap_number: u8, penalties: u8) -> LapFixture {
+        LapFixture {
+            current_lap_number,
+            penalties,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_penalties() {
+        let mut ledger = PenaltyLedger::new();
+
+        assert!(ledger
+            .update(&lap_packet(0.0, vec![lap(1, 0), lap(1, 0)]))
+            .is_empty());
+
+        let penalties = ledger.update(&lap_packet(10.0, vec![lap(2, 0), lap(2, 5)]));
+        assert_eq!(
+            vec![(vehicle(1), TimePenalty::new(Duration::from_secs(10), 2, 5))],
+            penalties
+        );
+
+        ledger.update(&lap_packet(20.0, vec![lap(4, 0), lap(4, 5)]));
+        ledger.update(&lap_packet(30.0, vec![lap(5, 0), lap(5, 8)]));
+
+        let record = ledger.record(vehicle(1)).unwrap();
+        assert_eq!(8, record.total_seconds());
+        assert_eq!(2, record.penalties().len());
+        assert_eq!(3, record.penalties()[1].seconds());
+
+        assert_eq!(1, ledger.records().count());
+    }
+}
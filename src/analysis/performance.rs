@@ -0,0 +1,331 @@
+//! Performance scores of drivers
+//!
+//! League statistics often rate drivers on more than their points. A driver in a slow car can
+//! still beat their teammate in qualifying, set a strong race pace, drive consistently, and stay
+//! out of trouble. The performance scorer rates every driver in these four areas over one or more
+//! sessions, and combines the ratings into a single score.
+//!
+//! Each rating is a number between 0 and 100. In qualifying, a driver scores 100 for beating their
+//! teammate and 0 for losing to them. In races, drivers are ranked by their average lap time, the
+//! standard deviation of their lap times, and their time penalties, and the best driver in each
+//! area scores 100 while the worst scores 0.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::analysis::consistency::ConsistencyTracker;
+use crate::analysis::result::{CarResult, SessionResult};
+
+/// Weights of the ratings in the overall score
+#[derive(new, Debug, CopyGetters, PartialEq, Copy, Clone, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PerformanceWeights {
+    /// Returns the weight of the qualifying rating.
+    #[getset(get_copy = "pub")]
+    qualifying: f32,
+
+    /// Returns the weight of the race pace rating.
+    #[getset(get_copy = "pub")]
+    race_pace: f32,
+
+    /// Returns the weight of the consistency rating.
+    #[getset(get_copy = "pub")]
+    consistency: f32,
+
+    /// Returns the weight of the incidents rating.
+    #[getset(get_copy = "pub")]
+    incidents: f32,
+}
+
+impl Default for PerformanceWeights {
+    fn default() -> Self {
+        PerformanceWeights::new(1.0, 1.0, 1.0, 1.0)
+    }
+}
+
+/// Performance score of a driver
+#[derive(Debug, Getters, CopyGetters, PartialEq, Clone, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DriverScore {
+    /// Returns the name of the driver.
+    #[getset(get = "pub")]
+    name: String,
+
+    /// Returns the rating of the qualifying pace against the teammate, if it is known.
+    #[getset(get_copy = "pub")]
+    qualifying: Option<f32>,
+
+    /// Returns the rating of the race pace, if it is known.
+    #[getset(get_copy = "pub")]
+    race_pace: Option<f32>,
+
+    /// Returns the rating of the consistency, if it is known.
+    #[getset(get_copy = "pub")]
+    consistency: Option<f32>,
+
+    /// Returns the rating of the incidents, if it is known.
+    #[getset(get_copy = "pub")]
+    incidents: Option<f32>,
+
+    /// Returns the weighted mean of the known ratings.
+    #[getset(get_copy = "pub")]
+    overall: Option<f32>,
+}
+
+/// Internal state that is tracked for each driver
+#[derive(Debug, Clone, PartialEq, Default)]
+struct DriverRatings {
+    qualifying: Vec<f32>,
+    race_pace: Vec<f32>,
+    consistency: Vec<f32>,
+    incidents: Vec<f32>,
+}
+
+/// Scorer for the performance of drivers over one or more sessions
+///
+/// The performance scorer consumes the results of sessions. Drivers are identified by their name,
+/// and the ratings of a driver are averaged over all sessions in which they could be rated.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PerformanceScorer {
+    weights: PerformanceWeights,
+    drivers: BTreeMap<String, DriverRatings>,
+}
+
+impl PerformanceScorer {
+    /// Create a new performance scorer with the given weights.
+    pub fn new(weights: PerformanceWeights) -> Self {
+        PerformanceScorer {
+            weights,
+            drivers: BTreeMap::new(),
+        }
+    }
+
+    /// Add the result of a session.
+    ///
+    /// The consistency of the drivers is only rated in races, and only if the consistency tracker
+    /// of the session is given.
+    pub fn add(&mut self, result: &SessionResult, consistency: Option<&ConsistencyTracker>) {
+        let session_type = result.session_type();
+
+        if session_type.is_qualifying() {
+            self.add_qualifying(result);
+        } else if session_type.is_race() {
+            self.add_race(result, consistency);
+        }
+    }
+
+    fn add_qualifying(&mut self, result: &SessionResult) {
+        let cars: Vec<&CarResult> = result
+            .classification()
+            .iter()
+            .filter(|car| *car.best_lap_time() > Duration::default())
+            .collect();
+
+        for car in cars.iter() {
+            let teammate = cars.iter().find(|teammate| {
+                teammate.vehicle_index() != car.vehicle_index()
+                    && teammate.team().is_some()
+                    && teammate.team() == car.team()
+            });
+
+            if let Some(teammate) = teammate {
+                let rating = match car.best_lap_time().cmp(teammate.best_lap_time()) {
+                    Ordering::Less => 100.0,
+                    Ordering::Equal => 50.0,
+                    Ordering::Greater => 0.0,
+                };
+
+                self.driver(car).qualifying.push(rating);
+            }
+        }
+    }
+
+    fn add_race(&mut self, result: &SessionResult, consistency: Option<&ConsistencyTracker>) {
+        let cars: Vec<&CarResult> = result
+            .classification()
+            .iter()
+            .filter(|car| car.laps() > 0)
+            .collect();
+
+        let pace: Vec<(&CarResult, f32)> = cars
+            .iter()
+            .map(|car| (*car, car.race_time().as_secs_f32() / car.laps() as f32))
+            .collect();
+        for (car, rating) in rate(&pace) {
+            self.driver(car).race_pace.push(rating);
+        }
+
+        let penalties: Vec<(&CarResult, f32)> = cars
+            .iter()
+            .map(|car| (*car, car.penalties().total_seconds() as f32))
+            .collect();
+        for (car, rating) in rate(&penalties) {
+            self.driver(car).incidents.push(rating);
+        }
+
+        if let Some(tracker) = consistency {
+            let deviations: Vec<(&CarResult, f32)> = cars
+                .iter()
+                .filter_map(|car| {
+                    let consistency = tracker.session(car.vehicle_index())?;
+                    Some((*car, consistency.standard_deviation().as_secs_f32()))
+                })
+                .collect();
+
+            for (car, rating) in rate(&deviations) {
+                self.driver(car).consistency.push(rating);
+            }
+        }
+    }
+
+    fn driver(&mut self, car: &CarResult) -> &mut DriverRatings {
+        self.drivers.entry(car.display_name()).or_default()
+    }
+
+    /// Returns the scores of all drivers, ordered from the highest to the lowest overall score.
+    pub fn scores(&self) -> Vec<DriverScore> {
+        let mut scores: Vec<DriverScore> = self
+            .drivers
+            .iter()
+            .map(|(name, ratings)| {
+                let qualifying = mean(&ratings.qualifying);
+                let race_pace = mean(&ratings.race_pace);
+                let consistency = mean(&ratings.consistency);
+                let incidents = mean(&ratings.incidents);
+
+                let weighted: Vec<(f32, f32)> = [
+                    (qualifying, self.weights.qualifying),
+                    (race_pace, self.weights.race_pace),
+                    (consistency, self.weights.consistency),
+                    (incidents, self.weights.incidents),
+                ]
+                .iter()
+                .filter_map(|(rating, weight)| rating.map(|rating| (rating, *weight)))
+                .collect();
+
+                let total_weight: f32 = weighted.iter().map(|(_, weight)| weight).sum();
+                let overall = if total_weight > 0.0 {
+                    Some(weighted.iter().map(|(r, w)| r * w).sum::<f32>() / total_weight)
+                } else {
+                    None
+                };
+
+                DriverScore {
+                    name: name.clone(),
+                    qualifying,
+                    race_pace,
+                    consistency,
+                    incidents,
+                    overall,
+                }
+            })
+            .collect();
+
+        scores.sort_by(|a, b| {
+            b.overall
+                .unwrap_or_default()
+                .total_cmp(&a.overall.unwrap_or_default())
+        });
+        scores
+    }
+}
+
+/// Rate values where lower is better, from 100 for the best to 0 for the worst value.
+///
+/// Drivers with the same value share the better rating.
+fn rate<'a>(values: &[(&'a CarResult, f32)]) -> Vec<(&'a CarResult, f32)> {
+    values
+        .iter()
+        .map(|(car, value)| {
+            let better = values.iter().filter(|(_, other)| other < value).count();
+            let rating = if values.len() > 1 {
+                100.0 - 100.0 * better as f32 / (values.len() - 1) as f32
+            } else {
+                100.0
+            };
+
+            (*car, rating)
+        })
+        .collect()
+}
+
+/// Returns the mean of the ratings, if there are any.
+fn mean(ratings: &[f32]) -> Option<f32> {
+    if ratings.is_empty() {
+        None
+    } else {
+        Some(ratings.iter().sum::<f32>() / ratings.len() as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::fixtures::{
+        lap_packet, participants_packet, session_packet, LapFixture, SessionFixture,
+    };
+    use crate::analysis::performance::{PerformanceScorer, PerformanceWeights};
+    use crate::analysis::result::{PointsSystem, ResultBuilder, SessionResult};
+    use crate::packet::session::Session;
+
+    fn session(session_type: Session, lap_times: Vec<f32>, penalties: Vec<u8>) -> SessionResult {
+        let mut builder = ResultBuilder::new(PointsSystem::default());
+        let lap = |index: usize, current_lap_number, last_lap_time| LapFixture {
+            position: index as u8 + 1,
+            current_lap_number,
+            last_lap_time,
+            penalties: penalties[index],
+            ..LapFixture::default()
+        };
+
+        builder.update(&session_packet(
+            0.0,
+            SessionFixture {
+                session_type,
+                ..SessionFixture::default()
+            },
+        ));
+        builder.update(&participants_packet(0.0, vec!["Alice", "Bob", "Carol"]));
+        builder.update(&lap_packet(
+            0.0,
+            (0..3).map(|index| lap(index, 1, 0.0)).collect(),
+        ));
+        builder.update(&lap_packet(
+            100.0,
+            (0..3)
+                .map(|index| lap(index, 2, lap_times[index]))
+                .collect(),
+        ));
+
+        builder.build()
+    }
+
+    #[test]
+    fn scores_with_qualifying_and_race() {
+        let mut scorer = PerformanceScorer::new(PerformanceWeights::default());
+
+        scorer.add(
+            &session(Session::Q3, vec![80.0, 79.5, 81.0], vec![0, 0, 0]),
+            None,
+        );
+        scorer.add(
+            &session(Session::Race, vec![90.0, 91.0, 92.0], vec![0, 5, 0]),
+            None,
+        );
+
+        let scores = scorer.scores();
+        assert_eq!("Alice", scores[0].name());
+        assert_approx_eq!(100.0, scores[0].race_pace().unwrap());
+        assert_approx_eq!(100.0, scores[0].incidents().unwrap());
+        assert_eq!(None, scores[0].consistency());
+
+        let bob = scores.iter().find(|score| score.name() == "Bob").unwrap();
+        assert_approx_eq!(50.0, bob.race_pace().unwrap());
+        assert_approx_eq!(0.0, bob.incidents().unwrap());
+    }
+}
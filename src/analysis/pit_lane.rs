@@ -0,0 +1,268 @@
+//! Time spent in the pit lane
+//!
+//! A pit stop costs a lot more time than the few seconds the car spends in its pit box. The speed
+//! limit in the pit lane means that every stop costs a fixed amount of time, which is different for
+//! each track and is a key input for strategy models. The pit lane analyzer measures the time each
+//! car spends between the pit entry and the pit exit, and how long it stands still in its box, and
+//! compares each stop with the average of the session.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::{LapPacket, PitStatus};
+use crate::packet::motion::MotionPacket;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Times of a car in the pit lane during a single pit stop
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+pub struct PitLaneTime {
+    /// Returns the index of the car.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the lap on which the car entered the pit lane.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the session time at which the car entered the pit lane.
+    #[getset(get = "pub")]
+    entry_time: Duration,
+
+    /// Returns the session time at which the car left the pit lane.
+    #[getset(get = "pub")]
+    exit_time: Duration,
+
+    /// Returns the time the car stood still in the pit lane.
+    #[getset(get = "pub")]
+    stationary_time: Duration,
+}
+
+impl PitLaneTime {
+    /// Returns the time between the pit entry and the pit exit.
+    pub fn pit_lane_time(&self) -> Duration {
+        self.exit_time.saturating_sub(self.entry_time)
+    }
+
+    /// Returns the time the car was moving in the pit lane.
+    pub fn moving_time(&self) -> Duration {
+        self.pit_lane_time().saturating_sub(self.stationary_time)
+    }
+}
+
+/// A visit to the pit lane that is still in progress
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct PitLaneVisit {
+    lap: u8,
+    entry_time: Duration,
+    in_pits_time: Duration,
+    motion_stationary_time: Option<Duration>,
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarPitLane {
+    pit_status: Option<(Duration, PitStatus)>,
+    last_motion: Option<(Duration, f32)>,
+    visit: Option<PitLaneVisit>,
+    stops: Vec<PitLaneTime>,
+}
+
+/// Analyzer for the pit lane times of all cars in the session
+///
+/// The pit lane analyzer consumes lap data and motion packets. The pit lane time is measured from
+/// the pit status in the lap data. A car is stationary while its speed is below the stationary
+/// speed, which is derived from the velocity in the motion packets. When no motion data has been
+/// received during a stop, the time in which the pit status was `InPits` is used instead.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PitLaneAnalyzer {
+    stationary_speed: f32,
+    cars: BTreeMap<VehicleIndex, CarPitLane>,
+}
+
+impl PitLaneAnalyzer {
+    /// Create a new pit lane analyzer with the given stationary speed in meters per second.
+    pub fn new(stationary_speed: f32) -> Self {
+        PitLaneAnalyzer {
+            stationary_speed,
+            cars: BTreeMap::new(),
+        }
+    }
+
+    /// Update the pit lane times with the data from the packet.
+    ///
+    /// The pit lane time of a stop is returned once the car has left the pit lane.
+    pub fn update(&mut self, packet: &Packet) -> Vec<PitLaneTime> {
+        match packet {
+            Packet::Lap(packet) => self.update_laps(packet),
+            Packet::Motion(packet) => {
+                self.update_motion(packet);
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) -> Vec<PitLaneTime> {
+        let session_time = *packet.header().session_time();
+        let mut stops = Vec::new();
+
+        for (vehicle_index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(vehicle_index).or_default();
+            let pit_status = lap.pit_status();
+
+            if let Some(visit) = car.visit.as_mut() {
+                if let Some((previous_time, PitStatus::InPits)) = car.pit_status {
+                    visit.in_pits_time += session_time.saturating_sub(previous_time);
+                }
+            } else if pit_status != PitStatus::None && car.pit_status.is_some() {
+                car.visit = Some(PitLaneVisit {
+                    lap: lap.current_lap_number(),
+                    entry_time: session_time,
+                    in_pits_time: Duration::default(),
+                    motion_stationary_time: None,
+                });
+            }
+
+            if pit_status == PitStatus::None {
+                if let Some(visit) = car.visit.take() {
+                    let stop = PitLaneTime::new(
+                        vehicle_index,
+                        visit.lap,
+                        visit.entry_time,
+                        session_time,
+                        visit.motion_stationary_time.unwrap_or(visit.in_pits_time),
+                    );
+
+                    car.stops.push(stop);
+                    stops.push(stop);
+                }
+            }
+
+            car.pit_status = Some((session_time, pit_status));
+        }
+
+        stops
+    }
+
+    fn update_motion(&mut self, packet: &MotionPacket) {
+        let session_time = *packet.header().session_time();
+
+        for (index, motion) in enumerate_cars(packet.cars()) {
+            let car = self.cars.entry(index).or_default();
+            let velocity = motion.velocity();
+            let speed = (velocity.x().powi(2) + velocity.y().powi(2) + velocity.z().powi(2)).sqrt();
+
+            if let (Some(visit), Some((previous_time, previous_speed))) =
+                (car.visit.as_mut(), car.last_motion)
+            {
+                let stationary_time = visit
+                    .motion_stationary_time
+                    .get_or_insert_with(Duration::default);
+
+                if previous_speed < self.stationary_speed {
+                    *stationary_time += session_time.saturating_sub(previous_time);
+                }
+            }
+
+            car.last_motion = Some((session_time, speed));
+        }
+    }
+
+    /// Returns the pit lane times of the stops of a car.
+    pub fn stops(&self, vehicle_index: VehicleIndex) -> &[PitLaneTime] {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.stops.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns the average pit lane time of all stops in the session.
+    pub fn average_pit_lane_time(&self) -> Option<Duration> {
+        let times: Vec<Duration> = self
+            .cars
+            .values()
+            .flat_map(|car| car.stops.iter())
+            .map(|stop| stop.pit_lane_time())
+            .collect();
+
+        if times.is_empty() {
+            None
+        } else {
+            Some(times.iter().sum::<Duration>() / times.len() as u32)
+        }
+    }
+
+    /// Returns the difference of a stop to the average pit lane time of the session in seconds.
+    ///
+    /// A negative difference means that the stop was quicker than the average.
+    pub fn delta_to_average(&self, stop: &PitLaneTime) -> Option<f32> {
+        let average = self.average_pit_lane_time()?;
+        Some(stop.pit_lane_time().as_secs_f32() - average.as_secs_f32())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::fixtures::{lap_packet, moving_motion_packet, LapFixture};
+    use crate::analysis::pit_lane::PitLaneAnalyzer;
+    use crate::packet::lap::PitStatus;
+    use crate::types::Property3D;
+
+    fn lap(pit_status: PitStatus) -> LapFixture {
+        LapFixture {
+            current_lap_number: 12,
+            pit_status,
+            ..LapFixture::default()
+        }
+    }
+
+    fn speed(x: f32) -> (Property3D<f32>, Property3D<f32>) {
+        (Property3D::default(), Property3D::new(x, 0.0, 0.0))
+    }
+
+    #[test]
+    fn update_with_pit_stops() {
+        let mut analyzer = PitLaneAnalyzer::new(0.5);
+
+        let mut stops = Vec::new();
+        for (time, first, second, first_speed) in [
+            (0.0, PitStatus::None, PitStatus::None, 80.0),
+            (10.0, PitStatus::Pitting, PitStatus::Pitting, 22.0),
+            (15.0, PitStatus::InPits, PitStatus::InPits, 0.0),
+            (18.0, PitStatus::Pitting, PitStatus::InPits, 22.0),
+            (28.0, PitStatus::None, PitStatus::Pitting, 80.0),
+            (32.0, PitStatus::None, PitStatus::None, 80.0),
+        ]
+        .iter()
+        {
+            analyzer.update(&moving_motion_packet(*time, vec![speed(*first_speed)]));
+            stops.extend(analyzer.update(&lap_packet(*time, vec![lap(*first), lap(*second)])));
+        }
+
+        assert_eq!(2, stops.len());
+
+        let first = stops[0];
+        assert_eq!(0, first.vehicle_index());
+        assert_eq!(Duration::from_secs(18), first.pit_lane_time());
+        assert_eq!(Duration::from_secs(3), *first.stationary_time());
+
+        let second = stops[1];
+        assert_eq!(1, second.vehicle_index());
+        assert_eq!(Duration::from_secs(22), second.pit_lane_time());
+        assert_eq!(Duration::from_secs(13), *second.stationary_time());
+
+        assert_eq!(
+            Some(Duration::from_secs(20)),
+            analyzer.average_pit_lane_time()
+        );
+        assert_approx_eq!(-2.0, analyzer.delta_to_average(&first).unwrap());
+    }
+}
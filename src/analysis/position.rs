@@ -0,0 +1,195 @@
+//! Overtakes and other changes in the running order
+//!
+//! Broadcast graphics highlight when a driver gains a position on track. The F1 2019 game does not
+//! publish an event for overtakes, so the position tracker derives them from the positions in the
+//! lap data packets. Positions can flicker briefly, for example when two cars cross a timing line
+//! side by side, which is why a swap is only reported once it has persisted for a configurable
+//! amount of time.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::analysis::is_racing;
+use crate::packet::lap::{Lap, LapPacket};
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Time for which a swap must persist before it is reported by the default position tracker
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// A change in the running order between two cars
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+pub struct PositionChange {
+    /// Returns the vehicle index of the car that gained the position.
+    #[getset(get_copy = "pub")]
+    gainer: VehicleIndex,
+
+    /// Returns the vehicle index of the car that lost the position.
+    #[getset(get_copy = "pub")]
+    loser: VehicleIndex,
+
+    /// Returns the lap of the gaining car on which the position changed.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the session time at which the position change was confirmed.
+    #[getset(get = "pub")]
+    session_time: Duration,
+}
+
+/// Relative order of a pair of cars
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct PairOrder {
+    first_ahead: bool,
+    swapped_since: Option<Duration>,
+}
+
+/// Tracker for changes in the running order
+///
+/// The position tracker compares the positions of every pair of cars in the lap data packets. When
+/// the order of two cars swaps, and stays swapped for at least the debounce time, a position change
+/// is reported. Cars that are not actively racing are ignored. The default tracker debounces swaps
+/// for `DEFAULT_DEBOUNCE`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PositionTracker {
+    debounce: Duration,
+    pairs: BTreeMap<VehicleIndex, BTreeMap<VehicleIndex, PairOrder>>,
+}
+
+impl PositionTracker {
+    /// Create a new position tracker that debounces swaps for the given time.
+    pub fn new(debounce: Duration) -> Self {
+        PositionTracker {
+            debounce,
+            pairs: BTreeMap::new(),
+        }
+    }
+
+    /// Update the running order and return the position changes detected in the packet.
+    pub fn update(&mut self, packet: &Packet) -> Vec<PositionChange> {
+        match packet {
+            Packet::Lap(packet) => self.update_laps(packet),
+            _ => Vec::new(),
+        }
+    }
+
+    pub(crate) fn update_laps(&mut self, packet: &LapPacket) -> Vec<PositionChange> {
+        let session_time = *packet.header().session_time();
+        let cars: Vec<(VehicleIndex, &Lap)> = enumerate_cars(packet.laps())
+            .filter(|(_, lap)| is_racing(lap))
+            .collect();
+
+        let mut changes = Vec::new();
+
+        for (position, (first, first_lap)) in cars.iter().enumerate() {
+            for (second, second_lap) in cars.iter().skip(position + 1) {
+                let first_ahead = first_lap.position() < second_lap.position();
+
+                let order = self
+                    .pairs
+                    .entry(*first)
+                    .or_default()
+                    .entry(*second)
+                    .or_insert(PairOrder {
+                        first_ahead,
+                        swapped_since: None,
+                    });
+
+                if order.first_ahead == first_ahead {
+                    order.swapped_since = None;
+                    continue;
+                }
+
+                let swapped_since = *order.swapped_since.get_or_insert(session_time);
+
+                if session_time.checked_sub(swapped_since).unwrap_or_default() < self.debounce {
+                    continue;
+                }
+
+                order.first_ahead = first_ahead;
+                order.swapped_since = None;
+
+                let (gainer, loser, lap) = if first_ahead {
+                    (*first, *second, first_lap.current_lap_number())
+                } else {
+                    (*second, *first, second_lap.current_lap_number())
+                };
+
+                changes.push(PositionChange::new(gainer, loser, lap, session_time));
+            }
+        }
+
+        changes
+    }
+}
+
+impl Default for PositionTracker {
+    fn default() -> Self {
+        PositionTracker::new(DEFAULT_DEBOUNCE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::fixtures::{lap_packet, vehicle, LapFixture};
+    use crate::analysis::position::{PositionChange, PositionTracker};
+
+    fn lap(position: u8) -> LapFixture {
+        LapFixture {
+            position,
+            current_lap_number: 3,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_overtake() {
+        let mut tracker = PositionTracker::new(Duration::from_secs(1));
+
+        assert!(tracker
+            .update(&lap_packet(0.0, vec![lap(1), lap(2)]))
+            .is_empty());
+        assert!(tracker
+            .update(&lap_packet(1.0, vec![lap(2), lap(1)]))
+            .is_empty());
+
+        let changes = tracker.update(&lap_packet(2.0, vec![lap(2), lap(1)]));
+
+        assert_eq!(
+            vec![PositionChange::new(
+                vehicle(1),
+                vehicle(0),
+                3,
+                Duration::from_secs(2)
+            )],
+            changes
+        );
+        assert!(tracker
+            .update(&lap_packet(3.0, vec![lap(2), lap(1)]))
+            .is_empty());
+    }
+
+    #[test]
+    fn update_debounces_transient_swap() {
+        let mut tracker = PositionTracker::new(Duration::from_secs(1));
+
+        tracker.update(&lap_packet(0.0, vec![lap(1), lap(2)]));
+        tracker.update(&lap_packet(0.5, vec![lap(2), lap(1)]));
+
+        assert!(tracker
+            .update(&lap_packet(1.0, vec![lap(1), lap(2)]))
+            .is_empty());
+        assert!(tracker
+            .update(&lap_packet(2.0, vec![lap(2), lap(1)]))
+            .is_empty());
+    }
+}
@@ -0,0 +1,77 @@
+//! Cars whose telemetry is restricted
+//!
+//! In multiplayer sessions, players can restrict the telemetry of their car. The games then set the
+//! private values of these cars to zero, for example their fuel, tyre wear, and ERS. Trackers that
+//! took these zeros as measurements would report a fuel burn, degradation, or deployment that never
+//! happened. The restrictions are read from the participants packets, so that the trackers can skip
+//! the private values of restricted cars and report them as unavailable.
+
+use std::collections::BTreeSet;
+
+use crate::packet::participants::{Participant, ParticipantsPacket, TelemetryPrivacy};
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Set of the cars whose telemetry is restricted
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TelemetryRestrictions {
+    restricted: BTreeSet<VehicleIndex>,
+}
+
+impl TelemetryRestrictions {
+    /// Create a new set of restrictions in which every car is public.
+    pub fn new() -> Self {
+        TelemetryRestrictions::default()
+    }
+
+    /// Update the restrictions with the telemetry settings of the participants, and return the
+    /// cars whose telemetry has become restricted.
+    pub fn update(&mut self, packet: &ParticipantsPacket) -> Vec<VehicleIndex> {
+        let mut restricted = Vec::new();
+
+        for (index, participant) in enumerate_cars(packet.participants()) {
+            if !is_restricted(participant) {
+                self.restricted.remove(&index);
+            } else if self.restricted.insert(index) {
+                restricted.push(index);
+            }
+        }
+
+        restricted
+    }
+
+    /// Returns whether the telemetry of the car is restricted.
+    pub fn is_restricted(&self, vehicle_index: VehicleIndex) -> bool {
+        self.restricted.contains(&vehicle_index)
+    }
+}
+
+/// Returns whether the participant has restricted the telemetry of their car.
+pub(crate) fn is_restricted(participant: &Participant) -> bool {
+    participant.telemetry_privacy() == Some(TelemetryPrivacy::Restricted)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::fixtures::{privacy_packet, vehicle};
+    use crate::analysis::privacy::TelemetryRestrictions;
+    use crate::packet::participants::TelemetryPrivacy;
+    use crate::packet::Packet;
+
+    #[test]
+    fn update_restrictions() {
+        let mut restrictions = TelemetryRestrictions::new();
+        let mut update = |settings| match privacy_packet(0.0, settings) {
+            Packet::Participants(packet) => restrictions.update(&packet),
+            packet => panic!("Unexpected packet {:?}", packet),
+        };
+
+        let settings = vec![TelemetryPrivacy::Public, TelemetryPrivacy::Restricted];
+        assert_eq!(vec![vehicle(1)], update(settings.clone()));
+        assert!(update(settings).is_empty());
+
+        let settings = vec![TelemetryPrivacy::Restricted, TelemetryPrivacy::Public];
+        assert_eq!(vec![vehicle(0)], update(settings));
+        assert!(restrictions.is_restricted(vehicle(0)));
+        assert!(!restrictions.is_restricted(vehicle(1)));
+    }
+}
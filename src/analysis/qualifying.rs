@@ -0,0 +1,362 @@
+//! Analysis of qualifying sessions
+//!
+//! Qualifying follows a different rhythm than a race. Drivers leave the garage for short runs with
+//! an out-lap, one or more push laps, and an in-lap, and the slowest drivers are eliminated at the
+//! end of Q1 and Q2. The qualifying analyzer classifies the laps of every driver, groups them into
+//! runs, and maintains the provisional grid with the drivers at risk of elimination and the time
+//! they need to find to advance.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::{DriverStatus, Lap, LapPacket};
+use crate::packet::session::Session;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Types of laps in a qualifying run
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+pub enum RunLap {
+    /// The lap after leaving the garage, which is used to prepare the tyres and brakes.
+    OutLap,
+
+    /// A flying lap on which the driver sets a time.
+    PushLap,
+
+    /// The lap back to the garage.
+    InLap,
+
+    /// Any other lap, for example a cool-down lap between two push laps.
+    #[default]
+    Other,
+}
+
+/// A completed lap in qualifying
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+pub struct QualifyingLap {
+    /// Returns the number of the lap.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the type of the lap.
+    #[getset(get_copy = "pub")]
+    kind: RunLap,
+
+    /// Returns the time of the lap.
+    #[getset(get = "pub")]
+    lap_time: Duration,
+
+    /// Returns whether the lap was valid.
+    #[getset(get_copy = "pub")]
+    is_valid: bool,
+}
+
+/// A run from the garage and back
+#[derive(Debug, Getters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+pub struct QualifyingRun {
+    /// Returns the completed laps of the run.
+    #[getset(get = "pub")]
+    laps: Vec<QualifyingLap>,
+}
+
+impl QualifyingRun {
+    /// Returns the fastest valid push lap of the run.
+    pub fn best_lap(&self) -> Option<&QualifyingLap> {
+        self.laps
+            .iter()
+            .filter(|lap| lap.kind == RunLap::PushLap && lap.is_valid)
+            .min_by_key(|lap| lap.lap_time)
+    }
+}
+
+/// Position of a car on the provisional grid
+#[derive(
+    Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+pub struct GridPosition {
+    /// Returns the provisional grid position of the car.
+    #[getset(get_copy = "pub")]
+    position: u8,
+
+    /// Returns the index of the car.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the best lap time of the car, if it has set one.
+    #[getset(get_copy = "pub")]
+    best_lap_time: Option<Duration>,
+
+    /// Returns the gap to the fastest car.
+    #[getset(get_copy = "pub")]
+    gap_to_pole: Option<Duration>,
+
+    /// Returns whether the car would be eliminated if the session ended now.
+    #[getset(get_copy = "pub")]
+    is_at_risk: bool,
+
+    /// Returns how much faster the car has to go to advance to the next session.
+    ///
+    /// No time is returned for cars that are not at risk, or for cars that have not set a time.
+    #[getset(get_copy = "pub")]
+    time_to_advance: Option<Duration>,
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarRuns {
+    previous: Option<Lap>,
+    runs: Vec<QualifyingRun>,
+}
+
+impl CarRuns {
+    fn update(&mut self, lap: &Lap) {
+        let previous_status = self
+            .previous
+            .map(|previous| previous.driver_status())
+            .unwrap_or(DriverStatus::InGarage);
+
+        if previous_status == DriverStatus::InGarage
+            && lap.driver_status() != DriverStatus::InGarage
+        {
+            self.runs.push(QualifyingRun::default());
+        }
+
+        if let Some(previous) = self.previous {
+            let has_crossed_line = lap.current_lap_number() > previous.current_lap_number();
+
+            if has_crossed_line && *lap.last_lap_time() > Duration::default() {
+                let kind = match previous.driver_status() {
+                    DriverStatus::OutLap => RunLap::OutLap,
+                    DriverStatus::FlyingLap => RunLap::PushLap,
+                    DriverStatus::InLap => RunLap::InLap,
+                    _ => RunLap::Other,
+                };
+
+                if let Some(run) = self.runs.last_mut() {
+                    run.laps.push(QualifyingLap::new(
+                        previous.current_lap_number(),
+                        kind,
+                        *lap.last_lap_time(),
+                        previous.is_valid_lap(),
+                    ));
+                }
+            }
+        }
+
+        self.previous = Some(*lap);
+    }
+}
+
+/// Analyzer for qualifying sessions
+///
+/// The qualifying analyzer consumes lap data and session packets. Laps are classified by the driver
+/// status that the game publishes, and the provisional grid is ordered by the best lap time of each
+/// car. Cars are only at risk of elimination in Q1 and Q2.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct QualifyingAnalyzer {
+    q1_cutoff: u8,
+    q2_cutoff: u8,
+    session_type: Session,
+    cars: BTreeMap<VehicleIndex, CarRuns>,
+}
+
+impl QualifyingAnalyzer {
+    /// Create a new qualifying analyzer.
+    ///
+    /// The cutoffs are the number of cars that advance from Q1 and Q2 to the next session.
+    pub fn new(q1_cutoff: u8, q2_cutoff: u8) -> Self {
+        QualifyingAnalyzer {
+            q1_cutoff,
+            q2_cutoff,
+            ..QualifyingAnalyzer::default()
+        }
+    }
+
+    /// Update the analysis with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Lap(packet) => self.update_laps(packet),
+            Packet::Session(packet) => self.session_type = packet.session_type(),
+            _ => {}
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (index, lap) in enumerate_cars(packet.laps()) {
+            self.cars.entry(index).or_default().update(lap);
+        }
+    }
+
+    /// Returns the runs of a car.
+    pub fn runs(&self, vehicle_index: VehicleIndex) -> &[QualifyingRun] {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.runs.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns the number of cars that advance to the next session, if cars are eliminated.
+    pub fn cutoff(&self) -> Option<u8> {
+        match self.session_type {
+            Session::Q1 => Some(self.q1_cutoff),
+            Session::Q2 => Some(self.q2_cutoff),
+            _ => None,
+        }
+    }
+
+    /// Returns the lap time that is currently needed to advance to the next session.
+    pub fn cutoff_time(&self) -> Option<Duration> {
+        let cutoff = self.cutoff()?;
+
+        self.grid()
+            .iter()
+            .find(|position| position.position == cutoff)
+            .and_then(|position| position.best_lap_time)
+    }
+
+    /// Returns the provisional grid, ordered by the best lap time of each car.
+    ///
+    /// Cars without a lap time are placed behind all cars with a time, in the order of their
+    /// position in the lap data.
+    pub fn grid(&self) -> Vec<GridPosition> {
+        let mut cars: Vec<(VehicleIndex, &Lap)> = self
+            .cars
+            .iter()
+            .filter_map(|(index, car)| car.previous.as_ref().map(|lap| (*index, lap)))
+            .filter(|(_, lap)| lap.position() > 0)
+            .collect();
+
+        cars.sort_by_key(|(_, lap)| {
+            (
+                best_lap_time(lap).is_none(),
+                best_lap_time(lap),
+                lap.position(),
+            )
+        });
+
+        let pole = cars.first().and_then(|(_, lap)| best_lap_time(lap));
+        let cutoff = self.cutoff();
+        let cutoff_time = cutoff
+            .and_then(|cutoff| (cutoff as usize).checked_sub(1))
+            .and_then(|index| cars.get(index))
+            .and_then(|(_, lap)| best_lap_time(lap));
+
+        cars.iter()
+            .enumerate()
+            .map(|(index, (vehicle_index, lap))| {
+                let position = index as u8 + 1;
+                let best_lap_time = best_lap_time(lap);
+                let is_at_risk = cutoff.is_some_and(|cutoff| position > cutoff);
+
+                GridPosition {
+                    position,
+                    vehicle_index: *vehicle_index,
+                    best_lap_time,
+                    gap_to_pole: best_lap_time
+                        .zip(pole)
+                        .map(|(best_lap_time, pole)| best_lap_time.saturating_sub(pole)),
+                    is_at_risk,
+                    time_to_advance: best_lap_time.zip(cutoff_time).filter(|_| is_at_risk).map(
+                        |(best_lap_time, cutoff_time)| best_lap_time.saturating_sub(cutoff_time),
+                    ),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returns the best lap time of a car, if it has set one.
+fn best_lap_time(lap: &Lap) -> Option<Duration> {
+    Some(*lap.best_lap_time()).filter(|time| *time > Duration::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::fixtures::{
+        lap_packet, session_packet, vehicle, LapFixture, SessionFixture,
+    };
+    use crate::analysis::qualifying::{QualifyingAnalyzer, RunLap};
+    use crate::packet::lap::DriverStatus;
+    use crate::packet::session::Session;
+    use crate::types::VehicleIndex;
+
+    fn lap(current_lap_number: u8, last_lap_time: f32, driver_status: DriverStatus) -> LapFixture {
+        LapFixture {
+            current_lap_number,
+            last_lap_time,
+            driver_status,
+            ..LapFixture::default()
+        }
+    }
+
+    fn best(position: u8, best_lap_time: f32) -> LapFixture {
+        LapFixture {
+            position,
+            best_lap_time,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_run() {
+        let mut analyzer = QualifyingAnalyzer::new(15, 10);
+        let laps = [
+            lap(1, 0.0, DriverStatus::InGarage),
+            lap(1, 0.0, DriverStatus::OutLap),
+            lap(2, 100.0, DriverStatus::FlyingLap),
+            lap(3, 80.0, DriverStatus::InLap),
+            lap(4, 110.0, DriverStatus::InGarage),
+        ];
+
+        for lap in laps.iter() {
+            analyzer.update(&lap_packet(0.0, vec![*lap]));
+        }
+
+        let runs = analyzer.runs(vehicle(0));
+        assert_eq!(1, runs.len());
+
+        let kinds: Vec<RunLap> = runs[0].laps().iter().map(|lap| lap.kind()).collect();
+        assert_eq!(vec![RunLap::OutLap, RunLap::PushLap, RunLap::InLap], kinds);
+        assert_eq!(
+            Duration::from_secs(80),
+            *runs[0].best_lap().unwrap().lap_time()
+        );
+    }
+
+    #[test]
+    fn grid_with_elimination() {
+        let mut analyzer = QualifyingAnalyzer::new(2, 1);
+
+        analyzer.update(&session_packet(
+            0.0,
+            SessionFixture {
+                session_type: Session::Q1,
+                ..SessionFixture::default()
+            },
+        ));
+        analyzer.update(&lap_packet(
+            0.0,
+            vec![best(1, 81.0), best(2, 80.0), best(3, 82.5), best(4, 0.0)],
+        ));
+
+        let grid = analyzer.grid();
+        let order: Vec<VehicleIndex> = grid
+            .iter()
+            .map(|position| position.vehicle_index())
+            .collect();
+        assert_eq!(vec![1, 0, 2, 3], order);
+
+        assert!(!grid[1].is_at_risk());
+        assert!(grid[2].is_at_risk());
+        assert_eq!(Some(Duration::from_millis(1500)), grid[2].time_to_advance());
+        assert_eq!(None, grid[3].time_to_advance());
+        assert_eq!(Some(Duration::from_secs(81)), analyzer.cutoff_time());
+    }
+}
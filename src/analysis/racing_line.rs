@@ -0,0 +1,376 @@
+//! Racing lines and their comparison
+//!
+//! The racing line is the path a driver takes around the track, and small differences in the line
+//! can make a big difference in lap time. The racing line recorder captures the position and the
+//! speed of every car along the lap distance, and stores a racing line for each completed lap. Two
+//! racing lines can be compared to find where they diverge, and how the speed differs between them.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::LapPacket;
+use crate::packet::motion::MotionPacket;
+use crate::packet::telemetry::TelemetryPacket;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, Property3D, VehicleIndex};
+
+/// A point on a racing line
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct RacingLinePoint {
+    /// Returns the distance around the lap in meters.
+    #[getset(get_copy = "pub")]
+    lap_distance: f32,
+
+    /// Returns the position of the car in the world.
+    #[getset(get = "pub")]
+    position: Property3D<f32>,
+
+    /// Returns the speed of the car in kilometers per hour.
+    #[getset(get_copy = "pub")]
+    speed: u16,
+}
+
+/// Racing line of a car during a lap
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Clone, PartialOrd, Default)]
+pub struct RacingLine {
+    /// Returns the vehicle index of the car that drove the lap.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the number of the lap.
+    #[getset(get_copy = "pub")]
+    lap_number: u8,
+
+    /// Returns the points of the racing line, ordered by their lap distance.
+    #[getset(get = "pub")]
+    points: Vec<RacingLinePoint>,
+}
+
+/// Difference between two racing lines at a point of the track
+#[derive(new, Debug, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct LineDifference {
+    /// Returns the distance around the lap in meters.
+    #[getset(get_copy = "pub")]
+    lap_distance: f32,
+
+    /// Returns the distance between the two racing lines in meters.
+    #[getset(get_copy = "pub")]
+    offset: f32,
+
+    /// Returns the speed of the other lap minus the speed of this lap in kilometers per hour.
+    #[getset(get_copy = "pub")]
+    speed_difference: f32,
+}
+
+/// Section of the track where two racing lines diverge
+#[derive(new, Debug, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct Divergence {
+    /// Returns the lap distance at which the racing lines start to diverge.
+    #[getset(get_copy = "pub")]
+    start_distance: f32,
+
+    /// Returns the lap distance at which the racing lines converge again.
+    #[getset(get_copy = "pub")]
+    end_distance: f32,
+
+    /// Returns the largest distance between the racing lines in the section.
+    #[getset(get_copy = "pub")]
+    max_offset: f32,
+}
+
+/// Comparison of two racing lines
+#[derive(new, Debug, Getters, PartialEq, Clone, PartialOrd, Default)]
+pub struct LineComparison {
+    /// Returns the differences between the racing lines at regular intervals.
+    #[getset(get = "pub")]
+    differences: Vec<LineDifference>,
+
+    /// Returns the sections of the track where the racing lines diverge.
+    #[getset(get = "pub")]
+    divergences: Vec<Divergence>,
+}
+
+impl RacingLine {
+    /// Returns the interpolated position and speed at the lap distance.
+    fn sample(&self, lap_distance: f32) -> Option<(Property3D<f32>, f32)> {
+        let index = self
+            .points
+            .iter()
+            .position(|point| point.lap_distance >= lap_distance)?;
+        let after = self.points[index];
+
+        if after.lap_distance == lap_distance {
+            return Some((after.position, after.speed as f32));
+        }
+
+        let before = self.points[index.checked_sub(1)?];
+        let fraction =
+            (lap_distance - before.lap_distance) / (after.lap_distance - before.lap_distance);
+        let interpolate = |from: f32, to: f32| from + (to - from) * fraction;
+
+        let position = Property3D::new(
+            interpolate(before.position.x(), after.position.x()),
+            interpolate(before.position.y(), after.position.y()),
+            interpolate(before.position.z(), after.position.z()),
+        );
+
+        Some((
+            position,
+            interpolate(before.speed as f32, after.speed as f32),
+        ))
+    }
+
+    /// Compare the racing line with another one.
+    ///
+    /// The racing lines are compared every `step` meters over the lap distance that both of them
+    /// cover. Sections where the racing lines are further apart than the threshold in meters are
+    /// reported as divergences.
+    pub fn compare(&self, other: &RacingLine, step: f32, threshold: f32) -> LineComparison {
+        let mut differences = Vec::new();
+        let mut divergences = Vec::new();
+        let mut divergence: Option<Divergence> = None;
+
+        let (start, end) = match (self.coverage(), other.coverage()) {
+            (Some((start, end)), Some((other_start, other_end))) => {
+                (start.max(other_start), end.min(other_end))
+            }
+            _ => return LineComparison::default(),
+        };
+
+        let step = step.max(f32::EPSILON);
+        let mut lap_distance = start;
+
+        while lap_distance <= end {
+            if let (Some((position, speed)), Some((other_position, other_speed))) =
+                (self.sample(lap_distance), other.sample(lap_distance))
+            {
+                let offset = ((other_position.x() - position.x()).powi(2)
+                    + (other_position.y() - position.y()).powi(2)
+                    + (other_position.z() - position.z()).powi(2))
+                .sqrt();
+
+                differences.push(LineDifference::new(
+                    lap_distance,
+                    offset,
+                    other_speed - speed,
+                ));
+
+                if offset > threshold {
+                    let current = divergence.get_or_insert(Divergence::new(
+                        lap_distance,
+                        lap_distance,
+                        offset,
+                    ));
+                    current.end_distance = lap_distance;
+                    current.max_offset = current.max_offset.max(offset);
+                } else if let Some(finished) = divergence.take() {
+                    divergences.push(finished);
+                }
+            }
+
+            lap_distance += step;
+        }
+
+        divergences.extend(divergence);
+
+        LineComparison::new(differences, divergences)
+    }
+
+    /// Returns the first and the last lap distance of the racing line.
+    fn coverage(&self) -> Option<(f32, f32)> {
+        Some((
+            self.points.first()?.lap_distance,
+            self.points.last()?.lap_distance,
+        ))
+    }
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarLine {
+    lap: Option<u8>,
+    lap_distance: Option<f32>,
+    speed: u16,
+    is_complete: bool,
+    points: Vec<RacingLinePoint>,
+    laps: Vec<RacingLine>,
+}
+
+/// Recorder for the racing lines of all cars in the session
+///
+/// The recorder consumes lap data, telemetry, and motion packets. A point is added to the racing
+/// line of a car with every motion packet, using the latest lap distance and speed of the car. Only
+/// laps that were observed from start to finish are stored.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RacingLineRecorder {
+    cars: BTreeMap<VehicleIndex, CarLine>,
+}
+
+impl RacingLineRecorder {
+    /// Create a new recorder without any racing lines.
+    pub fn new() -> Self {
+        RacingLineRecorder::default()
+    }
+
+    /// Update the racing lines with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Lap(packet) => self.update_laps(packet),
+            Packet::Motion(packet) => self.update_motion(packet),
+            Packet::Telemetry(packet) => self.update_telemetry(packet),
+            _ => {}
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (vehicle_index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(vehicle_index).or_default();
+            let lap_number = lap.current_lap_number();
+
+            match car.lap {
+                Some(previous) if lap_number != previous => {
+                    let points = std::mem::take(&mut car.points);
+
+                    if car.is_complete && lap_number > previous && !points.is_empty() {
+                        car.laps
+                            .push(RacingLine::new(vehicle_index, previous, points));
+                    }
+
+                    car.is_complete = lap_number > previous;
+                }
+                Some(_) => {}
+                None => car.is_complete = *lap.current_lap_time() == Duration::ZERO,
+            }
+
+            car.lap = Some(lap_number);
+            car.lap_distance = Some(lap.lap_distance()).filter(|distance| *distance >= 0.0);
+        }
+    }
+
+    fn update_telemetry(&mut self, packet: &TelemetryPacket) {
+        for (index, telemetry) in enumerate_cars(packet.telemetry()) {
+            self.cars.entry(index).or_default().speed = telemetry.speed().as_kph();
+        }
+    }
+
+    fn update_motion(&mut self, packet: &MotionPacket) {
+        for (index, motion) in enumerate_cars(packet.cars()) {
+            let car = match self.cars.get_mut(&index) {
+                Some(car) => car,
+                None => continue,
+            };
+
+            let lap_distance = match car.lap_distance {
+                Some(lap_distance) => lap_distance,
+                None => continue,
+            };
+
+            let is_ahead = car
+                .points
+                .last()
+                .map(|point| lap_distance > point.lap_distance)
+                .unwrap_or(true);
+
+            if is_ahead {
+                car.points.push(RacingLinePoint::new(
+                    lap_distance,
+                    *motion.position(),
+                    car.speed,
+                ));
+            }
+        }
+    }
+
+    /// Returns the racing lines of the completed laps of a car.
+    pub fn laps(&self, vehicle_index: VehicleIndex) -> &[RacingLine] {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.laps.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns the racing line of a car on the given lap.
+    pub fn lap(&self, vehicle_index: VehicleIndex, lap_number: u8) -> Option<&RacingLine> {
+        self.laps(vehicle_index)
+            .iter()
+            .find(|line| line.lap_number == lap_number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::fixtures::{
+        lap_packet, motion_packet, telemetry_packet, vehicle, LapFixture, TelemetryFixture,
+    };
+    use crate::analysis::racing_line::{RacingLine, RacingLinePoint, RacingLineRecorder};
+    use crate::types::Property3D;
+
+    fn line(offsets: &[f32], speed: u16) -> RacingLine {
+        let points = offsets
+            .iter()
+            .enumerate()
+            .map(|(index, offset)| {
+                let distance = index as f32 * 10.0;
+                RacingLinePoint::new(distance, Property3D::new(distance, 0.0, *offset), speed)
+            })
+            .collect();
+
+        RacingLine::new(vehicle(0), 1, points)
+    }
+
+    #[test]
+    fn compare_with_divergence() {
+        let reference = line(&[0.0, 0.0, 0.0, 0.0, 0.0], 200);
+        let other = line(&[0.0, 2.0, 3.0, 0.0, 0.0], 210);
+
+        let comparison = reference.compare(&other, 10.0, 1.0);
+
+        assert_eq!(5, comparison.differences().len());
+        assert_approx_eq!(10.0, comparison.differences()[0].speed_difference());
+        assert_approx_eq!(3.0, comparison.differences()[2].offset());
+
+        assert_eq!(1, comparison.divergences().len());
+        let divergence = comparison.divergences()[0];
+        assert_approx_eq!(10.0, divergence.start_distance());
+        assert_approx_eq!(20.0, divergence.end_distance());
+        assert_approx_eq!(3.0, divergence.max_offset());
+    }
+
+    #[test]
+    fn update_records_completed_laps() {
+        let mut recorder = RacingLineRecorder::new();
+
+        for (lap_number, lap_distance) in &[(1, 0.0), (1, 100.0), (1, 200.0), (2, 0.0)] {
+            recorder.update(&lap_packet(
+                0.0,
+                vec![LapFixture {
+                    current_lap_number: *lap_number,
+                    lap_distance: *lap_distance,
+                    ..LapFixture::default()
+                }],
+            ));
+            recorder.update(&telemetry_packet(
+                0.0,
+                vec![TelemetryFixture {
+                    speed: 250,
+                    ..TelemetryFixture::default()
+                }],
+            ));
+            recorder.update(&motion_packet(
+                0.0,
+                vec![Property3D::new(*lap_distance, 0.0, 0.0)],
+            ));
+        }
+
+        let line = recorder.lap(vehicle(0), 1).unwrap();
+
+        assert_eq!(3, line.points().len());
+        assert_eq!(250, line.points()[2].speed());
+        assert!(recorder.lap(vehicle(0), 2).is_none());
+    }
+}
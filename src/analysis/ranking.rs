@@ -0,0 +1,124 @@
+//! Ordering of lap and sector times
+//!
+//! The F1 games report lap and sector times that have not been set yet as zero. Sorting these times
+//! naively puts a driver without a lap at the top of the leaderboard, with a time of 0.000. Laps
+//! that have been invalidated, for example for exceeding the track limits, must not be ranked ahead
+//! of valid laps either. The ranked time orders valid times first, invalidated times second, and
+//! missing times last, and the comparators in this module use it to sort times and laps.
+
+use std::cmp::Ordering;
+use std::time::Duration;
+
+use crate::packet::lap::Lap;
+
+/// Time of a lap or sector that is ordered for a leaderboard
+///
+/// Valid times are ordered before invalidated times, and both are ordered before missing times.
+/// Times of the same kind are ordered from fastest to slowest.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RankedTime {
+    /// A valid time.
+    Valid(Duration),
+
+    /// A time that has been set on an invalidated lap.
+    Invalidated(Duration),
+
+    /// No time has been set.
+    #[default]
+    Missing,
+}
+
+impl RankedTime {
+    /// Rank a time as reported by the games, where a time of zero means that no time has been set.
+    pub fn new(time: Duration, is_valid: bool) -> Self {
+        if time.is_zero() {
+            RankedTime::Missing
+        } else if is_valid {
+            RankedTime::Valid(time)
+        } else {
+            RankedTime::Invalidated(time)
+        }
+    }
+
+    /// Rank a time that might not have been set.
+    pub fn from_option(time: Option<Duration>, is_valid: bool) -> Self {
+        time.map_or(RankedTime::Missing, |time| RankedTime::new(time, is_valid))
+    }
+
+    /// Returns the time, if it has been set and is valid.
+    pub fn valid(&self) -> Option<Duration> {
+        match self {
+            RankedTime::Valid(time) => Some(*time),
+            _ => None,
+        }
+    }
+}
+
+/// Compare two times as reported by the games, with times of zero ordered last.
+pub fn compare_times(a: &Duration, b: &Duration) -> Ordering {
+    RankedTime::new(*a, true).cmp(&RankedTime::new(*b, true))
+}
+
+/// Compare two sector times that might not have been set, with missing times ordered last.
+pub fn compare_sector_times(a: Option<Duration>, b: Option<Duration>) -> Ordering {
+    RankedTime::from_option(a, true).cmp(&RankedTime::from_option(b, true))
+}
+
+/// Compare the best lap times of two cars, with cars that have not set a lap time ordered last.
+pub fn compare_best_laps(a: &Lap, b: &Lap) -> Ordering {
+    compare_times(a.best_lap_time(), b.best_lap_time())
+}
+
+/// Compare the last lap times of two cars, with cars that have not completed a lap ordered last.
+pub fn compare_last_laps(a: &Lap, b: &Lap) -> Ordering {
+    compare_times(a.last_lap_time(), b.last_lap_time())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::fixtures::LapFixture;
+    use crate::analysis::ranking::{compare_best_laps, compare_sector_times, RankedTime};
+    use crate::packet::lap::Lap;
+
+    #[test]
+    fn rank_times_with_missing_and_invalidated_laps() {
+        let mut times = vec![
+            RankedTime::new(Duration::ZERO, true),
+            RankedTime::new(Duration::from_secs(89), false),
+            RankedTime::new(Duration::from_secs(91), true),
+            RankedTime::from_option(None, true),
+            RankedTime::new(Duration::from_secs(90), true),
+        ];
+        times.sort();
+
+        assert_eq!(
+            vec![
+                RankedTime::Valid(Duration::from_secs(90)),
+                RankedTime::Valid(Duration::from_secs(91)),
+                RankedTime::Invalidated(Duration::from_secs(89)),
+                RankedTime::Missing,
+                RankedTime::Missing,
+            ],
+            times
+        );
+
+        let mut laps: Vec<Lap> = vec![0.0, 92.0, 90.5]
+            .into_iter()
+            .map(|best_lap_time| {
+                LapFixture {
+                    best_lap_time,
+                    ..LapFixture::default()
+                }
+                .into()
+            })
+            .collect();
+        laps.sort_by(compare_best_laps);
+        assert_eq!(&Duration::from_secs_f32(90.5), laps[0].best_lap_time());
+        assert!(laps[2].best_lap_time().is_zero());
+
+        assert!(compare_sector_times(None, Some(Duration::from_secs(30))).is_gt());
+    }
+}
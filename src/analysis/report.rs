@@ -0,0 +1,339 @@
+//! Race reports
+//!
+//! After a race, league admins publish a report that goes beyond the classification, with a lap
+//! chart, a summary of the pit stops, the fastest laps of every driver, and the periods under the
+//! safety car. The report builder collects all of this data while the session is running, and
+//! assembles it into a single document when the session ends. With the `serde` feature enabled, the
+//! report can be serialized and rendered by other tools.
+//!
+//! The penalties of each driver are part of their result in the classification.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::analysis::result::{PointsSystem, ResultBuilder, SessionResult};
+use crate::analysis::safety_car::{NeutralizedPeriod, SafetyCarTracker};
+use crate::analysis::stint::StintTracker;
+use crate::packet::lap::LapPacket;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Order of the cars at the end of a lap
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LapChartLap {
+    /// Returns the number of the lap.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the indices of the cars that completed the lap, ordered by their position.
+    #[getset(get = "pub")]
+    order: Vec<VehicleIndex>,
+}
+
+/// Summary of a pit stop
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PitStopSummary {
+    /// Returns the index of the car.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the name of the driver.
+    #[getset(get = "pub")]
+    name: String,
+
+    /// Returns the lap on which the car entered the pit lane.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the time the car spent in the pit lane.
+    #[getset(get = "pub")]
+    pit_lane_time: Duration,
+
+    /// Returns the time the car spent stationary in its pit box.
+    #[getset(get = "pub")]
+    stationary_time: Duration,
+}
+
+/// The fastest lap of a driver
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FastestLapEntry {
+    /// Returns the rank of the lap among the fastest laps of all drivers.
+    #[getset(get_copy = "pub")]
+    position: u8,
+
+    /// Returns the index of the car.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the name of the driver.
+    #[getset(get = "pub")]
+    name: String,
+
+    /// Returns the number of the lap.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the lap time.
+    #[getset(get = "pub")]
+    lap_time: Duration,
+}
+
+/// Report of a race
+#[derive(Debug, Getters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RaceReport {
+    /// Returns the result of the session.
+    #[getset(get = "pub")]
+    result: SessionResult,
+
+    /// Returns the order of the cars at the end of each lap.
+    #[getset(get = "pub")]
+    lap_chart: Vec<LapChartLap>,
+
+    /// Returns the pit stops of all cars, in the order in which they were made.
+    #[getset(get = "pub")]
+    pit_stops: Vec<PitStopSummary>,
+
+    /// Returns the fastest lap of each driver, from the fastest to the slowest.
+    #[getset(get = "pub")]
+    fastest_laps: Vec<FastestLapEntry>,
+
+    /// Returns the periods in which the race was neutralized.
+    #[getset(get = "pub")]
+    neutralized_periods: Vec<NeutralizedPeriod>,
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarLaps {
+    lap: Option<u8>,
+    fastest_lap: Option<(u8, Duration)>,
+}
+
+/// Builder for the report of a race
+///
+/// The report builder consumes the same packets as the result builder, and in addition the session
+/// packets for the safety car. It returns the report when the session ends, and can build a
+/// provisional report at any time.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ReportBuilder {
+    results: ResultBuilder,
+    stints: StintTracker,
+    safety_car: SafetyCarTracker,
+    lap_chart: BTreeMap<u8, BTreeMap<u8, VehicleIndex>>,
+    cars: BTreeMap<VehicleIndex, CarLaps>,
+}
+
+impl ReportBuilder {
+    /// Create a new report builder that awards points with the given points system.
+    pub fn new(points_system: PointsSystem) -> Self {
+        ReportBuilder {
+            results: ResultBuilder::new(points_system),
+            ..ReportBuilder::default()
+        }
+    }
+
+    /// Update the report with the data from the packet.
+    ///
+    /// Returns the report of the race when the session has ended.
+    pub fn update(&mut self, packet: &Packet) -> Option<RaceReport> {
+        self.stints.update(packet);
+        self.safety_car.update(packet);
+
+        if let Packet::Lap(packet) = packet {
+            self.update_laps(packet);
+        }
+
+        self.results
+            .update(packet)
+            .map(|result| self.build_with(result))
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (vehicle_index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(vehicle_index).or_default();
+            let lap_number = lap.current_lap_number();
+
+            if let Some(previous) = car.lap.filter(|previous| lap_number > *previous) {
+                self.lap_chart
+                    .entry(previous)
+                    .or_default()
+                    .insert(lap.position(), vehicle_index);
+
+                let lap_time = *lap.last_lap_time();
+                if lap_time > Duration::default()
+                    && car
+                        .fastest_lap
+                        .is_none_or(|(_, fastest)| lap_time < fastest)
+                {
+                    car.fastest_lap = Some((previous, lap_time));
+                }
+            }
+
+            car.lap = Some(lap_number);
+        }
+    }
+
+    /// Build a provisional report from the data received so far.
+    pub fn build(&self) -> RaceReport {
+        self.build_with(self.results.build())
+    }
+
+    fn build_with(&self, result: SessionResult) -> RaceReport {
+        let name = |vehicle_index: VehicleIndex| {
+            result
+                .classification()
+                .iter()
+                .find(|car| car.vehicle_index() == vehicle_index)
+                .map(|car| car.display_name())
+                .unwrap_or_else(|| format!("Car #{}", vehicle_index))
+        };
+
+        let lap_chart = self
+            .lap_chart
+            .iter()
+            .map(|(lap, positions)| LapChartLap::new(*lap, positions.values().copied().collect()))
+            .collect();
+
+        let mut pit_stops: Vec<(Duration, PitStopSummary)> = self
+            .stints
+            .histories()
+            .flat_map(|(vehicle_index, history)| {
+                history
+                    .pit_stops()
+                    .iter()
+                    .map(move |pit_stop| (*pit_stop.entry_time(), vehicle_index, *pit_stop))
+            })
+            .map(|(entry_time, vehicle_index, pit_stop)| {
+                let summary = PitStopSummary::new(
+                    vehicle_index,
+                    name(vehicle_index),
+                    pit_stop.lap(),
+                    pit_stop.pit_lane_time(),
+                    *pit_stop.stationary_time(),
+                );
+
+                (entry_time, summary)
+            })
+            .collect();
+        pit_stops.sort_by_key(|(entry_time, _)| *entry_time);
+
+        let mut fastest_laps: Vec<(VehicleIndex, u8, Duration)> = self
+            .cars
+            .iter()
+            .filter_map(|(vehicle_index, car)| {
+                car.fastest_lap
+                    .map(|(lap, lap_time)| (*vehicle_index, lap, lap_time))
+            })
+            .collect();
+        fastest_laps.sort_by_key(|(_, _, lap_time)| *lap_time);
+
+        let fastest_laps = fastest_laps
+            .into_iter()
+            .enumerate()
+            .map(|(index, (vehicle_index, lap, lap_time))| {
+                FastestLapEntry::new(
+                    index as u8 + 1,
+                    vehicle_index,
+                    name(vehicle_index),
+                    lap,
+                    lap_time,
+                )
+            })
+            .collect();
+
+        RaceReport {
+            lap_chart,
+            pit_stops: pit_stops.into_iter().map(|(_, summary)| summary).collect(),
+            fastest_laps,
+            neutralized_periods: self.safety_car.periods().to_vec(),
+            result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::fixtures::{
+        event_packet, lap_packet, participants_packet, session_packet, LapFixture, SessionFixture,
+    };
+    use crate::analysis::report::ReportBuilder;
+    use crate::analysis::result::PointsSystem;
+    use crate::packet::event::Event;
+    use crate::packet::lap::PitStatus;
+    use crate::packet::session::SafetyCar;
+
+    fn lap(position: u8, current_lap_number: u8, last_lap_time: f32) -> LapFixture {
+        LapFixture {
+            position,
+            current_lap_number,
+            last_lap_time,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_session_end() {
+        let mut builder = ReportBuilder::new(PointsSystem::default());
+        let safety_car = |time, safety_car| {
+            session_packet(
+                time,
+                SessionFixture {
+                    safety_car,
+                    ..SessionFixture::default()
+                },
+            )
+        };
+
+        builder.update(&safety_car(0.0, SafetyCar::None));
+        builder.update(&participants_packet(0.0, vec!["Alice", "Bob"]));
+        builder.update(&lap_packet(0.0, vec![lap(1, 1, 0.0), lap(2, 1, 0.0)]));
+        builder.update(&lap_packet(90.0, vec![lap(1, 2, 90.0), lap(2, 2, 91.0)]));
+        builder.update(&safety_car(100.0, SafetyCar::Virtual));
+        builder.update(&lap_packet(
+            170.0,
+            vec![
+                lap(1, 2, 90.0),
+                LapFixture {
+                    pit_status: PitStatus::Pitting,
+                    ..lap(2, 2, 91.0)
+                },
+            ],
+        ));
+        builder.update(&safety_car(175.0, SafetyCar::None));
+        builder.update(&lap_packet(190.0, vec![lap(2, 3, 100.0), lap(1, 3, 88.0)]));
+
+        let report = builder
+            .update(&event_packet(190.0, Event::SessionEnded))
+            .unwrap();
+
+        assert_eq!(2, report.lap_chart().len());
+        assert_eq!(&vec![1, 0], report.lap_chart()[1].order());
+
+        assert_eq!(1, report.pit_stops().len());
+        assert_eq!("Bob", report.pit_stops()[0].name());
+        assert_eq!(
+            Duration::from_secs(20),
+            *report.pit_stops()[0].pit_lane_time()
+        );
+
+        assert_eq!("Bob", report.fastest_laps()[0].name());
+        assert_eq!(2, report.fastest_laps()[0].lap());
+        assert_eq!(1, report.neutralized_periods().len());
+        assert_eq!("Bob", report.result().winner().unwrap().name());
+    }
+}
@@ -0,0 +1,416 @@
+//! Results of a session
+//!
+//! After a race, leagues publish the results with the classification, the fastest lap, the
+//! penalties, and the points that each driver scored. The result builder collects this data while
+//! the session is running, and assembles the complete result of the session when the session ends.
+//!
+//! F1 2019 does not publish a final classification. The classification is instead built from the
+//! last lap data of each car, and the race time of a car is the sum of its lap times. With the
+//! `serde` feature enabled, the session result can be serialized for publishing.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::analysis::penalty::{PenaltyLedger, PenaltyRecord};
+use crate::analysis::stint::{Stint, StintTracker};
+use crate::packet::event::Event;
+use crate::packet::lap::{Lap, LapPacket, ResultStatus};
+use crate::packet::participants::{Participant, Team};
+use crate::packet::session::Session;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Points that are awarded for the result of a race
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointsSystem {
+    /// Returns the points for each position, starting with the winner.
+    #[getset(get = "pub")]
+    positions: Vec<u8>,
+
+    /// Returns the points for the fastest lap.
+    #[getset(get_copy = "pub")]
+    fastest_lap: u8,
+
+    /// Returns the lowest position that scores the point for the fastest lap.
+    #[getset(get_copy = "pub")]
+    fastest_lap_cutoff: u8,
+}
+
+impl PointsSystem {
+    /// Create the points system of a sprint race, which awards points to the top eight.
+    pub fn sprint() -> Self {
+        PointsSystem {
+            positions: vec![8, 7, 6, 5, 4, 3, 2, 1],
+            fastest_lap: 0,
+            fastest_lap_cutoff: 0,
+        }
+    }
+
+    /// Returns the points for a finishing position and the fastest lap.
+    pub fn points(&self, position: u8, has_fastest_lap: bool) -> u8 {
+        let points = (position as usize)
+            .checked_sub(1)
+            .and_then(|index| self.positions.get(index))
+            .copied()
+            .unwrap_or_default();
+
+        if has_fastest_lap && position > 0 && position <= self.fastest_lap_cutoff {
+            points.saturating_add(self.fastest_lap)
+        } else {
+            points
+        }
+    }
+}
+
+impl Default for PointsSystem {
+    fn default() -> Self {
+        PointsSystem {
+            positions: vec![25, 18, 15, 12, 10, 8, 6, 4, 2, 1],
+            fastest_lap: 1,
+            fastest_lap_cutoff: 10,
+        }
+    }
+}
+
+/// The fastest lap of a session
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FastestLap {
+    /// Returns the index of the car that set the fastest lap.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the number of the lap.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the lap time.
+    #[getset(get = "pub")]
+    lap_time: Duration,
+}
+
+/// Result of a car in the session
+#[derive(Debug, Getters, CopyGetters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CarResult {
+    /// Returns the classified position of the car.
+    #[getset(get_copy = "pub")]
+    position: u8,
+
+    /// Returns the index of the car.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the name of the driver, or an empty string if it is not known.
+    #[getset(get = "pub")]
+    name: String,
+
+    /// Returns the team of the driver, if it is known.
+    #[getset(get_copy = "pub")]
+    team: Option<Team>,
+
+    /// Returns the position in which the car started the session.
+    #[getset(get_copy = "pub")]
+    grid_position: u8,
+
+    /// Returns the number of laps the car completed.
+    #[getset(get_copy = "pub")]
+    laps: u8,
+
+    /// Returns the sum of the lap times of the car.
+    #[getset(get = "pub")]
+    race_time: Duration,
+
+    /// Returns the best lap time of the car.
+    #[getset(get = "pub")]
+    best_lap_time: Duration,
+
+    /// Returns the status of the car at the end of the session.
+    #[getset(get_copy = "pub")]
+    result_status: ResultStatus,
+
+    /// Returns the penalties the car received during the session.
+    #[getset(get = "pub")]
+    penalties: PenaltyRecord,
+
+    /// Returns the stints of the car.
+    #[getset(get = "pub")]
+    stints: Vec<Stint>,
+
+    /// Returns the points the car scored.
+    #[getset(get_copy = "pub")]
+    points: u8,
+}
+
+/// Result of a session
+#[derive(Debug, Getters, CopyGetters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SessionResult {
+    /// Returns the type of the session.
+    #[getset(get_copy = "pub")]
+    session_type: Session,
+
+    /// Returns the results of the cars, ordered by their classified position.
+    #[getset(get = "pub")]
+    classification: Vec<CarResult>,
+
+    /// Returns the fastest lap of the session, if a lap has been completed.
+    #[getset(get_copy = "pub")]
+    fastest_lap: Option<FastestLap>,
+}
+
+impl CarResult {
+    /// Returns the name of the driver, or the number of the car if the name is not known.
+    pub fn display_name(&self) -> String {
+        if self.name.is_empty() {
+            format!("Car #{}", self.vehicle_index)
+        } else {
+            self.name.clone()
+        }
+    }
+
+    /// Returns whether the car is classified, and can score points.
+    pub fn is_classified(&self) -> bool {
+        self.result_status == ResultStatus::Finished || self.result_status == ResultStatus::Active
+    }
+}
+
+impl SessionResult {
+    /// Returns the result of the car that won the session.
+    pub fn winner(&self) -> Option<&CarResult> {
+        self.classification.first()
+    }
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarLaps {
+    lap: Option<Lap>,
+    laps: u8,
+    race_time: Duration,
+    fastest_lap: Option<(u8, Duration)>,
+}
+
+/// Builder for the result of a session
+///
+/// The result builder consumes lap data, participants, session, and event packets. It returns the
+/// result of the session when the session ends, and can build a provisional result at any time.
+/// Points are only awarded in races.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ResultBuilder {
+    points_system: PointsSystem,
+    session_type: Session,
+    cars: BTreeMap<VehicleIndex, CarLaps>,
+    participants: BTreeMap<VehicleIndex, Participant>,
+    penalties: PenaltyLedger,
+    stints: StintTracker,
+}
+
+impl ResultBuilder {
+    /// Create a new result builder that awards points with the given points system.
+    pub fn new(points_system: PointsSystem) -> Self {
+        ResultBuilder {
+            points_system,
+            ..ResultBuilder::default()
+        }
+    }
+
+    /// Update the result with the data from the packet.
+    ///
+    /// The result of the session is returned when the packet announces the end of the session.
+    pub fn update(&mut self, packet: &Packet) -> Option<SessionResult> {
+        self.penalties.update(packet);
+        self.stints.update(packet);
+
+        match packet {
+            Packet::Event(packet) if *packet.event() == Event::SessionEnded => {
+                return Some(self.build());
+            }
+            Packet::Lap(packet) => self.update_laps(packet),
+            Packet::Participants(packet) => {
+                for (index, participant) in enumerate_cars(packet.participants()) {
+                    self.participants.insert(index, participant.clone());
+                }
+            }
+            Packet::Session(packet) => self.session_type = packet.session_type(),
+            _ => {}
+        }
+
+        None
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(index).or_default();
+            let lap_number = lap.current_lap_number();
+
+            if let Some(previous) = car.lap.filter(|previous| {
+                lap_number > previous.current_lap_number()
+                    && *lap.last_lap_time() > Duration::default()
+            }) {
+                let lap_time = *lap.last_lap_time();
+
+                car.laps = car.laps.saturating_add(1);
+                car.race_time += lap_time;
+
+                if car
+                    .fastest_lap
+                    .is_none_or(|(_, fastest)| lap_time < fastest)
+                {
+                    car.fastest_lap = Some((previous.current_lap_number(), lap_time));
+                }
+            }
+
+            car.lap = Some(*lap);
+        }
+    }
+
+    /// Build the result of the session from the data that has been received so far.
+    pub fn build(&self) -> SessionResult {
+        let fastest_lap = self
+            .cars
+            .iter()
+            .filter_map(|(index, car)| {
+                car.fastest_lap
+                    .map(|(lap, lap_time)| FastestLap::new(*index, lap, lap_time))
+            })
+            .min_by_key(|fastest_lap| fastest_lap.lap_time);
+
+        let is_race = self.session_type.is_race();
+
+        let mut classification: Vec<CarResult> = self
+            .cars
+            .iter()
+            .filter_map(|(index, car)| car.lap.map(|lap| (*index, car, lap)))
+            .filter(|(_, _, lap)| lap.position() > 0)
+            .map(|(vehicle_index, car, lap)| {
+                let participant = self.participants.get(&vehicle_index);
+                let has_fastest_lap = fastest_lap
+                    .is_some_and(|fastest_lap| fastest_lap.vehicle_index == vehicle_index);
+                let is_classified = lap.result_status() == ResultStatus::Finished
+                    || lap.result_status() == ResultStatus::Active;
+
+                CarResult {
+                    position: lap.position(),
+                    vehicle_index,
+                    name: participant
+                        .map(|participant| participant.name().clone())
+                        .unwrap_or_default(),
+                    team: participant.map(|participant| participant.team()),
+                    grid_position: lap.grid_position(),
+                    laps: car.laps,
+                    race_time: car.race_time,
+                    best_lap_time: car
+                        .fastest_lap
+                        .map(|(_, lap_time)| lap_time)
+                        .unwrap_or_default(),
+                    result_status: lap.result_status(),
+                    penalties: self
+                        .penalties
+                        .record(vehicle_index)
+                        .cloned()
+                        .unwrap_or_default(),
+                    stints: self
+                        .stints
+                        .history(vehicle_index)
+                        .map(|history| history.stints().clone())
+                        .unwrap_or_default(),
+                    points: if is_race && is_classified {
+                        self.points_system.points(lap.position(), has_fastest_lap)
+                    } else {
+                        0
+                    },
+                }
+            })
+            .collect();
+        classification.sort_by_key(|result| result.position);
+
+        SessionResult {
+            session_type: self.session_type,
+            classification,
+            fastest_lap,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::fixtures::{
+        event_packet, lap_packet, participants_packet, session_packet, LapFixture, SessionFixture,
+    };
+    use crate::analysis::result::{PointsSystem, ResultBuilder};
+    use crate::packet::event::Event;
+    use crate::packet::lap::ResultStatus;
+    use crate::packet::session::Session;
+
+    fn lap(position: u8, current_lap_number: u8, last_lap_time: f32) -> LapFixture {
+        LapFixture {
+            position,
+            current_lap_number,
+            last_lap_time,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn points_with_fastest_lap() {
+        let points_system = PointsSystem::default();
+
+        assert_eq!(26, points_system.points(1, true));
+        assert_eq!(1, points_system.points(10, false));
+        assert_eq!(0, points_system.points(11, true));
+        assert_eq!(0, points_system.points(0, false));
+    }
+
+    #[test]
+    fn update_with_session_end() {
+        let mut builder = ResultBuilder::new(PointsSystem::default());
+
+        builder.update(&session_packet(
+            0.0,
+            SessionFixture {
+                session_type: Session::Race,
+                ..SessionFixture::default()
+            },
+        ));
+        builder.update(&participants_packet(0.0, vec!["Alice", "Bob"]));
+        builder.update(&lap_packet(0.0, vec![lap(1, 1, 0.0), lap(2, 1, 0.0)]));
+        builder.update(&lap_packet(90.0, vec![lap(1, 2, 90.0), lap(2, 2, 91.0)]));
+        builder.update(&lap_packet(
+            180.0,
+            vec![
+                LapFixture {
+                    result_status: ResultStatus::Finished,
+                    ..lap(1, 3, 92.0)
+                },
+                LapFixture {
+                    result_status: ResultStatus::Finished,
+                    ..lap(2, 3, 89.0)
+                },
+            ],
+        ));
+
+        let result = builder
+            .update(&event_packet(180.0, Event::SessionEnded))
+            .unwrap();
+
+        let winner = result.winner().unwrap();
+        assert_eq!("Alice", winner.name());
+        assert_eq!(2, winner.laps());
+        assert_eq!(Duration::from_secs(182), *winner.race_time());
+        assert_eq!(25, winner.points());
+
+        let fastest_lap = result.fastest_lap().unwrap();
+        assert_eq!(1, fastest_lap.vehicle_index());
+        assert_eq!(2, fastest_lap.lap());
+        assert_eq!(19, result.classification()[1].points());
+    }
+}
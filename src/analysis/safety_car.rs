@@ -0,0 +1,237 @@
+//! Periods under the safety car or the virtual safety car
+//!
+//! When the track has to be neutralized, for example after a crash, race control deploys the safety
+//! car or the virtual safety car. Lap times set during these periods are much slower than normal
+//! and distort most lap time analysis. The safety car tracker watches the safety car status in the
+//! session packets, and records when and on which laps the race was neutralized.
+
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::LapPacket;
+use crate::packet::session::{SafetyCar, SessionPacket};
+use crate::packet::Packet;
+
+/// A period during which the race was neutralized
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NeutralizedPeriod {
+    /// Returns whether the full or the virtual safety car was deployed.
+    #[getset(get_copy = "pub")]
+    safety_car: SafetyCar,
+
+    /// Returns the session time at which the safety car was deployed.
+    #[getset(get = "pub")]
+    start_time: Duration,
+
+    /// Returns the session time at which the safety car was withdrawn, if it has been withdrawn.
+    #[getset(get = "pub")]
+    end_time: Option<Duration>,
+
+    /// Returns the lap of the leader on which the safety car was deployed.
+    #[getset(get_copy = "pub")]
+    start_lap: u8,
+
+    /// Returns the lap of the leader on which the safety car was withdrawn, if it has been withdrawn.
+    #[getset(get_copy = "pub")]
+    end_lap: Option<u8>,
+}
+
+impl NeutralizedPeriod {
+    /// Returns the duration of the period, if the safety car has been withdrawn.
+    pub fn duration(&self) -> Option<Duration> {
+        self.end_time
+            .map(|end_time| end_time.checked_sub(self.start_time).unwrap_or_default())
+    }
+
+    /// Returns whether the lap was at least partially neutralized by the period.
+    pub fn contains_lap(&self, lap: u8) -> bool {
+        lap >= self.start_lap && self.end_lap.map(|end_lap| lap <= end_lap).unwrap_or(true)
+    }
+}
+
+/// Events emitted by the safety car tracker
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+pub enum SafetyCarEvent {
+    /// The full or the virtual safety car has been deployed.
+    Deployed(SafetyCar),
+
+    /// The full or the virtual safety car has been withdrawn.
+    Withdrawn(SafetyCar),
+}
+
+impl SafetyCarEvent {
+    /// Returns the events for a change of the safety car status between two session packets.
+    ///
+    /// A change from the virtual to the full safety car, or the other way around, withdraws one and
+    /// deploys the other.
+    pub fn between(previous: SafetyCar, current: SafetyCar) -> Vec<SafetyCarEvent> {
+        let mut events = Vec::new();
+
+        if previous == current {
+            return events;
+        }
+
+        if previous.is_deployed() {
+            events.push(SafetyCarEvent::Withdrawn(previous));
+        }
+        if current.is_deployed() {
+            events.push(SafetyCarEvent::Deployed(current));
+        }
+
+        events
+    }
+}
+
+/// Tracker for the periods under the safety car
+///
+/// The safety car tracker consumes session and lap data packets. The latter are used to determine
+/// the lap of the race leader, which is used for the lap ranges of the periods.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SafetyCarTracker {
+    leader_lap: u8,
+    periods: Vec<NeutralizedPeriod>,
+}
+
+impl SafetyCarTracker {
+    /// Create a new safety car tracker without any periods.
+    pub fn new() -> Self {
+        SafetyCarTracker::default()
+    }
+
+    /// Update the tracker and return the safety car events detected in the packet.
+    pub fn update(&mut self, packet: &Packet) -> Vec<SafetyCarEvent> {
+        match packet {
+            Packet::Lap(packet) => {
+                self.update_laps(packet);
+                Vec::new()
+            }
+            Packet::Session(packet) => self.update_session(packet),
+            _ => Vec::new(),
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        if let Some(leader) = packet.laps().iter().find(|lap| lap.position() == 1) {
+            self.leader_lap = leader.current_lap_number();
+        }
+    }
+
+    fn update_session(&mut self, packet: &SessionPacket) -> Vec<SafetyCarEvent> {
+        let session_time = *packet.header().session_time();
+        let status = packet.safety_car();
+
+        let leader_lap = self.leader_lap;
+        let previous = self
+            .current()
+            .map_or(SafetyCar::None, |period| period.safety_car);
+
+        let events = SafetyCarEvent::between(previous, status);
+
+        for event in &events {
+            match event {
+                SafetyCarEvent::Withdrawn(_) => {
+                    if let Some(period) = self.periods.last_mut() {
+                        period.end_time = Some(session_time);
+                        period.end_lap = Some(leader_lap);
+                    }
+                }
+                SafetyCarEvent::Deployed(safety_car) => {
+                    self.periods.push(NeutralizedPeriod::new(
+                        *safety_car,
+                        session_time,
+                        None,
+                        leader_lap,
+                        None,
+                    ));
+                }
+            }
+        }
+
+        events
+    }
+
+    /// Returns the period that is currently ongoing, if the race is neutralized.
+    pub fn current(&self) -> Option<&NeutralizedPeriod> {
+        self.periods
+            .last()
+            .filter(|period| period.end_time.is_none())
+    }
+
+    /// Returns all periods in which the race was neutralized.
+    pub fn periods(&self) -> &[NeutralizedPeriod] {
+        &self.periods
+    }
+
+    /// Returns whether a lap of the leader was at least partially neutralized.
+    pub fn is_neutralized(&self, lap: u8) -> bool {
+        self.periods.iter().any(|period| period.contains_lap(lap))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::fixtures::{lap_packet, session_packet, LapFixture, SessionFixture};
+    use crate::analysis::safety_car::{SafetyCarEvent, SafetyCarTracker};
+    use crate::packet::session::SafetyCar;
+
+    fn session(safety_car: SafetyCar) -> SessionFixture {
+        SessionFixture {
+            safety_car,
+            ..SessionFixture::default()
+        }
+    }
+
+    fn leader(current_lap_number: u8) -> LapFixture {
+        LapFixture {
+            current_lap_number,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_safety_car_period() {
+        let mut tracker = SafetyCarTracker::new();
+
+        tracker.update(&lap_packet(0.0, vec![leader(5)]));
+        assert!(tracker
+            .update(&session_packet(0.0, session(SafetyCar::None)))
+            .is_empty());
+
+        let events = tracker.update(&session_packet(10.0, session(SafetyCar::Full)));
+        assert_eq!(vec![SafetyCarEvent::Deployed(SafetyCar::Full)], events);
+
+        tracker.update(&lap_packet(20.0, vec![leader(7)]));
+        let events = tracker.update(&session_packet(100.0, session(SafetyCar::None)));
+        assert_eq!(vec![SafetyCarEvent::Withdrawn(SafetyCar::Full)], events);
+
+        let period = tracker.periods()[0];
+        assert_eq!(Some(Duration::from_secs(90)), period.duration());
+        assert!(tracker.current().is_none());
+        assert!(!tracker.is_neutralized(4));
+        assert!(tracker.is_neutralized(6));
+        assert!(!tracker.is_neutralized(8));
+    }
+
+    #[test]
+    fn update_from_virtual_to_full_safety_car() {
+        let mut tracker = SafetyCarTracker::new();
+
+        tracker.update(&session_packet(0.0, session(SafetyCar::Virtual)));
+        let events = tracker.update(&session_packet(5.0, session(SafetyCar::Full)));
+
+        assert_eq!(
+            vec![
+                SafetyCarEvent::Withdrawn(SafetyCar::Virtual),
+                SafetyCarEvent::Deployed(SafetyCar::Full)
+            ],
+            events
+        );
+        assert_eq!(2, tracker.periods().len());
+        assert_eq!(SafetyCar::Full, tracker.current().unwrap().safety_car());
+    }
+}
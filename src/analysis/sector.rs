@@ -0,0 +1,349 @@
+//! Sector times and theoretical best laps
+//!
+//! Timing screens show the time of every sector a driver completes, and highlight personal and
+//! overall best sectors. Combining the best sectors of a driver results in their theoretical best
+//! lap, which shows how much faster they could go if they put everything together. The sector
+//! tracker records the sector times of every car, signals new best sectors, and calculates the
+//! theoretical best and rolling ideal laps.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::{Lap, LapPacket, Sector};
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Times of the three sectors of a lap
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+pub struct SectorTimes {
+    sectors: [Option<Duration>; 3],
+}
+
+impl SectorTimes {
+    /// Returns the time of a sector, if it is known.
+    pub fn sector(&self, sector: Sector) -> Option<Duration> {
+        self.sectors[sector_index(sector)]
+    }
+
+    /// Returns the sum of the sector times, if all three sectors are known.
+    pub fn total(&self) -> Option<Duration> {
+        self.sectors.iter().copied().sum()
+    }
+
+    /// Keep the faster of the current and the given time for a sector.
+    ///
+    /// Returns whether the given time is faster than the current time.
+    fn improve(&mut self, sector: Sector, time: Duration) -> bool {
+        let current = &mut self.sectors[sector_index(sector)];
+
+        if current.is_none_or(|current| time < current) {
+            *current = Some(time);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Sector times of a completed lap
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+pub struct LapSectors {
+    /// Returns the number of the lap.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the times of the valid sectors of the lap.
+    #[getset(get_copy = "pub")]
+    sectors: SectorTimes,
+}
+
+/// A sector that a car has completed
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+pub struct SectorEvent {
+    /// Returns the index of the car that completed the sector.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the lap on which the sector was completed.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the sector that was completed.
+    #[getset(get_copy = "pub")]
+    sector: Sector,
+
+    /// Returns the time of the sector.
+    #[getset(get = "pub")]
+    time: Duration,
+
+    /// Returns whether the time is the best time of the car in this sector.
+    #[getset(get_copy = "pub")]
+    is_personal_best: bool,
+
+    /// Returns whether the time is the best time of all cars in this sector.
+    #[getset(get_copy = "pub")]
+    is_overall_best: bool,
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarSectors {
+    previous: Option<Lap>,
+    current: LapSectors,
+    personal_best: SectorTimes,
+    laps: Vec<LapSectors>,
+}
+
+impl CarSectors {
+    /// Update the sectors of the car, and return the sectors it completed.
+    fn update(&mut self, lap: &Lap) -> Vec<(u8, Sector, Duration)> {
+        let mut completed = Vec::new();
+
+        if let Some(previous) = self.previous {
+            let lap_number = previous.current_lap_number();
+
+            if lap.current_lap_number() == lap_number && lap.is_valid_lap() {
+                if previous.sector() == Sector::First && lap.sector() != Sector::First {
+                    completed.push((lap_number, Sector::First, *lap.sector1_time()));
+                }
+
+                if previous.sector() != Sector::Third && lap.sector() == Sector::Third {
+                    completed.push((lap_number, Sector::Second, *lap.sector2_time()));
+                }
+            } else if lap.current_lap_number() > lap_number && previous.is_valid_lap() {
+                let sectors = self.current.sectors;
+                let third = sectors
+                    .sector(Sector::First)
+                    .zip(sectors.sector(Sector::Second))
+                    .and_then(|(first, second)| lap.last_lap_time().checked_sub(first + second));
+
+                if let Some(third) = third {
+                    completed.push((lap_number, Sector::Third, third));
+                }
+            }
+        }
+
+        for (_, sector, time) in completed.iter() {
+            self.current.sectors.sectors[sector_index(*sector)] = Some(*time);
+        }
+
+        if self
+            .previous
+            .is_some_and(|previous| lap.current_lap_number() > previous.current_lap_number())
+        {
+            let current = std::mem::take(&mut self.current);
+            self.laps.push(current);
+        }
+
+        self.current.lap = lap.current_lap_number();
+        self.previous = Some(*lap);
+
+        completed
+    }
+}
+
+/// Tracker for the sector times of all cars in the session
+///
+/// The sector tracker consumes lap data packets. The first two sectors are taken from the lap data
+/// when a car enters the next sector, while the third sector is calculated from the lap time when
+/// the car crosses the line. Sectors on invalid laps are ignored.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SectorTracker {
+    rolling_window: usize,
+    session_best: SectorTimes,
+    cars: BTreeMap<VehicleIndex, CarSectors>,
+}
+
+impl SectorTracker {
+    /// Create a new sector tracker.
+    ///
+    /// The rolling window is the number of recent laps from which the rolling ideal lap is built.
+    pub fn new(rolling_window: usize) -> Self {
+        SectorTracker {
+            rolling_window,
+            ..SectorTracker::default()
+        }
+    }
+
+    /// Update the sector times with the data from the packet.
+    ///
+    /// Every sector that a car completed is returned as an event.
+    pub fn update(&mut self, packet: &Packet) -> Vec<SectorEvent> {
+        match packet {
+            Packet::Lap(packet) => self.update_laps(packet),
+            _ => Vec::new(),
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) -> Vec<SectorEvent> {
+        let mut events = Vec::new();
+
+        for (vehicle_index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(vehicle_index).or_default();
+
+            for (lap_number, sector, time) in car.update(lap) {
+                let is_personal_best = car.personal_best.improve(sector, time);
+                let is_overall_best = self.session_best.improve(sector, time);
+
+                events.push(SectorEvent::new(
+                    vehicle_index,
+                    lap_number,
+                    sector,
+                    time,
+                    is_personal_best,
+                    is_overall_best,
+                ));
+            }
+        }
+
+        events
+    }
+
+    /// Returns the sector times of the completed laps of a car.
+    pub fn laps(&self, vehicle_index: VehicleIndex) -> &[LapSectors] {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.laps.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns the best lap of a car on which all three sectors were valid.
+    pub fn best_lap(&self, vehicle_index: VehicleIndex) -> Option<Duration> {
+        self.laps(vehicle_index)
+            .iter()
+            .filter_map(|lap| lap.sectors.total())
+            .min()
+    }
+
+    /// Returns the best sectors of a car, which make up its theoretical best lap.
+    pub fn theoretical_best(&self, vehicle_index: VehicleIndex) -> Option<SectorTimes> {
+        self.cars.get(&vehicle_index).map(|car| car.personal_best)
+    }
+
+    /// Returns the best sectors of a car in its most recent laps.
+    pub fn rolling_ideal(&self, vehicle_index: VehicleIndex) -> Option<SectorTimes> {
+        let laps = self.laps(vehicle_index);
+        if laps.is_empty() {
+            return None;
+        }
+
+        let mut ideal = SectorTimes::default();
+
+        for lap in laps.iter().rev().take(self.rolling_window) {
+            for sector in [Sector::First, Sector::Second, Sector::Third].iter() {
+                if let Some(time) = lap.sectors.sector(*sector) {
+                    ideal.improve(*sector, time);
+                }
+            }
+        }
+
+        Some(ideal)
+    }
+
+    /// Returns the best sectors of all cars, which make up the theoretical best lap of the session.
+    pub fn session_best(&self) -> SectorTimes {
+        self.session_best
+    }
+}
+
+/// Returns the index of a sector in an array of sector times.
+fn sector_index(sector: Sector) -> usize {
+    match sector {
+        Sector::First => 0,
+        Sector::Second => 1,
+        Sector::Third => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::fixtures::{lap_packet, vehicle, LapFixture};
+    use crate::analysis::sector::SectorTracker;
+    use crate::packet::lap::Sector;
+
+    fn drive_lap(tracker: &mut SectorTracker, lap: u8, first: f32, second: f32, third: f32) {
+        let sectors = [
+            (Sector::First, 0.0, 0.0),
+            (Sector::Second, first, 0.0),
+            (Sector::Third, first, second),
+        ];
+
+        for (sector, sector1_time, sector2_time) in sectors.iter() {
+            tracker.update(&lap_packet(
+                0.0,
+                vec![LapFixture {
+                    current_lap_number: lap,
+                    sector: *sector,
+                    sector1_time: *sector1_time,
+                    sector2_time: *sector2_time,
+                    last_lap_time: if lap > 1 { 90.0 } else { 0.0 },
+                    ..LapFixture::default()
+                }],
+            ));
+        }
+
+        let events = tracker.update(&lap_packet(
+            0.0,
+            vec![LapFixture {
+                current_lap_number: lap + 1,
+                last_lap_time: first + second + third,
+                ..LapFixture::default()
+            }],
+        ));
+        assert_eq!(Sector::Third, events[0].sector());
+    }
+
+    #[test]
+    fn update_with_best_sectors() {
+        let mut tracker = SectorTracker::new(2);
+
+        drive_lap(&mut tracker, 1, 30.0, 31.0, 32.0);
+        drive_lap(&mut tracker, 2, 29.0, 32.0, 33.0);
+        drive_lap(&mut tracker, 3, 31.0, 33.0, 29.0);
+
+        assert_eq!(3, tracker.laps(vehicle(0)).len());
+        assert_eq!(Some(Duration::from_secs(93)), tracker.best_lap(vehicle(0)));
+
+        let theoretical_best = tracker.theoretical_best(vehicle(0)).unwrap();
+        assert_eq!(Some(Duration::from_secs(89)), theoretical_best.total());
+
+        let rolling_ideal = tracker.rolling_ideal(vehicle(0)).unwrap();
+        assert_eq!(
+            Some(Duration::from_secs(29)),
+            rolling_ideal.sector(Sector::First)
+        );
+        assert_eq!(
+            Some(Duration::from_secs(32)),
+            rolling_ideal.sector(Sector::Second)
+        );
+        assert_eq!(Some(Duration::from_secs(90)), rolling_ideal.total());
+    }
+
+    #[test]
+    fn update_with_sector_events() {
+        let mut tracker = SectorTracker::new(2);
+        let lap = |sector, sector1_time| LapFixture {
+            sector,
+            sector1_time,
+            ..LapFixture::default()
+        };
+
+        tracker.update(&lap_packet(0.0, vec![lap(Sector::First, 0.0)]));
+        let events = tracker.update(&lap_packet(30.0, vec![lap(Sector::Second, 30.0)]));
+
+        assert_eq!(1, events.len());
+        assert_eq!(Sector::First, events[0].sector());
+        assert_eq!(Duration::from_secs(30), *events[0].time());
+        assert!(events[0].is_personal_best());
+        assert!(events[0].is_overall_best());
+    }
+}
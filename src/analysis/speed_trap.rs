@@ -0,0 +1,181 @@
+//! Speed trap
+//!
+//! The speed trap measures the speed of every car at a fixed point of the track, usually at the end
+//! of the longest straight. Comparing trap speeds shows which cars run less downforce, or which car
+//! gets a good tow. The speed trap keeps track of the best trap speed of every driver, and ranks the
+//! drivers in a leaderboard.
+//!
+//! F1 2019 does not publish speed trap events. Instead, the speed of a car is taken from the first
+//! telemetry packet after the car has passed the lap distance of the trap.
+
+use std::collections::BTreeMap;
+
+use derive_new::new;
+use getset::CopyGetters;
+
+use crate::packet::lap::LapPacket;
+use crate::packet::telemetry::TelemetryPacket;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// A speed that was measured in the speed trap
+#[derive(new, Debug, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+pub struct TrapSpeed {
+    /// Returns the index of the car.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the lap on which the speed was measured.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the speed in kilometers per hour.
+    #[getset(get_copy = "pub")]
+    speed: u16,
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarTrap {
+    lap: u8,
+    lap_distance: f32,
+    trapped_lap: Option<u8>,
+    best: Option<TrapSpeed>,
+}
+
+/// Speed trap for all cars in the session
+///
+/// The speed trap consumes lap data and telemetry packets. Each car is measured at most once per
+/// lap, as soon as it has passed the lap distance of the trap.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SpeedTrap {
+    lap_distance: f32,
+    cars: BTreeMap<VehicleIndex, CarTrap>,
+}
+
+impl SpeedTrap {
+    /// Create a new speed trap at the given lap distance in meters.
+    pub fn new(lap_distance: f32) -> Self {
+        SpeedTrap {
+            lap_distance,
+            cars: BTreeMap::new(),
+        }
+    }
+
+    /// Update the speed trap with the data from the packet, and return the new trap speeds.
+    pub fn update(&mut self, packet: &Packet) -> Vec<TrapSpeed> {
+        match packet {
+            Packet::Lap(packet) => {
+                self.update_laps(packet);
+                Vec::new()
+            }
+            Packet::Telemetry(packet) => self.update_telemetry(packet),
+            _ => Vec::new(),
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(index).or_default();
+
+            car.lap = lap.current_lap_number();
+            car.lap_distance = lap.lap_distance();
+        }
+    }
+
+    fn update_telemetry(&mut self, packet: &TelemetryPacket) -> Vec<TrapSpeed> {
+        let mut speeds = Vec::new();
+
+        for (vehicle_index, telemetry) in enumerate_cars(packet.telemetry()) {
+            let car = match self.cars.get_mut(&vehicle_index) {
+                Some(car) => car,
+                None => continue,
+            };
+
+            if car.lap_distance < self.lap_distance || car.trapped_lap == Some(car.lap) {
+                continue;
+            }
+
+            let speed = TrapSpeed::new(vehicle_index, car.lap, telemetry.speed().as_kph());
+            car.trapped_lap = Some(car.lap);
+
+            if car.best.is_none_or(|best| speed.speed > best.speed) {
+                car.best = Some(speed);
+            }
+
+            speeds.push(speed);
+        }
+
+        speeds
+    }
+
+    /// Returns the best trap speed of a car.
+    pub fn best(&self, vehicle_index: VehicleIndex) -> Option<TrapSpeed> {
+        self.cars.get(&vehicle_index)?.best
+    }
+
+    /// Returns the best trap speed of the session.
+    pub fn overall_best(&self) -> Option<TrapSpeed> {
+        self.leaderboard().first().copied()
+    }
+
+    /// Returns the best trap speeds of all cars, ordered from the fastest to the slowest.
+    pub fn leaderboard(&self) -> Vec<TrapSpeed> {
+        let mut leaderboard: Vec<TrapSpeed> =
+            self.cars.values().filter_map(|car| car.best).collect();
+
+        leaderboard.sort_by(|a, b| b.speed.cmp(&a.speed).then(a.lap.cmp(&b.lap)));
+        leaderboard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::fixtures::{
+        lap_packet, telemetry_packet, vehicle, LapFixture, TelemetryFixture,
+    };
+    use crate::analysis::speed_trap::SpeedTrap;
+
+    fn lap(current_lap_number: u8, lap_distance: f32) -> LapFixture {
+        LapFixture {
+            current_lap_number,
+            lap_distance,
+            ..LapFixture::default()
+        }
+    }
+
+    fn speed(speed: u16) -> TelemetryFixture {
+        TelemetryFixture {
+            speed,
+            ..TelemetryFixture::default()
+        }
+    }
+
+    #[test]
+    fn leaderboard_with_best_speeds() {
+        let mut trap = SpeedTrap::new(1000.0);
+
+        for (first, second, first_speed, second_speed) in [
+            (lap(1, 900.0), lap(1, 800.0), 300, 290),
+            (lap(1, 1010.0), lap(1, 950.0), 310, 305),
+            (lap(1, 1100.0), lap(1, 1020.0), 320, 315),
+            (lap(2, 1010.0), lap(2, 1005.0), 308, 318),
+        ]
+        .iter()
+        {
+            trap.update(&lap_packet(0.0, vec![*first, *second]));
+            trap.update(&telemetry_packet(
+                0.0,
+                vec![speed(*first_speed), speed(*second_speed)],
+            ));
+        }
+
+        assert_eq!(310, trap.best(vehicle(0)).unwrap().speed());
+        assert_eq!(1, trap.best(vehicle(0)).unwrap().lap());
+
+        let leaderboard = trap.leaderboard();
+        assert_eq!(1, leaderboard[0].vehicle_index());
+        assert_eq!(318, leaderboard[0].speed());
+        assert_eq!(Some(leaderboard[0]), trap.overall_best());
+    }
+}
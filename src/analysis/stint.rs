@@ -0,0 +1,411 @@
+//! Stints and pit stops of each car in a session
+//!
+//! A stint is the time a car spends on track between two pit stops. Stints are the building block
+//! of every race strategy, as they determine which tyres a car runs and for how long. The stint
+//! tracker watches the pit status in the lap data packets to detect when a car enters and exits the
+//! pit lane, and combines this with the tyre compounds from the car status packets to build a
+//! history of stints and pit stops for each car.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::{Lap, PitStatus, ResultStatus};
+use crate::packet::status::{CarStatus, PhysicalTyreCompound, VisualTyreCompound};
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// A pit stop performed by a car
+///
+/// A pit stop starts when a car enters the pit lane, and ends when the car leaves it again. While
+/// in the pit lane, the car stops in its pit box to change tyres or repair damage. The time spent
+/// stationary in the pit box is recorded separately from the total time spent in the pit lane.
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PitStop {
+    /// Returns the lap on which the car entered the pit lane.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the session time at which the car entered the pit lane.
+    #[getset(get = "pub")]
+    entry_time: Duration,
+
+    /// Returns the session time at which the car left the pit lane.
+    #[getset(get = "pub")]
+    exit_time: Duration,
+
+    /// Returns the time the car spent stationary in its pit box.
+    #[getset(get = "pub")]
+    stationary_time: Duration,
+}
+
+impl PitStop {
+    /// Returns the time the car spent in the pit lane.
+    pub fn pit_lane_time(&self) -> Duration {
+        self.exit_time
+            .checked_sub(self.entry_time)
+            .unwrap_or_default()
+    }
+}
+
+/// A stint between two pit stops
+///
+/// Each stint starts on the lap a car leaves the pits, and ends on the lap it enters them again.
+/// For the current stint of a car, the last lap is the lap the car is currently on. The tyre
+/// compounds are taken from the car status packets, and are unknown until the first status packet
+/// for the stint has been received.
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stint {
+    /// Returns the first lap of the stint.
+    #[getset(get_copy = "pub")]
+    start_lap: u8,
+
+    /// Returns the last lap of the stint.
+    #[getset(get_copy = "pub")]
+    end_lap: u8,
+
+    /// Returns the physical tyre compound used in the stint, if it is known.
+    #[getset(get_copy = "pub")]
+    physical_tyre_compound: Option<PhysicalTyreCompound>,
+
+    /// Returns the visual tyre compound used in the stint, if it is known.
+    #[getset(get_copy = "pub")]
+    visual_tyre_compound: Option<VisualTyreCompound>,
+}
+
+impl Stint {
+    /// Returns the number of laps in the stint, including the in- and out-lap.
+    pub fn lap_count(&self) -> u8 {
+        self.end_lap.saturating_sub(self.start_lap) + 1
+    }
+}
+
+/// History of the stints and pit stops of a car
+#[derive(new, Debug, Getters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StintHistory {
+    /// Returns the stints of the car in the order in which they were driven.
+    #[getset(get = "pub")]
+    stints: Vec<Stint>,
+
+    /// Returns the pit stops of the car in the order in which they were performed.
+    #[getset(get = "pub")]
+    pit_stops: Vec<PitStop>,
+}
+
+impl StintHistory {
+    /// Returns the stint the car is currently on.
+    pub fn current_stint(&self) -> Option<&Stint> {
+        self.stints.last()
+    }
+}
+
+/// A visit to the pit lane that is still in progress
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct PitLaneVisit {
+    lap: u8,
+    entry_time: Duration,
+    box_entry_time: Option<Duration>,
+    stationary_time: Duration,
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct CarStints {
+    history: StintHistory,
+    pit_status: PitStatus,
+    visit: Option<PitLaneVisit>,
+}
+
+impl CarStints {
+    fn new(start_lap: u8) -> Self {
+        CarStints {
+            history: StintHistory::new(vec![Stint::new(start_lap, start_lap, None, None)], vec![]),
+            pit_status: PitStatus::None,
+            visit: None,
+        }
+    }
+
+    fn update_lap(&mut self, session_time: Duration, lap: &Lap) {
+        let lap_number = lap.current_lap_number();
+        let pit_status = lap.pit_status();
+
+        if self.pit_status == PitStatus::None && pit_status != PitStatus::None {
+            self.visit = Some(PitLaneVisit {
+                lap: lap_number,
+                entry_time: session_time,
+                box_entry_time: None,
+                stationary_time: Duration::default(),
+            });
+        }
+
+        if let Some(visit) = self.visit.as_mut() {
+            match (self.pit_status, pit_status) {
+                (PitStatus::InPits, PitStatus::InPits) => {}
+                (PitStatus::InPits, _) => {
+                    if let Some(box_entry_time) = visit.box_entry_time.take() {
+                        visit.stationary_time +=
+                            session_time.checked_sub(box_entry_time).unwrap_or_default();
+                    }
+                }
+                (_, PitStatus::InPits) => visit.box_entry_time = Some(session_time),
+                _ => {}
+            }
+        }
+
+        if pit_status == PitStatus::None {
+            if let Some(visit) = self.visit.take() {
+                self.finish_pit_stop(visit, session_time, lap_number);
+            } else if let Some(stint) = self.history.stints.last_mut() {
+                stint.end_lap = lap_number.max(stint.start_lap);
+            }
+        }
+
+        self.pit_status = pit_status;
+    }
+
+    fn update_status(&mut self, status: &CarStatus) {
+        if self.visit.is_some() {
+            return;
+        }
+
+        if let Some(stint) = self.history.stints.last_mut() {
+            stint.physical_tyre_compound = Some(status.physical_tyre_compound());
+            stint.visual_tyre_compound = Some(status.visual_tyre_compound());
+        }
+    }
+
+    fn finish_pit_stop(&mut self, visit: PitLaneVisit, exit_time: Duration, lap_number: u8) {
+        self.history.pit_stops.push(PitStop::new(
+            visit.lap,
+            visit.entry_time,
+            exit_time,
+            visit.stationary_time,
+        ));
+
+        if let Some(stint) = self.history.stints.last_mut() {
+            stint.end_lap = visit.lap.max(stint.start_lap);
+        }
+
+        self.history
+            .stints
+            .push(Stint::new(lap_number, lap_number, None, None));
+    }
+}
+
+/// Tracker for the stints and pit stops of all cars in a session
+///
+/// The stint tracker consumes lap data and car status packets, and ignores all other packets. A car
+/// is tracked from the first lap data packet in which it is active, which means that stints and pit
+/// stops before that point in time are unknown.
+///
+/// # Examples
+///
+/// ```
+/// use f1_api::analysis::stint::StintTracker;
+/// use f1_api::types::VehicleIndex;
+/// # use f1_api::packet::Packet;
+///
+/// # fn example(packets: Vec<Packet>) {
+/// let mut tracker = StintTracker::new();
+///
+/// for packet in packets {
+///     tracker.update(&packet);
+/// }
+///
+/// if let Some(history) = tracker.history(VehicleIndex::default()) {
+///     println!("Car #0 has made {} pit stops", history.pit_stops().len());
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StintTracker {
+    cars: BTreeMap<VehicleIndex, CarStints>,
+}
+
+impl StintTracker {
+    /// Create a new stint tracker without any history.
+    pub fn new() -> Self {
+        StintTracker::default()
+    }
+
+    /// Update the stints and pit stops with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Lap(packet) => {
+                let session_time = *packet.header().session_time();
+
+                for (index, lap) in enumerate_cars(packet.laps()) {
+                    if matches!(
+                        lap.result_status(),
+                        ResultStatus::Invalid | ResultStatus::Inactive
+                    ) {
+                        continue;
+                    }
+
+                    self.cars
+                        .entry(index)
+                        .or_insert_with(|| CarStints::new(lap.current_lap_number()))
+                        .update_lap(session_time, lap);
+                }
+            }
+            Packet::Status(packet) => {
+                for (index, status) in enumerate_cars(packet.statuses()) {
+                    if let Some(car) = self.cars.get_mut(&index) {
+                        car.update_status(status);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the stint history of the given car, if the car has been seen on track.
+    pub fn history(&self, vehicle_index: VehicleIndex) -> Option<&StintHistory> {
+        self.cars.get(&vehicle_index).map(|car| &car.history)
+    }
+
+    /// Returns the stint histories of all cars that have been seen on track.
+    pub fn histories(&self) -> impl Iterator<Item = (VehicleIndex, &StintHistory)> {
+        self.cars
+            .iter()
+            .map(|(vehicle_index, car)| (*vehicle_index, &car.history))
+    }
+
+    /// Returns whether the given car is currently in the pit lane.
+    pub fn is_in_pit_lane(&self, vehicle_index: VehicleIndex) -> bool {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.visit.is_some())
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::fixtures::{
+        lap_packet, status_packet, vehicle, LapFixture, StatusFixture,
+    };
+    use crate::analysis::stint::StintTracker;
+    use crate::packet::lap::{PitStatus, ResultStatus};
+    use crate::packet::status::{PhysicalTyreCompound, VisualTyreCompound};
+
+    fn lap(current_lap_number: u8, pit_status: PitStatus) -> LapFixture {
+        LapFixture {
+            current_lap_number,
+            pit_status,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_pit_stop() {
+        let mut tracker = StintTracker::new();
+
+        tracker.update(&lap_packet(0.0, vec![lap(1, PitStatus::None)]));
+        tracker.update(&status_packet(1.0, vec![StatusFixture::default()]));
+        tracker.update(&lap_packet(90.0, vec![lap(2, PitStatus::None)]));
+        tracker.update(&lap_packet(100.0, vec![lap(2, PitStatus::Pitting)]));
+        tracker.update(&lap_packet(110.0, vec![lap(2, PitStatus::InPits)]));
+        tracker.update(&lap_packet(112.5, vec![lap(2, PitStatus::Pitting)]));
+        tracker.update(&lap_packet(120.0, vec![lap(3, PitStatus::None)]));
+        tracker.update(&status_packet(
+            121.0,
+            vec![StatusFixture {
+                physical_tyre_compound: PhysicalTyreCompound::F1C4,
+                visual_tyre_compound: VisualTyreCompound::F1Soft,
+                ..StatusFixture::default()
+            }],
+        ));
+
+        let history = tracker.history(vehicle(0)).unwrap();
+        assert_eq!(2, history.stints().len());
+        assert_eq!(1, history.pit_stops().len());
+
+        let first_stint = history.stints()[0];
+        assert_eq!(1, first_stint.start_lap());
+        assert_eq!(2, first_stint.end_lap());
+        assert_eq!(2, first_stint.lap_count());
+        assert_eq!(
+            Some(VisualTyreCompound::F1Medium),
+            first_stint.visual_tyre_compound()
+        );
+
+        let second_stint = history.current_stint().unwrap();
+        assert_eq!(3, second_stint.start_lap());
+        assert_eq!(
+            Some(PhysicalTyreCompound::F1C4),
+            second_stint.physical_tyre_compound()
+        );
+
+        let pit_stop = history.pit_stops()[0];
+        assert_eq!(2, pit_stop.lap());
+        assert_eq!(Duration::from_secs(20), pit_stop.pit_lane_time());
+        assert_eq!(Duration::from_secs_f32(2.5), *pit_stop.stationary_time());
+        assert!(!tracker.is_in_pit_lane(vehicle(0)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn restore_serialized_tracker() {
+        let mut tracker = StintTracker::new();
+
+        tracker.update(&lap_packet(0.0, vec![lap(1, PitStatus::None)]));
+        tracker.update(&lap_packet(100.0, vec![lap(2, PitStatus::Pitting)]));
+        tracker.update(&lap_packet(110.0, vec![lap(2, PitStatus::InPits)]));
+        tracker.update(&lap_packet(120.0, vec![lap(3, PitStatus::None)]));
+        tracker.update(&lap_packet(200.0, vec![lap(4, PitStatus::Pitting)]));
+
+        let json = serde_json::to_string(&tracker).unwrap();
+        let mut restored: StintTracker = serde_json::from_str(&json).unwrap();
+        assert_eq!(tracker, restored);
+
+        // The restored tracker finishes the pit stop that was in progress.
+        restored.update(&lap_packet(220.0, vec![lap(5, PitStatus::None)]));
+        let history = restored.history(vehicle(0)).unwrap();
+        assert_eq!(2, history.pit_stops().len());
+        assert_eq!(
+            Duration::from_secs(20),
+            history.pit_stops()[1].pit_lane_time()
+        );
+    }
+
+    #[test]
+    fn update_while_in_pit_lane() {
+        let mut tracker = StintTracker::new();
+
+        tracker.update(&lap_packet(0.0, vec![lap(4, PitStatus::None)]));
+        tracker.update(&lap_packet(1.0, vec![lap(4, PitStatus::Pitting)]));
+
+        assert!(tracker.is_in_pit_lane(vehicle(0)));
+        assert!(tracker.history(vehicle(0)).unwrap().pit_stops().is_empty());
+    }
+
+    #[test]
+    fn update_ignores_inactive_cars() {
+        let mut tracker = StintTracker::new();
+
+        tracker.update(&lap_packet(
+            0.0,
+            vec![LapFixture {
+                result_status: ResultStatus::Inactive,
+                ..LapFixture::default()
+            }],
+        ));
+
+        assert!(tracker.history(vehicle(0)).is_none());
+    }
+}
@@ -0,0 +1,282 @@
+//! Estimates for the pit strategy of each car
+//!
+//! Deciding when to pit is one of the most important calls in a race. It depends on how long the
+//! tyres last, whether the car has enough fuel, how much time a pit stop costs, and where the car
+//! would rejoin the race. The strategy estimator combines the tyre wear model, the fuel tracker, the
+//! stint tracker, and the gap tracker, and derives a strategy advisory for each car from them.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use getset::{CopyGetters, Getters};
+
+use crate::analysis::fuel::FuelTracker;
+use crate::analysis::gap::GapTracker;
+use crate::analysis::is_racing;
+use crate::analysis::stint::StintTracker;
+use crate::analysis::tyre_wear::TyreWearModel;
+use crate::packet::lap::Lap;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Strategy advisory for a car
+///
+/// The advisory assumes a one-stop strategy from the current point of the race. The pit window is
+/// the range of laps in which a car can stop so that both its current and its next set of tyres
+/// stay below the wear threshold. Undercut and overcut opportunities are reported for the cars
+/// directly ahead and behind if they are within the undercut gap.
+#[derive(Debug, Getters, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct StrategyAdvice {
+    /// Returns the current position of the car.
+    #[getset(get_copy = "pub")]
+    position: u8,
+
+    /// Returns the number of laps until the tyres of the car reach the wear threshold.
+    #[getset(get_copy = "pub")]
+    tyre_laps_remaining: Option<f32>,
+
+    /// Returns the fuel delta to the finish in laps.
+    #[getset(get_copy = "pub")]
+    fuel_delta: Option<f32>,
+
+    /// Returns the first and the last lap on which the car should pit.
+    #[getset(get_copy = "pub")]
+    pit_window: Option<(u8, u8)>,
+
+    /// Returns the predicted position of the car if it pitted now.
+    #[getset(get_copy = "pub")]
+    rejoin_position: u8,
+
+    /// Returns the car ahead that could be undercut by pitting now.
+    #[getset(get_copy = "pub")]
+    undercut_target: Option<VehicleIndex>,
+
+    /// Returns the car behind that could undercut the car by pitting first.
+    #[getset(get_copy = "pub")]
+    undercut_threat: Option<VehicleIndex>,
+}
+
+/// Estimator for the pit strategy of all cars in the session
+///
+/// The strategy estimator consumes all packets that the underlying trackers need. The time lost in
+/// a pit stop is estimated from the average time cars have spent in the pit lane in the session,
+/// and falls back to a default until the first pit stop has been observed.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StrategyEstimator {
+    wear_threshold: f32,
+    default_pit_loss: Duration,
+    undercut_gap: Duration,
+    total_laps: u8,
+    laps: BTreeMap<VehicleIndex, Lap>,
+    tyres: TyreWearModel,
+    fuel: FuelTracker,
+    stints: StintTracker,
+    gaps: GapTracker,
+}
+
+impl StrategyEstimator {
+    /// Create a new strategy estimator.
+    ///
+    /// The wear threshold is the tyre wear in percent at which a set of tyres is considered worn
+    /// out. The default pit loss is used until a pit stop has been observed, and the undercut gap
+    /// is the largest gap to another car at which an undercut or an overcut is considered possible.
+    pub fn new(wear_threshold: f32, default_pit_loss: Duration, undercut_gap: Duration) -> Self {
+        StrategyEstimator {
+            wear_threshold,
+            default_pit_loss,
+            undercut_gap,
+            ..StrategyEstimator::default()
+        }
+    }
+
+    /// Update the strategy estimates with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        self.tyres.update(packet);
+        self.fuel.update(packet);
+        self.stints.update(packet);
+        self.gaps.update(packet);
+
+        match packet {
+            Packet::Session(packet) => self.total_laps = packet.total_laps(),
+            Packet::Lap(packet) => {
+                for (index, lap) in enumerate_cars(packet.laps()) {
+                    self.laps.insert(index, *lap);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the estimated time that a pit stop costs.
+    pub fn pit_loss(&self) -> Duration {
+        let pit_lane_times: Vec<Duration> = self
+            .stints
+            .histories()
+            .flat_map(|(_, history)| history.pit_stops().iter())
+            .map(|pit_stop| pit_stop.pit_lane_time())
+            .collect();
+
+        if pit_lane_times.is_empty() {
+            self.default_pit_loss
+        } else {
+            pit_lane_times.iter().sum::<Duration>() / pit_lane_times.len() as u32
+        }
+    }
+
+    /// Returns the strategy advisory for a car, if it is actively racing.
+    pub fn advice(&self, vehicle_index: VehicleIndex) -> Option<StrategyAdvice> {
+        let lap = self.laps.get(&vehicle_index).filter(|lap| is_racing(lap))?;
+        let prediction = self.tyres.predict(vehicle_index, self.wear_threshold);
+
+        let tyre_laps_remaining = prediction.map(|prediction| prediction.laps_remaining());
+        let fuel_delta = self
+            .fuel
+            .metric(vehicle_index)
+            .and_then(|metric| metric.fuel_delta());
+
+        let pit_window = prediction.and_then(|prediction| {
+            let curve = self.tyres.curve(prediction.compound())?;
+            let max_stint = (self.wear_threshold - curve.initial_wear()) / curve.wear_per_lap();
+
+            let current_lap = lap.current_lap_number();
+            let latest = current_lap.saturating_add(prediction.laps_remaining() as u8);
+            let earliest = (self.total_laps as f32 - max_stint).ceil().max(0.0) as u8;
+
+            if self.total_laps == 0 || earliest.max(current_lap) > latest {
+                None
+            } else {
+                Some((earliest.max(current_lap), latest.min(self.total_laps)))
+            }
+        });
+
+        Some(StrategyAdvice {
+            position: lap.position(),
+            tyre_laps_remaining,
+            fuel_delta,
+            pit_window,
+            rejoin_position: self.rejoin_position(vehicle_index, lap),
+            undercut_target: self
+                .neighbour(lap.position().checked_sub(1))
+                .filter(|ahead| {
+                    self.gaps
+                        .gap(*ahead, vehicle_index)
+                        .is_some_and(|gap| gap <= self.undercut_gap)
+                }),
+            undercut_threat: self
+                .neighbour(lap.position().checked_add(1))
+                .filter(|behind| {
+                    self.gaps
+                        .gap(vehicle_index, *behind)
+                        .is_some_and(|gap| gap <= self.undercut_gap)
+                }),
+        })
+    }
+
+    /// Returns the position at which a car would rejoin the race if it pitted now.
+    ///
+    /// Cars behind are passed if they are closer than the time lost in the pit stop. Cars for which
+    /// no gap is known are too far behind to be relevant.
+    fn rejoin_position(&self, vehicle_index: VehicleIndex, lap: &Lap) -> u8 {
+        let pit_loss = self.pit_loss();
+        let mut passed_by = 0;
+
+        for (other, other_lap) in self.laps.iter() {
+            if *other == vehicle_index
+                || !is_racing(other_lap)
+                || other_lap.position() < lap.position()
+            {
+                continue;
+            }
+
+            let is_passed = self
+                .gaps
+                .gap(vehicle_index, *other)
+                .is_some_and(|gap| gap < pit_loss);
+
+            if is_passed {
+                passed_by += 1;
+            }
+        }
+
+        lap.position().saturating_add(passed_by)
+    }
+
+    /// Returns the car that is racing in the given position.
+    fn neighbour(&self, position: Option<u8>) -> Option<VehicleIndex> {
+        let position = position.filter(|position| *position > 0)?;
+
+        self.laps
+            .iter()
+            .find(|(_, lap)| is_racing(lap) && lap.position() == position)
+            .map(|(index, _)| *index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::fixtures::{
+        lap_packet, session_packet, status_packet, vehicle, LapFixture, SessionFixture,
+        StatusFixture,
+    };
+    use crate::analysis::strategy::StrategyEstimator;
+    use crate::types::CornerProperty;
+
+    fn lap(position: u8, current_lap_number: u8, total_distance: f32) -> LapFixture {
+        LapFixture {
+            position,
+            current_lap_number,
+            total_distance,
+            ..LapFixture::default()
+        }
+    }
+
+    fn status(wear: u8) -> StatusFixture {
+        StatusFixture {
+            tyre_wear: CornerProperty::new(wear, wear, wear, wear),
+            ..StatusFixture::default()
+        }
+    }
+
+    #[test]
+    fn advice_with_pit_window_and_gaps() {
+        let mut estimator =
+            StrategyEstimator::new(60.0, Duration::from_secs(20), Duration::from_secs(2));
+
+        estimator.update(&session_packet(
+            0.0,
+            SessionFixture {
+                total_laps: 20,
+                ..SessionFixture::default()
+            },
+        ));
+
+        // Three cars drive at 100 meters per second. The second car is one second behind the first,
+        // and the third car is 30 seconds behind the second.
+        for lap_number in 1..=5u8 {
+            let time = lap_number as f32 * 50.0;
+            let distance = time * 100.0;
+
+            estimator.update(&status_packet(time, vec![status(lap_number * 5); 3]));
+            estimator.update(&lap_packet(
+                time,
+                vec![
+                    lap(1, lap_number, distance),
+                    lap(2, lap_number, distance - 100.0),
+                    lap(3, lap_number, distance - 3100.0),
+                ],
+            ));
+        }
+
+        let advice = estimator.advice(vehicle(1)).unwrap();
+
+        assert_eq!(2, advice.position());
+        assert_eq!(Some(7.0), advice.tyre_laps_remaining());
+        assert_eq!(Some((9, 12)), advice.pit_window());
+        assert_eq!(2, advice.rejoin_position());
+        assert_eq!(Some(vehicle(0)), advice.undercut_target());
+        assert_eq!(None, advice.undercut_threat());
+        assert_eq!(Duration::from_secs(20), estimator.pit_loss());
+    }
+}
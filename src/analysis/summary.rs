@@ -0,0 +1,305 @@
+//! Statistical summaries of the telemetry of each car
+//!
+//! Reports about a session often need aggregated telemetry, for example the top speed of a car or
+//! how much of a lap it spent at full throttle. The telemetry summarizer aggregates the telemetry
+//! channels of every car into statistics for each lap and for the whole session, so that reports do
+//! not need to export and process the raw samples.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::LapPacket;
+use crate::packet::telemetry::{Telemetry, TelemetryPacket};
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Throttle ratio above which the throttle counts as fully applied
+const FULL_THROTTLE: f32 = 0.99;
+
+/// Running minimum, maximum, and mean of a series of values
+#[derive(Debug, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct Summary {
+    /// Returns the number of values in the summary.
+    #[getset(get_copy = "pub")]
+    count: usize,
+
+    /// Returns the smallest value in the summary.
+    #[getset(get_copy = "pub")]
+    min: f32,
+
+    /// Returns the largest value in the summary.
+    #[getset(get_copy = "pub")]
+    max: f32,
+
+    sum: f32,
+}
+
+impl Summary {
+    /// Create a new summary without any values.
+    pub fn new() -> Self {
+        Summary::default()
+    }
+
+    /// Add a value to the summary.
+    pub fn add(&mut self, value: f32) {
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+
+        self.sum += value;
+        self.count += 1;
+    }
+
+    /// Returns the mean of the values in the summary, if it has any values.
+    pub fn mean(&self) -> Option<f32> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.sum / self.count as f32)
+        }
+    }
+}
+
+/// Summary of the telemetry channels of a car
+#[derive(Debug, Getters, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct TelemetrySummary {
+    /// Returns the summary of the speed in kilometers per hour.
+    #[getset(get = "pub")]
+    speed: Summary,
+
+    /// Returns the summary of the throttle ratio.
+    #[getset(get = "pub")]
+    throttle: Summary,
+
+    /// Returns the summary of the brake ratio.
+    #[getset(get = "pub")]
+    brake: Summary,
+
+    /// Returns the summary of the steering ratio.
+    #[getset(get = "pub")]
+    steering: Summary,
+
+    /// Returns the summary of the engine RPM.
+    #[getset(get = "pub")]
+    engine_rpm: Summary,
+
+    /// Returns the summary of the engine temperature in degrees celsius.
+    #[getset(get = "pub")]
+    engine_temperature: Summary,
+
+    /// Returns the time covered by the summary.
+    #[getset(get = "pub")]
+    duration: Duration,
+
+    /// Returns the time spent at full throttle.
+    #[getset(get = "pub")]
+    full_throttle_time: Duration,
+
+    /// Returns the time spent with the brakes applied.
+    #[getset(get = "pub")]
+    braking_time: Duration,
+}
+
+impl TelemetrySummary {
+    /// Returns the top speed in kilometers per hour.
+    pub fn top_speed(&self) -> f32 {
+        self.speed.max
+    }
+
+    /// Returns the percentage of the time that was spent at full throttle.
+    pub fn full_throttle_percentage(&self) -> f32 {
+        percentage(self.full_throttle_time, self.duration)
+    }
+
+    /// Returns the percentage of the time that was spent with the brakes applied.
+    pub fn braking_percentage(&self) -> f32 {
+        percentage(self.braking_time, self.duration)
+    }
+
+    fn add(&mut self, telemetry: &Telemetry) {
+        self.speed.add(telemetry.speed().as_kph() as f32);
+        self.throttle.add(telemetry.throttle());
+        self.brake.add(telemetry.brake());
+        self.steering.add(telemetry.steering());
+        self.engine_rpm.add(telemetry.engine_rpm() as f32);
+        self.engine_temperature
+            .add(telemetry.engine_temperature().as_celsius() as f32);
+    }
+
+    fn add_time(&mut self, previous: &Telemetry, elapsed: Duration) {
+        self.duration += elapsed;
+
+        if previous.throttle() >= FULL_THROTTLE {
+            self.full_throttle_time += elapsed;
+        }
+
+        if previous.brake() > 0.0 {
+            self.braking_time += elapsed;
+        }
+    }
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarSummaries {
+    lap: Option<u8>,
+    previous: Option<(Duration, Telemetry)>,
+    session: TelemetrySummary,
+    current: TelemetrySummary,
+    laps: BTreeMap<u8, TelemetrySummary>,
+}
+
+/// Aggregator for the telemetry of all cars in the session
+///
+/// The telemetry summarizer consumes lap data and telemetry packets. The time-based statistics are
+/// calculated from the time between two telemetry packets, which is attributed to the state of the
+/// car in the earlier packet.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TelemetrySummarizer {
+    cars: BTreeMap<VehicleIndex, CarSummaries>,
+}
+
+impl TelemetrySummarizer {
+    /// Create a new summarizer without any telemetry.
+    pub fn new() -> Self {
+        TelemetrySummarizer::default()
+    }
+
+    /// Update the summaries with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Lap(packet) => self.update_laps(packet),
+            Packet::Telemetry(packet) => self.update_telemetry(packet),
+            _ => {}
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(index).or_default();
+            let lap_number = lap.current_lap_number();
+
+            if let Some(previous) = car.lap.filter(|previous| *previous != lap_number) {
+                let summary = std::mem::take(&mut car.current);
+                car.laps.insert(previous, summary);
+            }
+
+            car.lap = Some(lap_number);
+        }
+    }
+
+    fn update_telemetry(&mut self, packet: &TelemetryPacket) {
+        let session_time = *packet.header().session_time();
+
+        for (index, telemetry) in enumerate_cars(packet.telemetry()) {
+            let car = self.cars.entry(index).or_default();
+
+            if let Some((previous_time, previous)) = car.previous {
+                let elapsed = session_time.saturating_sub(previous_time);
+
+                car.session.add_time(&previous, elapsed);
+                car.current.add_time(&previous, elapsed);
+            }
+
+            car.session.add(telemetry);
+            car.current.add(telemetry);
+            car.previous = Some((session_time, *telemetry));
+        }
+    }
+
+    /// Returns the telemetry summary of a car for the whole session.
+    pub fn session(&self, vehicle_index: VehicleIndex) -> Option<&TelemetrySummary> {
+        self.cars.get(&vehicle_index).map(|car| &car.session)
+    }
+
+    /// Returns the telemetry summary of a car for the lap it is currently on.
+    pub fn current_lap(&self, vehicle_index: VehicleIndex) -> Option<&TelemetrySummary> {
+        self.cars.get(&vehicle_index).map(|car| &car.current)
+    }
+
+    /// Returns the telemetry summary of a car for a completed lap.
+    pub fn lap(&self, vehicle_index: VehicleIndex, lap_number: u8) -> Option<&TelemetrySummary> {
+        self.cars.get(&vehicle_index)?.laps.get(&lap_number)
+    }
+}
+
+/// Returns the part as a percentage of the total.
+fn percentage(part: Duration, total: Duration) -> f32 {
+    if total.as_secs_f32() == 0.0 {
+        0.0
+    } else {
+        part.as_secs_f32() / total.as_secs_f32() * 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::fixtures::{
+        lap_packet, telemetry_packet, vehicle, LapFixture, TelemetryFixture,
+    };
+    use crate::analysis::summary::{Summary, TelemetrySummarizer};
+
+    fn lap(current_lap_number: u8) -> LapFixture {
+        LapFixture {
+            current_lap_number,
+            ..LapFixture::default()
+        }
+    }
+
+    fn telemetry(speed: u16, throttle: f32, brake: f32) -> TelemetryFixture {
+        TelemetryFixture {
+            speed,
+            throttle,
+            brake,
+            ..TelemetryFixture::default()
+        }
+    }
+
+    #[test]
+    fn summary_with_values() {
+        let mut summary = Summary::new();
+        assert!(summary.mean().is_none());
+
+        summary.add(3.0);
+        summary.add(1.0);
+        summary.add(2.0);
+
+        assert_eq!(3, summary.count());
+        assert_approx_eq!(1.0, summary.min());
+        assert_approx_eq!(3.0, summary.max());
+        assert_approx_eq!(2.0, summary.mean().unwrap());
+    }
+
+    #[test]
+    fn update_with_laps() {
+        let mut summarizer = TelemetrySummarizer::new();
+
+        summarizer.update(&lap_packet(0.0, vec![lap(1)]));
+        summarizer.update(&telemetry_packet(0.0, vec![telemetry(300, 1.0, 0.0)]));
+        summarizer.update(&telemetry_packet(3.0, vec![telemetry(100, 0.0, 1.0)]));
+        summarizer.update(&telemetry_packet(4.0, vec![telemetry(150, 0.5, 0.0)]));
+        summarizer.update(&lap_packet(4.0, vec![lap(2)]));
+        summarizer.update(&telemetry_packet(5.0, vec![telemetry(320, 1.0, 0.0)]));
+
+        let summary = summarizer.lap(vehicle(0), 1).unwrap();
+        assert_approx_eq!(300.0, summary.top_speed());
+        assert_approx_eq!(75.0, summary.full_throttle_percentage());
+        assert_approx_eq!(25.0, summary.braking_percentage());
+
+        let session = summarizer.session(vehicle(0)).unwrap();
+        assert_approx_eq!(320.0, session.top_speed());
+        assert_eq!(4, session.speed().count());
+        assert_eq!(
+            1,
+            summarizer.current_lap(vehicle(0)).unwrap().speed().count()
+        );
+    }
+}
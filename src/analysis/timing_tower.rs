@@ -0,0 +1,271 @@
+//! Live timing tower with the running order of the session
+//!
+//! Every broadcast and most stream overlays show a timing tower, a leaderboard with the position,
+//! gaps, and tyres of every driver. Building one requires data from several packets, which are sent
+//! at different rates. The timing tower combines them into a list of rows that is updated with
+//! every relevant packet, and can be rendered as it is.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use getset::{CopyGetters, Getters};
+
+use crate::analysis::gap::GapTracker;
+use crate::analysis::stint::StintTracker;
+use crate::packet::lap::{Lap, PitStatus, ResultStatus};
+use crate::packet::participants::Participant;
+use crate::packet::status::{CarStatus, VisualTyreCompound};
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// A row in the timing tower
+#[derive(Debug, Getters, CopyGetters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+pub struct TimingRow {
+    /// Returns the position of the car.
+    #[getset(get_copy = "pub")]
+    position: u8,
+
+    /// Returns the index of the car.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the name of the driver, or an empty string if it is not yet known.
+    #[getset(get = "pub")]
+    name: String,
+
+    /// Returns the number of the car, if it is known.
+    #[getset(get_copy = "pub")]
+    race_number: Option<u8>,
+
+    /// Returns the time gap to the leader of the session.
+    #[getset(get_copy = "pub")]
+    gap_to_leader: Option<Duration>,
+
+    /// Returns the time gap to the car ahead.
+    #[getset(get_copy = "pub")]
+    interval: Option<Duration>,
+
+    /// Returns the lap the car is currently on.
+    #[getset(get_copy = "pub")]
+    current_lap: u8,
+
+    /// Returns the time of the last lap of the car.
+    #[getset(get = "pub")]
+    last_lap_time: Duration,
+
+    /// Returns the time of the best lap of the car.
+    #[getset(get = "pub")]
+    best_lap_time: Duration,
+
+    /// Returns the tyre compound that the car is using, if it is known.
+    #[getset(get_copy = "pub")]
+    tyre_compound: Option<VisualTyreCompound>,
+
+    /// Returns the number of laps the car has driven on its current tyres.
+    #[getset(get_copy = "pub")]
+    tyre_age: u8,
+
+    /// Returns the pit status of the car.
+    #[getset(get_copy = "pub")]
+    pit_status: PitStatus,
+
+    /// Returns the number of pit stops the car has made.
+    #[getset(get_copy = "pub")]
+    pit_stops: usize,
+
+    /// Returns the accumulated time penalties of the car in seconds.
+    #[getset(get_copy = "pub")]
+    penalties: u8,
+
+    /// Returns the result status of the car.
+    #[getset(get_copy = "pub")]
+    result_status: ResultStatus,
+}
+
+/// Leaderboard of all cars in the session
+///
+/// The timing tower consumes lap data, participants, and car status packets, and rebuilds its rows
+/// whenever one of them is received. Cars without a position, which are not part of the session,
+/// do not appear in the timing tower.
+///
+/// # Examples
+///
+/// ```
+/// use f1_api::analysis::timing_tower::TimingTower;
+/// # use f1_api::packet::Packet;
+///
+/// # fn example(packets: Vec<Packet>) {
+/// let mut tower = TimingTower::new();
+///
+/// for packet in packets {
+///     tower.update(&packet);
+/// }
+///
+/// for row in tower.rows() {
+///     println!("P{} {}", row.position(), row.name());
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TimingTower {
+    laps: BTreeMap<VehicleIndex, Lap>,
+    participants: BTreeMap<VehicleIndex, Participant>,
+    statuses: BTreeMap<VehicleIndex, CarStatus>,
+    gaps: GapTracker,
+    stints: StintTracker,
+    rows: Vec<TimingRow>,
+}
+
+impl TimingTower {
+    /// Create a new timing tower without any cars.
+    pub fn new() -> Self {
+        TimingTower::default()
+    }
+
+    /// Update the timing tower with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        self.gaps.update(packet);
+        self.stints.update(packet);
+
+        match packet {
+            Packet::Lap(packet) => {
+                for (index, lap) in enumerate_cars(packet.laps()) {
+                    self.laps.insert(index, *lap);
+                }
+            }
+            Packet::Participants(packet) => {
+                for (index, participant) in enumerate_cars(packet.participants()) {
+                    self.participants.insert(index, participant.clone());
+                }
+            }
+            Packet::Status(packet) => {
+                for (index, status) in enumerate_cars(packet.statuses()) {
+                    self.statuses.insert(index, *status);
+                }
+            }
+            _ => return,
+        }
+
+        self.rebuild();
+    }
+
+    /// Returns the rows of the timing tower, ordered by position.
+    pub fn rows(&self) -> &[TimingRow] {
+        &self.rows
+    }
+
+    /// Returns the row of a car, if it is in the timing tower.
+    pub fn row(&self, vehicle_index: VehicleIndex) -> Option<&TimingRow> {
+        self.rows
+            .iter()
+            .find(|row| row.vehicle_index == vehicle_index)
+    }
+
+    fn rebuild(&mut self) {
+        let mut order: Vec<(VehicleIndex, &Lap)> = self
+            .laps
+            .iter()
+            .filter(|(_, lap)| lap.position() > 0)
+            .map(|(index, lap)| (*index, lap))
+            .collect();
+        order.sort_by_key(|(_, lap)| lap.position());
+
+        let leader = order.first().map(|(index, _)| *index);
+        let mut ahead: Option<VehicleIndex> = None;
+        let mut rows = Vec::with_capacity(order.len());
+
+        for (vehicle_index, lap) in order {
+            let participant = self.participants.get(&vehicle_index);
+            let history = self.stints.history(vehicle_index);
+            let tyre_age = history
+                .and_then(|history| history.current_stint())
+                .map(|stint| lap.current_lap_number().saturating_sub(stint.start_lap()))
+                .unwrap_or_default();
+
+            rows.push(TimingRow {
+                position: lap.position(),
+                vehicle_index,
+                name: participant
+                    .map(|participant| participant.name().clone())
+                    .unwrap_or_default(),
+                race_number: participant.map(|participant| participant.race_number()),
+                gap_to_leader: leader
+                    .filter(|leader| *leader != vehicle_index)
+                    .and_then(|leader| self.gaps.gap(leader, vehicle_index)),
+                interval: ahead.and_then(|ahead| self.gaps.gap(ahead, vehicle_index)),
+                current_lap: lap.current_lap_number(),
+                last_lap_time: *lap.last_lap_time(),
+                best_lap_time: *lap.best_lap_time(),
+                tyre_compound: self
+                    .statuses
+                    .get(&vehicle_index)
+                    .map(|status| status.visual_tyre_compound()),
+                tyre_age,
+                pit_status: lap.pit_status(),
+                pit_stops: history
+                    .map(|history| history.pit_stops().len())
+                    .unwrap_or_default(),
+                penalties: lap.penalties(),
+                result_status: lap.result_status(),
+            });
+
+            ahead = Some(vehicle_index);
+        }
+
+        self.rows = rows;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::fixtures::{
+        lap_packet, participants_packet, status_packet, vehicle, LapFixture, StatusFixture,
+    };
+    use crate::analysis::timing_tower::TimingTower;
+    use crate::packet::status::VisualTyreCompound;
+
+    fn lap(position: u8, total_distance: f32) -> LapFixture {
+        LapFixture {
+            position,
+            total_distance,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_running_order() {
+        let mut tower = TimingTower::new();
+
+        tower.update(&participants_packet(0.0, vec!["Alice", "Bob", "Carol"]));
+        tower.update(&status_packet(0.0, vec![StatusFixture::default(); 3]));
+
+        // The cars drive at 100 meters per second. Bob leads, followed by Carol one second and
+        // Alice three seconds behind.
+        for second in 0..=5 {
+            let distance = 1000.0 + second as f32 * 100.0;
+
+            tower.update(&lap_packet(
+                second as f32,
+                vec![
+                    lap(3, distance - 300.0),
+                    lap(1, distance),
+                    lap(2, distance - 100.0),
+                ],
+            ));
+        }
+
+        let names: Vec<&str> = tower.rows().iter().map(|row| row.name().as_str()).collect();
+        assert_eq!(vec!["Bob", "Carol", "Alice"], names);
+
+        let alice = tower.row(vehicle(0)).unwrap();
+        assert_eq!(Some(Duration::from_secs(3)), alice.gap_to_leader());
+        assert_eq!(Some(Duration::from_secs(2)), alice.interval());
+        assert_eq!(Some(VisualTyreCompound::F1Medium), alice.tyre_compound());
+
+        let bob = tower.row(vehicle(1)).unwrap();
+        assert_eq!(None, bob.gap_to_leader());
+        assert_eq!(None, bob.interval());
+    }
+}
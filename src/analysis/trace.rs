@@ -0,0 +1,551 @@
+//! Lap traces and ghost comparisons
+//!
+//! Sim racing communities compare hot laps by overlaying the telemetry of two laps, often from
+//! different drivers or sessions. A lap trace records the telemetry channels of a car together with
+//! its lap distance and lap time, so that it can be stored and compared later. Two traces are
+//! aligned by their lap distance, which results in a delta trace and a side-by-side comparison of
+//! the channels. Traces can also be resampled at a regular interval of lap distance, which is the
+//! common representation of telemetry for lap comparisons and corner analysis. The comparison of
+//! two laps can be exported as an overlay of aligned arrays for plotting.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::LapPacket;
+use crate::packet::telemetry::{Gear, TelemetryPacket};
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// A sample of the telemetry of a car during a lap
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+#[allow(clippy::too_many_arguments)]
+pub struct TraceSample {
+    /// Returns the distance around the lap in meters.
+    #[getset(get_copy = "pub")]
+    lap_distance: f32,
+
+    /// Returns the time since the start of the lap.
+    #[getset(get = "pub")]
+    lap_time: Duration,
+
+    /// Returns the speed of the car in kilometers per hour.
+    #[getset(get_copy = "pub")]
+    speed: f32,
+
+    /// Returns the ratio of the applied throttle.
+    #[getset(get_copy = "pub")]
+    throttle: f32,
+
+    /// Returns the ratio of the applied brake.
+    #[getset(get_copy = "pub")]
+    brake: f32,
+
+    /// Returns the ratio of the steering input.
+    #[getset(get_copy = "pub")]
+    steering: f32,
+
+    /// Returns the gear the car is in.
+    #[getset(get_copy = "pub")]
+    gear: Gear,
+
+    /// Returns the engine RPM.
+    #[getset(get_copy = "pub")]
+    engine_rpm: f32,
+}
+
+/// Telemetry trace of a car during a lap
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Clone, PartialOrd, Default)]
+pub struct LapTrace {
+    /// Returns the vehicle index of the car that drove the lap.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the number of the lap.
+    #[getset(get_copy = "pub")]
+    lap_number: u8,
+
+    /// Returns the time of the lap.
+    #[getset(get = "pub")]
+    lap_time: Duration,
+
+    /// Returns the samples of the trace, ordered by their lap distance.
+    #[getset(get = "pub")]
+    samples: Vec<TraceSample>,
+}
+
+impl LapTrace {
+    /// Returns the sample at the lap distance, interpolated from the recorded samples.
+    ///
+    /// The gear is taken from the last recorded sample before the lap distance. No sample is
+    /// returned if the lap distance lies outside of the trace.
+    pub fn sample_at(&self, lap_distance: f32) -> Option<TraceSample> {
+        let index = self
+            .samples
+            .iter()
+            .position(|sample| sample.lap_distance >= lap_distance)?;
+        let after = self.samples[index];
+
+        if after.lap_distance == lap_distance {
+            return Some(after);
+        }
+
+        let before = self.samples[index.checked_sub(1)?];
+        Some(interpolate(&before, &after, lap_distance))
+    }
+
+    /// Resample the trace at a regular interval of lap distance.
+    ///
+    /// The samples of the resampled trace lie on multiples of `step` meters, so that the resampled
+    /// traces of different laps share the same lap distances and can be compared sample by sample.
+    pub fn resample(&self, step: f32) -> LapTrace {
+        let mut samples = Vec::new();
+
+        if let Some((start, end)) = self.coverage() {
+            let step = step.max(f32::EPSILON);
+            let mut index = (start / step).ceil() as u32;
+            let mut after = 0;
+
+            loop {
+                let lap_distance = index as f32 * step;
+                if lap_distance > end {
+                    break;
+                }
+
+                while self.samples[after].lap_distance < lap_distance {
+                    after += 1;
+                }
+
+                let sample = match after.checked_sub(1) {
+                    Some(before) if self.samples[after].lap_distance > lap_distance => {
+                        interpolate(&self.samples[before], &self.samples[after], lap_distance)
+                    }
+                    _ => TraceSample {
+                        lap_distance,
+                        ..self.samples[after]
+                    },
+                };
+
+                samples.push(sample);
+                index += 1;
+            }
+        }
+
+        LapTrace::new(self.vehicle_index, self.lap_number, self.lap_time, samples)
+    }
+
+    /// Compare the trace with the trace of another lap.
+    ///
+    /// The traces are compared every `step` meters over the lap distance that both of them cover.
+    pub fn compare(&self, other: &LapTrace, step: f32) -> GhostComparison {
+        let mut points = Vec::new();
+
+        let (start, end) = match (self.coverage(), other.coverage()) {
+            (Some((start, end)), Some((other_start, other_end))) => {
+                (start.max(other_start), end.min(other_end))
+            }
+            _ => return GhostComparison::default(),
+        };
+
+        let step = step.max(f32::EPSILON);
+        let mut lap_distance = start;
+
+        while lap_distance <= end {
+            if let (Some(reference), Some(candidate)) =
+                (self.sample_at(lap_distance), other.sample_at(lap_distance))
+            {
+                let delta = candidate.lap_time.as_secs_f32() - reference.lap_time.as_secs_f32();
+                points.push(GhostPoint::new(lap_distance, delta, reference, candidate));
+            }
+
+            lap_distance += step;
+        }
+
+        GhostComparison::new(points)
+    }
+
+    /// Returns the first and the last lap distance of the trace.
+    fn coverage(&self) -> Option<(f32, f32)> {
+        Some((
+            self.samples.first()?.lap_distance,
+            self.samples.last()?.lap_distance,
+        ))
+    }
+}
+
+/// Interpolate between two samples at the lap distance.
+///
+/// The gear is taken from the sample before the lap distance.
+fn interpolate(before: &TraceSample, after: &TraceSample, lap_distance: f32) -> TraceSample {
+    let fraction =
+        (lap_distance - before.lap_distance) / (after.lap_distance - before.lap_distance);
+    let interpolate = |from: f32, to: f32| from + (to - from) * fraction;
+
+    TraceSample {
+        lap_distance,
+        lap_time: Duration::from_secs_f32(interpolate(
+            before.lap_time.as_secs_f32(),
+            after.lap_time.as_secs_f32(),
+        )),
+        speed: interpolate(before.speed, after.speed),
+        throttle: interpolate(before.throttle, after.throttle),
+        brake: interpolate(before.brake, after.brake),
+        steering: interpolate(before.steering, after.steering),
+        gear: before.gear,
+        engine_rpm: interpolate(before.engine_rpm, after.engine_rpm),
+    }
+}
+
+/// Comparison of two laps at a point of the track
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct GhostPoint {
+    /// Returns the distance around the lap in meters.
+    #[getset(get_copy = "pub")]
+    lap_distance: f32,
+
+    /// Returns the time of the candidate lap minus the time of the reference lap in seconds.
+    #[getset(get_copy = "pub")]
+    delta: f32,
+
+    /// Returns the sample of the reference lap.
+    #[getset(get = "pub")]
+    reference: TraceSample,
+
+    /// Returns the sample of the candidate lap.
+    #[getset(get = "pub")]
+    candidate: TraceSample,
+}
+
+/// Comparison of two lap traces
+#[derive(new, Debug, Getters, PartialEq, Clone, PartialOrd, Default)]
+pub struct GhostComparison {
+    /// Returns the comparison of the laps at regular intervals.
+    #[getset(get = "pub")]
+    points: Vec<GhostPoint>,
+}
+
+impl GhostComparison {
+    /// Returns the time delta at the end of the compared distance in seconds.
+    pub fn final_delta(&self) -> Option<f32> {
+        self.points.last().map(|point| point.delta)
+    }
+
+    /// Returns the channels of both laps as aligned arrays.
+    pub fn overlay(&self) -> ChannelOverlay {
+        let mut overlay = ChannelOverlay::default();
+
+        for point in self.points.iter() {
+            overlay.lap_distance.push(point.lap_distance);
+            overlay.delta.push(point.delta);
+            overlay
+                .speed
+                .push(point.reference.speed, point.candidate.speed);
+            overlay
+                .throttle
+                .push(point.reference.throttle, point.candidate.throttle);
+            overlay
+                .brake
+                .push(point.reference.brake, point.candidate.brake);
+            overlay.gear.push(
+                i8::from(point.reference.gear),
+                i8::from(point.candidate.gear),
+            );
+        }
+
+        overlay
+    }
+}
+
+/// Values of a telemetry channel for the reference and the candidate lap
+#[derive(Debug, Getters, PartialEq, Clone, PartialOrd, Default)]
+pub struct ChannelPair<T> {
+    /// Returns the values of the reference lap.
+    #[getset(get = "pub")]
+    reference: Vec<T>,
+
+    /// Returns the values of the candidate lap.
+    #[getset(get = "pub")]
+    candidate: Vec<T>,
+}
+
+impl<T> ChannelPair<T> {
+    fn push(&mut self, reference: T, candidate: T) {
+        self.reference.push(reference);
+        self.candidate.push(candidate);
+    }
+}
+
+/// Telemetry channels of two laps as arrays that are aligned by lap distance
+///
+/// The values at the same index of each array belong to the same lap distance, which makes the
+/// overlay easy to pass to plotting libraries. Gears are numbered, with -1 for reverse and 0 for
+/// neutral.
+#[derive(Debug, Getters, PartialEq, Clone, PartialOrd, Default)]
+pub struct ChannelOverlay {
+    /// Returns the lap distances in meters.
+    #[getset(get = "pub")]
+    lap_distance: Vec<f32>,
+
+    /// Returns the cumulative time delta of the candidate lap in seconds.
+    #[getset(get = "pub")]
+    delta: Vec<f32>,
+
+    /// Returns the speed in kilometers per hour.
+    #[getset(get = "pub")]
+    speed: ChannelPair<f32>,
+
+    /// Returns the ratio of the applied throttle.
+    #[getset(get = "pub")]
+    throttle: ChannelPair<f32>,
+
+    /// Returns the ratio of the applied brake.
+    #[getset(get = "pub")]
+    brake: ChannelPair<f32>,
+
+    /// Returns the gear.
+    #[getset(get = "pub")]
+    gear: ChannelPair<i8>,
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarTrace {
+    lap: Option<u8>,
+    lap_distance: Option<f32>,
+    lap_time: Duration,
+    is_complete: bool,
+    samples: Vec<TraceSample>,
+    laps: Vec<LapTrace>,
+}
+
+/// Recorder for the lap traces of all cars in the session
+///
+/// The recorder consumes lap data and telemetry packets. A sample is added to the trace of a car
+/// with every telemetry packet, using the latest lap distance and lap time of the car. Only laps
+/// that were observed from start to finish are stored.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LapTraceRecorder {
+    cars: BTreeMap<VehicleIndex, CarTrace>,
+}
+
+impl LapTraceRecorder {
+    /// Create a new recorder without any traces.
+    pub fn new() -> Self {
+        LapTraceRecorder::default()
+    }
+
+    /// Update the traces with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Lap(packet) => self.update_laps(packet),
+            Packet::Telemetry(packet) => self.update_telemetry(packet),
+            _ => {}
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (vehicle_index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(vehicle_index).or_default();
+            let lap_number = lap.current_lap_number();
+
+            match car.lap {
+                Some(previous) if lap_number != previous => {
+                    let samples = std::mem::take(&mut car.samples);
+
+                    if car.is_complete && lap_number > previous && !samples.is_empty() {
+                        car.laps.push(LapTrace::new(
+                            vehicle_index,
+                            previous,
+                            *lap.last_lap_time(),
+                            samples,
+                        ));
+                    }
+
+                    car.is_complete = lap_number > previous;
+                }
+                Some(_) => {}
+                None => car.is_complete = *lap.current_lap_time() == Duration::ZERO,
+            }
+
+            car.lap = Some(lap_number);
+            car.lap_time = *lap.current_lap_time();
+            car.lap_distance = Some(lap.lap_distance()).filter(|distance| *distance >= 0.0);
+        }
+    }
+
+    fn update_telemetry(&mut self, packet: &TelemetryPacket) {
+        for (index, telemetry) in enumerate_cars(packet.telemetry()) {
+            let car = match self.cars.get_mut(&index) {
+                Some(car) => car,
+                None => continue,
+            };
+
+            let lap_distance = match car.lap_distance {
+                Some(lap_distance) => lap_distance,
+                None => continue,
+            };
+
+            let is_ahead = car
+                .samples
+                .last()
+                .map(|sample| lap_distance > sample.lap_distance)
+                .unwrap_or(true);
+
+            if is_ahead {
+                car.samples.push(TraceSample::new(
+                    lap_distance,
+                    car.lap_time,
+                    telemetry.speed().as_kph() as f32,
+                    telemetry.throttle(),
+                    telemetry.brake(),
+                    telemetry.steering(),
+                    telemetry.gear(),
+                    telemetry.engine_rpm() as f32,
+                ));
+            }
+        }
+    }
+
+    /// Returns the traces of the completed laps of a car.
+    pub fn laps(&self, vehicle_index: VehicleIndex) -> &[LapTrace] {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.laps.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns the trace of a car on the given lap.
+    pub fn lap(&self, vehicle_index: VehicleIndex, lap_number: u8) -> Option<&LapTrace> {
+        self.laps(vehicle_index)
+            .iter()
+            .find(|trace| trace.lap_number == lap_number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::fixtures::{
+        lap_packet, telemetry_packet, vehicle, LapFixture, TelemetryFixture,
+    };
+    use crate::analysis::trace::{LapTrace, LapTraceRecorder, TraceSample};
+    use crate::packet::telemetry::Gear;
+
+    fn sample(lap_distance: f32, lap_time: f32, speed: f32) -> TraceSample {
+        TraceSample::new(
+            lap_distance,
+            Duration::from_secs_f32(lap_time),
+            speed,
+            1.0,
+            0.0,
+            0.0,
+            Gear::Seventh,
+            11000.0,
+        )
+    }
+
+    #[test]
+    fn compare_with_slower_lap() {
+        let reference = LapTrace::new(
+            vehicle(0),
+            1,
+            Duration::from_secs(20),
+            vec![sample(0.0, 0.0, 200.0), sample(1000.0, 20.0, 200.0)],
+        );
+        let candidate = LapTrace::new(
+            vehicle(1),
+            3,
+            Duration::from_secs(22),
+            vec![sample(0.0, 0.0, 180.0), sample(1000.0, 22.0, 180.0)],
+        );
+
+        let comparison = reference.compare(&candidate, 250.0);
+        let points = comparison.points();
+
+        assert_eq!(5, points.len());
+        assert_approx_eq!(1.0, points[2].delta());
+        assert_approx_eq!(180.0, points[2].candidate().speed());
+        assert_approx_eq!(2.0, comparison.final_delta().unwrap());
+    }
+
+    #[test]
+    fn overlay_with_aligned_channels() {
+        let reference = LapTrace::new(
+            vehicle(0),
+            1,
+            Duration::from_secs(20),
+            vec![sample(0.0, 0.0, 200.0), sample(1000.0, 20.0, 200.0)],
+        );
+        let candidate = LapTrace::new(
+            vehicle(1),
+            3,
+            Duration::from_secs(22),
+            vec![sample(0.0, 0.0, 180.0), sample(1000.0, 22.0, 180.0)],
+        );
+
+        let overlay = reference.compare(&candidate, 500.0).overlay();
+
+        assert_eq!(&vec![0.0, 500.0, 1000.0], overlay.lap_distance());
+        assert_eq!(3, overlay.delta().len());
+        assert_eq!(&vec![200.0; 3], overlay.speed().reference());
+        assert_eq!(&vec![180.0; 3], overlay.speed().candidate());
+        assert_eq!(&vec![7; 3], overlay.gear().candidate());
+    }
+
+    #[test]
+    fn resample_with_step() {
+        let trace = LapTrace::new(
+            vehicle(0),
+            1,
+            Duration::from_secs(20),
+            vec![
+                sample(2.0, 0.0, 100.0),
+                sample(10.0, 1.0, 200.0),
+                sample(14.0, 2.0, 100.0),
+            ],
+        );
+
+        let resampled = trace.resample(5.0);
+        let distances: Vec<f32> = resampled
+            .samples()
+            .iter()
+            .map(|sample| sample.lap_distance())
+            .collect();
+
+        assert_eq!(vec![5.0, 10.0], distances);
+        assert_approx_eq!(137.5, resampled.samples()[0].speed());
+        assert_approx_eq!(200.0, resampled.samples()[1].speed());
+        assert_eq!(1, resampled.lap_number());
+    }
+
+    #[test]
+    fn update_with_complete_lap() {
+        let mut recorder = LapTraceRecorder::new();
+        let lap = |current_lap_number, lap_distance, current_lap_time, last_lap_time| LapFixture {
+            current_lap_number,
+            lap_distance,
+            current_lap_time,
+            last_lap_time,
+            ..LapFixture::default()
+        };
+
+        for (distance, time) in [(0.0, 0.0), (500.0, 10.0), (1000.0, 20.0)].iter() {
+            recorder.update(&lap_packet(0.0, vec![lap(1, *distance, *time, 0.0)]));
+            recorder.update(&telemetry_packet(0.0, vec![TelemetryFixture::default()]));
+        }
+        recorder.update(&lap_packet(0.0, vec![lap(2, 0.0, 0.0, 21.0)]));
+
+        let trace = recorder.lap(vehicle(0), 1).unwrap();
+        assert_eq!(3, trace.samples().len());
+        assert_eq!(Duration::from_secs(21), *trace.lap_time());
+        assert_eq!(
+            Duration::from_secs(15),
+            *trace.sample_at(750.0).unwrap().lap_time()
+        );
+    }
+}
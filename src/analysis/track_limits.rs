@@ -0,0 +1,227 @@
+//! Track limits violations of each driver
+//!
+//! Drivers that leave the track and gain an advantage, for example by cutting a corner, have their
+//! lap invalidated. Most leagues hand out a penalty after a number of these track limits
+//! violations. The track limits tracker counts the violations of each driver, records the corners
+//! at which they happened, and reports when a driver reaches the penalty threshold.
+//!
+//! F1 2019 does not publish corner cutting warnings. A violation is recorded every time the game
+//! invalidates the current lap of a driver, which is the closest approximation available in the
+//! lap data. Newer games publish the warnings directly, which are not available here.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::analysis::corner::{SegmentKind, TrackSegmentation};
+use crate::packet::lap::LapPacket;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// A track limits violation of a driver
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct TrackLimitsViolation {
+    /// Returns the session time at which the lap was invalidated.
+    #[getset(get = "pub")]
+    session_time: Duration,
+
+    /// Returns the lap that was invalidated.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the lap distance at which the lap was invalidated.
+    #[getset(get_copy = "pub")]
+    lap_distance: f32,
+
+    /// Returns the number of the corner at which the lap was invalidated, if it is known.
+    #[getset(get_copy = "pub")]
+    corner: Option<u8>,
+}
+
+/// Record of the track limits violations of a driver
+#[derive(Debug, Getters, PartialEq, Clone, PartialOrd, Default)]
+pub struct TrackLimitsRecord {
+    /// Returns the violations in the order in which they happened.
+    #[getset(get = "pub")]
+    violations: Vec<TrackLimitsViolation>,
+}
+
+impl TrackLimitsRecord {
+    /// Returns the number of violations.
+    pub fn count(&self) -> usize {
+        self.violations.len()
+    }
+
+    /// Returns the number of violations at each corner.
+    pub fn corners(&self) -> BTreeMap<u8, usize> {
+        let mut corners = BTreeMap::new();
+
+        for corner in self
+            .violations
+            .iter()
+            .filter_map(|violation| violation.corner)
+        {
+            *corners.entry(corner).or_default() += 1;
+        }
+
+        corners
+    }
+}
+
+/// Event that is emitted when a driver reaches the penalty threshold
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct TrackLimitsPenalty {
+    /// Returns the index of the car.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the number of violations of the driver.
+    #[getset(get_copy = "pub")]
+    violations: usize,
+
+    /// Returns the violation that made the driver reach the threshold.
+    #[getset(get = "pub")]
+    violation: TrackLimitsViolation,
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+struct CarTrackLimits {
+    previous: Option<(u8, bool)>,
+    record: TrackLimitsRecord,
+}
+
+/// Tracker for the track limits violations of all drivers in the session
+///
+/// The track limits tracker consumes lap data packets. The corners of the violations are looked up
+/// in the segmentation of the track, which can be empty if the corners are not of interest. A
+/// penalty event is emitted every time the number of violations of a driver reaches a multiple of
+/// the penalty threshold.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TrackLimitsTracker {
+    penalty_threshold: usize,
+    segmentation: TrackSegmentation,
+    cars: BTreeMap<VehicleIndex, CarTrackLimits>,
+}
+
+impl TrackLimitsTracker {
+    /// Create a new track limits tracker.
+    pub fn new(penalty_threshold: usize, segmentation: TrackSegmentation) -> Self {
+        TrackLimitsTracker {
+            penalty_threshold,
+            segmentation,
+            cars: BTreeMap::new(),
+        }
+    }
+
+    /// Update the violations with the data from the packet, and return the penalty events.
+    pub fn update(&mut self, packet: &Packet) -> Vec<TrackLimitsPenalty> {
+        match packet {
+            Packet::Lap(packet) => self.update_laps(packet),
+            _ => Vec::new(),
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) -> Vec<TrackLimitsPenalty> {
+        let session_time = *packet.header().session_time();
+        let mut penalties = Vec::new();
+
+        for (vehicle_index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(vehicle_index).or_default();
+            let lap_number = lap.current_lap_number();
+
+            // A lap is invalidated if it was valid before, or if a new lap starts out invalid.
+            // Nothing is known about a lap that was already invalid when the tracker first saw it.
+            let is_invalidated = !lap.is_valid_lap()
+                && car.previous.is_some_and(|(previous_lap, was_valid)| {
+                    was_valid || previous_lap != lap_number
+                });
+
+            car.previous = Some((lap_number, lap.is_valid_lap()));
+
+            if !is_invalidated {
+                continue;
+            }
+
+            let corner = corner_at(&self.segmentation, lap.lap_distance());
+            let violation =
+                TrackLimitsViolation::new(session_time, lap_number, lap.lap_distance(), corner);
+            car.record.violations.push(violation);
+
+            let count = car.record.count();
+            if self.penalty_threshold > 0 && count.is_multiple_of(self.penalty_threshold) {
+                penalties.push(TrackLimitsPenalty::new(vehicle_index, count, violation));
+            }
+        }
+
+        penalties
+    }
+
+    /// Returns the track limits record of a driver.
+    pub fn record(&self, vehicle_index: VehicleIndex) -> Option<&TrackLimitsRecord> {
+        self.cars.get(&vehicle_index).map(|car| &car.record)
+    }
+}
+
+/// Returns the number of the corner that contains the lap distance.
+fn corner_at(segmentation: &TrackSegmentation, lap_distance: f32) -> Option<u8> {
+    let segment = segmentation.segments()[segmentation.segment_at(lap_distance)?];
+
+    if segment.kind() == SegmentKind::Corner {
+        Some(segment.number())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::corner::{SegmentKind, TrackSegment, TrackSegmentation};
+    use crate::analysis::fixtures::{lap_packet, vehicle, LapFixture};
+    use crate::analysis::track_limits::TrackLimitsTracker;
+
+    fn lap(current_lap_number: u8, lap_distance: f32, is_valid_lap: bool) -> LapFixture {
+        LapFixture {
+            current_lap_number,
+            lap_distance,
+            is_valid_lap,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_penalty_threshold() {
+        let segmentation = TrackSegmentation::new(vec![
+            TrackSegment::new(SegmentKind::Straight, 1, 0.0, 400.0),
+            TrackSegment::new(SegmentKind::Corner, 4, 400.0, 500.0),
+        ]);
+        let mut tracker = TrackLimitsTracker::new(2, segmentation);
+
+        let mut penalties = Vec::new();
+        for (time, fixture) in [
+            lap(1, 100.0, true),
+            lap(1, 450.0, false),
+            lap(1, 480.0, false),
+            lap(2, 0.0, true),
+            lap(2, 200.0, false),
+            lap(3, 0.0, false),
+        ]
+        .iter()
+        .enumerate()
+        {
+            penalties.extend(tracker.update(&lap_packet(time as f32, vec![*fixture])));
+        }
+
+        let record = tracker.record(vehicle(0)).unwrap();
+        assert_eq!(3, record.count());
+        assert_eq!(Some(&1), record.corners().get(&4));
+        assert_eq!(Some(4), record.violations()[0].corner());
+        assert_eq!(None, record.violations()[1].corner());
+
+        assert_eq!(1, penalties.len());
+        assert_eq!(2, penalties[0].violations());
+        assert_eq!(2, penalties[0].violation().lap());
+    }
+}
@@ -0,0 +1,239 @@
+//! Maps of the track built from the positions of the cars
+//!
+//! The F1 games publish the position of every car in the world, but not the layout of the track.
+//! Plotting the cars on a map therefore requires knowing the shape of the track first. The track map
+//! builder accumulates the positions of the cars over the course of a session, groups them by their
+//! distance around the lap, and averages them into a centerline of the track.
+
+use std::collections::BTreeMap;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::{DriverStatus, LapPacket, PitStatus};
+use crate::packet::motion::MotionPacket;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, Property3D, VehicleIndex};
+
+/// A point on the centerline of the track
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct TrackPoint {
+    /// Returns the distance around the lap in meters.
+    #[getset(get_copy = "pub")]
+    lap_distance: f32,
+
+    /// Returns the position of the point in the world.
+    #[getset(get = "pub")]
+    position: Property3D<f32>,
+}
+
+/// Map of a track as a polyline along its centerline
+///
+/// The points of the track map are ordered by their distance around the lap, which allows looking
+/// up the position of any point on the track from a lap distance.
+#[derive(new, Debug, Getters, PartialEq, Clone, PartialOrd, Default)]
+pub struct TrackMap {
+    /// Returns the points of the track map, ordered by their lap distance.
+    #[getset(get = "pub")]
+    points: Vec<TrackPoint>,
+}
+
+impl TrackMap {
+    /// Returns the interpolated position on the track at the lap distance.
+    ///
+    /// No position is returned if the lap distance lies outside of the points of the map.
+    pub fn position_at(&self, lap_distance: f32) -> Option<Property3D<f32>> {
+        let index = self
+            .points
+            .iter()
+            .position(|point| point.lap_distance >= lap_distance)?;
+        let after = self.points[index];
+
+        if after.lap_distance == lap_distance {
+            return Some(after.position);
+        }
+
+        let before = self.points[index.checked_sub(1)?];
+        let fraction =
+            (lap_distance - before.lap_distance) / (after.lap_distance - before.lap_distance);
+        let interpolate = |from: f32, to: f32| from + (to - from) * fraction;
+
+        Some(Property3D::new(
+            interpolate(before.position.x(), after.position.x()),
+            interpolate(before.position.y(), after.position.y()),
+            interpolate(before.position.z(), after.position.z()),
+        ))
+    }
+}
+
+/// Sum of the samples that fall into a section of the track
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+struct Bin {
+    lap_distance: f32,
+    x: f32,
+    y: f32,
+    z: f32,
+    count: u32,
+}
+
+/// Builder that accumulates the positions of the cars into a track map
+///
+/// The builder consumes lap data and motion packets. Each position of a car is associated with the
+/// latest lap distance of the car, and put into a bin that covers a section of the track of the
+/// configured resolution. Positions of cars in the pit lane, in the garage, or before the start
+/// line are discarded, since they are not on the racing surface.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TrackMapBuilder {
+    resolution: f32,
+    lap_distances: BTreeMap<VehicleIndex, f32>,
+    bins: BTreeMap<u32, Bin>,
+}
+
+impl TrackMapBuilder {
+    /// Create a new builder with bins of the given length in meters.
+    pub fn new(resolution: f32) -> Self {
+        TrackMapBuilder {
+            resolution: resolution.max(f32::EPSILON),
+            lap_distances: BTreeMap::new(),
+            bins: BTreeMap::new(),
+        }
+    }
+
+    /// Update the track map with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Lap(packet) => self.update_laps(packet),
+            Packet::Motion(packet) => self.update_motion(packet),
+            _ => {}
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (vehicle_index, lap) in enumerate_cars(packet.laps()) {
+            let is_on_track = lap.pit_status() == PitStatus::None
+                && lap.driver_status() != DriverStatus::InGarage
+                && lap.lap_distance() >= 0.0;
+
+            if is_on_track {
+                self.lap_distances.insert(vehicle_index, lap.lap_distance());
+            } else {
+                self.lap_distances.remove(&vehicle_index);
+            }
+        }
+    }
+
+    fn update_motion(&mut self, packet: &MotionPacket) {
+        for (index, motion) in enumerate_cars(packet.cars()) {
+            let lap_distance = match self.lap_distances.get(&index) {
+                Some(lap_distance) => *lap_distance,
+                None => continue,
+            };
+
+            let bin = self
+                .bins
+                .entry((lap_distance / self.resolution) as u32)
+                .or_default();
+
+            bin.lap_distance += lap_distance;
+            bin.x += motion.position().x();
+            bin.y += motion.position().y();
+            bin.z += motion.position().z();
+            bin.count += 1;
+        }
+    }
+
+    /// Returns the number of positions that have been accumulated.
+    pub fn sample_count(&self) -> u32 {
+        self.bins.values().map(|bin| bin.count).sum()
+    }
+
+    /// Build the track map from the positions that have been accumulated so far.
+    pub fn build(&self) -> TrackMap {
+        let points = self
+            .bins
+            .values()
+            .map(|bin| {
+                let count = bin.count as f32;
+
+                TrackPoint::new(
+                    bin.lap_distance / count,
+                    Property3D::new(bin.x / count, bin.y / count, bin.z / count),
+                )
+            })
+            .collect();
+
+        TrackMap::new(points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::fixtures::{lap_packet, motion_packet, LapFixture};
+    use crate::analysis::track_map::TrackMapBuilder;
+    use crate::packet::lap::PitStatus;
+    use crate::types::Property3D;
+
+    fn lap(lap_distance: f32) -> LapFixture {
+        LapFixture {
+            lap_distance,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn build_averages_positions() {
+        let mut builder = TrackMapBuilder::new(10.0);
+
+        builder.update(&lap_packet(0.0, vec![lap(2.0), lap(4.0)]));
+        builder.update(&motion_packet(
+            0.0,
+            vec![
+                Property3D::new(0.0, 0.0, 1.0),
+                Property3D::new(0.0, 0.0, 3.0),
+            ],
+        ));
+        builder.update(&lap_packet(1.0, vec![lap(22.0), lap(24.0)]));
+        builder.update(&motion_packet(
+            1.0,
+            vec![
+                Property3D::new(20.0, 0.0, 1.0),
+                Property3D::new(22.0, 0.0, 3.0),
+            ],
+        ));
+
+        let map = builder.build();
+
+        assert_eq!(2, map.points().len());
+        assert_approx_eq!(3.0, map.points()[0].lap_distance());
+        assert_approx_eq!(2.0, map.points()[0].position().z());
+
+        let position = map.position_at(13.0).unwrap();
+        assert_approx_eq!(10.5, position.x());
+        assert_approx_eq!(2.0, position.z());
+    }
+
+    #[test]
+    fn build_ignores_cars_in_pits() {
+        let mut builder = TrackMapBuilder::new(10.0);
+
+        builder.update(&lap_packet(
+            0.0,
+            vec![
+                LapFixture {
+                    pit_status: PitStatus::Pitting,
+                    ..lap(100.0)
+                },
+                lap(-20.0),
+            ],
+        ));
+        builder.update(&motion_packet(
+            0.0,
+            vec![Property3D::default(), Property3D::default()],
+        ));
+
+        assert_eq!(0, builder.sample_count());
+        assert!(builder.build().points().is_empty());
+    }
+}
@@ -0,0 +1,384 @@
+//! Tyre wear and predictions of the remaining tyre life
+//!
+//! Tyres degrade over the course of a stint, and how quickly they wear is one of the most important
+//! inputs to a race strategy. The tyre wear model samples the tyre wear of every car at the end of
+//! each lap, fits a linear degradation curve for each tyre compound, and uses these curves to
+//! predict how many laps a car can run before its tyres reach a given wear threshold.
+
+use std::collections::BTreeMap;
+
+use derive_new::new;
+use getset::CopyGetters;
+
+use crate::analysis::privacy::TelemetryRestrictions;
+use crate::packet::lap::LapPacket;
+use crate::packet::status::{CarStatus, CarStatusPacket, PhysicalTyreCompound};
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Linear degradation curve for a tyre compound
+///
+/// The degradation curve describes the tyre wear as a linear function of the age of the tyres in
+/// laps. It is fitted with a least squares regression over the samples of all cars that have run
+/// the compound in the session.
+#[derive(new, Debug, CopyGetters, PartialEq, Copy, Clone, PartialOrd)]
+pub struct DegradationCurve {
+    /// Returns the tyre compound the curve has been fitted for.
+    #[getset(get_copy = "pub")]
+    compound: PhysicalTyreCompound,
+
+    /// Returns the tyre wear in percent that is added with each lap.
+    #[getset(get_copy = "pub")]
+    wear_per_lap: f32,
+
+    /// Returns the tyre wear in percent of a new set of tyres.
+    #[getset(get_copy = "pub")]
+    initial_wear: f32,
+
+    /// Returns the number of samples the curve has been fitted with.
+    #[getset(get_copy = "pub")]
+    sample_count: usize,
+}
+
+impl DegradationCurve {
+    /// Returns the predicted tyre wear in percent after the given number of laps.
+    pub fn predict(&self, tyre_age: f32) -> f32 {
+        self.initial_wear + self.wear_per_lap * tyre_age
+    }
+}
+
+/// Prediction of the remaining life of a car's tyres
+#[derive(new, Debug, CopyGetters, PartialEq, Copy, Clone, PartialOrd)]
+pub struct TyrePrediction {
+    /// Returns the tyre compound the car is currently running.
+    #[getset(get_copy = "pub")]
+    compound: PhysicalTyreCompound,
+
+    /// Returns the number of completed laps on the current set of tyres.
+    #[getset(get_copy = "pub")]
+    tyre_age: u8,
+
+    /// Returns the current wear of the most worn tyre in percent.
+    #[getset(get_copy = "pub")]
+    wear: u8,
+
+    /// Returns the tyre wear in percent that is added with each lap.
+    #[getset(get_copy = "pub")]
+    wear_per_lap: f32,
+
+    /// Returns the number of laps until the most worn tyre reaches the wear threshold.
+    #[getset(get_copy = "pub")]
+    laps_remaining: f32,
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+struct CarTyres {
+    compound: Option<PhysicalTyreCompound>,
+    wear: Option<u8>,
+    lap: Option<u8>,
+    tyre_age: u8,
+}
+
+/// Model of the tyre wear of all cars in the session
+///
+/// The model consumes lap data, car status, and participants packets. A new set of tyres is detected
+/// when the compound of a car changes or its tyre wear decreases, which happens when tyres are
+/// changed in the pits. The tyre wear of cars with restricted telemetry is not published, so these
+/// cars are not sampled and no prediction is made for them.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TyreWearModel {
+    cars: BTreeMap<VehicleIndex, CarTyres>,
+    restrictions: TelemetryRestrictions,
+    samples: BTreeMap<PhysicalTyreCompound, Vec<Sample>>,
+}
+
+/// Tyre wear of a car at a tyre age
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Sample {
+    vehicle_index: VehicleIndex,
+    tyre_age: f32,
+    wear: f32,
+}
+
+impl TyreWearModel {
+    /// Create a new tyre wear model without any samples.
+    pub fn new() -> Self {
+        TyreWearModel::default()
+    }
+
+    /// Update the model with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Lap(packet) => self.update_laps(packet),
+            Packet::Participants(packet) => {
+                // Samples that were taken before the restriction became known are discarded as well.
+                for index in self.restrictions.update(packet) {
+                    self.cars.remove(&index);
+
+                    for samples in self.samples.values_mut() {
+                        samples.retain(|sample| sample.vehicle_index != index);
+                    }
+                }
+            }
+            Packet::Status(packet) => self.update_statuses(packet),
+            _ => {}
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.cars.entry(index).or_default();
+            let lap_number = lap.current_lap_number();
+
+            if let Some(previous_lap) = car.lap {
+                if lap_number > previous_lap {
+                    car.tyre_age = car.tyre_age.saturating_add(lap_number - previous_lap);
+
+                    if let (Some(compound), Some(wear)) = (car.compound, car.wear) {
+                        self.samples.entry(compound).or_default().push(Sample {
+                            vehicle_index: index,
+                            tyre_age: car.tyre_age as f32,
+                            wear: wear as f32,
+                        });
+                    }
+                }
+            }
+
+            car.lap = Some(lap_number);
+        }
+    }
+
+    fn update_statuses(&mut self, packet: &CarStatusPacket) {
+        for (index, status) in enumerate_cars(packet.statuses()) {
+            if self.restrictions.is_restricted(index) {
+                continue;
+            }
+
+            let car = self.cars.entry(index).or_default();
+
+            let compound = status.physical_tyre_compound();
+            let wear = max_wear(status);
+
+            let is_new_set = match (car.compound, car.wear) {
+                (Some(previous_compound), Some(previous_wear)) => {
+                    previous_compound != compound || wear < previous_wear
+                }
+                _ => true,
+            };
+
+            if is_new_set {
+                car.tyre_age = 0;
+            }
+
+            car.compound = Some(compound);
+            car.wear = Some(wear);
+        }
+    }
+
+    /// Returns the degradation curve for the compound, if enough samples have been collected.
+    ///
+    /// At least two samples at different tyre ages are required to fit a curve.
+    pub fn curve(&self, compound: PhysicalTyreCompound) -> Option<DegradationCurve> {
+        let samples: Vec<_> = self
+            .samples
+            .get(&compound)?
+            .iter()
+            .map(|sample| (sample.tyre_age, sample.wear))
+            .collect();
+        let (wear_per_lap, initial_wear) = fit_line(&samples)?;
+
+        Some(DegradationCurve::new(
+            compound,
+            wear_per_lap,
+            initial_wear,
+            samples.len(),
+        ))
+    }
+
+    /// Predict the remaining life of the tyres of a car.
+    ///
+    /// The prediction uses the degradation curve of the compound that the car is currently running
+    /// to estimate how many laps it takes until its most worn tyre reaches the wear threshold in
+    /// percent. If not enough data is available, the tyres do not degrade, or the telemetry of the
+    /// car is restricted, no prediction is made.
+    pub fn predict(
+        &self,
+        vehicle_index: VehicleIndex,
+        wear_threshold: f32,
+    ) -> Option<TyrePrediction> {
+        let car = self.cars.get(&vehicle_index)?;
+        let compound = car.compound?;
+        let wear = car.wear?;
+        let curve = self.curve(compound)?;
+
+        if curve.wear_per_lap() <= 0.0 {
+            return None;
+        }
+
+        let laps_remaining = ((wear_threshold - wear as f32) / curve.wear_per_lap()).max(0.0);
+
+        Some(TyrePrediction::new(
+            compound,
+            car.tyre_age,
+            wear,
+            curve.wear_per_lap(),
+            laps_remaining,
+        ))
+    }
+}
+
+/// Returns the wear of the most worn tyre of the car.
+fn max_wear(status: &CarStatus) -> u8 {
+    status.tyre_wear().iter().max().unwrap_or_default()
+}
+
+/// Fit a line through the samples, and return its slope and intercept.
+fn fit_line(samples: &[(f32, f32)]) -> Option<(f32, f32)> {
+    let count = samples.len() as f32;
+
+    if samples.len() < 2 {
+        return None;
+    }
+
+    let mean_x = samples.iter().map(|(x, _)| x).sum::<f32>() / count;
+    let mean_y = samples.iter().map(|(_, y)| y).sum::<f32>() / count;
+
+    let covariance: f32 = samples
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance: f32 = samples.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+
+    if variance == 0.0 {
+        return None;
+    }
+
+    let slope = covariance / variance;
+    Some((slope, mean_y - slope * mean_x))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::fixtures::{
+        lap_packet, privacy_packet, status_packet, vehicle, LapFixture, StatusFixture,
+    };
+    use crate::analysis::tyre_wear::{fit_line, TyreWearModel};
+    use crate::packet::participants::TelemetryPrivacy;
+    use crate::packet::status::PhysicalTyreCompound;
+    use crate::types::CornerProperty;
+
+    fn lap(current_lap_number: u8) -> LapFixture {
+        LapFixture {
+            current_lap_number,
+            ..LapFixture::default()
+        }
+    }
+
+    fn status(wear: u8) -> StatusFixture {
+        StatusFixture {
+            tyre_wear: CornerProperty::new(wear, wear / 2, wear, wear / 2),
+            ..StatusFixture::default()
+        }
+    }
+
+    #[test]
+    fn fit_line_with_linear_samples() {
+        let (slope, intercept) = fit_line(&[(0.0, 1.0), (1.0, 3.0), (2.0, 5.0)]).unwrap();
+
+        assert_approx_eq!(2.0, slope);
+        assert_approx_eq!(1.0, intercept);
+    }
+
+    #[test]
+    fn fit_line_without_variance() {
+        assert!(fit_line(&[(1.0, 1.0), (1.0, 3.0)]).is_none());
+    }
+
+    #[test]
+    fn predict_remaining_laps() {
+        let mut model = TyreWearModel::new();
+
+        for lap_number in 1..=5 {
+            model.update(&status_packet(0.0, vec![status(lap_number * 4)]));
+            model.update(&lap_packet(0.0, vec![lap(lap_number)]));
+        }
+
+        let curve = model.curve(PhysicalTyreCompound::F1C3).unwrap();
+        assert_approx_eq!(4.0, curve.wear_per_lap());
+
+        let prediction = model.predict(vehicle(0), 60.0).unwrap();
+        assert_eq!(20, prediction.wear());
+        assert_eq!(4, prediction.tyre_age());
+        assert_approx_eq!(10.0, prediction.laps_remaining());
+    }
+
+    #[test]
+    fn predict_resets_age_with_new_tyres() {
+        let mut model = TyreWearModel::new();
+        let status_on = |physical_tyre_compound, wear| StatusFixture {
+            physical_tyre_compound,
+            ..status(wear)
+        };
+
+        // The worn set of tyres degrades by ten percent per lap.
+        for lap_number in 1..=4 {
+            model.update(&status_packet(
+                0.0,
+                vec![status_on(PhysicalTyreCompound::F1C3, lap_number * 10)],
+            ));
+            model.update(&lap_packet(0.0, vec![lap(lap_number)]));
+        }
+
+        // The new set of tyres degrades by four percent per lap.
+        for lap_number in 0..=3 {
+            model.update(&status_packet(
+                0.0,
+                vec![status_on(PhysicalTyreCompound::F1C2, lap_number * 4)],
+            ));
+            model.update(&lap_packet(0.0, vec![lap(lap_number + 4)]));
+        }
+
+        let worn = model.curve(PhysicalTyreCompound::F1C3).unwrap();
+        assert_approx_eq!(10.0, worn.wear_per_lap());
+
+        let new = model.curve(PhysicalTyreCompound::F1C2).unwrap();
+        assert_approx_eq!(4.0, new.wear_per_lap());
+        assert_approx_eq!(0.0, new.initial_wear());
+        assert_eq!(3, new.sample_count());
+
+        let prediction = model.predict(vehicle(0), 60.0).unwrap();
+        assert_eq!(PhysicalTyreCompound::F1C2, prediction.compound());
+        assert_eq!(3, prediction.tyre_age());
+        assert_eq!(12, prediction.wear());
+        assert_approx_eq!(4.0, prediction.wear_per_lap());
+        assert_approx_eq!(12.0, prediction.laps_remaining());
+    }
+
+    #[test]
+    fn skip_cars_with_restricted_telemetry() {
+        let mut model = TyreWearModel::new();
+
+        // The game sends zero tyre wear for the restricted car.
+        for lap_number in 1..=5 {
+            model.update(&status_packet(0.0, vec![status(lap_number * 4), status(0)]));
+            model.update(&lap_packet(0.0, vec![lap(lap_number), lap(lap_number)]));
+
+            if lap_number == 2 {
+                model.update(&privacy_packet(
+                    0.0,
+                    vec![TelemetryPrivacy::Public, TelemetryPrivacy::Restricted],
+                ));
+            }
+        }
+
+        let curve = model.curve(PhysicalTyreCompound::F1C3).unwrap();
+        assert_approx_eq!(4.0, curve.wear_per_lap());
+        assert_eq!(4, curve.sample_count());
+
+        assert!(model.predict(vehicle(0), 60.0).is_some());
+        assert!(model.predict(vehicle(1), 60.0).is_none());
+    }
+}
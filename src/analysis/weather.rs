@@ -0,0 +1,167 @@
+//! Weather and temperatures over the course of a session
+//!
+//! Changing weather has a big impact on the strategy of a race, for example when teams have to
+//! decide when to switch to wet tyres. The weather tracker records the weather and the track and air
+//! temperatures from the session packets whenever they change, and emits an event when the weather
+//! changes.
+//!
+//! Newer F1 games also publish a weather forecast in the session packet. F1 2019 does not, so the
+//! tracker can only report changes once they have happened.
+
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::session::{SessionPacket, Weather};
+use crate::packet::Packet;
+use crate::types::Temperature;
+
+/// Weather conditions at a point in time
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+pub struct WeatherSample {
+    /// Returns the session time at which the conditions were observed.
+    #[getset(get = "pub")]
+    session_time: Duration,
+
+    /// Returns the weather.
+    #[getset(get_copy = "pub")]
+    weather: Weather,
+
+    /// Returns the track temperature.
+    #[getset(get_copy = "pub")]
+    track_temperature: Temperature,
+
+    /// Returns the air temperature.
+    #[getset(get_copy = "pub")]
+    air_temperature: Temperature,
+}
+
+/// Events emitted by the weather tracker
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+pub enum WeatherEvent {
+    /// The weather changed from the first to the second weather condition.
+    WeatherChanged(Weather, Weather),
+}
+
+/// Tracker for the weather of a session
+///
+/// The weather tracker consumes session packets, and keeps a sample of the conditions every time
+/// the weather or one of the temperatures changes.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WeatherTracker {
+    samples: Vec<WeatherSample>,
+}
+
+impl WeatherTracker {
+    /// Create a new weather tracker without any samples.
+    pub fn new() -> Self {
+        WeatherTracker::default()
+    }
+
+    /// Update the weather and return the weather events detected in the packet.
+    pub fn update(&mut self, packet: &Packet) -> Vec<WeatherEvent> {
+        match packet {
+            Packet::Session(packet) => self.update_session(packet),
+            _ => Vec::new(),
+        }
+    }
+
+    fn update_session(&mut self, packet: &SessionPacket) -> Vec<WeatherEvent> {
+        let sample = WeatherSample::new(
+            *packet.header().session_time(),
+            packet.weather(),
+            packet.track_temperature(),
+            packet.air_temperature(),
+        );
+
+        let mut events = Vec::new();
+
+        if let Some(previous) = self.samples.last() {
+            let is_unchanged = previous.weather == sample.weather
+                && previous.track_temperature == sample.track_temperature
+                && previous.air_temperature == sample.air_temperature;
+
+            if is_unchanged {
+                return events;
+            }
+
+            if previous.weather != sample.weather {
+                events.push(WeatherEvent::WeatherChanged(
+                    previous.weather,
+                    sample.weather,
+                ));
+            }
+        }
+
+        self.samples.push(sample);
+        events
+    }
+
+    /// Returns the current weather conditions.
+    pub fn current(&self) -> Option<&WeatherSample> {
+        self.samples.last()
+    }
+
+    /// Returns the weather conditions whenever they changed during the session.
+    pub fn samples(&self) -> &[WeatherSample] {
+        &self.samples
+    }
+
+    /// Returns whether it is currently raining.
+    pub fn is_wet(&self) -> bool {
+        self.current().is_some_and(|sample| sample.weather.is_wet())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::fixtures::{session_packet, SessionFixture};
+    use crate::analysis::weather::{WeatherEvent, WeatherTracker};
+    use crate::packet::session::Weather;
+
+    fn session(weather: Weather, track_temperature: i8) -> SessionFixture {
+        SessionFixture {
+            weather,
+            track_temperature,
+            ..SessionFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_weather_change() {
+        let mut tracker = WeatherTracker::new();
+
+        let packet = session_packet(0.0, session(Weather::Overcast, 30));
+        assert!(tracker.update(&packet).is_empty());
+        assert!(tracker.update(&packet).is_empty());
+
+        let events = tracker.update(&session_packet(1.0, session(Weather::LightRain, 30)));
+
+        assert_eq!(
+            vec![WeatherEvent::WeatherChanged(
+                Weather::Overcast,
+                Weather::LightRain
+            )],
+            events
+        );
+        assert!(Weather::LightRain > Weather::Overcast);
+        assert!(tracker.is_wet());
+        assert_eq!(2, tracker.samples().len());
+    }
+
+    #[test]
+    fn update_with_temperature_change() {
+        let mut tracker = WeatherTracker::new();
+
+        tracker.update(&session_packet(0.0, session(Weather::Clear, 30)));
+        let events = tracker.update(&session_packet(1.0, session(Weather::Clear, 32)));
+
+        assert!(events.is_empty());
+        assert!(!tracker.is_wet());
+        assert_eq!(
+            32,
+            tracker.current().unwrap().track_temperature().as_celsius()
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Rolling windows of samples
+//!
+//! Many metrics are more useful over a recent window than over a whole session, for example the
+//! pace over the last five laps, or the peak tyre temperature over the last minute. The rolling
+//! window keeps the samples that fall into a window of time or laps, drops older samples as new
+//! ones are added, and aggregates the remaining samples.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+/// Size of a rolling window
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+pub enum WindowSize {
+    /// The window contains the samples of the given session time before the latest sample.
+    Time(Duration),
+
+    /// The window contains the samples of the given number of laps, including the lap of the latest
+    /// sample.
+    Laps(u8),
+}
+
+impl Default for WindowSize {
+    fn default() -> Self {
+        WindowSize::Laps(1)
+    }
+}
+
+/// A sample in a rolling window
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+pub struct WindowSample {
+    /// Returns the session time of the sample.
+    #[getset(get = "pub")]
+    session_time: Duration,
+
+    /// Returns the lap of the sample.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the value of the sample.
+    #[getset(get_copy = "pub")]
+    value: f32,
+}
+
+/// Rolling window of samples
+///
+/// Samples must be added in the order of their session time. Samples that fall out of the window
+/// are dropped whenever a new sample is added.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use f1_api::analysis::window::{RollingWindow, WindowSize};
+///
+/// let mut window = RollingWindow::new(WindowSize::Laps(2));
+///
+/// window.push(Duration::from_secs(90), 1, 91.2);
+/// window.push(Duration::from_secs(180), 2, 90.4);
+/// window.push(Duration::from_secs(270), 3, 90.8);
+///
+/// assert_eq!(2, window.len());
+/// assert_eq!(Some(90.8), window.max());
+/// ```
+#[derive(Debug, Getters, CopyGetters, PartialEq, Clone, Default)]
+pub struct RollingWindow {
+    /// Returns the size of the window.
+    #[getset(get_copy = "pub")]
+    size: WindowSize,
+
+    /// Returns the samples in the window, from the oldest to the latest.
+    #[getset(get = "pub")]
+    samples: VecDeque<WindowSample>,
+}
+
+impl RollingWindow {
+    /// Create a new, empty rolling window of the given size.
+    pub fn new(size: WindowSize) -> Self {
+        RollingWindow {
+            size,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Add a sample to the window, and drop the samples that fall out of the window.
+    pub fn push(&mut self, session_time: Duration, lap: u8, value: f32) {
+        self.samples
+            .push_back(WindowSample::new(session_time, lap, value));
+
+        while let Some(oldest) = self.samples.front() {
+            let is_outside = match self.size {
+                WindowSize::Time(duration) => {
+                    session_time.saturating_sub(oldest.session_time) > duration
+                }
+                WindowSize::Laps(laps) => lap.saturating_sub(oldest.lap) >= laps,
+            };
+
+            if !is_outside {
+                break;
+            }
+
+            self.samples.pop_front();
+        }
+    }
+
+    /// Remove all samples from the window.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Returns the number of samples in the window.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns whether the window is empty.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the mean of the samples in the window.
+    pub fn mean(&self) -> Option<f32> {
+        if self.samples.is_empty() {
+            None
+        } else {
+            Some(self.values().sum::<f32>() / self.samples.len() as f32)
+        }
+    }
+
+    /// Returns the smallest sample in the window.
+    pub fn min(&self) -> Option<f32> {
+        self.values().min_by(|a, b| a.total_cmp(b))
+    }
+
+    /// Returns the largest sample in the window.
+    pub fn max(&self) -> Option<f32> {
+        self.values().max_by(|a, b| a.total_cmp(b))
+    }
+
+    /// Returns the percentile of the samples in the window.
+    ///
+    /// The percentile must be between 0 and 100, and is interpolated linearly between the two
+    /// closest samples.
+    pub fn percentile(&self, percentile: f32) -> Option<f32> {
+        let mut values: Vec<f32> = self.values().collect();
+
+        if values.is_empty() {
+            return None;
+        }
+
+        values.sort_by(|a, b| a.total_cmp(b));
+
+        let rank = percentile.clamp(0.0, 100.0) / 100.0 * (values.len() - 1) as f32;
+        let lower = rank.floor() as usize;
+        let upper = rank.ceil() as usize;
+
+        Some(values[lower] + (values[upper] - values[lower]) * (rank - lower as f32))
+    }
+
+    fn values(&self) -> impl Iterator<Item = f32> + '_ {
+        self.samples.iter().map(|sample| sample.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::window::{RollingWindow, WindowSize};
+
+    #[test]
+    fn push_with_time_window() {
+        let mut window = RollingWindow::new(WindowSize::Time(Duration::from_secs(2)));
+
+        for (time, value) in [(0, 5.0), (1, 1.0), (2, 3.0), (3, 4.0), (4, 2.0)].iter() {
+            window.push(Duration::from_secs(*time), 1, *value);
+        }
+
+        assert_eq!(3, window.len());
+        assert_approx_eq!(3.0, window.mean().unwrap());
+        assert_eq!(Some(2.0), window.min());
+        assert_eq!(Some(4.0), window.max());
+        assert_approx_eq!(3.5, window.percentile(75.0).unwrap());
+    }
+
+    #[test]
+    fn aggregate_empty_window() {
+        let window = RollingWindow::new(WindowSize::Laps(3));
+
+        assert!(window.is_empty());
+        assert_eq!(None, window.mean());
+        assert_eq!(None, window.percentile(50.0));
+    }
+}
@@ -0,0 +1,384 @@
+//! Packets scoped to a single car
+//!
+//! Most packets sent by the F1 games contain the data of every car in the session. Tools that only
+//! care about a single driver, for example a dashboard for the player, would have to pick their car
+//! out of every packet. The functions in this module split the packets into the data of each car,
+//! and can turn a stream of packets into one stream for each car.
+//!
+//! The number of cars is taken from the API specification of the packets. F1 2019 supports at most
+//! 20 cars in a session, so a stream of its packets is split into 20 car streams.
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::packet::header::{ApiSpec, Header};
+use crate::packet::lap::Lap;
+use crate::packet::motion::Motion;
+use crate::packet::participants::{Participant, ParticipantsPacket};
+use crate::packet::setup::CarSetup;
+use crate::packet::status::CarStatus;
+use crate::packet::telemetry::Telemetry;
+use crate::packet::Packet;
+use crate::types::{car_entry, VehicleIndex};
+
+/// The maximum number of cars in a session of any supported API specification.
+pub const MAX_CARS: usize = 20;
+
+/// Data of a single car from one of the packets
+#[derive(Debug, PartialEq, Clone, PartialOrd)]
+pub enum CarData {
+    /// Lap data of the car.
+    Lap(Lap),
+
+    /// Motion data of the car.
+    Motion(Motion),
+
+    /// The participant driving the car.
+    Participant(Participant),
+
+    /// Setup of the car.
+    Setup(CarSetup),
+
+    /// Status of the car.
+    Status(CarStatus),
+
+    /// Telemetry of the car.
+    Telemetry(Telemetry),
+}
+
+/// Packet with the data of a single car
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Clone, PartialOrd)]
+pub struct CarPacket {
+    /// Returns the header of the packet the data was taken from.
+    #[getset(get = "pub")]
+    header: Header,
+
+    /// Returns the index of the car.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the data of the car.
+    #[getset(get = "pub")]
+    data: CarData,
+}
+
+impl CarPacket {
+    /// Split a packet into the data of each car.
+    ///
+    /// Event and session packets do not contain data for individual cars, and return no car
+    /// packets.
+    pub fn split(packet: &Packet) -> Vec<CarPacket> {
+        match packet {
+            Packet::Lap(packet) => scope(packet.header(), packet.laps(), |lap| CarData::Lap(*lap)),
+            Packet::Motion(packet) => scope(packet.header(), packet.cars(), |motion| {
+                CarData::Motion(*motion)
+            }),
+            Packet::Participants(packet) => {
+                scope(packet.header(), packet.participants(), |participant| {
+                    CarData::Participant(participant.clone())
+                })
+            }
+            Packet::Setup(packet) => scope(packet.header(), packet.setups(), |setup| {
+                CarData::Setup(*setup)
+            }),
+            Packet::Status(packet) => scope(packet.header(), packet.statuses(), |status| {
+                CarData::Status(*status)
+            }),
+            Packet::Telemetry(packet) => scope(packet.header(), packet.telemetry(), |telemetry| {
+                CarData::Telemetry(*telemetry)
+            }),
+            Packet::Event(_) | Packet::Session(_) => Vec::new(),
+        }
+    }
+
+    /// Returns the data of a single car from the packet, if the packet contains data for it.
+    ///
+    /// Only the entry of the car is copied, so that picking one car out of a packet does not cost as
+    /// much as splitting it.
+    pub fn for_car(packet: &Packet, vehicle_index: VehicleIndex) -> Option<CarPacket> {
+        let data = match packet {
+            Packet::Lap(packet) => {
+                car_entry(packet.laps(), vehicle_index).map(|lap| CarData::Lap(*lap))
+            }
+            Packet::Motion(packet) => {
+                car_entry(packet.cars(), vehicle_index).map(|motion| CarData::Motion(*motion))
+            }
+            Packet::Participants(packet) => car_entry(packet.participants(), vehicle_index)
+                .map(|participant| CarData::Participant(participant.clone())),
+            Packet::Setup(packet) => {
+                car_entry(packet.setups(), vehicle_index).map(|setup| CarData::Setup(*setup))
+            }
+            Packet::Status(packet) => {
+                car_entry(packet.statuses(), vehicle_index).map(|status| CarData::Status(*status))
+            }
+            Packet::Telemetry(packet) => car_entry(packet.telemetry(), vehicle_index)
+                .map(|telemetry| CarData::Telemetry(*telemetry)),
+            Packet::Event(_) | Packet::Session(_) => None,
+        }?;
+
+        Some(CarPacket::new(*packet.header(), vehicle_index, data))
+    }
+}
+
+/// Wrap each entry of a packet in a car packet.
+fn scope<T, F>(header: &Header, entries: &[T], data: F) -> Vec<CarPacket>
+where
+    F: Fn(&T) -> CarData,
+{
+    VehicleIndex::all(header.api_spec())
+        .zip(entries)
+        .map(|(index, entry)| CarPacket::new(*header, index, data(entry)))
+        .collect()
+}
+
+/// Zip the entries of a packet with the active participants of the session.
+///
+/// The packets always contain an entry for every car on the grid of their API specification, even
+/// if fewer cars take part in the session. Only the entries of the active cars are returned,
+/// together with the index and the participant of each car.
+pub fn cars_with<'a, T>(
+    participants: &'a ParticipantsPacket,
+    entries: &'a [T],
+) -> impl Iterator<Item = (VehicleIndex, &'a Participant, &'a T)> {
+    VehicleIndex::all(participants.header().api_spec())
+        .zip(participants.participants())
+        .zip(entries)
+        .take(participants.active_participants_count() as usize)
+        .map(|((index, participant), entry)| (index, participant, entry))
+}
+
+/// Turn a stream of packets into a stream of the data of a single car.
+///
+/// # Examples
+///
+/// ```
+/// use std::net::{IpAddr, SocketAddr};
+///
+/// use f1_api::car::car_stream;
+/// use f1_api::types::VehicleIndex;
+/// use f1_api::{ok_packets, F1};
+/// use tokio_stream::StreamExt;
+///
+/// async fn example() {
+///     let socket = SocketAddr::new(IpAddr::from([0, 0, 0, 0]), 20777);
+///     let stream = ok_packets(F1::stream(socket).unwrap());
+///
+///     let mut car = car_stream(stream, VehicleIndex::default());
+///
+///     while let Some(packet) = car.next().await {
+///         println!("Received {:?}", packet.data());
+///     }
+/// }
+/// ```
+pub fn car_stream<S>(stream: S, vehicle_index: VehicleIndex) -> impl Stream<Item = CarPacket>
+where
+    S: Stream<Item = Packet>,
+{
+    stream.filter_map(move |packet| CarPacket::for_car(&packet, vehicle_index))
+}
+
+/// Split a stream of packets into one stream for each car on the grid of the API specification.
+///
+/// The packets are read from the stream by a task that is spawned on the current Tokio runtime, and
+/// forwarded to the car streams, which are ordered by the index of their car. Each car stream
+/// buffers up to `capacity` car packets, and a car stream that is full holds back the packets for
+/// all other cars until it is read again. The task stops when the stream of packets ends, or when
+/// all car streams have been dropped.
+///
+/// # Examples
+///
+/// ```
+/// use std::net::{IpAddr, SocketAddr};
+///
+/// use f1_api::car::split_stream;
+/// use f1_api::packet::header::ApiSpec;
+/// use f1_api::{ok_packets, F1};
+/// use tokio_stream::StreamExt;
+///
+/// async fn example() {
+///     let socket = SocketAddr::new(IpAddr::from([0, 0, 0, 0]), 20777);
+///     let stream = ok_packets(F1::stream(socket).unwrap());
+///
+///     let mut cars = split_stream(stream, ApiSpec::Nineteen, 16);
+///     let mut leader = cars.remove(0);
+///
+///     while let Some(packet) = leader.next().await {
+///         println!("Received {:?}", packet.data());
+///     }
+/// }
+/// ```
+pub fn split_stream<S>(
+    stream: S,
+    api_spec: ApiSpec,
+    capacity: usize,
+) -> Vec<ReceiverStream<CarPacket>>
+where
+    S: Stream<Item = Packet> + Send + Unpin + 'static,
+{
+    let (senders, receivers): (Vec<_>, Vec<_>) = VehicleIndex::all(api_spec)
+        .map(|_| tokio::sync::mpsc::channel(capacity))
+        .unzip();
+
+    tokio::spawn(async move {
+        let mut stream = stream;
+
+        while let Some(packet) = stream.next().await {
+            for car in CarPacket::split(&packet) {
+                if let Some(sender) = senders.get(car.vehicle_index.as_usize()) {
+                    // Cars whose stream has been dropped are skipped.
+                    sender.send(car).await.ok();
+                }
+            }
+
+            if senders.iter().all(|sender| sender.is_closed()) {
+                break;
+            }
+        }
+    });
+
+    receivers.into_iter().map(ReceiverStream::new).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_stream::StreamExt;
+
+    use crate::analysis::fixtures::{
+        event_packet, invalid_datagram, lap_datagram, lap_packet, participants_packet, udp_stream,
+        vehicle, LapFixture,
+    };
+    use crate::car::{car_stream, split_stream, CarData, CarPacket};
+    use crate::ok_packets;
+    use crate::packet::event::Event;
+    use crate::packet::header::ApiSpec;
+    use crate::packet::Packet;
+
+    fn lap(position: u8) -> LapFixture {
+        LapFixture {
+            position,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn for_car_with_lap_packet() {
+        let packet = lap_packet(0.0, vec![lap(2), lap(1)]);
+
+        let car = CarPacket::for_car(&packet, vehicle(1)).unwrap();
+        assert_eq!(1, car.vehicle_index());
+        match car.data() {
+            CarData::Lap(lap) => assert_eq!(1, lap.position()),
+            data => panic!("Unexpected car data {:?}", data),
+        }
+
+        assert!(CarPacket::split(&event_packet(0.0, Event::SessionStarted)).is_empty());
+    }
+
+    #[test]
+    fn for_car_matches_split() {
+        let packets = vec![
+            lap_packet(0.0, vec![lap(2), lap(1)]),
+            participants_packet(0.0, vec!["Alice", "Bob"]),
+        ];
+
+        for packet in &packets {
+            for car in CarPacket::split(packet) {
+                assert_eq!(
+                    Some(&car),
+                    CarPacket::for_car(packet, car.vehicle_index()).as_ref()
+                );
+            }
+
+            assert_eq!(None, CarPacket::for_car(packet, vehicle(2)));
+        }
+
+        let event = event_packet(0.0, Event::SessionStarted);
+        assert_eq!(None, CarPacket::for_car(&event, vehicle(0)));
+    }
+
+    #[test]
+    fn cars_with_active_participants() {
+        let participants = match participants_packet(0.0, vec!["Alice", "Bob"]) {
+            Packet::Participants(packet) => packet,
+            packet => panic!("Unexpected packet {:?}", packet),
+        };
+        let laps = match lap_packet(0.0, vec![lap(2), lap(1), lap(3)]) {
+            Packet::Lap(packet) => packet,
+            packet => panic!("Unexpected packet {:?}", packet),
+        };
+
+        let cars: Vec<_> = laps.cars_with(&participants).collect();
+        assert_eq!(2, cars.len());
+
+        let (vehicle_index, participant, lap) = cars[1];
+        assert_eq!(1, vehicle_index);
+        assert_eq!("Bob", participant.name());
+        assert_eq!(1, lap.position());
+    }
+
+    #[test]
+    fn index_packet_by_vehicle_index() {
+        let laps = match lap_packet(0.0, vec![lap(2), lap(1)]) {
+            Packet::Lap(packet) => packet,
+            packet => panic!("Unexpected packet {:?}", packet),
+        };
+
+        assert_eq!(1, laps[vehicle(1)].position());
+        assert_eq!(Some(2), laps.get(vehicle(0)).map(|lap| lap.position()));
+        assert!(laps.get(vehicle(2)).is_none());
+        assert!(laps.get(vehicle(19)).is_none());
+        assert_eq!(Some(2), laps.player().map(|lap| lap.position()));
+    }
+
+    #[tokio::test]
+    async fn split_stream_by_car() {
+        let packets = vec![
+            lap_packet(0.0, vec![lap(2), lap(1)]),
+            lap_packet(1.0, vec![lap(1), lap(2)]),
+        ];
+
+        let mut streams = split_stream(tokio_stream::iter(packets), ApiSpec::Nineteen, 4);
+        assert_eq!(ApiSpec::Nineteen.grid_size(), streams.len());
+
+        let second: Vec<CarPacket> = streams.remove(1).collect().await;
+        assert_eq!(2, second.len());
+        assert!(second.iter().all(|car| car.vehicle_index() == 1));
+    }
+
+    fn position(car: &CarPacket) -> u8 {
+        match car.data() {
+            CarData::Lap(lap) => lap.position(),
+            data => panic!("Unexpected car data {:?}", data),
+        }
+    }
+
+    #[tokio::test]
+    async fn car_stream_from_udp_stream() {
+        let (sender, stream) = udp_stream().await;
+        sender.send(&invalid_datagram()).await.unwrap();
+        sender.send(&lap_datagram(0.0)).await.unwrap();
+
+        let mut car = car_stream(ok_packets(stream), vehicle(3));
+
+        let packet = car.next().await.unwrap();
+        assert_eq!(3, packet.vehicle_index());
+        assert_eq!(4, position(&packet));
+    }
+
+    #[tokio::test]
+    async fn split_udp_stream_by_car() {
+        let (sender, stream) = udp_stream().await;
+        sender.send(&invalid_datagram()).await.unwrap();
+        sender.send(&lap_datagram(0.0)).await.unwrap();
+
+        let streams = split_stream(ok_packets(stream), ApiSpec::Nineteen, 1);
+
+        for (index, mut car) in streams.into_iter().enumerate() {
+            let packet = car.next().await.unwrap();
+            assert_eq!(index, packet.vehicle_index().as_usize());
+            assert_eq!(index + 1, position(&packet) as usize);
+        }
+    }
+}
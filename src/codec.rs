@@ -1,23 +1,144 @@
 //! Codec for modern F1 games
 
-use std::io::{Cursor, Error, ErrorKind};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io::Cursor;
+use std::sync::Arc;
 
 use bytes::{Buf, BytesMut};
 use tokio_util::codec::Decoder;
 
-use crate::nineteen::decode_nineteen;
+use crate::error::{Diagnostic, F1Error, Warning};
+use crate::lazy::LazyCodec;
+use crate::nineteen::{decode_nineteen, is_decoder_enabled, packet_size, peek_header};
+use crate::packet::header::{ApiSpec, PacketType};
+use crate::packet::lap::{DriverStatus, PitStatus, ResultStatus};
+use crate::packet::participants::{Controller, Driver, Nationality, Team, TelemetryPrivacy};
+use crate::packet::session::{Formula, SafetyCar, Session, Track, Weather};
+use crate::packet::status::{
+    DrsSetting, ErsDeployMode, FuelMix, PhysicalTyreCompound, TractionControl, VisualTyreCompound,
+};
+use crate::packet::telemetry::{Gear, Surface};
 use crate::packet::Packet;
+use crate::pool::BufferPool;
+use crate::types::Flag;
+
+/// Number of warnings that a codec keeps until they are taken
+///
+/// Codecs of long-running streams record a warning for every packet of a patched game. Only the most
+/// recent warnings are kept, so that their memory stays bounded if nobody takes them.
+pub const WARNING_CAPACITY: usize = 64;
+
+/// Modes in which the codec can decode packets
+///
+/// Games that have been patched sometimes send packets that deviate from their API specification,
+/// for example with values that the specification does not list yet or with additional bytes at the
+/// end of a packet. The strict mode rejects any such deviation, which is useful to test that a
+/// packet matches the specification exactly. The lenient mode accepts them, and records a warning
+/// instead of failing the stream.
+///
+/// Identifiers that are unknown to this crate are decoded as an `Unknown` variant with their raw
+/// value in both modes. Fields whose value cannot be represented at all, for example a sector after
+/// the third or a vehicle index beyond the grid, fail to decode in both modes.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+pub enum DecodeMode {
+    Strict,
+    #[default]
+    Lenient,
+}
 
 /// Codec to decode UDP packets published by modern F1 games.
 ///
 /// This struct implements the `Decoder` trait for tokio-utils. It can be used to decode incoming
 /// UDP packets, and convert them into internal data representations. The F1 codec can decode the
 /// packets of all F1 games that are supported by this library.
-pub struct F1Codec;
+///
+/// The codec decodes packets leniently by default, detects the API specification of each packet,
+/// and decodes packets of every type. Codecs with other settings are created with the
+/// `DecoderBuilder`. Warnings about deviations from the API specification are passed to the
+/// warning handler of the builder, or kept until they are taken from the codec. Up to
+/// `WARNING_CAPACITY` warnings are kept, and older warnings are dropped to make room for new ones.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct F1Codec {
+    mode: DecodeMode,
+    api_spec: Option<ApiSpec>,
+    packet_types: Option<BTreeSet<PacketType>>,
+    warning_handler: Option<WarningHandler>,
+    warnings: Vec<Warning>,
+}
+
+impl F1Codec {
+    /// Create a new codec that decodes packets in the given mode.
+    pub fn new(mode: DecodeMode) -> Self {
+        F1Codec {
+            mode,
+            ..F1Codec::default()
+        }
+    }
+
+    /// Returns the mode in which the codec decodes packets.
+    pub fn mode(&self) -> DecodeMode {
+        self.mode
+    }
+
+    /// Returns the API specification that the codec accepts, or `None` if it detects the API
+    /// specification of each packet.
+    pub fn api_spec(&self) -> Option<ApiSpec> {
+        self.api_spec
+    }
+
+    /// Returns whether the codec decodes packets of the given type.
+    ///
+    /// Packets of types whose decoder has been compiled out, for example motion packets without the
+    /// `motion` feature, are never decoded.
+    pub fn decodes(&self, packet_type: PacketType) -> bool {
+        is_decoder_enabled(packet_type)
+            && self
+                .packet_types
+                .as_ref()
+                .is_none_or(|packet_types| packet_types.contains(&packet_type))
+    }
+
+    /// Returns the most recent warnings that have been recorded since they were last taken.
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Take the warnings that have been recorded, leaving no warnings behind.
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    fn record_warnings(&mut self, warnings: Vec<Warning>) {
+        if let Some(handler) = &self.warning_handler {
+            warnings
+                .into_iter()
+                .for_each(|warning| (handler.0)(warning));
+            return;
+        }
+
+        self.warnings.extend(warnings);
+
+        let overflow = self.warnings.len().saturating_sub(WARNING_CAPACITY);
+        self.warnings.drain(..overflow);
+    }
+
+    /// Decode a UDP packet like `decode`, and return a diagnostic if the packet is malformed.
+    ///
+    /// The diagnostic describes the packet and the bytes around the field that failed to decode,
+    /// which helps to report and debug packets that the codec cannot decode.
+    pub fn decode_with_diagnostic(
+        &mut self,
+        src: &mut BytesMut,
+    ) -> Result<Option<Packet>, Diagnostic> {
+        self.decode(src)
+            .map_err(|error| Diagnostic::new(error, src.as_ref()))
+    }
+}
 
 impl Decoder for F1Codec {
     type Item = Packet;
-    type Error = Error;
+    type Error = F1Error;
 
     /// Decode a UDP packet and return its data.
     ///
@@ -38,8 +159,12 @@ impl Decoder for F1Codec {
     /// trait. If the packet can be decoded successfully, it is returned. Otherwise, the error from
     /// the decoding is returned, signaling that the UDP stream is corrupted and should be shut
     /// down.
-    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Error> {
-        let mut cursor = Cursor::new(src);
+    ///
+    /// Depending on the mode of the codec, packets that deviate from the API specification are
+    /// either rejected, or returned while a warning is recorded.
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, F1Error> {
+        let size = src.len();
+        let mut cursor = Cursor::new(&src[..]);
 
         // Not enough data yet to decode the packet format.
         if cursor.remaining() < 2 {
@@ -48,20 +173,456 @@ impl Decoder for F1Codec {
 
         let packet_format = cursor.get_u16_le();
 
+        // The decoders read the packet from its start, including the packet format.
+        cursor.set_position(0);
+
+        let accepts = |api_spec: ApiSpec| self.api_spec.is_none_or(|accepted| accepted == api_spec);
+
         let packet = match packet_format {
-            2019 => decode_nineteen(&mut cursor),
-            format => Err(Error::new(
-                ErrorKind::InvalidData,
-                format!("Unknown packet format {}.", format),
-            )),
+            2019 if accepts(ApiSpec::Nineteen) => {
+                let skipped = peek_header(cursor.get_ref())
+                    .is_some_and(|header| !self.decodes(header.packet_type()));
+
+                // Packets of types that are not decoded are dropped after reading their header.
+                if skipped {
+                    return Ok(None);
+                }
+
+                decode_nineteen(&mut cursor)
+            }
+            format => Err(F1Error::UnknownPacketFormat { format }),
         };
 
-        match packet {
-            Ok(packet) => Ok(Some(packet)),
-            Err(error) => match error.kind() {
-                ErrorKind::UnexpectedEof => Ok(None),
-                _ => Err(error),
-            },
+        let packet = match packet {
+            Ok(packet) => packet,
+            Err(F1Error::IncompleteData { .. }) => return Ok(None),
+            Err(error) => return Err(error),
+        };
+
+        let mut warnings = check_packet(&packet, size);
+
+        if self.mode == DecodeMode::Strict && !warnings.is_empty() {
+            return Err(F1Error::Rejected(warnings.remove(0)));
         }
+
+        self.record_warnings(warnings);
+        Ok(Some(packet))
+    }
+
+    /// Decode the last UDP packet that has been received.
+    ///
+    /// UDP sockets call this method for every packet they receive. Packets that are incomplete or
+    /// dropped by the codec are skipped instead of failing the stream.
+    fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, F1Error> {
+        self.decode(buf)
+    }
+}
+
+/// Builder for codecs with custom settings
+///
+/// The builder starts with the settings of the default codec: packets are decoded leniently, the API
+/// specification of each packet is detected from its packet format, packets of every type are
+/// decoded, and the receive buffer of the socket keeps the size that the operating system picks.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DecoderBuilder {
+    mode: DecodeMode,
+    api_spec: Option<ApiSpec>,
+    packet_types: Option<BTreeSet<PacketType>>,
+    warning_handler: Option<WarningHandler>,
+    pub(crate) recv_buffer_size: Option<usize>,
+    pub(crate) buffer_pool: Option<BufferPool>,
+}
+
+impl DecoderBuilder {
+    /// Create a new builder with the settings of the default codec.
+    pub fn new() -> Self {
+        DecoderBuilder::default()
+    }
+
+    /// Decode packets in the given mode.
+    pub fn mode(mut self, mode: DecodeMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Only accept packets of the given API specification, and reject all other packet formats.
+    pub fn api_spec(mut self, api_spec: ApiSpec) -> Self {
+        self.api_spec = Some(api_spec);
+        self
+    }
+
+    /// Detect the API specification of each packet from its packet format.
+    pub fn detect_api_spec(mut self) -> Self {
+        self.api_spec = None;
+        self
+    }
+
+    /// Only decode packets of the given types, and drop all other packets.
+    ///
+    /// Packets of other types are dropped after their header has been read. Their body is neither
+    /// decoded nor validated, which saves the cost of decoding frequent packets such as the motion
+    /// data when nobody consumes them.
+    pub fn packet_types(mut self, packet_types: &[PacketType]) -> Self {
+        self.packet_types = Some(packet_types.iter().copied().collect());
+        self
+    }
+
+    /// Call the given function for each warning, instead of keeping the warnings in the codec.
+    ///
+    /// Streams own their codec, so its warnings cannot be taken while the stream is running. The
+    /// handler receives the warnings of a lenient stream as soon as the packet is decoded, for
+    /// example to log them or to count the deviations of a patched game.
+    pub fn on_warning<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(Warning) + Send + Sync + 'static,
+    {
+        self.warning_handler = Some(WarningHandler(Arc::new(handler)));
+        self
+    }
+
+    /// Set the size of the receive buffer of the socket that streams read from, in bytes.
+    ///
+    /// A larger buffer prevents packets from being dropped when the consumer of a stream falls
+    /// behind for a moment.
+    pub fn recv_buffer_size(mut self, size: usize) -> Self {
+        self.recv_buffer_size = Some(size);
+        self
+    }
+
+    /// Receive the datagrams of streams into buffers from the given pool.
+    ///
+    /// Sharing a pool between several streams lets them reuse each other's buffers. A clone of the
+    /// pool can be kept to read its metrics while the streams are running.
+    pub fn buffer_pool(mut self, pool: BufferPool) -> Self {
+        self.buffer_pool = Some(pool);
+        self
+    }
+
+    /// Build a codec with the settings of the builder.
+    pub fn build(&self) -> F1Codec {
+        F1Codec {
+            mode: self.mode,
+            api_spec: self.api_spec,
+            packet_types: self.packet_types.clone(),
+            warning_handler: self.warning_handler.clone(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Build a codec that only decodes the headers of packets, with the settings of the builder.
+    ///
+    /// The mode of the builder is ignored, since the body of the packets is not decoded. The API
+    /// specification is ignored as well, since the lazy codec accepts every supported API
+    /// specification.
+    pub fn build_lazy(&self) -> LazyCodec {
+        LazyCodec::new(self.packet_types.clone())
+    }
+}
+
+/// Function that is called for each warning of a codec
+///
+/// Handlers are equal if they share the same function.
+#[derive(Clone)]
+struct WarningHandler(Arc<dyn Fn(Warning) + Send + Sync>);
+
+impl fmt::Debug for WarningHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WarningHandler")
+    }
+}
+
+impl PartialEq for WarningHandler {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Check a decoded packet for deviations from the API specification.
+fn check_packet(packet: &Packet, size: usize) -> Vec<Warning> {
+    let packet_type = packet.header().packet_type();
+    let mut warnings = Vec::new();
+
+    let expected = packet_size(packet_type);
+    if size > expected {
+        warnings.push(Warning::TrailingBytes {
+            packet_type,
+            expected,
+            actual: size,
+        });
+    }
+
+    let mut unknown = |field: &'static str, value: i64| {
+        warnings.push(Warning::UnknownValue {
+            packet_type,
+            field,
+            value,
+        });
+    };
+
+    match packet {
+        Packet::Lap(packet) => {
+            for lap in packet.laps() {
+                if let PitStatus::Unknown(value) = lap.pit_status() {
+                    unknown("pit status", value.into());
+                }
+                if let DriverStatus::Unknown(value) = lap.driver_status() {
+                    unknown("driver status", value.into());
+                }
+                if let ResultStatus::Unknown(value) = lap.result_status() {
+                    unknown("result status", value.into());
+                }
+            }
+        }
+        Packet::Participants(packet) => {
+            for participant in packet.participants() {
+                if let Controller::Unknown(value) = participant.controller() {
+                    unknown("controller", value.into());
+                }
+                if let Driver::Unknown(value) = participant.driver() {
+                    unknown("driver", value.into());
+                }
+                if let Team::Unknown(value) = participant.team() {
+                    unknown("team", value.into());
+                }
+                if let Nationality::Unknown(value) = participant.nationality() {
+                    unknown("nationality", value.into());
+                }
+                if let Some(TelemetryPrivacy::Unknown(value)) = participant.telemetry_privacy() {
+                    unknown("telemetry privacy setting", value.into());
+                }
+            }
+        }
+        Packet::Session(packet) => {
+            if let Weather::Unknown(value) = packet.weather() {
+                unknown("weather", value.into());
+            }
+            // The game reports sessions and tracks that have not been loaded yet as unknown.
+            if let Session::Unknown(value @ 1..=u8::MAX) = packet.session_type() {
+                unknown("session", value.into());
+            }
+            if let Track::Unknown(value @ 0..=i8::MAX) = packet.track() {
+                unknown("track", value.into());
+            }
+            if let Formula::Unknown(value) = packet.formula() {
+                unknown("formula", value.into());
+            }
+            if let SafetyCar::Unknown(value) = packet.safety_car() {
+                unknown("safety car", value.into());
+            }
+            for zone in packet.marshal_zones() {
+                if let Flag::Unknown(value) = zone.flag() {
+                    unknown("flag", value.into());
+                }
+            }
+        }
+        Packet::Status(packet) => {
+            for status in packet.statuses() {
+                if let PhysicalTyreCompound::Unknown(value) = status.physical_tyre_compound() {
+                    unknown("physical tyre compound", value.into());
+                }
+                if let VisualTyreCompound::Unknown(value) = status.visual_tyre_compound() {
+                    unknown("visual tyre compound", value.into());
+                }
+                if let TractionControl::Unknown(value) = status.traction_control() {
+                    unknown("traction control", value.into());
+                }
+                if let FuelMix::Unknown(value) = status.fuel_mix() {
+                    unknown("fuel mix", value.into());
+                }
+                // The game reports the DRS setting of formulas without DRS as unknown.
+                if let DrsSetting::Unknown(value @ 0..=i8::MAX) = status.drs() {
+                    unknown("DRS status", value.into());
+                }
+                if let Flag::Unknown(value) = status.vehicle_flags() {
+                    unknown("flag", value.into());
+                }
+                if let ErsDeployMode::Unknown(value) = status.ers_deploy_mode() {
+                    unknown("ERS deployment mode", value.into());
+                }
+            }
+        }
+        Packet::Telemetry(packet) => {
+            for telemetry in packet.telemetry() {
+                if let Gear::Unknown(value) = telemetry.gear() {
+                    unknown("gear", value.into());
+                }
+                for surface in telemetry.surface_type().iter() {
+                    if let Surface::Unknown(value) = surface {
+                        unknown("surface", value.into());
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use bytes::{BufMut, BytesMut};
+    use tokio_util::codec::Decoder;
+
+    use crate::codec::{check_packet, DecodeMode, DecoderBuilder, F1Codec, WARNING_CAPACITY};
+    use crate::error::{F1Error, Warning};
+    use crate::nineteen::packet_size;
+    use crate::packet::header::{ApiSpec, Header, PacketType};
+    use crate::packet::status::{CarStatus, CarStatusPacket, DrsSetting, ErsDeployMode};
+    use crate::packet::Packet;
+
+    fn put_event_packet(padding: usize) -> BytesMut {
+        let mut bytes = BytesMut::with_capacity(32 + padding);
+
+        bytes.put_u16_le(2019);
+        bytes.put_u8(1);
+        bytes.put_u8(2);
+        bytes.put_u8(1);
+        bytes.put_u8(3);
+        bytes.put_u64_le(u64::MAX);
+        bytes.put_f32_le(1.0);
+        bytes.put_u32_le(u32::MAX);
+        bytes.put_u8(0);
+        bytes.put(&b"SSTA"[..]);
+        bytes.put(vec![0u8; 5 + padding].as_slice());
+
+        bytes
+    }
+
+    #[test]
+    fn decode_with_lenient_mode() {
+        let mut codec = F1Codec::new(DecodeMode::Lenient);
+
+        assert!(codec.decode(&mut put_event_packet(0)).unwrap().is_some());
+        assert!(codec.warnings().is_empty());
+
+        assert!(codec.decode(&mut put_event_packet(4)).unwrap().is_some());
+        assert_eq!(
+            vec![Warning::TrailingBytes {
+                packet_type: PacketType::Event,
+                expected: 32,
+                actual: 36
+            }],
+            codec.take_warnings()
+        );
+        assert!(codec.warnings().is_empty());
+    }
+
+    #[test]
+    fn keep_most_recent_warnings() {
+        let mut codec = F1Codec::default();
+
+        for padding in 1..=WARNING_CAPACITY + 8 {
+            codec.decode(&mut put_event_packet(padding)).unwrap();
+        }
+
+        let warnings = codec.take_warnings();
+        assert_eq!(WARNING_CAPACITY, warnings.len());
+        assert_eq!(
+            Warning::TrailingBytes {
+                packet_type: PacketType::Event,
+                expected: 32,
+                actual: 32 + WARNING_CAPACITY + 8
+            },
+            warnings[WARNING_CAPACITY - 1]
+        );
+    }
+
+    #[test]
+    fn pass_warnings_to_handler() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let handled = received.clone();
+
+        let mut codec = DecoderBuilder::new()
+            .on_warning(move |warning| handled.lock().unwrap().push(warning))
+            .build();
+
+        assert!(codec.decode(&mut put_event_packet(4)).unwrap().is_some());
+        assert!(codec.warnings().is_empty());
+        assert!(matches!(
+            received.lock().unwrap().as_slice(),
+            [Warning::TrailingBytes { actual: 36, .. }]
+        ));
+    }
+
+    #[test]
+    fn decode_with_strict_mode() {
+        let mut codec = F1Codec::new(DecodeMode::Strict);
+
+        assert!(codec.decode(&mut put_event_packet(0)).unwrap().is_some());
+        assert!(matches!(
+            codec.decode(&mut put_event_packet(4)),
+            Err(F1Error::Rejected(Warning::TrailingBytes { .. }))
+        ));
+    }
+
+    #[test]
+    fn warn_about_unknown_values() {
+        let statuses = vec![
+            CarStatus::default().with_ers_deploy_mode(ErsDeployMode::Unknown(6)),
+            CarStatus::default().with_drs(DrsSetting::Unknown(-1)),
+        ];
+        let packet = Packet::from(
+            CarStatusPacket::default()
+                .with_header(Header::default().with_packet_type(PacketType::Status))
+                .with_statuses(statuses),
+        );
+
+        assert_eq!(
+            vec![Warning::UnknownValue {
+                packet_type: PacketType::Status,
+                field: "ERS deployment mode",
+                value: 6,
+            }],
+            check_packet(&packet, packet_size(PacketType::Status))
+        );
+    }
+
+    #[test]
+    fn decode_with_filtered_packet_types() {
+        let mut codec = DecoderBuilder::new()
+            .mode(DecodeMode::Strict)
+            .packet_types(&[PacketType::Lap])
+            .build();
+
+        // The event code is invalid, but the body of a dropped packet is never decoded.
+        let mut bytes = put_event_packet(0);
+        bytes[23..27].copy_from_slice(b"XXXX");
+
+        assert!(codec.decode(&mut bytes).unwrap().is_none());
+        assert!(codec.warnings().is_empty());
+        assert!(F1Codec::default().decode(&mut bytes).is_err());
+    }
+
+    #[test]
+    fn decodes_with_features() {
+        let codec = F1Codec::default();
+
+        assert!(codec.decodes(PacketType::Lap));
+        assert_eq!(cfg!(feature = "motion"), codec.decodes(PacketType::Motion));
+        assert_eq!(
+            cfg!(feature = "telemetry"),
+            codec.decodes(PacketType::Telemetry)
+        );
+    }
+
+    #[test]
+    fn decode_with_builder() {
+        let mut codec = DecoderBuilder::new()
+            .mode(DecodeMode::Strict)
+            .api_spec(ApiSpec::Nineteen)
+            .packet_types(&[PacketType::Lap, PacketType::Telemetry])
+            .build();
+
+        assert_eq!(DecodeMode::Strict, codec.mode());
+        assert_eq!(Some(ApiSpec::Nineteen), codec.api_spec());
+        assert!(codec.decodes(PacketType::Lap));
+        assert!(!codec.decodes(PacketType::Event));
+        assert!(codec
+            .decode_eof(&mut put_event_packet(0))
+            .unwrap()
+            .is_none());
     }
 }
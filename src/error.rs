@@ -0,0 +1,302 @@
+//! Errors that can occur when decoding packets
+//!
+//! Decoding a packet can fail for a number of reasons. The data might not form a complete packet
+//! yet, it might have been sent by a game or in a format that this crate does not support, or one
+//! of its fields might contain a value that the API specification does not define. The `F1Error`
+//! distinguishes these cases, and carries the information that is needed to diagnose them.
+//!
+//! Users that want to report or debug a malformed packet can turn the error into a `Diagnostic`,
+//! which adds the API specification and type of the packet, and a hexdump of the bytes around the
+//! field that failed to decode.
+
+use std::fmt;
+use std::fmt::{Display, Write};
+use std::io::Cursor;
+use std::mem::size_of;
+
+use getset::{CopyGetters, Getters};
+use thiserror::Error;
+
+use crate::nineteen::peek_header;
+use crate::packet::header::{ApiSpec, PacketType};
+
+/// Number of bytes that are shown in each line of a hexdump.
+const HEXDUMP_WIDTH: usize = 16;
+
+/// Number of lines that are shown before and after the line with the offending byte.
+const HEXDUMP_CONTEXT: usize = 1;
+
+/// Error that occurred while decoding a packet
+#[derive(Debug, Error)]
+pub enum F1Error {
+    /// The data does not form a complete packet yet.
+    #[error("Packet is expected to have a size of {expected} bytes, but was {actual}.")]
+    IncompleteData { expected: usize, actual: usize },
+
+    /// The packet was sent in a packet format that this crate does not support.
+    #[error("Unknown packet format {format}.")]
+    UnknownPacketFormat { format: u16 },
+
+    /// The packet has a type that is not defined by the API specification.
+    #[error("Unknown packet type {packet_id} in API specification {api_spec:?}.")]
+    UnknownPacketType { api_spec: ApiSpec, packet_id: u8 },
+
+    /// The decoder for packets of this type has been compiled out by disabling its feature.
+    #[error("Decoding of {packet_type:?} packets is disabled.")]
+    DisabledPacketType { packet_type: PacketType },
+
+    /// The packet has a different type than the packet it is decoded into.
+    #[error("Expected a {expected:?} packet, but received a {actual:?} packet.")]
+    UnexpectedPacketType {
+        expected: PacketType,
+        actual: PacketType,
+    },
+
+    /// The event packet has an event code that is not defined by the API specification.
+    #[error("Unknown event code {code} at offset {offset}.")]
+    UnknownEventCode { code: String, offset: u64 },
+
+    /// A field of the packet has a value that is not defined by the API specification. The offset
+    /// is the position of the field from the start of the packet.
+    #[error("Failed to decode {field} with value {value} at offset {offset}.")]
+    InvalidField {
+        field: &'static str,
+        value: i64,
+        offset: u64,
+    },
+
+    /// The packet deviates from the API specification, and was rejected by a strict decoder.
+    #[error("{0}")]
+    Rejected(Warning),
+
+    /// Reading the packet failed.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Deviation of a packet from its API specification
+///
+/// A lenient decoder accepts packets that deviate from their API specification in ways that do not
+/// prevent them from being decoded, and records a warning for each deviation.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+pub enum Warning {
+    /// The packet is larger than its API specification defines, and the additional bytes at its end
+    /// have been ignored.
+    TrailingBytes {
+        packet_type: PacketType,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// A field of the packet has a value that is unknown to this crate.
+    UnknownValue {
+        packet_type: PacketType,
+        field: &'static str,
+        value: i64,
+    },
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::TrailingBytes {
+                packet_type,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{:?} packet is expected to have a size of {} bytes, but was {}.",
+                packet_type, expected, actual
+            ),
+            Warning::UnknownValue {
+                packet_type,
+                field,
+                value,
+            } => write!(
+                f,
+                "{:?} packet has an unknown {} with value {}.",
+                packet_type, field, value
+            ),
+        }
+    }
+}
+
+impl F1Error {
+    /// Create an error for a field whose value has just been read from the cursor.
+    pub(crate) fn invalid_field<T>(field: &'static str, value: T, cursor: &Cursor<&[u8]>) -> Self
+    where
+        T: Into<i64>,
+    {
+        F1Error::InvalidField {
+            field,
+            value: value.into(),
+            offset: cursor.position() - size_of::<T>() as u64,
+        }
+    }
+
+    /// Returns the offset of the offending data from the start of the packet, if it is known.
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            F1Error::UnknownEventCode { offset, .. } | F1Error::InvalidField { offset, .. } => {
+                Some(*offset)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the field that failed to decode, if the error is caused by a field.
+    pub fn field(&self) -> Option<&'static str> {
+        match self {
+            F1Error::UnknownEventCode { .. } => Some("event code"),
+            F1Error::InvalidField { field, .. } => Some(field),
+            _ => None,
+        }
+    }
+}
+
+/// Diagnostic information about a packet that failed to decode
+///
+/// The diagnostic collects everything that is known about a malformed packet: the error, the API
+/// specification and type of the packet if its header could be decoded, the offset and name of the
+/// offending field, and a hexdump of the surrounding bytes. Its `Display` implementation formats
+/// this information as a report that can be attached to bug reports.
+#[derive(Debug, Getters, CopyGetters)]
+pub struct Diagnostic {
+    /// Returns the error that occurred while decoding the packet.
+    #[getset(get = "pub")]
+    error: F1Error,
+
+    /// Returns the API specification of the packet, if it is known.
+    #[getset(get_copy = "pub")]
+    api_spec: Option<ApiSpec>,
+
+    /// Returns the type of the packet, if it is known.
+    #[getset(get_copy = "pub")]
+    packet_type: Option<PacketType>,
+
+    /// Returns the offset of the offending data from the start of the packet, if it is known.
+    #[getset(get_copy = "pub")]
+    offset: Option<u64>,
+
+    /// Returns the name of the field that failed to decode, if it is known.
+    #[getset(get_copy = "pub")]
+    field: Option<&'static str>,
+
+    /// Returns a hexdump of the bytes around the offending data, or of the start of the packet if
+    /// the offset is not known.
+    #[getset(get = "pub")]
+    hexdump: String,
+}
+
+impl Diagnostic {
+    /// Create a diagnostic for an error that occurred while decoding the given data.
+    pub fn new(error: F1Error, data: &[u8]) -> Self {
+        let header = peek_header(data);
+
+        let api_spec = match &error {
+            F1Error::UnknownPacketType { api_spec, .. } => Some(*api_spec),
+            _ => header.map(|header| header.api_spec()),
+        };
+        let packet_type = header.map(|header| header.packet_type());
+        let offset = error.offset();
+        let field = error.field();
+        let hexdump = hexdump(data, offset);
+
+        Diagnostic {
+            error,
+            api_spec,
+            packet_type,
+            offset,
+            field,
+            hexdump,
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.error)?;
+
+        if let Some(api_spec) = self.api_spec {
+            writeln!(f, "API specification: {:?}", api_spec)?;
+        }
+        if let Some(packet_type) = self.packet_type {
+            writeln!(f, "Packet type: {:?}", packet_type)?;
+        }
+        if let Some(field) = self.field {
+            writeln!(f, "Field: {}", field)?;
+        }
+        if let Some(offset) = self.offset {
+            writeln!(f, "Offset: {}", offset)?;
+        }
+
+        write!(f, "{}", self.hexdump)
+    }
+}
+
+impl std::error::Error for Diagnostic {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Format the bytes around an offset as a hexdump, with one line per 16 bytes.
+fn hexdump(data: &[u8], offset: Option<u64>) -> String {
+    let line = offset.unwrap_or(0) as usize / HEXDUMP_WIDTH;
+    let first = line.saturating_sub(HEXDUMP_CONTEXT) * HEXDUMP_WIDTH;
+    let last = ((line + HEXDUMP_CONTEXT + 1) * HEXDUMP_WIDTH).min(data.len());
+
+    let mut hexdump = String::new();
+
+    for start in (first..last).step_by(HEXDUMP_WIDTH) {
+        let end = (start + HEXDUMP_WIDTH).min(last);
+        let bytes: Vec<String> = data[start..end]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        // Writing to a string cannot fail.
+        writeln!(hexdump, "{:08x}  {}", start, bytes.join(" ")).ok();
+    }
+
+    hexdump
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{hexdump, Diagnostic, F1Error};
+    use crate::packet::header::{ApiSpec, PacketType};
+
+    #[test]
+    fn hexdump_around_offset() {
+        let data: Vec<u8> = (0..64).collect();
+
+        let hexdump = hexdump(&data, Some(40));
+        let lines: Vec<&str> = hexdump.lines().collect();
+
+        assert_eq!(3, lines.len());
+        assert!(lines[0].starts_with("00000010  10 11"));
+        assert!(lines[2].ends_with("3e 3f"));
+    }
+
+    #[test]
+    fn diagnostic_with_invalid_field() {
+        let mut data = vec![0u8; 32];
+        data[0..2].copy_from_slice(&2019u16.to_le_bytes());
+        data[5] = 2;
+
+        let error = F1Error::InvalidField {
+            field: "pit status",
+            value: 7,
+            offset: 25,
+        };
+        let diagnostic = Diagnostic::new(error, &data);
+
+        assert_eq!(Some(ApiSpec::Nineteen), diagnostic.api_spec());
+        assert_eq!(Some(PacketType::Lap), diagnostic.packet_type());
+        assert_eq!(Some("pit status"), diagnostic.field());
+        assert_eq!(Some(25), diagnostic.offset());
+        assert_eq!(2, diagnostic.hexdump().lines().count());
+        assert!(diagnostic.to_string().contains("Field: pit status"));
+    }
+}
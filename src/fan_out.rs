@@ -0,0 +1,204 @@
+//! Distribution of packets to many consumers
+//!
+//! Applications often consume the same packets in several places at once, for example to record a
+//! session, to aggregate statistics, and to relay the packets to other machines. Cloning the large
+//! motion and telemetry packets for each consumer is expensive. The fan-out decodes each packet
+//! once, wraps it in an `Arc`, and publishes it on a broadcast channel that any number of consumers
+//! can subscribe to, so that all consumers share the same packet.
+
+use std::sync::Arc;
+
+use tokio::sync::broadcast::{self, Receiver, Sender};
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::packet::Packet;
+
+/// Fan-out that publishes shared packets to its subscribers
+///
+/// Subscribers only receive the packets that are published after they have subscribed. Subscribers
+/// that fall behind by more than the capacity of the channel miss the oldest packets.
+#[derive(Debug, Clone)]
+pub struct FanOut {
+    sender: Sender<Arc<Packet>>,
+}
+
+impl FanOut {
+    /// Create a new fan-out whose channel holds the given number of packets.
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+
+        FanOut { sender }
+    }
+
+    /// Subscribe to the packets that are published after this call.
+    pub fn subscribe(&self) -> Receiver<Arc<Packet>> {
+        self.sender.subscribe()
+    }
+
+    /// Subscribe to the packets that are published after this call, as a stream.
+    ///
+    /// Packets that the subscriber missed because it fell behind are skipped.
+    pub fn subscribe_stream(&self) -> impl Stream<Item = Arc<Packet>> {
+        BroadcastStream::new(self.sender.subscribe()).filter_map(|packet| packet.ok())
+    }
+
+    /// Publish a packet to all subscribers, and return the number of subscribers it was sent to.
+    pub fn publish(&self, packet: Packet) -> usize {
+        // Sending only fails when there are no subscribers, in which case the packet is dropped.
+        self.sender.send(Arc::new(packet)).unwrap_or(0)
+    }
+
+    /// Returns the number of subscribers.
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Publish the packets of a stream to all subscribers.
+    ///
+    /// The packets are read from the stream by a task that is spawned on the current Tokio runtime.
+    /// The task stops when the stream ends.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::{IpAddr, SocketAddr};
+    ///
+    /// use f1_api::fan_out::FanOut;
+    /// use f1_api::{ok_packets, F1};
+    ///
+    /// async fn example() {
+    ///     let socket = SocketAddr::new(IpAddr::from([0, 0, 0, 0]), 20777);
+    ///     let stream = ok_packets(F1::stream(socket).unwrap());
+    ///
+    ///     let fan_out = FanOut::new(64);
+    ///     let mut receiver = fan_out.subscribe();
+    ///     fan_out.forward(stream);
+    ///
+    ///     while let Ok(packet) = receiver.recv().await {
+    ///         println!("Received {:?} packet", packet.header().packet_type());
+    ///     }
+    /// }
+    /// ```
+    pub fn forward<S>(&self, stream: S) -> JoinHandle<()>
+    where
+        S: Stream<Item = Packet> + Send + Unpin + 'static,
+    {
+        let fan_out = self.clone();
+
+        tokio::spawn(async move {
+            let mut stream = stream;
+
+            while let Some(packet) = stream.next().await {
+                fan_out.publish(packet);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tokio::sync::broadcast::error::RecvError;
+    use tokio_stream::StreamExt;
+
+    use crate::analysis::fixtures::{
+        invalid_datagram, lap_datagram, lap_packet, udp_stream, LapFixture,
+    };
+    use crate::fan_out::FanOut;
+    use crate::ok_packets;
+    use crate::packet::Packet;
+
+    fn session_time(packet: &Packet) -> f32 {
+        match packet {
+            Packet::Lap(packet) => packet.header().session_time().as_secs_f32(),
+            packet => panic!("Unexpected packet {:?}", packet),
+        }
+    }
+
+    #[tokio::test]
+    async fn forward_to_subscribers() {
+        let fan_out = FanOut::new(8);
+        let mut receiver = fan_out.subscribe();
+        let stream = fan_out.subscribe_stream();
+        assert_eq!(2, fan_out.subscriber_count());
+
+        let packets = vec![
+            lap_packet(0.0, vec![LapFixture::default()]),
+            lap_packet(1.0, vec![LapFixture::default()]),
+        ];
+        fan_out.forward(tokio_stream::iter(packets)).await.unwrap();
+        drop(fan_out);
+
+        let first = receiver.recv().await.unwrap();
+        let streamed: Vec<_> = stream.collect().await;
+        assert_eq!(2, streamed.len());
+        assert!(Arc::ptr_eq(&first, &streamed[0]));
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_packets_for_slow_subscribers() {
+        let fan_out = FanOut::new(2);
+        let mut receiver = fan_out.subscribe();
+        let stream = fan_out.subscribe_stream();
+
+        for time in 0..5 {
+            fan_out.publish(lap_packet(time as f32, vec![LapFixture::default()]));
+        }
+        drop(fan_out);
+
+        assert!(matches!(receiver.recv().await, Err(RecvError::Lagged(3))));
+        assert_eq!(3.0, session_time(&receiver.recv().await.unwrap()));
+        assert_eq!(4.0, session_time(&receiver.recv().await.unwrap()));
+        assert!(matches!(receiver.recv().await, Err(RecvError::Closed)));
+
+        let streamed: Vec<_> = stream.map(|packet| session_time(&packet)).collect().await;
+        assert_eq!(vec![3.0, 4.0], streamed);
+    }
+
+    #[tokio::test]
+    async fn count_subscribers_that_are_gone() {
+        let fan_out = FanOut::new(8);
+        assert_eq!(
+            0,
+            fan_out.publish(lap_packet(0.0, vec![LapFixture::default()]))
+        );
+
+        let receiver = fan_out.subscribe();
+        let stream = fan_out.subscribe_stream();
+        assert_eq!(
+            2,
+            fan_out.publish(lap_packet(1.0, vec![LapFixture::default()]))
+        );
+
+        drop(receiver);
+        assert_eq!(1, fan_out.subscriber_count());
+        assert_eq!(
+            1,
+            fan_out.publish(lap_packet(2.0, vec![LapFixture::default()]))
+        );
+
+        drop(stream);
+        assert_eq!(0, fan_out.subscriber_count());
+        assert_eq!(
+            0,
+            fan_out.publish(lap_packet(3.0, vec![LapFixture::default()]))
+        );
+    }
+
+    #[tokio::test]
+    async fn forward_udp_stream() {
+        let (sender, stream) = udp_stream().await;
+
+        let fan_out = FanOut::new(8);
+        let mut receiver = fan_out.subscribe();
+        fan_out.forward(ok_packets(stream));
+
+        sender.send(&invalid_datagram()).await.unwrap();
+        sender.send(&lap_datagram(1.0)).await.unwrap();
+
+        assert_eq!(1.0, session_time(&receiver.recv().await.unwrap()));
+    }
+}
@@ -0,0 +1,204 @@
+//! Frames of packets
+//!
+//! The F1 games send the motion, telemetry, lap data, and car status of a frame as separate packets,
+//! which all carry the same frame identifier. Consumers that process the session tick by tick, for
+//! example to render a frame of a replay, want to handle these packets together. The frame
+//! assembler groups the packets by their frame identifier, and returns a frame as soon as all of its
+//! packets have arrived.
+//!
+//! The rate at which the packets are sent can be configured in the games, and some packets are not
+//! sent for every frame. Frames that are still missing packets are returned incomplete once they
+//! have timed out.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::LapPacket;
+use crate::packet::motion::MotionPacket;
+use crate::packet::status::CarStatusPacket;
+use crate::packet::telemetry::TelemetryPacket;
+use crate::packet::Packet;
+
+/// Packets that were sent for the same frame
+#[derive(Debug, Getters, CopyGetters, PartialEq, Clone, PartialOrd, Default)]
+pub struct Frame {
+    /// Returns the identifier of the frame.
+    #[getset(get_copy = "pub")]
+    frame_identifier: u32,
+
+    /// Returns the session time of the frame.
+    #[getset(get = "pub")]
+    session_time: Duration,
+
+    /// Returns the motion packet of the frame, if it has been received.
+    #[getset(get = "pub")]
+    motion: Option<MotionPacket>,
+
+    /// Returns the telemetry packet of the frame, if it has been received.
+    #[getset(get = "pub")]
+    telemetry: Option<TelemetryPacket>,
+
+    /// Returns the lap data packet of the frame, if it has been received.
+    #[getset(get = "pub")]
+    lap: Option<LapPacket>,
+
+    /// Returns the car status packet of the frame, if it has been received.
+    #[getset(get = "pub")]
+    status: Option<CarStatusPacket>,
+}
+
+impl Frame {
+    /// Returns whether all packets of the frame have been received.
+    pub fn is_complete(&self) -> bool {
+        self.motion.is_some()
+            && self.telemetry.is_some()
+            && self.lap.is_some()
+            && self.status.is_some()
+    }
+}
+
+/// Assembler that groups packets into frames
+///
+/// The assembler consumes motion, telemetry, lap data, and car status packets, and ignores all other
+/// packets. A frame times out when a packet is received whose session time is more than the timeout
+/// after the session time of the frame. When a packet of a new session is received, all pending
+/// frames of the previous session are returned.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FrameAssembler {
+    timeout: Duration,
+    session_uid: Option<u64>,
+    pending: BTreeMap<u32, Frame>,
+}
+
+impl FrameAssembler {
+    /// Create a new frame assembler with the given timeout for incomplete frames.
+    pub fn new(timeout: Duration) -> Self {
+        FrameAssembler {
+            timeout,
+            session_uid: None,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Add the packet to its frame, and return the frames that are complete or have timed out.
+    ///
+    /// Frames are returned in the order of their frame identifier.
+    pub fn update(&mut self, packet: &Packet) -> Vec<Frame> {
+        let header = match packet {
+            Packet::Lap(packet) => packet.header(),
+            Packet::Motion(packet) => packet.header(),
+            Packet::Status(packet) => packet.header(),
+            Packet::Telemetry(packet) => packet.header(),
+            _ => return Vec::new(),
+        };
+
+        let mut frames = Vec::new();
+
+        if self
+            .session_uid
+            .is_some_and(|session_uid| session_uid != header.session_uid())
+        {
+            frames.append(&mut self.flush());
+        }
+        self.session_uid = Some(header.session_uid());
+
+        let session_time = *header.session_time();
+        let frame = self
+            .pending
+            .entry(header.frame_identifier())
+            .or_insert_with(|| Frame {
+                frame_identifier: header.frame_identifier(),
+                session_time,
+                ..Frame::default()
+            });
+
+        match packet {
+            Packet::Lap(packet) => frame.lap = Some(packet.clone()),
+            Packet::Motion(packet) => frame.motion = Some(packet.clone()),
+            Packet::Status(packet) => frame.status = Some(packet.clone()),
+            Packet::Telemetry(packet) => frame.telemetry = Some(packet.clone()),
+            _ => {}
+        }
+
+        let timeout = self.timeout;
+        let done: Vec<u32> = self
+            .pending
+            .values()
+            .filter(|frame| {
+                frame.is_complete() || session_time.saturating_sub(frame.session_time) > timeout
+            })
+            .map(|frame| frame.frame_identifier)
+            .collect();
+
+        for frame_identifier in done {
+            if let Some(frame) = self.pending.remove(&frame_identifier) {
+                frames.push(frame);
+            }
+        }
+
+        frames
+    }
+
+    /// Return all pending frames, whether they are complete or not.
+    pub fn flush(&mut self) -> Vec<Frame> {
+        let pending = std::mem::take(&mut self.pending);
+        pending.into_values().collect()
+    }
+
+    /// Returns the number of frames that are still waiting for packets.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::fixtures::{
+        framed_header, lap_packet, motion_packet, status_packet, telemetry_packet,
+    };
+    use crate::frame::FrameAssembler;
+    use crate::packet::header::PacketType;
+    use crate::packet::lap::LapPacket;
+    use crate::packet::Packet;
+
+    #[test]
+    fn update_with_complete_frame() {
+        let mut assembler = FrameAssembler::new(Duration::from_secs(1));
+
+        assert!(assembler.update(&motion_packet(0.0, Vec::new())).is_empty());
+        assert!(assembler
+            .update(&telemetry_packet(0.0, Vec::new()))
+            .is_empty());
+        assert!(assembler.update(&lap_packet(0.0, Vec::new())).is_empty());
+
+        let frames = assembler.update(&status_packet(0.0, Vec::new()));
+        assert_eq!(1, frames.len());
+        assert!(frames[0].is_complete());
+        assert_eq!(0, assembler.pending());
+    }
+
+    #[test]
+    fn update_with_timeout() {
+        let mut assembler = FrameAssembler::new(Duration::from_secs(1));
+        let lap = |session_time, frame_identifier| {
+            Packet::Lap(LapPacket::new(
+                framed_header(PacketType::Lap, session_time, frame_identifier),
+                Vec::new(),
+            ))
+        };
+
+        assembler.update(&motion_packet(0.0, Vec::new()));
+        assert!(assembler.update(&lap(1.0, 1)).is_empty());
+
+        let frames = assembler.update(&lap(1.5, 2));
+        assert_eq!(1, frames.len());
+        assert_eq!(0, frames[0].frame_identifier());
+        assert!(!frames[0].is_complete());
+        assert!(frames[0].motion().is_some());
+        assert_eq!(2, assembler.pending());
+    }
+}
@@ -0,0 +1,252 @@
+//! Handlers for packets
+//!
+//! Applications that read packets from a stream usually match on the packet enum, and handle only a
+//! few of its variants. The packet handler replaces this match with one method for each packet type.
+//! Every method does nothing by default, so that an application only implements the handlers for
+//! the packets it cares about. The dispatcher reads the packets from a stream, and passes each packet
+//! to the method for its type.
+
+use tokio_stream::{Stream, StreamExt};
+
+use crate::packet::event::EventPacket;
+use crate::packet::lap::LapPacket;
+use crate::packet::motion::MotionPacket;
+use crate::packet::participants::ParticipantsPacket;
+use crate::packet::session::SessionPacket;
+use crate::packet::setup::CarSetupPacket;
+use crate::packet::status::CarStatusPacket;
+use crate::packet::telemetry::TelemetryPacket;
+use crate::packet::Packet;
+
+/// Handler with a method for each type of packet
+///
+/// # Examples
+///
+/// ```
+/// use f1_api::handler::PacketHandler;
+/// use f1_api::packet::lap::LapPacket;
+///
+/// struct LapCounter {
+///     packets: usize,
+/// }
+///
+/// impl PacketHandler for LapCounter {
+///     fn on_lap(&mut self, _packet: &LapPacket) {
+///         self.packets += 1;
+///     }
+/// }
+/// ```
+pub trait PacketHandler {
+    /// Handle an event packet.
+    fn on_event(&mut self, _packet: &EventPacket) {}
+
+    /// Handle a lap data packet.
+    fn on_lap(&mut self, _packet: &LapPacket) {}
+
+    /// Handle a motion packet.
+    fn on_motion(&mut self, _packet: &MotionPacket) {}
+
+    /// Handle a participants packet.
+    fn on_participants(&mut self, _packet: &ParticipantsPacket) {}
+
+    /// Handle a session packet.
+    fn on_session(&mut self, _packet: &SessionPacket) {}
+
+    /// Handle a car setup packet.
+    fn on_setup(&mut self, _packet: &CarSetupPacket) {}
+
+    /// Handle a car status packet.
+    fn on_status(&mut self, _packet: &CarStatusPacket) {}
+
+    /// Handle a telemetry packet.
+    fn on_telemetry(&mut self, _packet: &TelemetryPacket) {}
+
+    /// Pass the packet to the method for its type.
+    fn handle(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Event(packet) => self.on_event(packet),
+            Packet::Lap(packet) => self.on_lap(packet),
+            Packet::Motion(packet) => self.on_motion(packet),
+            Packet::Participants(packet) => self.on_participants(packet),
+            Packet::Session(packet) => self.on_session(packet),
+            Packet::Setup(packet) => self.on_setup(packet),
+            Packet::Status(packet) => self.on_status(packet),
+            Packet::Telemetry(packet) => self.on_telemetry(packet),
+        }
+    }
+}
+
+/// Read the packets from the stream, and pass each of them to the handler.
+///
+/// The dispatcher returns when the stream ends. The errors of the streams created by `F1` can be
+/// skipped with `ok_packets`.
+///
+/// # Examples
+///
+/// ```
+/// use std::net::{IpAddr, SocketAddr};
+///
+/// use f1_api::handler::{dispatch, PacketHandler};
+/// use f1_api::packet::lap::LapPacket;
+/// use f1_api::{ok_packets, F1};
+///
+/// struct LapPrinter;
+///
+/// impl PacketHandler for LapPrinter {
+///     fn on_lap(&mut self, packet: &LapPacket) {
+///         println!("Received lap data at {:?}", packet.header().session_time());
+///     }
+/// }
+///
+/// async fn example() {
+///     let socket = SocketAddr::new(IpAddr::from([0, 0, 0, 0]), 20777);
+///     let stream = ok_packets(F1::stream(socket).unwrap());
+///
+///     dispatch(stream, &mut LapPrinter).await;
+/// }
+/// ```
+pub async fn dispatch<S, H>(stream: S, handler: &mut H)
+where
+    S: Stream<Item = Packet> + Unpin,
+    H: PacketHandler + ?Sized,
+{
+    let mut stream = stream;
+
+    while let Some(packet) = stream.next().await {
+        handler.handle(&packet);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_stream::StreamExt;
+
+    use crate::analysis::fixtures::{
+        event_packet, invalid_datagram, lap_datagram, lap_packet, motion_packet,
+        participants_packet, session_packet, status_packet, telemetry_packet, udp_stream,
+        LapFixture, SessionFixture, StatusFixture, TelemetryFixture,
+    };
+    use crate::handler::{dispatch, PacketHandler};
+    use crate::ok_packets;
+    use crate::packet::event::{Event, EventPacket};
+    use crate::packet::lap::LapPacket;
+    use crate::packet::motion::MotionPacket;
+    use crate::packet::participants::ParticipantsPacket;
+    use crate::packet::session::SessionPacket;
+    use crate::packet::setup::CarSetupPacket;
+    use crate::packet::status::CarStatusPacket;
+    use crate::packet::telemetry::TelemetryPacket;
+    use crate::packet::Packet;
+    use crate::types::Property3D;
+
+    #[derive(Default)]
+    struct LapCounter {
+        packets: usize,
+    }
+
+    impl PacketHandler for LapCounter {
+        fn on_lap(&mut self, _packet: &LapPacket) {
+            self.packets += 1;
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        handled: Vec<&'static str>,
+    }
+
+    impl PacketHandler for Recorder {
+        fn on_event(&mut self, _packet: &EventPacket) {
+            self.handled.push("event");
+        }
+
+        fn on_lap(&mut self, _packet: &LapPacket) {
+            self.handled.push("lap");
+        }
+
+        fn on_motion(&mut self, _packet: &MotionPacket) {
+            self.handled.push("motion");
+        }
+
+        fn on_participants(&mut self, _packet: &ParticipantsPacket) {
+            self.handled.push("participants");
+        }
+
+        fn on_session(&mut self, _packet: &SessionPacket) {
+            self.handled.push("session");
+        }
+
+        fn on_setup(&mut self, _packet: &CarSetupPacket) {
+            self.handled.push("setup");
+        }
+
+        fn on_status(&mut self, _packet: &CarStatusPacket) {
+            self.handled.push("status");
+        }
+
+        fn on_telemetry(&mut self, _packet: &TelemetryPacket) {
+            self.handled.push("telemetry");
+        }
+    }
+
+    #[test]
+    fn handle_each_packet_type() {
+        let packets = vec![
+            event_packet(0.0, Event::SessionStarted),
+            lap_packet(0.0, vec![LapFixture::default()]),
+            motion_packet(0.0, vec![Property3D::new(0.0, 0.0, 0.0)]),
+            participants_packet(0.0, vec!["Alice"]),
+            session_packet(0.0, SessionFixture::default()),
+            Packet::from(CarSetupPacket::default()),
+            status_packet(0.0, vec![StatusFixture::default()]),
+            telemetry_packet(0.0, vec![TelemetryFixture::default()]),
+        ];
+
+        let mut recorder = Recorder::default();
+        for packet in &packets {
+            recorder.handle(packet);
+        }
+
+        assert_eq!(
+            vec![
+                "event",
+                "lap",
+                "motion",
+                "participants",
+                "session",
+                "setup",
+                "status",
+                "telemetry"
+            ],
+            recorder.handled
+        );
+    }
+
+    #[tokio::test]
+    async fn dispatch_to_handler() {
+        let packets = vec![
+            lap_packet(0.0, vec![LapFixture::default()]),
+            event_packet(0.5, Event::SessionStarted),
+            participants_packet(0.5, vec!["Alice"]),
+            lap_packet(1.0, vec![LapFixture::default()]),
+        ];
+
+        let mut counter = LapCounter::default();
+        dispatch(tokio_stream::iter(packets), &mut counter).await;
+
+        assert_eq!(2, counter.packets);
+    }
+
+    #[tokio::test]
+    async fn dispatch_udp_stream() {
+        let (sender, stream) = udp_stream().await;
+        sender.send(&invalid_datagram()).await.unwrap();
+        sender.send(&lap_datagram(0.0)).await.unwrap();
+        sender.send(&lap_datagram(1.0)).await.unwrap();
+
+        let mut counter = LapCounter::default();
+        dispatch(ok_packets(stream).take(2), &mut counter).await;
+
+        assert_eq!(2, counter.packets);
+    }
+}
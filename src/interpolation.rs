@@ -0,0 +1,255 @@
+//! Interpolation of motion between packets
+//!
+//! The F1 games send the motion of the cars at a rate between 20 and 60 Hz, while renderers often
+//! draw frames at the refresh rate of the display. Drawing the cars at the position of the latest
+//! motion packet makes them jump from packet to packet. The motion interpolator keeps the two
+//! latest motion packets, and calculates the pose of each car at any point in time between them.
+//!
+//! Positions are interpolated along a cubic Hermite curve, which uses the velocity of the cars to
+//! follow their path smoothly through each packet. The yaw, pitch, and roll are interpolated along
+//! the shorter way around the circle. Poses after the latest packet are extrapolated from the
+//! velocity of the car for at most the interval between the two packets.
+
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::motion::Motion;
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, Property3D, VehicleIndex};
+
+/// Position and orientation of a car at a point in time
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pose {
+    /// Returns the position of the car in world space.
+    #[getset(get = "pub")]
+    position: Property3D<f32>,
+
+    /// Returns the yaw angle of the car in radians.
+    #[getset(get_copy = "pub")]
+    yaw: f32,
+
+    /// Returns the pitch angle of the car in radians.
+    #[getset(get_copy = "pub")]
+    pitch: f32,
+
+    /// Returns the roll angle of the car in radians.
+    #[getset(get_copy = "pub")]
+    roll: f32,
+}
+
+/// Motion of all cars at a point in time
+#[derive(Debug, Clone, PartialEq, Default)]
+struct MotionSample {
+    session_time: Duration,
+    cars: Vec<Motion>,
+}
+
+/// Interpolator for the motion of the cars between packets
+///
+/// The interpolator consumes motion packets, and ignores all other packets. Packets that arrive out
+/// of order are dropped. When a packet of a new session is received, the packets of the previous
+/// session are discarded.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MotionInterpolator {
+    session_uid: Option<u64>,
+    previous: Option<MotionSample>,
+    latest: Option<MotionSample>,
+}
+
+impl MotionInterpolator {
+    /// Create a new interpolator without any motion.
+    pub fn new() -> Self {
+        MotionInterpolator::default()
+    }
+
+    /// Update the interpolator with the motion from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        let packet = match packet {
+            Packet::Motion(packet) => packet,
+            _ => return,
+        };
+
+        let header = packet.header();
+        if self.session_uid != Some(header.session_uid()) {
+            self.session_uid = Some(header.session_uid());
+            self.previous = None;
+            self.latest = None;
+        }
+
+        let session_time = *header.session_time();
+        if self
+            .latest
+            .as_ref()
+            .is_some_and(|latest| latest.session_time >= session_time)
+        {
+            return;
+        }
+
+        self.previous = self.latest.take();
+        self.latest = Some(MotionSample {
+            session_time,
+            cars: packet.cars().clone(),
+        });
+    }
+
+    /// Returns the pose of a car at the given session time, if its motion is known.
+    pub fn pose(&self, vehicle_index: VehicleIndex, session_time: Duration) -> Option<Pose> {
+        let index = vehicle_index.as_usize();
+        let latest = self.latest.as_ref()?;
+        let to = latest.cars.get(index)?;
+
+        let (previous, from) = match self
+            .previous
+            .as_ref()
+            .and_then(|previous| previous.cars.get(index).map(|from| (previous, from)))
+        {
+            Some(previous) => previous,
+            None => return Some(pose_of(to)),
+        };
+
+        let interval = (latest.session_time - previous.session_time).as_secs_f32();
+
+        if session_time <= previous.session_time {
+            Some(pose_of(from))
+        } else if session_time <= latest.session_time {
+            let t = (session_time - previous.session_time).as_secs_f32() / interval;
+
+            Some(Pose::new(
+                hermite(from, to, interval, t),
+                lerp_angle(from.yaw(), to.yaw(), t),
+                lerp_angle(from.pitch(), to.pitch(), t),
+                lerp_angle(from.roll(), to.roll(), t),
+            ))
+        } else {
+            let elapsed = (session_time - latest.session_time)
+                .as_secs_f32()
+                .min(interval);
+            let position = to.position();
+            let velocity = to.velocity();
+
+            Some(Pose::new(
+                Property3D::new(
+                    position.x() + velocity.x() * elapsed,
+                    position.y() + velocity.y() * elapsed,
+                    position.z() + velocity.z() * elapsed,
+                ),
+                to.yaw(),
+                to.pitch(),
+                to.roll(),
+            ))
+        }
+    }
+
+    /// Returns the poses of all cars at the given session time, ordered by their vehicle index.
+    pub fn poses(&self, session_time: Duration) -> Vec<Pose> {
+        let cars = match self.latest.as_ref() {
+            Some(latest) => &latest.cars[..],
+            None => &[],
+        };
+
+        enumerate_cars(cars)
+            .filter_map(|(index, _)| self.pose(index, session_time))
+            .collect()
+    }
+}
+
+/// Returns the pose of a car as reported in a motion packet.
+fn pose_of(motion: &Motion) -> Pose {
+    Pose::new(
+        *motion.position(),
+        motion.yaw(),
+        motion.pitch(),
+        motion.roll(),
+    )
+}
+
+/// Interpolate the position of a car along a cubic Hermite curve, with the interval between the
+/// packets in seconds and the fraction of the interval that has passed.
+fn hermite(from: &Motion, to: &Motion, interval: f32, t: f32) -> Property3D<f32> {
+    let (t2, t3) = (t * t, t * t * t);
+    let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+    let h10 = t3 - 2.0 * t2 + t;
+    let h01 = -2.0 * t3 + 3.0 * t2;
+    let h11 = t3 - t2;
+
+    let component = |p0: f32, v0: f32, p1: f32, v1: f32| {
+        h00 * p0 + h10 * interval * v0 + h01 * p1 + h11 * interval * v1
+    };
+
+    let (p0, v0, p1, v1) = (
+        from.position(),
+        from.velocity(),
+        to.position(),
+        to.velocity(),
+    );
+    Property3D::new(
+        component(p0.x(), v0.x(), p1.x(), v1.x()),
+        component(p0.y(), v0.y(), p1.y(), v1.y()),
+        component(p0.z(), v0.z(), p1.z(), v1.z()),
+    )
+}
+
+/// Interpolate between two angles in radians along the shorter way around the circle.
+fn lerp_angle(from: f32, to: f32, t: f32) -> f32 {
+    let delta = (to - from + PI).rem_euclid(2.0 * PI) - PI;
+    let angle = from + delta * t;
+
+    (angle + PI).rem_euclid(2.0 * PI) - PI
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+    use std::time::Duration;
+
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::analysis::fixtures::{moving_motion_packet, vehicle};
+    use crate::interpolation::{lerp_angle, MotionInterpolator};
+    use crate::types::Property3D;
+
+    #[test]
+    fn pose_between_packets() {
+        let velocity = Property3D::new(10.0, 0.0, 0.0);
+        let mut interpolator = MotionInterpolator::new();
+
+        interpolator.update(&moving_motion_packet(
+            1.0,
+            vec![(Property3D::new(0.0, 0.0, 0.0), velocity)],
+        ));
+        assert_eq!(
+            0.0,
+            interpolator
+                .pose(vehicle(0), Duration::from_secs(5))
+                .unwrap()
+                .position()
+                .x()
+        );
+
+        interpolator.update(&moving_motion_packet(
+            2.0,
+            vec![(Property3D::new(10.0, 0.0, 0.0), velocity)],
+        ));
+
+        let position = |seconds: f32| {
+            *interpolator
+                .pose(vehicle(0), Duration::from_secs_f32(seconds))
+                .unwrap()
+                .position()
+        };
+        assert_approx_eq!(0.0, position(0.5).x());
+        assert_approx_eq!(2.5, position(1.25).x());
+        assert_approx_eq!(15.0, position(2.5).x());
+        assert_approx_eq!(20.0, position(4.0).x());
+        assert_eq!(1, interpolator.poses(Duration::from_secs(2)).len());
+        assert!(interpolator
+            .pose(vehicle(1), Duration::from_secs(2))
+            .is_none());
+
+        assert_approx_eq!(PI, lerp_angle(PI - 0.1, -PI + 0.1, 0.5).abs());
+    }
+}
@@ -0,0 +1,190 @@
+//! Packets whose body is decoded on demand
+//!
+//! Recorders and forwarders receive every packet, but rarely look inside them. They only need the
+//! header to tell the packets apart, and pass on the raw bytes. Decoding the body of each packet
+//! into its owned representation is wasted for these consumers.
+//!
+//! The lazy codec only decodes the header of each packet, checks that the packet is complete, and
+//! keeps its raw bytes. The bytes are taken from the receive buffer without copying them, and the
+//! buffer returns to its pool once the packet has been dropped. The body is decoded when `decode`
+//! or `view` is called on the packet.
+
+use std::collections::BTreeSet;
+
+use bytes::{Bytes, BytesMut};
+use tokio_util::codec::Decoder;
+
+use crate::error::F1Error;
+use crate::nineteen::view::PacketView;
+use crate::nineteen::{decode_nineteen, peek_header};
+use crate::packet::header::{Header, PacketType};
+use crate::packet::Packet;
+
+/// Packet whose header has been decoded, and whose body is kept as raw bytes
+#[derive(Debug, PartialEq, Clone)]
+pub struct LazyPacket {
+    header: Header,
+    data: Bytes,
+}
+
+impl LazyPacket {
+    /// Create a lazy packet from the raw bytes of a packet, and decode its header.
+    ///
+    /// Returns an error if the data does not start with a valid header, or if it is smaller than
+    /// the packet that the header announces.
+    pub fn new(data: Bytes) -> Result<Self, F1Error> {
+        let header = *PacketView::new(&data)?.header();
+
+        Ok(LazyPacket { header, data })
+    }
+
+    /// Returns the header of the packet.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Returns the raw bytes of the packet, including its header.
+    pub fn data(&self) -> &Bytes {
+        &self.data
+    }
+
+    /// Take the raw bytes of the packet.
+    pub fn into_data(self) -> Bytes {
+        self.data
+    }
+
+    /// Decode the body of the packet into its owned representation.
+    ///
+    /// The packet is decoded leniently, and deviations from the API specification are not
+    /// reported. Packets of types whose decoder has been compiled out return an error.
+    pub fn decode(&self) -> Result<Packet, F1Error> {
+        decode_nineteen(&mut std::io::Cursor::new(&self.data[..]))
+    }
+
+    /// Returns a view of the packet that decodes the data of each car when it is accessed.
+    pub fn view(&self) -> PacketView<'_> {
+        // The header and the size of the packet have been checked when it was created.
+        PacketView::new(&self.data).unwrap()
+    }
+}
+
+/// Codec that only decodes the headers of UDP packets
+///
+/// The lazy codec is created by `DecoderBuilder::build_lazy`, and honors the packet types of the
+/// builder. Packets of other types are dropped. The lazy codec accepts the packets of every
+/// supported API specification. Since the body of a packet is not decoded, the lazy codec also
+/// yields packets whose decoder has been compiled out, and it does not record warnings.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct LazyCodec {
+    packet_types: Option<BTreeSet<PacketType>>,
+}
+
+impl LazyCodec {
+    pub(crate) fn new(packet_types: Option<BTreeSet<PacketType>>) -> Self {
+        LazyCodec { packet_types }
+    }
+
+    /// Returns whether the codec yields packets of the given type.
+    pub fn yields(&self, packet_type: PacketType) -> bool {
+        self.packet_types
+            .as_ref()
+            .is_none_or(|packet_types| packet_types.contains(&packet_type))
+    }
+}
+
+impl Decoder for LazyCodec {
+    type Item = LazyPacket;
+    type Error = F1Error;
+
+    /// Decode the header of a UDP packet, and take its raw bytes from the buffer.
+    ///
+    /// Like the `F1Codec`, the lazy codec returns `Ok(None)` for incomplete packets and for packets
+    /// of types that it does not yield.
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, F1Error> {
+        if peek_header(src).is_some_and(|header| !self.yields(header.packet_type())) {
+            return Ok(None);
+        }
+
+        let header = match PacketView::new(src) {
+            Ok(view) => *view.header(),
+            Err(F1Error::IncompleteData { .. }) => return Ok(None),
+            Err(error) => return Err(error),
+        };
+
+        Ok(Some(LazyPacket {
+            header,
+            data: src.split().freeze(),
+        }))
+    }
+
+    fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, F1Error> {
+        self.decode(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::{BufMut, Bytes, BytesMut};
+    use tokio_util::codec::Decoder;
+
+    use crate::codec::DecoderBuilder;
+    use crate::error::F1Error;
+    use crate::lazy::{LazyCodec, LazyPacket};
+    use crate::packet::event::Event;
+    use crate::packet::header::PacketType;
+    use crate::packet::Packet;
+
+    fn put_event_packet() -> BytesMut {
+        let mut bytes = BytesMut::with_capacity(32);
+
+        bytes.put_u16_le(2019);
+        bytes.put_u8(1);
+        bytes.put_u8(2);
+        bytes.put_u8(1);
+        bytes.put_u8(3);
+        bytes.put_u64_le(u64::MAX);
+        bytes.put_f32_le(1.0);
+        bytes.put_u32_le(u32::MAX);
+        bytes.put_u8(0);
+        bytes.put(&b"SSTA"[..]);
+        bytes.put(&[0u8; 5][..]);
+
+        bytes
+    }
+
+    #[test]
+    fn decode_lazily() {
+        let mut codec = LazyCodec::default();
+
+        // The event code is invalid, but the body is only decoded on demand.
+        let mut bytes = put_event_packet();
+        bytes[23..27].copy_from_slice(b"XXXX");
+
+        let expected = bytes.clone();
+        let packet = codec.decode(&mut bytes).unwrap().unwrap();
+        assert_eq!(PacketType::Event, packet.header().packet_type());
+        assert_eq!(&expected[..], &packet.data()[..]);
+        assert!(bytes.is_empty());
+        assert!(packet.decode().is_err());
+
+        let packet = LazyPacket::new(put_event_packet().freeze()).unwrap();
+        match packet.decode().unwrap() {
+            Packet::Event(packet) => assert_eq!(&Event::SessionStarted, packet.event()),
+            packet => panic!("Unexpected packet {:?}", packet),
+        }
+
+        assert!(codec
+            .decode(&mut put_event_packet().split_to(20))
+            .unwrap()
+            .is_none());
+        assert!(matches!(
+            LazyPacket::new(Bytes::from_static(&[0; 32])),
+            Err(F1Error::UnknownPacketFormat { format: 0 })
+        ));
+
+        let mut codec = DecoderBuilder::new()
+            .packet_types(&[PacketType::Lap])
+            .build_lazy();
+        assert!(codec.decode(&mut put_event_packet()).unwrap().is_none());
+    }
+}
@@ -6,14 +6,30 @@ use std::net::SocketAddr;
 use socket2::{Domain, Protocol, Socket, Type};
 use tokio::net::UdpSocket;
 use tokio_stream::{Stream, StreamExt};
-use tokio_util::udp::UdpFramed;
 
-use crate::codec::F1Codec;
+use crate::codec::DecoderBuilder;
+use crate::error::F1Error;
+use crate::lazy::LazyPacket;
+use crate::listener::Listener;
 use crate::packet::Packet;
 
+pub mod analysis;
+pub mod car;
 pub mod codec;
+pub mod error;
+pub mod fan_out;
+pub mod frame;
+pub mod handler;
+pub mod interpolation;
+pub mod lazy;
+mod listener;
 pub mod nineteen;
 pub mod packet;
+pub mod pool;
+pub mod prelude;
+pub mod pretty;
+pub mod state;
+pub mod throttle;
 pub mod types;
 
 /// A high-level interface to the telemetry data of modern F1 video games.
@@ -31,6 +47,11 @@ impl F1 {
     /// this function, a stream can be created that listens at the given socket for incoming
     /// packets, decodes them using the `F1Codec`, and returns their Rust representations.
     ///
+    /// Datagrams that cannot be decoded, and errors of the socket, are yielded as errors. The stream
+    /// keeps listening after an error, so consumers can skip packets that they cannot use. The
+    /// errors can be dropped with `ok_packets`, which also connects the stream to the stream
+    /// adapters of this crate.
+    ///
     /// # Examples
     ///
     /// ```
@@ -49,28 +70,105 @@ impl F1 {
     ///
     ///     while let Some(packet) = stream.next().await {
     ///         match packet {
-    ///             Event(_) => println!("Received Event packet"),
-    ///             Lap(_) => println!("Received Lap packet"),
-    ///             Motion(_) => println!("Received Motion packet"),
-    ///             Participants(_) => println!("Received Participants packet"),
-    ///             Session(_) => println!("Received Session packet"),
-    ///             Setup(_) => println!("Received Setup packet"),
-    ///             Status(_) => println!("Received Status packet"),
-    ///             Telemetry(_) => println!("Received Telemetry packet"),
+    ///             Ok(Event(_)) => println!("Received Event packet"),
+    ///             Ok(Lap(_)) => println!("Received Lap packet"),
+    ///             Ok(Motion(_)) => println!("Received Motion packet"),
+    ///             Ok(Participants(_)) => println!("Received Participants packet"),
+    ///             Ok(Session(_)) => println!("Received Session packet"),
+    ///             Ok(Setup(_)) => println!("Received Setup packet"),
+    ///             Ok(Status(_)) => println!("Received Status packet"),
+    ///             Ok(Telemetry(_)) => println!("Received Telemetry packet"),
+    ///             Err(error) => println!("Failed to decode packet: {}", error),
     ///         }
     ///     }
     /// }
     /// ```
-    pub fn stream(socket_address: SocketAddr) -> Result<impl Stream<Item = Packet>, Error> {
-        let socket = match socket_address {
-            SocketAddr::V4(_) => Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP)),
-            SocketAddr::V6(_) => Socket::new(Domain::IPV6, Type::DGRAM, Some(Protocol::UDP)),
-        }?;
+    pub fn stream(
+        socket_address: SocketAddr,
+    ) -> Result<impl Stream<Item = Result<Packet, F1Error>>, Error> {
+        F1::stream_with(socket_address, DecoderBuilder::new())
+    }
+
+    /// Create a stream that yields decoded UDP packets, with a codec built by the given builder.
+    ///
+    /// The stream listens at the given socket like the stream created by `stream`, but decodes the
+    /// packets with the settings of the builder, for example only the packets of certain types.
+    ///
+    /// Datagrams are received into buffers from the builder's buffer pool, or from a default pool
+    /// if the builder has none. Once the pool has warmed up, no buffers are allocated anymore.
+    ///
+    /// In strict mode, packets that deviate from the API specification are yielded as
+    /// `F1Error::Rejected` errors. In lenient mode, their warnings are passed to the warning handler
+    /// of the builder, which can be set with `DecoderBuilder::on_warning`.
+    pub fn stream_with(
+        socket_address: SocketAddr,
+        builder: DecoderBuilder,
+    ) -> Result<impl Stream<Item = Result<Packet, F1Error>>, Error> {
+        let socket = bind(socket_address, &builder)?;
+        let pool = builder.buffer_pool.clone().unwrap_or_default();
 
-        socket.bind(&socket_address.into())?;
+        Ok(Listener::new(socket, builder.build(), pool))
+    }
+
+    /// Create a stream that yields UDP packets whose body is decoded on demand.
+    ///
+    /// Only the header of each packet is decoded, and its raw bytes are kept in a `LazyPacket`.
+    /// This suits consumers that record or forward packets without looking at most of them. The
+    /// API specification, packet types, and buffers of the builder are used like in `stream_with`.
+    pub fn lazy_stream(
+        socket_address: SocketAddr,
+        builder: DecoderBuilder,
+    ) -> Result<impl Stream<Item = Result<LazyPacket, F1Error>>, Error> {
+        let socket = bind(socket_address, &builder)?;
+        let pool = builder.buffer_pool.clone().unwrap_or_default();
 
-        Ok(UdpFramed::new(UdpSocket::from_std(socket.into())?, F1Codec)
-            .map(|result| result.unwrap())
-            .map(|(packet, _address)| packet))
+        Ok(Listener::new(socket, builder.build_lazy(), pool))
     }
 }
+
+/// Skip the errors of a stream, and yield its packets.
+///
+/// The streams created by `F1` yield an error for each datagram that cannot be decoded. The stream
+/// adapters in this crate, like `handler::dispatch` or `throttle::throttle_stream`, take a stream
+/// of packets instead. This function connects the two for consumers that do not handle errors.
+///
+/// # Examples
+///
+/// ```
+/// use std::net::{IpAddr, SocketAddr};
+///
+/// use f1_api::{ok_packets, F1};
+/// use tokio_stream::StreamExt;
+///
+/// async fn example() {
+///     let socket = SocketAddr::new(IpAddr::from([0, 0, 0, 0]), 20777);
+///     let mut stream = ok_packets(F1::stream(socket).unwrap());
+///
+///     while let Some(packet) = stream.next().await {
+///         println!("Received {:?} packet", packet.header().packet_type());
+///     }
+/// }
+/// ```
+pub fn ok_packets<S, T>(stream: S) -> impl Stream<Item = T>
+where
+    S: Stream<Item = Result<T, F1Error>>,
+{
+    stream.filter_map(|packet| packet.ok())
+}
+
+/// Bind a non-blocking UDP socket at the given address, with the socket settings of the builder.
+fn bind(socket_address: SocketAddr, builder: &DecoderBuilder) -> Result<UdpSocket, Error> {
+    let socket = match socket_address {
+        SocketAddr::V4(_) => Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP)),
+        SocketAddr::V6(_) => Socket::new(Domain::IPV6, Type::DGRAM, Some(Protocol::UDP)),
+    }?;
+
+    if let Some(size) = builder.recv_buffer_size {
+        socket.set_recv_buffer_size(size)?;
+    }
+
+    socket.bind(&socket_address.into())?;
+    socket.set_nonblocking(true)?;
+
+    UdpSocket::from_std(socket.into())
+}
@@ -0,0 +1,168 @@
+//! Stream of packets received from a UDP socket
+//!
+//! The listener receives each datagram into a buffer from a pool, decodes it with a codec, and
+//! returns the buffer to the pool before the packet is yielded. Codecs that take the bytes of the
+//! datagram out of the buffer, like the lazy codec, share its allocation with the packet, and the
+//! pool reuses the buffer once the packet has been dropped.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::ReadBuf;
+use tokio::net::UdpSocket;
+use tokio_stream::Stream;
+use tokio_util::codec::Decoder;
+
+use crate::error::F1Error;
+use crate::pool::BufferPool;
+
+/// Stream that decodes the datagrams received by a socket
+#[derive(Debug)]
+pub(crate) struct Listener<C> {
+    socket: UdpSocket,
+    codec: C,
+    pool: BufferPool,
+}
+
+impl<C> Listener<C> {
+    pub(crate) fn new(socket: UdpSocket, codec: C, pool: BufferPool) -> Self {
+        Listener {
+            socket,
+            codec,
+            pool,
+        }
+    }
+}
+
+impl<C> Stream for Listener<C>
+where
+    C: Decoder<Error = F1Error> + Unpin,
+{
+    type Item = Result<C::Item, F1Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let listener = self.get_mut();
+
+        loop {
+            let mut buffer = listener.pool.get();
+            let mut read_buf = ReadBuf::new(&mut buffer[..]);
+
+            match listener.socket.poll_recv_from(cx, &mut read_buf) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(error)) => return Poll::Ready(Some(Err(error.into()))),
+                Poll::Ready(Ok(_address)) => {}
+            }
+
+            let length = read_buf.filled().len();
+            buffer.truncate(length);
+
+            // Datagrams that the codec skips or that are incomplete are dropped.
+            match listener.codec.decode_eof(&mut buffer) {
+                Ok(Some(packet)) => return Poll::Ready(Some(Ok(packet))),
+                Ok(None) => continue,
+                Err(error) => return Poll::Ready(Some(Err(error))),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::{BufMut, BytesMut};
+    use tokio::net::UdpSocket;
+    use tokio_stream::StreamExt;
+
+    use crate::codec::{DecodeMode, F1Codec};
+    use crate::error::F1Error;
+    use crate::lazy::LazyCodec;
+    use crate::listener::Listener;
+    use crate::packet::header::PacketType;
+    use crate::packet::Packet;
+    use crate::pool::BufferPool;
+
+    fn put_event_packet() -> BytesMut {
+        let mut bytes = BytesMut::with_capacity(32);
+
+        bytes.put_u16_le(2019);
+        bytes.put_u8(1);
+        bytes.put_u8(2);
+        bytes.put_u8(1);
+        bytes.put_u8(3);
+        bytes.put_u64_le(u64::MAX);
+        bytes.put_f32_le(1.0);
+        bytes.put_u32_le(u32::MAX);
+        bytes.put_u8(0);
+        bytes.put(&b"SSTA"[..]);
+        bytes.put(&[0u8; 5][..]);
+
+        bytes
+    }
+
+    #[tokio::test]
+    async fn receive_into_pooled_buffers() {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = socket.local_addr().unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+        let pool = BufferPool::default();
+        let mut listener = Listener::new(socket, F1Codec::default(), pool.clone());
+
+        for _ in 0..3 {
+            sender.send_to(&put_event_packet(), address).await.unwrap();
+            let packet = listener.next().await.unwrap().unwrap();
+            assert!(matches!(packet, Packet::Event(_)));
+        }
+
+        let metrics = pool.metrics();
+        assert_eq!(1, metrics.allocations());
+        assert!(metrics.reuses() >= 2);
+        assert_eq!(1, metrics.available());
+    }
+
+    #[tokio::test]
+    async fn hand_pooled_buffers_to_lazy_packets() {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = socket.local_addr().unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+        let pool = BufferPool::default();
+        let mut listener = Listener::new(socket, LazyCodec::default(), pool.clone());
+
+        for _ in 0..3 {
+            sender.send_to(&put_event_packet(), address).await.unwrap();
+            let packet = listener.next().await.unwrap().unwrap();
+            assert_eq!(&put_event_packet()[..], &packet.data()[..]);
+        }
+        assert_eq!(1, pool.metrics().allocations());
+
+        // The buffer of a packet that is kept cannot be reused.
+        sender.send_to(&put_event_packet(), address).await.unwrap();
+        let kept = listener.next().await.unwrap().unwrap();
+        sender.send_to(&put_event_packet(), address).await.unwrap();
+        listener.next().await.unwrap().unwrap();
+
+        assert_eq!(2, pool.metrics().allocations());
+        assert_eq!(PacketType::Event, kept.header().packet_type());
+    }
+
+    #[tokio::test]
+    async fn yield_errors_and_keep_listening() {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = socket.local_addr().unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+        let codec = F1Codec::new(DecodeMode::Strict);
+        let mut listener = Listener::new(socket, codec, BufferPool::default());
+
+        let mut trailing = put_event_packet();
+        trailing.put_u8(0);
+        sender.send_to(&trailing, address).await.unwrap();
+        sender.send_to(&put_event_packet(), address).await.unwrap();
+
+        let error = listener.next().await.unwrap().unwrap_err();
+        assert!(matches!(error, F1Error::Rejected(_)));
+
+        let packet = listener.next().await.unwrap().unwrap();
+        assert!(matches!(packet, Packet::Event(_)));
+    }
+}
@@ -7,33 +7,47 @@
 //! The full API specification can be found here:
 //! https://forums.codemasters.com/topic/44592-f1-2019-udp-specification/
 
-use std::io::{Cursor, Error};
-
-use bytes::BytesMut;
+use std::io::Cursor;
 
+use crate::error::F1Error;
 use crate::nineteen::event::decode_event;
 use crate::nineteen::header::decode_header;
-use crate::nineteen::lap::decode_lap_data;
-use crate::nineteen::motion::decode_motion;
-use crate::nineteen::participants::decode_participants;
+use crate::nineteen::lap::{decode_lap_data, decode_lap_data_into};
+#[cfg(feature = "motion")]
+use crate::nineteen::motion::{decode_motion, decode_motion_into};
+use crate::nineteen::participants::{decode_participants, decode_participants_into};
 use crate::nineteen::session::decode_session;
-use crate::nineteen::setup::decode_setups;
-use crate::nineteen::status::decode_statuses;
-use crate::nineteen::telemetry::decode_telemetry;
-use crate::packet::header::PacketType;
+use crate::nineteen::setup::{decode_setups, decode_setups_into};
+use crate::nineteen::status::{decode_statuses, decode_statuses_into};
+#[cfg(feature = "telemetry")]
+use crate::nineteen::telemetry::{decode_telemetry, decode_telemetry_into};
+use crate::packet::header::{Header, PacketType};
+use crate::packet::lap::LapPacket;
+#[cfg(feature = "motion")]
+use crate::packet::motion::MotionPacket;
+use crate::packet::participants::ParticipantsPacket;
+use crate::packet::setup::CarSetupPacket;
+use crate::packet::status::CarStatusPacket;
+#[cfg(feature = "telemetry")]
+use crate::packet::telemetry::TelemetryPacket;
 use crate::packet::Packet;
 
 mod header;
 
+pub mod corner;
 pub mod event;
 pub mod flag;
 pub mod lap;
+#[cfg(feature = "motion")]
 pub mod motion;
 pub mod participants;
 pub mod session;
 pub mod setup;
 pub mod status;
+#[cfg(feature = "telemetry")]
 pub mod telemetry;
+pub mod vehicle;
+pub mod view;
 
 /// Flags shown in F1 2019.
 ///
@@ -54,27 +68,162 @@ pub enum Flag {
 ///
 /// Data for all vehicles is provided as an array. References to the data in
 /// this array are made in the form of a vehicle index.
-pub type VehicleIndex = u8;
+pub use crate::types::VehicleIndex;
+
+/// Size of the motion packet in bytes, which is known even if its decoder has been compiled out
+const MOTION_PACKET_SIZE: usize = 1343;
+
+/// Size of the telemetry packet in bytes, which is known even if its decoder has been compiled out
+const TELEMETRY_PACKET_SIZE: usize = 1347;
 
 /// Decode a packet sent by F1 2019
 ///
 /// F1 2019 defines its own API specification that is implemented in the `nineteen` module. For each
 /// packet type defined in the API specification, a decoder function exists that maps the packet
 /// from F1 2019 to the unified packet format of this crate.
-pub fn decode_nineteen(cursor: &mut Cursor<&mut BytesMut>) -> Result<Packet, Error> {
+///
+/// Motion and telemetry packets are only decoded if the `motion` and `telemetry` features are
+/// enabled. Packets of types whose decoder has been compiled out return an error.
+pub fn decode_nineteen(cursor: &mut Cursor<&[u8]>) -> Result<Packet, F1Error> {
     let header = decode_header(cursor)?;
     cursor.set_position(0);
 
     let packet = match header.packet_type() {
         PacketType::Event => Packet::Event(decode_event(cursor)?),
         PacketType::Lap => Packet::Lap(decode_lap_data(cursor)?),
+        #[cfg(feature = "motion")]
         PacketType::Motion => Packet::Motion(decode_motion(cursor)?),
         PacketType::Participants => Packet::Participants(decode_participants(cursor)?),
         PacketType::Session => Packet::Session(decode_session(cursor)?),
         PacketType::Setup => Packet::Setup(decode_setups(cursor)?),
         PacketType::Status => Packet::Status(decode_statuses(cursor)?),
+        #[cfg(feature = "telemetry")]
         PacketType::Telemetry => Packet::Telemetry(decode_telemetry(cursor)?),
+        #[allow(unreachable_patterns)]
+        packet_type => return Err(F1Error::DisabledPacketType { packet_type }),
     };
 
     Ok(packet)
 }
+
+/// Returns the size of a packet of the given type in F1 2019.
+pub fn packet_size(packet_type: PacketType) -> usize {
+    match packet_type {
+        PacketType::Event => event::PACKET_SIZE,
+        PacketType::Lap => lap::PACKET_SIZE,
+        PacketType::Motion => MOTION_PACKET_SIZE,
+        PacketType::Participants => participants::PACKET_SIZE,
+        PacketType::Session => session::PACKET_SIZE,
+        PacketType::Setup => setup::PACKET_SIZE,
+        PacketType::Status => status::PACKET_SIZE,
+        PacketType::Telemetry => TELEMETRY_PACKET_SIZE,
+    }
+}
+
+/// Returns whether the decoder for packets of the given type has been compiled into the crate.
+pub fn is_decoder_enabled(packet_type: PacketType) -> bool {
+    let motion = cfg!(feature = "motion") || packet_type != PacketType::Motion;
+    let telemetry = cfg!(feature = "telemetry") || packet_type != PacketType::Telemetry;
+
+    motion && telemetry
+}
+
+/// Packet that can be decoded into storage that the caller provides
+///
+/// Real-time consumers can keep one packet of each type, and decode every new packet of that type
+/// into it. The vectors of the packet are reused, so that no memory is allocated once a packet has
+/// been decoded into the storage for the first time.
+pub trait DecodeInto {
+    /// Type of the packets that can be decoded into the storage
+    const PACKET_TYPE: PacketType;
+
+    /// Decode the packet at the cursor into the storage, and overwrite its previous contents.
+    fn decode_into(&mut self, cursor: &mut Cursor<&[u8]>) -> Result<(), F1Error>;
+}
+
+impl DecodeInto for LapPacket {
+    const PACKET_TYPE: PacketType = PacketType::Lap;
+
+    fn decode_into(&mut self, cursor: &mut Cursor<&[u8]>) -> Result<(), F1Error> {
+        decode_lap_data_into(cursor, self)
+    }
+}
+
+#[cfg(feature = "motion")]
+impl DecodeInto for MotionPacket {
+    const PACKET_TYPE: PacketType = PacketType::Motion;
+
+    fn decode_into(&mut self, cursor: &mut Cursor<&[u8]>) -> Result<(), F1Error> {
+        decode_motion_into(cursor, self)
+    }
+}
+
+impl DecodeInto for ParticipantsPacket {
+    const PACKET_TYPE: PacketType = PacketType::Participants;
+
+    fn decode_into(&mut self, cursor: &mut Cursor<&[u8]>) -> Result<(), F1Error> {
+        decode_participants_into(cursor, self)
+    }
+}
+
+impl DecodeInto for CarSetupPacket {
+    const PACKET_TYPE: PacketType = PacketType::Setup;
+
+    fn decode_into(&mut self, cursor: &mut Cursor<&[u8]>) -> Result<(), F1Error> {
+        decode_setups_into(cursor, self)
+    }
+}
+
+impl DecodeInto for CarStatusPacket {
+    const PACKET_TYPE: PacketType = PacketType::Status;
+
+    fn decode_into(&mut self, cursor: &mut Cursor<&[u8]>) -> Result<(), F1Error> {
+        decode_statuses_into(cursor, self)
+    }
+}
+
+#[cfg(feature = "telemetry")]
+impl DecodeInto for TelemetryPacket {
+    const PACKET_TYPE: PacketType = PacketType::Telemetry;
+
+    fn decode_into(&mut self, cursor: &mut Cursor<&[u8]>) -> Result<(), F1Error> {
+        decode_telemetry_into(cursor, self)
+    }
+}
+
+/// Decode a packet sent by F1 2019 into a packet of the same type that the caller provides
+///
+/// Returns an error if the data contains a packet of another type, in which case the provided
+/// packet is left untouched.
+///
+/// # Examples
+///
+/// ```
+/// use f1_api::nineteen::decode_into;
+/// use f1_api::packet::lap::LapPacket;
+///
+/// fn on_datagram(laps: &mut LapPacket, data: &[u8]) {
+///     if decode_into(laps, data).is_ok() {
+///         println!("{} cars", laps.laps().len());
+///     }
+/// }
+/// ```
+pub fn decode_into<P: DecodeInto>(packet: &mut P, data: &[u8]) -> Result<(), F1Error> {
+    let mut cursor = Cursor::new(data);
+    let header = decode_header(&mut cursor)?;
+
+    if header.packet_type() != P::PACKET_TYPE {
+        return Err(F1Error::UnexpectedPacketType {
+            expected: P::PACKET_TYPE,
+            actual: header.packet_type(),
+        });
+    }
+
+    cursor.set_position(0);
+    packet.decode_into(&mut cursor)
+}
+
+/// Decode the header of a packet sent by F1 2019, if the data contains a valid header
+pub(crate) fn peek_header(data: &[u8]) -> Option<Header> {
+    decode_header(&mut Cursor::new(data)).ok()
+}
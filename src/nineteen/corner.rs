@@ -0,0 +1,45 @@
+//! Decoder for properties on each corner of a car
+
+use std::io::Cursor;
+
+use crate::error::F1Error;
+use crate::types::CornerProperty;
+
+/// Decode a property on each corner of a car
+///
+/// F1 2019 publishes the values of a property in the order rear left, rear right, front left, and
+/// front right, which differs from the order of the fields in `CornerProperty`.
+pub fn decode_corners<T, F>(cursor: &mut Cursor<&[u8]>, decode: F) -> CornerProperty<T>
+where
+    T: Copy,
+    F: Fn(&mut Cursor<&[u8]>) -> T,
+{
+    let rear_left = decode(cursor);
+    let rear_right = decode(cursor);
+    let front_left = decode(cursor);
+    let front_right = decode(cursor);
+
+    CornerProperty::new(front_left, front_right, rear_left, rear_right)
+}
+
+/// Decode a property on each corner of a car with a decoder that can fail
+pub fn try_decode_corners<T, F>(
+    cursor: &mut Cursor<&[u8]>,
+    decode: F,
+) -> Result<CornerProperty<T>, F1Error>
+where
+    T: Copy,
+    F: Fn(&mut Cursor<&[u8]>) -> Result<T, F1Error>,
+{
+    let rear_left = decode(cursor)?;
+    let rear_right = decode(cursor)?;
+    let front_left = decode(cursor)?;
+    let front_right = decode(cursor)?;
+
+    Ok(CornerProperty::new(
+        front_left,
+        front_right,
+        rear_left,
+        rear_right,
+    ))
+}
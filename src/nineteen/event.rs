@@ -3,12 +3,14 @@
 //! F1 2019 extended the event packet with seven new events compared to its predecessor, four of
 //! which can carry a payload.
 
-use std::io::{Cursor, Error, ErrorKind};
+use std::io::Cursor;
 use std::time::Duration;
 
-use bytes::{Buf, BytesMut};
+use bytes::Buf;
 
+use crate::error::F1Error;
 use crate::nineteen::header::decode_header;
+use crate::nineteen::vehicle::decode_vehicle_index;
 use crate::packet::ensure_packet_size;
 use crate::packet::event::{
     Event, EventPacket, FastestLap, RaceWinner, Retirement, TeammateInPits,
@@ -26,7 +28,7 @@ pub const PACKET_SIZE: usize = 32;
 /// which can carry a payload. A four character event code is provided after the packet header to
 /// identify the event. Based on this code the right decoding function is called, and a variant of
 /// the `EventPacket` is returned.
-pub fn decode_event(cursor: &mut Cursor<&mut BytesMut>) -> Result<EventPacket, Error> {
+pub fn decode_event(cursor: &mut Cursor<&[u8]>) -> Result<EventPacket, F1Error> {
     ensure_packet_size(PACKET_SIZE, cursor)?;
 
     let header = decode_header(cursor)?;
@@ -35,18 +37,18 @@ pub fn decode_event(cursor: &mut Cursor<&mut BytesMut>) -> Result<EventPacket, E
     let payload = match event_code.as_str() {
         "SSTA" => Event::SessionStarted,
         "SEND" => Event::SessionEnded,
-        "FTLP" => decode_fastest_lap(cursor),
-        "RTMT" => decode_retirement(cursor),
+        "FTLP" => decode_fastest_lap(cursor)?,
+        "RTMT" => decode_retirement(cursor)?,
         "DRSE" => Event::DrsEnabled,
         "DRSD" => Event::DrsDisabled,
-        "TMPT" => decode_teammate_pits(cursor),
+        "TMPT" => decode_teammate_pits(cursor)?,
         "CHQF" => Event::ChequeredFlag,
-        "RCWN" => decode_race_winner(cursor),
+        "RCWN" => decode_race_winner(cursor)?,
         event_code => {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                format!("Unexpected event code {}", event_code),
-            ))
+            return Err(F1Error::UnknownEventCode {
+                code: event_code.to_string(),
+                offset: cursor.position() - 4,
+            })
         }
     };
 
@@ -57,7 +59,7 @@ pub fn decode_event(cursor: &mut Cursor<&mut BytesMut>) -> Result<EventPacket, E
 ///
 /// The event packet contains a string that identifies the type of the event. Based on the event
 /// code different logic can be used to decode the remainder of the packet.
-fn decode_event_code(cursor: &mut Cursor<&mut BytesMut>) -> String {
+fn decode_event_code(cursor: &mut Cursor<&[u8]>) -> String {
     [
         cursor.get_u8() as char,
         cursor.get_u8() as char,
@@ -69,26 +71,32 @@ fn decode_event_code(cursor: &mut Cursor<&mut BytesMut>) -> String {
 }
 
 /// Decode the "Fastest Lap" event.
-fn decode_fastest_lap(cursor: &mut Cursor<&mut BytesMut>) -> Event {
-    Event::FastestLap(FastestLap::new(
-        cursor.get_u8(),
+fn decode_fastest_lap(cursor: &mut Cursor<&[u8]>) -> Result<Event, F1Error> {
+    Ok(Event::FastestLap(FastestLap::new(
+        decode_vehicle_index(cursor)?,
         Duration::from_secs_f32(cursor.get_f32_le()),
-    ))
+    )))
 }
 
 /// Decode the "Retirement" event.
-fn decode_retirement(cursor: &mut Cursor<&mut BytesMut>) -> Event {
-    Event::Retirement(Retirement::new(cursor.get_u8()))
+fn decode_retirement(cursor: &mut Cursor<&[u8]>) -> Result<Event, F1Error> {
+    Ok(Event::Retirement(Retirement::new(decode_vehicle_index(
+        cursor,
+    )?)))
 }
 
 /// Decode the "Teammate in Pits" event.
-fn decode_teammate_pits(cursor: &mut Cursor<&mut BytesMut>) -> Event {
-    Event::TeammatesInPits(TeammateInPits::new(cursor.get_u8()))
+fn decode_teammate_pits(cursor: &mut Cursor<&[u8]>) -> Result<Event, F1Error> {
+    Ok(Event::TeammatesInPits(TeammateInPits::new(
+        decode_vehicle_index(cursor)?,
+    )))
 }
 
 /// Decode the "Race Winner" event.
-fn decode_race_winner(cursor: &mut Cursor<&mut BytesMut>) -> Event {
-    Event::RaceWinner(RaceWinner::new(cursor.get_u8()))
+fn decode_race_winner(cursor: &mut Cursor<&[u8]>) -> Result<Event, F1Error> {
+    Ok(Event::RaceWinner(RaceWinner::new(decode_vehicle_index(
+        cursor,
+    )?)))
 }
 
 #[cfg(test)]
@@ -106,9 +114,9 @@ mod tests {
         bytes.put_u8(2);
         bytes.put_u8(3);
         bytes.put_u8(0);
-        bytes.put_u64_le(u64::max_value());
+        bytes.put_u64_le(u64::MAX);
         bytes.put_f32_le(1.0);
-        bytes.put_u32_le(u32::max_value());
+        bytes.put_u32_le(u32::MAX);
         bytes.put_u8(0);
 
         bytes
@@ -116,8 +124,8 @@ mod tests {
 
     #[test]
     fn decode_event_with_error() {
-        let mut bytes = BytesMut::with_capacity(0);
-        let mut cursor = Cursor::new(&mut bytes);
+        let bytes = BytesMut::with_capacity(0);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_event(&mut cursor);
         assert!(packet.is_err());
@@ -135,7 +143,7 @@ mod tests {
         bytes.put_u8(1);
         bytes.put_f32_le(2.0);
 
-        let mut cursor = Cursor::new(&mut bytes);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_event(&mut cursor).unwrap();
         match packet.event() {
@@ -144,6 +152,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn decode_rtmt_event_with_invalid_vehicle_index() {
+        let bytes = BytesMut::with_capacity(PACKET_SIZE);
+        let mut bytes = put_packet_header(bytes);
+
+        bytes.put_u8(b'R');
+        bytes.put_u8(b'T');
+        bytes.put_u8(b'M');
+        bytes.put_u8(b'T');
+        bytes.put_u8(255);
+        bytes.put(vec![0u8; 4].as_slice());
+
+        let mut cursor = Cursor::new(&bytes[..]);
+
+        assert!(decode_event(&mut cursor).is_err());
+    }
+
     #[test]
     fn decode_ssta_event() {
         let bytes = BytesMut::with_capacity(PACKET_SIZE);
@@ -156,7 +181,7 @@ mod tests {
         let padding = vec![0u8; 5];
         bytes.put(padding.as_slice());
 
-        let mut cursor = Cursor::new(&mut bytes);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_event(&mut cursor).unwrap();
         assert_eq!(Event::SessionStarted, *packet.event())
@@ -1,22 +1,22 @@
 //! Decoder for flags that can be shown to cars
 
-use std::io::{Cursor, Error, ErrorKind};
+use std::io::Cursor;
 
-use bytes::{Buf, BytesMut};
+use bytes::Buf;
 
 use crate::types::Flag;
 
 /// Decode a flag that can be shown to cars
-pub fn decode_flag(cursor: &mut Cursor<&mut BytesMut>) -> Result<Flag, Error> {
+pub fn decode_flag(cursor: &mut Cursor<&[u8]>) -> Flag {
     let value = cursor.get_i8();
 
     match value {
-        -1 => Ok(Flag::Invalid),
-        0 => Ok(Flag::None),
-        1 => Ok(Flag::Green),
-        2 => Ok(Flag::Blue),
-        3 => Ok(Flag::Yellow),
-        4 => Ok(Flag::Red),
-        _ => Err(Error::new(ErrorKind::InvalidData, "Failed to decode flag.")),
+        -1 => Flag::Invalid,
+        0 => Flag::None,
+        1 => Flag::Green,
+        2 => Flag::Blue,
+        3 => Flag::Yellow,
+        4 => Flag::Red,
+        value => Flag::Unknown(value),
     }
 }
@@ -1,10 +1,12 @@
 //! Decoder for header prefixing packets sent by F1 2019
 
-use std::io::{Cursor, Error, ErrorKind};
+use std::io::Cursor;
 
 use bitflags::_core::time::Duration;
-use bytes::{Buf, BytesMut};
+use bytes::Buf;
 
+use crate::error::F1Error;
+use crate::nineteen::vehicle::decode_vehicle_index;
 use crate::packet::ensure_packet_size;
 use crate::packet::header::{ApiSpec, GameVersion, Header, PacketType};
 
@@ -17,7 +19,7 @@ pub const HEADER_SIZE: usize = 23;
 /// required to decode the package properly and information about the session the packet belongs to.
 /// The latter is extracted from the header and returned to the caller. The technical details are
 /// dropped, since their information is encoded in the type system once the packet has been decoded.
-pub fn decode_header(cursor: &mut Cursor<&mut BytesMut>) -> Result<Header, Error> {
+pub fn decode_header(cursor: &mut Cursor<&[u8]>) -> Result<Header, F1Error> {
     ensure_packet_size(HEADER_SIZE, cursor)?;
 
     let api_spec = decode_api_spec(cursor)?;
@@ -29,7 +31,7 @@ pub fn decode_header(cursor: &mut Cursor<&mut BytesMut>) -> Result<Header, Error
     let session_uid = cursor.get_u64_le();
     let session_time = Duration::from_secs_f32(cursor.get_f32_le());
     let frame_identifier = cursor.get_u32_le();
-    let player_car_index = cursor.get_u8();
+    let player_car_index = decode_vehicle_index(cursor)?;
 
     Ok(Header::new(
         api_spec,
@@ -39,26 +41,24 @@ pub fn decode_header(cursor: &mut Cursor<&mut BytesMut>) -> Result<Header, Error
         session_time,
         frame_identifier,
         player_car_index,
+        None,
     ))
 }
 
-fn decode_api_spec(cursor: &mut Cursor<&mut BytesMut>) -> Result<ApiSpec, Error> {
+fn decode_api_spec(cursor: &mut Cursor<&[u8]>) -> Result<ApiSpec, F1Error> {
     let value = cursor.get_u16_le();
 
     match value {
         2019 => Ok(ApiSpec::Nineteen),
-        format => Err(Error::new(
-            ErrorKind::InvalidData,
-            format!("Unknown API specification {}.", format),
-        )),
+        format => Err(F1Error::UnknownPacketFormat { format }),
     }
 }
 
-fn decode_game_version(cursor: &mut Cursor<&mut BytesMut>) -> Option<GameVersion> {
+fn decode_game_version(cursor: &mut Cursor<&[u8]>) -> Option<GameVersion> {
     Some(GameVersion::new(cursor.get_u8(), cursor.get_u8()))
 }
 
-fn decode_packet_type(cursor: &mut Cursor<&mut BytesMut>) -> Result<PacketType, Error> {
+fn decode_packet_type(cursor: &mut Cursor<&[u8]>) -> Result<PacketType, F1Error> {
     let value = cursor.get_u8();
 
     match value {
@@ -70,10 +70,10 @@ fn decode_packet_type(cursor: &mut Cursor<&mut BytesMut>) -> Result<PacketType,
         5 => Ok(PacketType::Setup),
         6 => Ok(PacketType::Telemetry),
         7 => Ok(PacketType::Status),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode packet id.",
-        )),
+        _ => Err(F1Error::UnknownPacketType {
+            api_spec: ApiSpec::Nineteen,
+            packet_id: value,
+        }),
     }
 }
 
@@ -83,16 +83,48 @@ mod tests {
 
     use bytes::{BufMut, BytesMut};
 
+    use crate::error::F1Error;
     use crate::nineteen::header::{decode_header, HEADER_SIZE};
     use crate::packet::header::{ApiSpec, PacketType};
 
     #[test]
     fn decode_header_with_error() {
-        let mut bytes = BytesMut::with_capacity(0);
-        let mut cursor = Cursor::new(&mut bytes);
+        let bytes = BytesMut::with_capacity(0);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_header(&mut cursor);
-        assert!(packet.is_err());
+        assert!(matches!(
+            packet,
+            Err(F1Error::IncompleteData {
+                expected: HEADER_SIZE,
+                actual: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn decode_header_with_unknown_packet_type() {
+        let mut bytes = BytesMut::with_capacity(HEADER_SIZE);
+        bytes.put_u16_le(2019);
+        bytes.put_u8(1);
+        bytes.put_u8(2);
+        bytes.put_u8(3);
+        bytes.put_u8(9);
+        bytes.put_u64_le(u64::MAX);
+        bytes.put_f32_le(1.0);
+        bytes.put_u32_le(u32::MAX);
+        bytes.put_u8(0);
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let header = decode_header(&mut cursor);
+
+        assert!(matches!(
+            header,
+            Err(F1Error::UnknownPacketType {
+                api_spec: ApiSpec::Nineteen,
+                packet_id: 9
+            })
+        ));
     }
 
     #[test]
@@ -103,21 +135,21 @@ mod tests {
         bytes.put_u8(2);
         bytes.put_u8(3);
         bytes.put_u8(0);
-        bytes.put_u64_le(u64::max_value());
+        bytes.put_u64_le(u64::MAX);
         bytes.put_f32_le(1.0);
-        bytes.put_u32_le(u32::max_value());
+        bytes.put_u32_le(u32::MAX);
         bytes.put_u8(0);
 
-        let mut cursor = Cursor::new(&mut bytes);
+        let mut cursor = Cursor::new(&bytes[..]);
         let header = decode_header(&mut cursor).unwrap();
 
         assert_eq!(ApiSpec::Nineteen, header.api_spec());
         assert_eq!(1, header.game_version().unwrap().major());
         assert_eq!(2, header.game_version().unwrap().minor());
         assert_eq!(PacketType::Motion, header.packet_type());
-        assert_eq!(u64::max_value(), header.session_uid());
+        assert_eq!(u64::MAX, header.session_uid());
         assert_eq!(1, header.session_time().as_secs());
-        assert_eq!(u32::max_value(), header.frame_identifier());
+        assert_eq!(u32::MAX, header.frame_identifier());
         assert_eq!(0, header.player_car_index());
     }
 }
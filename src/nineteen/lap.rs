@@ -3,11 +3,12 @@
 //! The lap data packets by F1 2018 and F1 2019 differ only in their packet headers, the rest of the
 //! packet format is identical.
 
-use std::io::{Cursor, Error, ErrorKind};
+use std::io::Cursor;
 use std::time::Duration;
 
-use bytes::{Buf, BytesMut};
+use bytes::Buf;
 
+use crate::error::F1Error;
 use crate::nineteen::header::decode_header;
 use crate::packet::ensure_packet_size;
 use crate::packet::lap::{DriverStatus, Lap, LapPacket, PitStatus, ResultStatus, Sector};
@@ -15,100 +16,116 @@ use crate::packet::lap::{DriverStatus, Lap, LapPacket, PitStatus, ResultStatus,
 /// Size of the lap data packet in bytes
 pub const PACKET_SIZE: usize = 843;
 
+/// Size of the lap data of a single car in bytes
+pub(crate) const ENTRY_SIZE: usize = 41;
+
 /// Decode a lap data packet sent by F1 2019
 ///
 /// F1 2018 and F1 2019 publish the same data in their lap data packets, but with different packet
 /// headers.
-pub fn decode_lap_data(cursor: &mut Cursor<&mut BytesMut>) -> Result<LapPacket, Error> {
+pub fn decode_lap_data(cursor: &mut Cursor<&[u8]>) -> Result<LapPacket, F1Error> {
+    let mut packet = LapPacket::default();
+    decode_lap_data_into(cursor, &mut packet)?;
+
+    Ok(packet)
+}
+
+/// Decode a lap data packet sent by F1 2019 into an existing packet
+///
+/// The packet is overwritten, and the vector that holds the data of the cars is reused, so that
+/// decoding into the same packet over and over again does not allocate. If decoding fails, the
+/// contents of the packet are unspecified.
+pub fn decode_lap_data_into(
+    cursor: &mut Cursor<&[u8]>,
+    packet: &mut LapPacket,
+) -> Result<(), F1Error> {
     ensure_packet_size(PACKET_SIZE, cursor)?;
 
     let header = decode_header(cursor)?;
-    let mut laps = Vec::with_capacity(20);
+    let laps = packet.laps_mut();
+    laps.clear();
+    laps.reserve(20);
 
     for _ in 0..20 {
-        laps.push(Lap::new(
-            Duration::from_secs_f32(cursor.get_f32_le()),
-            Duration::from_secs_f32(cursor.get_f32_le()),
-            Duration::from_secs_f32(cursor.get_f32_le()),
-            Duration::from_secs_f32(cursor.get_f32_le()),
-            Duration::from_secs_f32(cursor.get_f32_le()),
-            cursor.get_f32_le(),
-            cursor.get_f32_le(),
-            Duration::from_secs_f32(cursor.get_f32_le()),
-            cursor.get_u8(),
-            cursor.get_u8(),
-            decode_pit_status(cursor)?,
-            decode_sector(cursor)?,
-            cursor.get_u8() < 1,
-            cursor.get_u8(),
-            cursor.get_u8(),
-            decode_driver_status(cursor)?,
-            decode_result_status(cursor)?,
-        ));
+        laps.push(decode_lap(cursor)?);
     }
 
-    Ok(LapPacket::new(header, laps))
+    let laps = std::mem::take(packet.laps_mut());
+    *packet = LapPacket::new(header, laps);
+
+    Ok(())
+}
+
+/// Decode the lap data of a single car.
+pub(crate) fn decode_lap(cursor: &mut Cursor<&[u8]>) -> Result<Lap, F1Error> {
+    Ok(Lap::new(
+        Duration::from_secs_f32(cursor.get_f32_le()),
+        Duration::from_secs_f32(cursor.get_f32_le()),
+        Duration::from_secs_f32(cursor.get_f32_le()),
+        Duration::from_secs_f32(cursor.get_f32_le()),
+        Duration::from_secs_f32(cursor.get_f32_le()),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        Duration::from_secs_f32(cursor.get_f32_le()),
+        cursor.get_u8(),
+        cursor.get_u8(),
+        decode_pit_status(cursor),
+        decode_sector(cursor)?,
+        cursor.get_u8() < 1,
+        cursor.get_u8(),
+        cursor.get_u8(),
+        decode_driver_status(cursor),
+        decode_result_status(cursor),
+    ))
 }
 
-fn decode_sector(cursor: &mut Cursor<&mut BytesMut>) -> Result<Sector, Error> {
+fn decode_sector(cursor: &mut Cursor<&[u8]>) -> Result<Sector, F1Error> {
     let value = cursor.get_u8();
 
     match value {
         0 => Ok(Sector::First),
         1 => Ok(Sector::Second),
         2 => Ok(Sector::Third),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode sector.",
-        )),
+        _ => Err(F1Error::invalid_field("sector", value, cursor)),
     }
 }
 
-fn decode_pit_status(cursor: &mut Cursor<&mut BytesMut>) -> Result<PitStatus, Error> {
+fn decode_pit_status(cursor: &mut Cursor<&[u8]>) -> PitStatus {
     let value = cursor.get_u8();
 
     match value {
-        0 => Ok(PitStatus::None),
-        1 => Ok(PitStatus::Pitting),
-        2 => Ok(PitStatus::InPits),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode pit status.",
-        )),
+        0 => PitStatus::None,
+        1 => PitStatus::Pitting,
+        2 => PitStatus::InPits,
+        value => PitStatus::Unknown(value),
     }
 }
 
-fn decode_driver_status(cursor: &mut Cursor<&mut BytesMut>) -> Result<DriverStatus, Error> {
+fn decode_driver_status(cursor: &mut Cursor<&[u8]>) -> DriverStatus {
     let value = cursor.get_u8();
 
     match value {
-        0 => Ok(DriverStatus::InGarage),
-        1 => Ok(DriverStatus::FlyingLap),
-        2 => Ok(DriverStatus::InLap),
-        3 => Ok(DriverStatus::OutLap),
-        4 => Ok(DriverStatus::OnTrack),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode driver status.",
-        )),
+        0 => DriverStatus::InGarage,
+        1 => DriverStatus::FlyingLap,
+        2 => DriverStatus::InLap,
+        3 => DriverStatus::OutLap,
+        4 => DriverStatus::OnTrack,
+        value => DriverStatus::Unknown(value),
     }
 }
 
-fn decode_result_status(cursor: &mut Cursor<&mut BytesMut>) -> Result<ResultStatus, Error> {
+fn decode_result_status(cursor: &mut Cursor<&[u8]>) -> ResultStatus {
     let value = cursor.get_u8();
 
     match value {
-        0 => Ok(ResultStatus::Invalid),
-        1 => Ok(ResultStatus::Inactive),
-        2 => Ok(ResultStatus::Active),
-        3 => Ok(ResultStatus::Finished),
-        4 => Ok(ResultStatus::Disqualified),
-        5 => Ok(ResultStatus::NotClassified),
-        6 => Ok(ResultStatus::Retired),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode result status.",
-        )),
+        0 => ResultStatus::Invalid,
+        1 => ResultStatus::Inactive,
+        2 => ResultStatus::Active,
+        3 => ResultStatus::Finished,
+        4 => ResultStatus::Disqualified,
+        5 => ResultStatus::NotClassified,
+        6 => ResultStatus::Retired,
+        value => ResultStatus::Unknown(value),
     }
 }
 
@@ -118,6 +135,7 @@ mod tests {
 
     use bytes::{BufMut, BytesMut};
 
+    use crate::error::F1Error;
     use crate::nineteen::lap::{decode_lap_data, PACKET_SIZE};
     use crate::packet::lap::{DriverStatus, PitStatus, ResultStatus, Sector};
 
@@ -127,9 +145,9 @@ mod tests {
         bytes.put_u8(2);
         bytes.put_u8(3);
         bytes.put_u8(0);
-        bytes.put_u64_le(u64::max_value());
+        bytes.put_u64_le(u64::MAX);
         bytes.put_f32_le(1.0);
-        bytes.put_u32_le(u32::max_value());
+        bytes.put_u32_le(u32::MAX);
         bytes.put_u8(0);
 
         bytes
@@ -137,8 +155,8 @@ mod tests {
 
     #[test]
     fn decode_lap_data_with_error() {
-        let mut bytes = BytesMut::with_capacity(0);
-        let mut cursor = Cursor::new(&mut bytes);
+        let bytes = BytesMut::with_capacity(0);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_lap_data(&mut cursor);
         assert!(packet.is_err());
@@ -170,7 +188,7 @@ mod tests {
         let padding = vec![0u8; 779];
         bytes.put(padding.as_slice());
 
-        let mut cursor = Cursor::new(&mut bytes);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_lap_data(&mut cursor).unwrap();
         let lap = packet.laps()[0];
@@ -193,4 +211,61 @@ mod tests {
         assert_eq!(DriverStatus::FlyingLap, lap.driver_status());
         assert_eq!(ResultStatus::Active, lap.result_status())
     }
+
+    #[test]
+    fn decode_lap_data_with_unknown_statuses() {
+        let mut bytes = BytesMut::with_capacity(PACKET_SIZE);
+        bytes = put_packet_header(bytes);
+
+        let times = vec![0u8; 32];
+        bytes.put(times.as_slice());
+        bytes.put_u8(1);
+        bytes.put_u8(4);
+        bytes.put_u8(7);
+        bytes.put_u8(0);
+        bytes.put_u8(0);
+        bytes.put_u8(0);
+        bytes.put_u8(3);
+        bytes.put_u8(8);
+        bytes.put_u8(9);
+
+        let padding = vec![0u8; 779];
+        bytes.put(padding.as_slice());
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let packet = decode_lap_data(&mut cursor).unwrap();
+        let lap = &packet.laps()[0];
+
+        assert_eq!(PitStatus::Unknown(7), lap.pit_status());
+        assert_eq!(DriverStatus::Unknown(8), lap.driver_status());
+        assert_eq!(ResultStatus::Unknown(9), lap.result_status());
+    }
+
+    #[test]
+    fn decode_lap_data_with_invalid_sector() {
+        let mut bytes = BytesMut::with_capacity(PACKET_SIZE);
+        bytes = put_packet_header(bytes);
+
+        let times = vec![0u8; 32];
+        bytes.put(times.as_slice());
+        bytes.put_u8(1);
+        bytes.put_u8(4);
+        bytes.put_u8(0);
+        bytes.put_u8(3);
+
+        let padding = vec![0u8; 784];
+        bytes.put(padding.as_slice());
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let packet = decode_lap_data(&mut cursor);
+
+        assert!(matches!(
+            packet,
+            Err(F1Error::InvalidField {
+                field: "sector",
+                value: 3,
+                offset: 58
+            })
+        ));
+    }
 }
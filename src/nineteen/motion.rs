@@ -3,42 +3,57 @@
 //! The motion packets by F1 2018 and F1 2019 differ only in their packet headers, the rest of the
 //! packet format is identical.
 
-use std::io::{Cursor, Error};
+use std::io::Cursor;
 
-use bytes::{Buf, BytesMut};
+use bytes::Buf;
 
+use crate::error::F1Error;
+use crate::nineteen::corner::decode_corners;
 use crate::nineteen::header::decode_header;
+use crate::nineteen::MOTION_PACKET_SIZE;
 use crate::packet::ensure_packet_size;
 use crate::packet::motion::{Motion, MotionPacket};
 use crate::types::{CornerProperty, Property3D};
 
 /// Size of the motion packet in bytes
-pub const PACKET_SIZE: usize = 1343;
+pub const PACKET_SIZE: usize = MOTION_PACKET_SIZE;
+
+/// Size of the motion data of a single car in bytes
+pub(crate) const ENTRY_SIZE: usize = 60;
 
 /// Decode a motion packet sent by F1 2019
 ///
 /// F1 2018 and F1 2019 publish the same data in their motion packets, but with different packet
 /// headers.
-pub fn decode_motion(cursor: &mut Cursor<&mut BytesMut>) -> Result<MotionPacket, Error> {
+pub fn decode_motion(cursor: &mut Cursor<&[u8]>) -> Result<MotionPacket, F1Error> {
+    let mut packet = MotionPacket::default();
+    decode_motion_into(cursor, &mut packet)?;
+
+    Ok(packet)
+}
+
+/// Decode a motion packet sent by F1 2019 into an existing packet
+///
+/// The packet is overwritten, and the vector that holds the data of the cars is reused, so that
+/// decoding into the same packet over and over again does not allocate. If decoding fails, the
+/// contents of the packet are unspecified.
+pub fn decode_motion_into(
+    cursor: &mut Cursor<&[u8]>,
+    packet: &mut MotionPacket,
+) -> Result<(), F1Error> {
     ensure_packet_size(PACKET_SIZE, cursor)?;
 
     let header = decode_header(cursor)?;
-    let mut cars = Vec::with_capacity(20);
+    let cars = packet.cars_mut();
+    cars.clear();
+    cars.reserve(20);
 
     for _ in 0..20 {
-        cars.push(Motion::new(
-            decode_position(cursor),
-            decode_velocity(cursor),
-            decode_forward_direction(cursor),
-            decode_right_direction(cursor),
-            decode_g_force(cursor),
-            cursor.get_f32_le(),
-            cursor.get_f32_le(),
-            cursor.get_f32_le(),
-        ))
+        cars.push(decode_car_motion(cursor)?);
     }
 
-    Ok(MotionPacket::new(
+    let cars = std::mem::take(packet.cars_mut());
+    *packet = MotionPacket::new(
         header,
         cars,
         decode_suspension_position(cursor),
@@ -50,11 +65,27 @@ pub fn decode_motion(cursor: &mut Cursor<&mut BytesMut>) -> Result<MotionPacket,
         decode_angular_velocity(cursor),
         decode_angular_acceleration(cursor),
         cursor.get_f32_le(),
+    );
+
+    Ok(())
+}
+
+/// Decode the motion of a single car.
+pub(crate) fn decode_car_motion(cursor: &mut Cursor<&[u8]>) -> Result<Motion, F1Error> {
+    Ok(Motion::new(
+        decode_position(cursor),
+        decode_velocity(cursor),
+        decode_forward_direction(cursor),
+        decode_right_direction(cursor),
+        decode_g_force(cursor),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
     ))
 }
 
 /// Decode position of the car
-fn decode_position(cursor: &mut Cursor<&mut BytesMut>) -> Property3D<f32> {
+fn decode_position(cursor: &mut Cursor<&[u8]>) -> Property3D<f32> {
     Property3D::new(
         cursor.get_f32_le(),
         cursor.get_f32_le(),
@@ -63,7 +94,7 @@ fn decode_position(cursor: &mut Cursor<&mut BytesMut>) -> Property3D<f32> {
 }
 
 /// Decode velocity of the car
-fn decode_velocity(cursor: &mut Cursor<&mut BytesMut>) -> Property3D<f32> {
+fn decode_velocity(cursor: &mut Cursor<&[u8]>) -> Property3D<f32> {
     Property3D::new(
         cursor.get_f32_le(),
         cursor.get_f32_le(),
@@ -72,7 +103,7 @@ fn decode_velocity(cursor: &mut Cursor<&mut BytesMut>) -> Property3D<f32> {
 }
 
 /// Decode forward direction of the car
-fn decode_forward_direction(cursor: &mut Cursor<&mut BytesMut>) -> Property3D<i16> {
+fn decode_forward_direction(cursor: &mut Cursor<&[u8]>) -> Property3D<i16> {
     Property3D::new(
         cursor.get_i16_le(),
         cursor.get_i16_le(),
@@ -81,7 +112,7 @@ fn decode_forward_direction(cursor: &mut Cursor<&mut BytesMut>) -> Property3D<i1
 }
 
 /// Decode right direction of the car
-fn decode_right_direction(cursor: &mut Cursor<&mut BytesMut>) -> Property3D<i16> {
+fn decode_right_direction(cursor: &mut Cursor<&[u8]>) -> Property3D<i16> {
     Property3D::new(
         cursor.get_i16_le(),
         cursor.get_i16_le(),
@@ -90,7 +121,7 @@ fn decode_right_direction(cursor: &mut Cursor<&mut BytesMut>) -> Property3D<i16>
 }
 
 /// Decode G forces on the car
-fn decode_g_force(cursor: &mut Cursor<&mut BytesMut>) -> Property3D<f32> {
+fn decode_g_force(cursor: &mut Cursor<&[u8]>) -> Property3D<f32> {
     Property3D::new(
         cursor.get_f32_le(),
         cursor.get_f32_le(),
@@ -99,57 +130,32 @@ fn decode_g_force(cursor: &mut Cursor<&mut BytesMut>) -> Property3D<f32> {
 }
 
 /// Decode suspension position of the player's car
-fn decode_suspension_position(cursor: &mut Cursor<&mut BytesMut>) -> CornerProperty<f32> {
-    CornerProperty::new(
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-    )
+fn decode_suspension_position(cursor: &mut Cursor<&[u8]>) -> CornerProperty<f32> {
+    decode_corners(cursor, |cursor| cursor.get_f32_le())
 }
 
 /// Decode suspension velocity of the player's car
-fn decode_suspension_velocity(cursor: &mut Cursor<&mut BytesMut>) -> CornerProperty<f32> {
-    CornerProperty::new(
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-    )
+fn decode_suspension_velocity(cursor: &mut Cursor<&[u8]>) -> CornerProperty<f32> {
+    decode_corners(cursor, |cursor| cursor.get_f32_le())
 }
 
 /// Decode suspension acceleration of the player's car
-fn decode_suspension_acceleration(cursor: &mut Cursor<&mut BytesMut>) -> CornerProperty<f32> {
-    CornerProperty::new(
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-    )
+fn decode_suspension_acceleration(cursor: &mut Cursor<&[u8]>) -> CornerProperty<f32> {
+    decode_corners(cursor, |cursor| cursor.get_f32_le())
 }
 
 /// Decode the wheel speed of the player's car
-fn decode_wheel_speed(cursor: &mut Cursor<&mut BytesMut>) -> CornerProperty<f32> {
-    CornerProperty::new(
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-    )
+fn decode_wheel_speed(cursor: &mut Cursor<&[u8]>) -> CornerProperty<f32> {
+    decode_corners(cursor, |cursor| cursor.get_f32_le())
 }
 
 /// Decode the wheel slip of the player's car
-fn decode_wheel_slip(cursor: &mut Cursor<&mut BytesMut>) -> CornerProperty<f32> {
-    CornerProperty::new(
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-    )
+fn decode_wheel_slip(cursor: &mut Cursor<&[u8]>) -> CornerProperty<f32> {
+    decode_corners(cursor, |cursor| cursor.get_f32_le())
 }
 
 /// Decode the local velocity of the player's car
-fn decode_local_velocity(cursor: &mut Cursor<&mut BytesMut>) -> Property3D<f32> {
+fn decode_local_velocity(cursor: &mut Cursor<&[u8]>) -> Property3D<f32> {
     Property3D::new(
         cursor.get_f32_le(),
         cursor.get_f32_le(),
@@ -158,7 +164,7 @@ fn decode_local_velocity(cursor: &mut Cursor<&mut BytesMut>) -> Property3D<f32>
 }
 
 /// Decode the angular velocity of the player's car
-fn decode_angular_velocity(cursor: &mut Cursor<&mut BytesMut>) -> Property3D<f32> {
+fn decode_angular_velocity(cursor: &mut Cursor<&[u8]>) -> Property3D<f32> {
     Property3D::new(
         cursor.get_f32_le(),
         cursor.get_f32_le(),
@@ -166,7 +172,7 @@ fn decode_angular_velocity(cursor: &mut Cursor<&mut BytesMut>) -> Property3D<f32
     )
 }
 /// Decode the angular acceleration of the player's car
-fn decode_angular_acceleration(cursor: &mut Cursor<&mut BytesMut>) -> Property3D<f32> {
+fn decode_angular_acceleration(cursor: &mut Cursor<&[u8]>) -> Property3D<f32> {
     Property3D::new(
         cursor.get_f32_le(),
         cursor.get_f32_le(),
@@ -189,9 +195,9 @@ mod tests {
         bytes.put_u8(2);
         bytes.put_u8(3);
         bytes.put_u8(0);
-        bytes.put_u64_le(u64::max_value());
+        bytes.put_u64_le(u64::MAX);
         bytes.put_f32_le(1.0);
-        bytes.put_u32_le(u32::max_value());
+        bytes.put_u32_le(u32::MAX);
         bytes.put_u8(0);
 
         bytes
@@ -199,8 +205,8 @@ mod tests {
 
     #[test]
     fn decode_motion_with_error() {
-        let mut bytes = BytesMut::with_capacity(0);
-        let mut cursor = Cursor::new(&mut bytes);
+        let bytes = BytesMut::with_capacity(0);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_motion(&mut cursor);
         assert!(packet.is_err());
@@ -264,7 +270,7 @@ mod tests {
         bytes.put_f32_le(47.0);
         bytes.put_f32_le(48.0);
 
-        let mut cursor = Cursor::new(&mut bytes);
+        let mut cursor = Cursor::new(&bytes[..]);
         let packet = decode_motion(&mut cursor).unwrap();
 
         let motion = packet.cars()[0];
@@ -276,11 +282,12 @@ mod tests {
         assert_approx_eq!(16.0, motion.yaw());
         assert_approx_eq!(17.0, motion.pitch());
         assert_approx_eq!(18.0, motion.roll());
-        assert_approx_eq!(19.0, packet.suspension_position().front_left());
-        assert_approx_eq!(23.0, packet.suspension_velocity().front_left());
-        assert_approx_eq!(27.0, packet.suspension_acceleration().front_left());
-        assert_approx_eq!(31.0, packet.wheel_speed().front_left());
-        assert_approx_eq!(35.0, packet.wheel_slip().front_left());
+        assert_approx_eq!(19.0, packet.suspension_position().rear_left());
+        assert_approx_eq!(21.0, packet.suspension_position().front_left());
+        assert_approx_eq!(23.0, packet.suspension_velocity().rear_left());
+        assert_approx_eq!(27.0, packet.suspension_acceleration().rear_left());
+        assert_approx_eq!(31.0, packet.wheel_speed().rear_left());
+        assert_approx_eq!(35.0, packet.wheel_slip().rear_left());
         assert_approx_eq!(39.0, packet.local_velocity().x());
         assert_approx_eq!(42.0, packet.angular_velocity().x());
         assert_approx_eq!(45.0, packet.angular_acceleration().x());
@@ -3,11 +3,13 @@
 //! F1 2019 extends the participants packet from F1 2018 with the `telemetry_privacy` field. Other
 //! than that both games use the same packet format.
 
-use std::io::{Cursor, Error, ErrorKind};
+use std::borrow::Cow;
+use std::io::Cursor;
 
-use bytes::{Buf, BytesMut};
+use bytes::Buf;
 
-use crate::nineteen::header::decode_header;
+use crate::error::F1Error;
+use crate::nineteen::header::{decode_header, HEADER_SIZE};
 use crate::packet::ensure_packet_size;
 use crate::packet::participants::{
     Controller, Driver, Nationality, Participant, ParticipantsPacket, Team, TelemetryPrivacy,
@@ -16,328 +18,373 @@ use crate::packet::participants::{
 /// Size of the participants packet.
 pub const PACKET_SIZE: usize = 1104;
 
+/// Offset of the data of the first participant, after the number of active participants
+pub(crate) const ENTRIES_OFFSET: usize = HEADER_SIZE + 1;
+
+/// Size of the data of a single participant in bytes
+pub(crate) const ENTRY_SIZE: usize = 54;
+
+/// Offset of the name in the data of a participant
+pub(crate) const NAME_OFFSET: usize = 5;
+
+/// Size of the name of a participant in bytes
+pub(crate) const NAME_SIZE: usize = 48;
+
 /// Decode a participants packet sent by F1 2019
 ///
 /// F1 2019 extends the participants packet from F1 2018 with the `telemetry_privacy` field. Other
 /// than that both games use the same packet format.
-pub fn decode_participants(
-    cursor: &mut Cursor<&mut BytesMut>,
-) -> Result<ParticipantsPacket, Error> {
+pub fn decode_participants(cursor: &mut Cursor<&[u8]>) -> Result<ParticipantsPacket, F1Error> {
+    let mut packet = ParticipantsPacket::default();
+    decode_participants_into(cursor, &mut packet)?;
+
+    Ok(packet)
+}
+
+/// Decode a participants packet sent by F1 2019 into an existing packet
+///
+/// The packet is overwritten, and the vector of participants and the strings that hold their names
+/// are reused, so that decoding into the same packet over and over again does not allocate. If
+/// decoding fails, the contents of the packet are unspecified.
+pub fn decode_participants_into(
+    cursor: &mut Cursor<&[u8]>,
+    packet: &mut ParticipantsPacket,
+) -> Result<(), F1Error> {
     ensure_packet_size(PACKET_SIZE, cursor)?;
 
     let header = decode_header(cursor)?;
     let active_participants_count = cursor.get_u8();
 
-    let mut participants = Vec::with_capacity(20);
-
-    for _ in 0..20 {
-        participants.push(Participant::new(
-            decode_controller(cursor)?,
-            decode_driver(cursor)?,
-            decode_team(cursor)?,
-            cursor.get_u8(),
-            decode_nationality(cursor)?,
-            decode_name(cursor),
-            decode_telemetry_privacy(cursor)?,
-        ))
+    let participants = packet.participants_mut();
+    participants.resize_with(20, Participant::default);
+
+    for participant in participants.iter_mut() {
+        decode_participant_into(cursor, participant)?;
     }
 
-    Ok(ParticipantsPacket::new(
-        header,
-        active_participants_count,
-        participants,
-    ))
+    let participants = std::mem::take(packet.participants_mut());
+    *packet = ParticipantsPacket::new(header, active_participants_count, participants);
+
+    Ok(())
 }
 
-fn decode_controller(cursor: &mut Cursor<&mut BytesMut>) -> Result<Controller, Error> {
+/// Decode the participant data of a single car.
+pub(crate) fn decode_participant(cursor: &mut Cursor<&[u8]>) -> Result<Participant, F1Error> {
+    let mut participant = Participant::default();
+    decode_participant_into(cursor, &mut participant)?;
+
+    Ok(participant)
+}
+
+/// Decode the participant data of a single car, and reuse the string that holds the name.
+fn decode_participant_into(
+    cursor: &mut Cursor<&[u8]>,
+    participant: &mut Participant,
+) -> Result<(), F1Error> {
+    let controller = decode_controller(cursor);
+    let driver = decode_driver(cursor);
+    let team = decode_team(cursor);
+    let race_number = cursor.get_u8();
+    let nationality = decode_nationality(cursor);
+
+    let mut name = std::mem::take(participant.name_mut());
+    decode_name_into(cursor, &mut name);
+
+    *participant = Participant::new(
+        controller,
+        driver,
+        team,
+        race_number,
+        nationality,
+        name,
+        decode_telemetry_privacy(cursor),
+    );
+
+    Ok(())
+}
+
+fn decode_controller(cursor: &mut Cursor<&[u8]>) -> Controller {
     let value = cursor.get_u8();
 
     match value {
-        0 => Ok(Controller::Human),
-        1 => Ok(Controller::AI),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode controller.",
-        )),
+        0 => Controller::Human,
+        1 => Controller::AI,
+        value => Controller::Unknown(value),
     }
 }
 
-fn decode_driver(cursor: &mut Cursor<&mut BytesMut>) -> Result<Driver, Error> {
+fn decode_driver(cursor: &mut Cursor<&[u8]>) -> Driver {
     let value = cursor.get_u8();
 
     match value {
-        0 => Ok(Driver::CarlosSainz),
-        1 => Ok(Driver::DaniilKvyat),
-        2 => Ok(Driver::DanielRicciardo),
-        6 => Ok(Driver::KimiRaikkonen),
-        7 => Ok(Driver::LewisHamilton),
-        9 => Ok(Driver::MaxVerstappen),
-        10 => Ok(Driver::NicoHulkenburg),
-        11 => Ok(Driver::KevinMagnussen),
-        12 => Ok(Driver::RomainGrosjean),
-        13 => Ok(Driver::SebastianVettel),
-        14 => Ok(Driver::SergioPerez),
-        15 => Ok(Driver::ValtteriBottas),
-        19 => Ok(Driver::LanceStroll),
-        20 => Ok(Driver::ArronBarnes),
-        21 => Ok(Driver::MartinGiles),
-        22 => Ok(Driver::AlexMurray),
-        23 => Ok(Driver::LucasRoth),
-        24 => Ok(Driver::IgorCorreia),
-        25 => Ok(Driver::SophieLevasseur),
-        26 => Ok(Driver::JonasSchiffer),
-        27 => Ok(Driver::AlainForest),
-        28 => Ok(Driver::JayLetourneau),
-        29 => Ok(Driver::EstoSaari),
-        30 => Ok(Driver::YasarAtiyeh),
-        31 => Ok(Driver::CallistoCalabresi),
-        32 => Ok(Driver::NaotaIzum),
-        33 => Ok(Driver::HowardClarke),
-        34 => Ok(Driver::WilheimKaufmann),
-        35 => Ok(Driver::MarieLaursen),
-        36 => Ok(Driver::FlavioNieves),
-        37 => Ok(Driver::PeterBelousov),
-        38 => Ok(Driver::KlimekMichalski),
-        39 => Ok(Driver::SantiagoMoreno),
-        40 => Ok(Driver::BenjaminCoppens),
-        41 => Ok(Driver::NoahVisser),
-        42 => Ok(Driver::GertWaldmuller),
-        43 => Ok(Driver::JulianQuesada),
-        44 => Ok(Driver::DanielJones),
-        45 => Ok(Driver::ArtemMarkelov),
-        46 => Ok(Driver::TadasukeMakino),
-        47 => Ok(Driver::SeanGelael),
-        48 => Ok(Driver::NyckDeVries),
-        49 => Ok(Driver::JackAitken),
-        50 => Ok(Driver::GeorgeRussell),
-        51 => Ok(Driver::MaximilianGunther),
-        52 => Ok(Driver::NireiFukuzumi),
-        53 => Ok(Driver::LucaGhiotto),
-        54 => Ok(Driver::LandoNorris),
-        55 => Ok(Driver::SergioSetteCamara),
-        56 => Ok(Driver::LouisDeletraz),
-        57 => Ok(Driver::AntonioFuoco),
-        58 => Ok(Driver::CharlesLeclerc),
-        59 => Ok(Driver::PierreGasly),
-        62 => Ok(Driver::AlexanderAlbon),
-        63 => Ok(Driver::NicholasLatifi),
-        64 => Ok(Driver::DorianBoccolacci),
-        65 => Ok(Driver::NikoKari),
-        66 => Ok(Driver::RobertoMerhi),
-        67 => Ok(Driver::ArjunMaini),
-        68 => Ok(Driver::AlessioLorandi),
-        69 => Ok(Driver::RubenMeijer),
-        70 => Ok(Driver::RashidNair),
-        71 => Ok(Driver::JackTremblay),
-        74 => Ok(Driver::AntonioGiovinazzi),
-        75 => Ok(Driver::RobertKubica),
-        78 => Ok(Driver::NobuharuMatsushita),
-        79 => Ok(Driver::NikitaMazepin),
-        80 => Ok(Driver::GuanyaZhou),
-        81 => Ok(Driver::MickSchumacher),
-        82 => Ok(Driver::CallumIlott),
-        83 => Ok(Driver::JuanManuelCorrea),
-        84 => Ok(Driver::JordanKing),
-        85 => Ok(Driver::MahaveerRaghunathan),
-        86 => Ok(Driver::TatianaCalderon),
-        87 => Ok(Driver::AnthoineHubert),
-        88 => Ok(Driver::GuilianoAlesi),
-        89 => Ok(Driver::RalphBoschung),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode driver.",
-        )),
+        0 => Driver::CarlosSainz,
+        1 => Driver::DaniilKvyat,
+        2 => Driver::DanielRicciardo,
+        6 => Driver::KimiRaikkonen,
+        7 => Driver::LewisHamilton,
+        9 => Driver::MaxVerstappen,
+        10 => Driver::NicoHulkenburg,
+        11 => Driver::KevinMagnussen,
+        12 => Driver::RomainGrosjean,
+        13 => Driver::SebastianVettel,
+        14 => Driver::SergioPerez,
+        15 => Driver::ValtteriBottas,
+        19 => Driver::LanceStroll,
+        20 => Driver::ArronBarnes,
+        21 => Driver::MartinGiles,
+        22 => Driver::AlexMurray,
+        23 => Driver::LucasRoth,
+        24 => Driver::IgorCorreia,
+        25 => Driver::SophieLevasseur,
+        26 => Driver::JonasSchiffer,
+        27 => Driver::AlainForest,
+        28 => Driver::JayLetourneau,
+        29 => Driver::EstoSaari,
+        30 => Driver::YasarAtiyeh,
+        31 => Driver::CallistoCalabresi,
+        32 => Driver::NaotaIzum,
+        33 => Driver::HowardClarke,
+        34 => Driver::WilheimKaufmann,
+        35 => Driver::MarieLaursen,
+        36 => Driver::FlavioNieves,
+        37 => Driver::PeterBelousov,
+        38 => Driver::KlimekMichalski,
+        39 => Driver::SantiagoMoreno,
+        40 => Driver::BenjaminCoppens,
+        41 => Driver::NoahVisser,
+        42 => Driver::GertWaldmuller,
+        43 => Driver::JulianQuesada,
+        44 => Driver::DanielJones,
+        45 => Driver::ArtemMarkelov,
+        46 => Driver::TadasukeMakino,
+        47 => Driver::SeanGelael,
+        48 => Driver::NyckDeVries,
+        49 => Driver::JackAitken,
+        50 => Driver::GeorgeRussell,
+        51 => Driver::MaximilianGunther,
+        52 => Driver::NireiFukuzumi,
+        53 => Driver::LucaGhiotto,
+        54 => Driver::LandoNorris,
+        55 => Driver::SergioSetteCamara,
+        56 => Driver::LouisDeletraz,
+        57 => Driver::AntonioFuoco,
+        58 => Driver::CharlesLeclerc,
+        59 => Driver::PierreGasly,
+        62 => Driver::AlexanderAlbon,
+        63 => Driver::NicholasLatifi,
+        64 => Driver::DorianBoccolacci,
+        65 => Driver::NikoKari,
+        66 => Driver::RobertoMerhi,
+        67 => Driver::ArjunMaini,
+        68 => Driver::AlessioLorandi,
+        69 => Driver::RubenMeijer,
+        70 => Driver::RashidNair,
+        71 => Driver::JackTremblay,
+        74 => Driver::AntonioGiovinazzi,
+        75 => Driver::RobertKubica,
+        78 => Driver::NobuharuMatsushita,
+        79 => Driver::NikitaMazepin,
+        80 => Driver::GuanyaZhou,
+        81 => Driver::MickSchumacher,
+        82 => Driver::CallumIlott,
+        83 => Driver::JuanManuelCorrea,
+        84 => Driver::JordanKing,
+        85 => Driver::MahaveerRaghunathan,
+        86 => Driver::TatianaCalderon,
+        87 => Driver::AnthoineHubert,
+        88 => Driver::GuilianoAlesi,
+        89 => Driver::RalphBoschung,
+        // Human players in online and LAN sessions are assigned identifiers from 100 upwards.
+        100..=u8::MAX => Driver::Human,
+        value => Driver::Unknown(value),
     }
 }
 
-fn decode_team(cursor: &mut Cursor<&mut BytesMut>) -> Result<Team, Error> {
+fn decode_team(cursor: &mut Cursor<&[u8]>) -> Team {
     let value = cursor.get_u8();
 
     match value {
-        0 => Ok(Team::Mercedes),
-        1 => Ok(Team::Ferrari),
-        2 => Ok(Team::RedBullRacing),
-        3 => Ok(Team::Williams),
-        4 => Ok(Team::RacingPoint),
-        5 => Ok(Team::Renault),
-        6 => Ok(Team::ToroRosso),
-        7 => Ok(Team::Haas),
-        8 => Ok(Team::McLaren),
-        9 => Ok(Team::AlfaRomeo),
-        10 => Ok(Team::McLaren1988),
-        11 => Ok(Team::McLaren1991),
-        12 => Ok(Team::Williams1992),
-        13 => Ok(Team::Ferrari1995),
-        14 => Ok(Team::Williams1996),
-        15 => Ok(Team::McLaren1998),
-        16 => Ok(Team::Ferrari2002),
-        17 => Ok(Team::Ferrari2004),
-        18 => Ok(Team::Renault2006),
-        19 => Ok(Team::Ferrari2007),
-        21 => Ok(Team::RedBull2010),
-        22 => Ok(Team::Ferrari1976),
-        23 => Ok(Team::ARTGrandPrix),
-        24 => Ok(Team::CamposVexatecRacing),
-        25 => Ok(Team::Carlin),
-        26 => Ok(Team::CharouzRacingSystem),
-        27 => Ok(Team::DAMS),
-        28 => Ok(Team::RussianTime),
-        29 => Ok(Team::MPMotorsport),
-        30 => Ok(Team::Pertamina),
-        31 => Ok(Team::McLaren1990),
-        32 => Ok(Team::Trident),
-        33 => Ok(Team::BWTArden),
-        34 => Ok(Team::McLaren1976),
-        35 => Ok(Team::Lotus1972),
-        36 => Ok(Team::Ferrari1979),
-        37 => Ok(Team::McLaren1982),
-        38 => Ok(Team::Williams2003),
-        39 => Ok(Team::Brawn2009),
-        40 => Ok(Team::Lotus1978),
-        42 => Ok(Team::ArtGP2019),
-        43 => Ok(Team::Campos2019),
-        44 => Ok(Team::Carlin2019),
-        45 => Ok(Team::SauberJuniorCharouz2019),
-        46 => Ok(Team::Dams2019),
-        47 => Ok(Team::UniVirtuosi2019),
-        48 => Ok(Team::MPMotorsport2019),
-        49 => Ok(Team::Prema2019),
-        50 => Ok(Team::Trident2019),
-        51 => Ok(Team::Arden2019),
-        63 => Ok(Team::Ferrari1990),
-        64 => Ok(Team::McLaren2010),
-        65 => Ok(Team::Ferrari2010),
-        _ => Err(Error::new(ErrorKind::InvalidData, "Failed to decode team.")),
+        0 => Team::Mercedes,
+        1 => Team::Ferrari,
+        2 => Team::RedBullRacing,
+        3 => Team::Williams,
+        4 => Team::RacingPoint,
+        5 => Team::Renault,
+        6 => Team::ToroRosso,
+        7 => Team::Haas,
+        8 => Team::McLaren,
+        9 => Team::AlfaRomeo,
+        10 => Team::McLaren1988,
+        11 => Team::McLaren1991,
+        12 => Team::Williams1992,
+        13 => Team::Ferrari1995,
+        14 => Team::Williams1996,
+        15 => Team::McLaren1998,
+        16 => Team::Ferrari2002,
+        17 => Team::Ferrari2004,
+        18 => Team::Renault2006,
+        19 => Team::Ferrari2007,
+        21 => Team::RedBull2010,
+        22 => Team::Ferrari1976,
+        23 => Team::ARTGrandPrix,
+        24 => Team::CamposVexatecRacing,
+        25 => Team::Carlin,
+        26 => Team::CharouzRacingSystem,
+        27 => Team::DAMS,
+        28 => Team::RussianTime,
+        29 => Team::MPMotorsport,
+        30 => Team::Pertamina,
+        31 => Team::McLaren1990,
+        32 => Team::Trident,
+        33 => Team::BWTArden,
+        34 => Team::McLaren1976,
+        35 => Team::Lotus1972,
+        36 => Team::Ferrari1979,
+        37 => Team::McLaren1982,
+        38 => Team::Williams2003,
+        39 => Team::Brawn2009,
+        40 => Team::Lotus1978,
+        42 => Team::ArtGP2019,
+        43 => Team::Campos2019,
+        44 => Team::Carlin2019,
+        45 => Team::SauberJuniorCharouz2019,
+        46 => Team::Dams2019,
+        47 => Team::UniVirtuosi2019,
+        48 => Team::MPMotorsport2019,
+        49 => Team::Prema2019,
+        50 => Team::Trident2019,
+        51 => Team::Arden2019,
+        63 => Team::Ferrari1990,
+        64 => Team::McLaren2010,
+        65 => Team::Ferrari2010,
+        value => Team::Unknown(value),
     }
 }
 
-fn decode_nationality(cursor: &mut Cursor<&mut BytesMut>) -> Result<Nationality, Error> {
+fn decode_nationality(cursor: &mut Cursor<&[u8]>) -> Nationality {
     let value = cursor.get_u8();
 
     match value {
-        1 => Ok(Nationality::American),
-        2 => Ok(Nationality::Argentinean),
-        3 => Ok(Nationality::Australian),
-        4 => Ok(Nationality::Austrian),
-        5 => Ok(Nationality::Azerbaijani),
-        6 => Ok(Nationality::Bahraini),
-        7 => Ok(Nationality::Belgian),
-        8 => Ok(Nationality::Bolivian),
-        9 => Ok(Nationality::Brazilian),
-        10 => Ok(Nationality::British),
-        11 => Ok(Nationality::Bulgarian),
-        12 => Ok(Nationality::Cameroonian),
-        13 => Ok(Nationality::Canadian),
-        14 => Ok(Nationality::Chilean),
-        15 => Ok(Nationality::Chinese),
-        16 => Ok(Nationality::Colombian),
-        17 => Ok(Nationality::CostaRican),
-        18 => Ok(Nationality::Croatian),
-        19 => Ok(Nationality::Cypriot),
-        20 => Ok(Nationality::Czech),
-        21 => Ok(Nationality::Danish),
-        22 => Ok(Nationality::Dutch),
-        23 => Ok(Nationality::Ecuadorian),
-        24 => Ok(Nationality::English),
-        25 => Ok(Nationality::Emirian),
-        26 => Ok(Nationality::Estonian),
-        27 => Ok(Nationality::Finnish),
-        28 => Ok(Nationality::French),
-        29 => Ok(Nationality::German),
-        30 => Ok(Nationality::Ghanaian),
-        31 => Ok(Nationality::Greek),
-        32 => Ok(Nationality::Guatemalan),
-        33 => Ok(Nationality::Honduran),
-        34 => Ok(Nationality::HongKonger),
-        35 => Ok(Nationality::Hungarian),
-        36 => Ok(Nationality::Icelander),
-        37 => Ok(Nationality::Indian),
-        38 => Ok(Nationality::Indonesian),
-        39 => Ok(Nationality::Irish),
-        40 => Ok(Nationality::Israeli),
-        41 => Ok(Nationality::Italian),
-        42 => Ok(Nationality::Jamaican),
-        43 => Ok(Nationality::Japanese),
-        44 => Ok(Nationality::Jordanian),
-        45 => Ok(Nationality::Kuwaiti),
-        46 => Ok(Nationality::Latvian),
-        47 => Ok(Nationality::Lebanese),
-        48 => Ok(Nationality::Lithuanian),
-        49 => Ok(Nationality::Luxembourger),
-        50 => Ok(Nationality::Malaysian),
-        51 => Ok(Nationality::Maltese),
-        52 => Ok(Nationality::Mexican),
-        53 => Ok(Nationality::Monegasque),
-        54 => Ok(Nationality::NewZealander),
-        55 => Ok(Nationality::Nicaraguan),
-        56 => Ok(Nationality::NorthKorean),
-        57 => Ok(Nationality::NorthernIrish),
-        58 => Ok(Nationality::Norwegian),
-        59 => Ok(Nationality::Omani),
-        60 => Ok(Nationality::Pakistani),
-        61 => Ok(Nationality::Panamanian),
-        62 => Ok(Nationality::Paraguayan),
-        63 => Ok(Nationality::Peruvian),
-        64 => Ok(Nationality::Polish),
-        65 => Ok(Nationality::Portuguese),
-        66 => Ok(Nationality::Qatari),
-        67 => Ok(Nationality::Romanian),
-        68 => Ok(Nationality::Russian),
-        69 => Ok(Nationality::Salvadoran),
-        70 => Ok(Nationality::Saudi),
-        71 => Ok(Nationality::Scottish),
-        72 => Ok(Nationality::Serbian),
-        73 => Ok(Nationality::Singaporean),
-        74 => Ok(Nationality::Slovakian),
-        75 => Ok(Nationality::Slovenian),
-        76 => Ok(Nationality::SouthKorean),
-        77 => Ok(Nationality::SouthAfrican),
-        78 => Ok(Nationality::Spanish),
-        79 => Ok(Nationality::Swedish),
-        80 => Ok(Nationality::Swiss),
-        81 => Ok(Nationality::Thai),
-        82 => Ok(Nationality::Turkish),
-        83 => Ok(Nationality::Uruguayan),
-        84 => Ok(Nationality::Ukrainian),
-        85 => Ok(Nationality::Venezuelan),
-        86 => Ok(Nationality::Welsh),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode nationality.",
-        )),
+        1 => Nationality::American,
+        2 => Nationality::Argentinean,
+        3 => Nationality::Australian,
+        4 => Nationality::Austrian,
+        5 => Nationality::Azerbaijani,
+        6 => Nationality::Bahraini,
+        7 => Nationality::Belgian,
+        8 => Nationality::Bolivian,
+        9 => Nationality::Brazilian,
+        10 => Nationality::British,
+        11 => Nationality::Bulgarian,
+        12 => Nationality::Cameroonian,
+        13 => Nationality::Canadian,
+        14 => Nationality::Chilean,
+        15 => Nationality::Chinese,
+        16 => Nationality::Colombian,
+        17 => Nationality::CostaRican,
+        18 => Nationality::Croatian,
+        19 => Nationality::Cypriot,
+        20 => Nationality::Czech,
+        21 => Nationality::Danish,
+        22 => Nationality::Dutch,
+        23 => Nationality::Ecuadorian,
+        24 => Nationality::English,
+        25 => Nationality::Emirian,
+        26 => Nationality::Estonian,
+        27 => Nationality::Finnish,
+        28 => Nationality::French,
+        29 => Nationality::German,
+        30 => Nationality::Ghanaian,
+        31 => Nationality::Greek,
+        32 => Nationality::Guatemalan,
+        33 => Nationality::Honduran,
+        34 => Nationality::HongKonger,
+        35 => Nationality::Hungarian,
+        36 => Nationality::Icelander,
+        37 => Nationality::Indian,
+        38 => Nationality::Indonesian,
+        39 => Nationality::Irish,
+        40 => Nationality::Israeli,
+        41 => Nationality::Italian,
+        42 => Nationality::Jamaican,
+        43 => Nationality::Japanese,
+        44 => Nationality::Jordanian,
+        45 => Nationality::Kuwaiti,
+        46 => Nationality::Latvian,
+        47 => Nationality::Lebanese,
+        48 => Nationality::Lithuanian,
+        49 => Nationality::Luxembourger,
+        50 => Nationality::Malaysian,
+        51 => Nationality::Maltese,
+        52 => Nationality::Mexican,
+        53 => Nationality::Monegasque,
+        54 => Nationality::NewZealander,
+        55 => Nationality::Nicaraguan,
+        56 => Nationality::NorthKorean,
+        57 => Nationality::NorthernIrish,
+        58 => Nationality::Norwegian,
+        59 => Nationality::Omani,
+        60 => Nationality::Pakistani,
+        61 => Nationality::Panamanian,
+        62 => Nationality::Paraguayan,
+        63 => Nationality::Peruvian,
+        64 => Nationality::Polish,
+        65 => Nationality::Portuguese,
+        66 => Nationality::Qatari,
+        67 => Nationality::Romanian,
+        68 => Nationality::Russian,
+        69 => Nationality::Salvadoran,
+        70 => Nationality::Saudi,
+        71 => Nationality::Scottish,
+        72 => Nationality::Serbian,
+        73 => Nationality::Singaporean,
+        74 => Nationality::Slovakian,
+        75 => Nationality::Slovenian,
+        76 => Nationality::SouthKorean,
+        77 => Nationality::SouthAfrican,
+        78 => Nationality::Spanish,
+        79 => Nationality::Swedish,
+        80 => Nationality::Swiss,
+        81 => Nationality::Thai,
+        82 => Nationality::Turkish,
+        83 => Nationality::Uruguayan,
+        84 => Nationality::Ukrainian,
+        85 => Nationality::Venezuelan,
+        86 => Nationality::Welsh,
+        value => Nationality::Unknown(value),
     }
 }
 
-fn decode_name(cursor: &mut Cursor<&mut BytesMut>) -> String {
-    let cursor_position = cursor.position();
-    let mut letters = Vec::with_capacity(48);
+fn decode_name_into(cursor: &mut Cursor<&[u8]>, name: &mut String) {
+    let start = cursor.position() as usize;
+    let bytes = &cursor.get_ref()[start..start + NAME_SIZE];
 
-    for _ in 0..48 {
-        let letter = cursor.get_u8() as char;
+    name.clear();
+    name.push_str(&decode_name(bytes));
 
-        if letter == '\0' {
-            break;
-        } else {
-            letters.push(letter);
-        }
-    }
+    cursor.advance(NAME_SIZE);
+}
 
-    cursor.set_position(cursor_position + 48);
-    letters.iter().collect()
+/// Decode the name of a participant, which is UTF-8 and terminated by a null byte.
+///
+/// Invalid UTF-8 sequences are replaced with the replacement character. Valid names borrow the
+/// bytes of the packet.
+pub(crate) fn decode_name(bytes: &[u8]) -> Cow<'_, str> {
+    let length = bytes
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(bytes.len());
+
+    String::from_utf8_lossy(&bytes[..length])
 }
 
-fn decode_telemetry_privacy(
-    cursor: &mut Cursor<&mut BytesMut>,
-) -> Result<Option<TelemetryPrivacy>, Error> {
+fn decode_telemetry_privacy(cursor: &mut Cursor<&[u8]>) -> Option<TelemetryPrivacy> {
     let value = cursor.get_u8();
 
     match value {
-        0 => Ok(Some(TelemetryPrivacy::Restricted)),
-        1 => Ok(Some(TelemetryPrivacy::Public)),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode telemetry privacy setting.",
-        )),
+        0 => Some(TelemetryPrivacy::Restricted),
+        1 => Some(TelemetryPrivacy::Public),
+        value => Some(TelemetryPrivacy::Unknown(value)),
     }
 }
 
@@ -347,8 +394,16 @@ mod tests {
 
     use bytes::{BufMut, BytesMut};
 
-    use crate::nineteen::participants::{decode_name, decode_participants, PACKET_SIZE};
-    use crate::packet::participants::{Controller, Driver, Nationality, Team, TelemetryPrivacy};
+    use crate::error::F1Error;
+    use crate::nineteen::decode_into;
+    use crate::nineteen::participants::{
+        decode_name, decode_name_into, decode_participants, PACKET_SIZE,
+    };
+    use crate::packet::header::PacketType;
+    use crate::packet::lap::LapPacket;
+    use crate::packet::participants::{
+        Controller, Driver, Nationality, ParticipantsPacket, Team, TelemetryPrivacy,
+    };
 
     fn put_packet_header(mut bytes: BytesMut) -> BytesMut {
         bytes.put_u16_le(2019);
@@ -356,9 +411,9 @@ mod tests {
         bytes.put_u8(2);
         bytes.put_u8(3);
         bytes.put_u8(4);
-        bytes.put_u64_le(u64::max_value());
+        bytes.put_u64_le(u64::MAX);
         bytes.put_f32_le(1.0);
-        bytes.put_u32_le(u32::max_value());
+        bytes.put_u32_le(u32::MAX);
         bytes.put_u8(0);
 
         bytes
@@ -366,8 +421,8 @@ mod tests {
 
     #[test]
     fn decode_participants_with_error() {
-        let mut bytes = BytesMut::with_capacity(0);
-        let mut cursor = Cursor::new(&mut bytes);
+        let bytes = BytesMut::with_capacity(0);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_participants(&mut cursor);
         assert!(packet.is_err());
@@ -400,7 +455,7 @@ mod tests {
             bytes.put_u8(0);
         }
 
-        let mut cursor = Cursor::new(&mut bytes);
+        let mut cursor = Cursor::new(&bytes[..]);
         let packet = decode_participants(&mut cursor).unwrap();
 
         assert_eq!(20, packet.active_participants_count());
@@ -409,9 +464,12 @@ mod tests {
 
         assert_eq!(Controller::AI, participant.controller());
         assert_eq!(Driver::DanielRicciardo, participant.driver());
+        assert_eq!("Daniel Ricciardo", participant.driver().name());
         assert_eq!(Team::Williams, participant.team());
+        assert_eq!("Williams Racing", participant.team().name());
         assert_eq!(4, participant.race_number());
         assert_eq!(Nationality::Azerbaijani, participant.nationality());
+        assert_eq!("AZ", participant.nationality().country_code());
         assert_eq!(String::from("Player"), *participant.name());
         assert_eq!(
             TelemetryPrivacy::Restricted,
@@ -419,21 +477,108 @@ mod tests {
         );
     }
 
+    #[test]
+    fn decode_participants_with_unknown_identifiers() {
+        let bytes = BytesMut::with_capacity(PACKET_SIZE);
+        let mut bytes = put_packet_header(bytes);
+
+        bytes.put_u8(20);
+
+        for _ in 0..20 {
+            bytes.put_u8(1);
+            bytes.put_u8(95);
+            bytes.put_u8(200);
+            bytes.put_u8(4);
+            bytes.put_u8(250);
+
+            let name = vec![0u8; 48];
+            bytes.put(name.as_slice());
+
+            bytes.put_u8(0);
+        }
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let packet = decode_participants(&mut cursor).unwrap();
+
+        let participant = &packet.participants()[0];
+
+        assert_eq!(Driver::Unknown(95), participant.driver());
+        assert_eq!(Team::Unknown(200), participant.team());
+        assert_eq!(Nationality::Unknown(250), participant.nationality());
+    }
+
+    #[test]
+    fn decode_participants_into_existing_packet() {
+        let bytes = BytesMut::with_capacity(PACKET_SIZE);
+        let mut bytes = put_packet_header(bytes);
+
+        bytes.put_u8(20);
+
+        for _ in 0..20 {
+            bytes.put_u8(1);
+            bytes.put_u8(2);
+            bytes.put_u8(3);
+            bytes.put_u8(4);
+            bytes.put_u8(5);
+
+            let mut name = [0u8; 48];
+            name[..6].copy_from_slice(b"Player");
+            bytes.put(&name[..]);
+
+            bytes.put_u8(0);
+        }
+
+        let mut packet = ParticipantsPacket::default();
+        decode_into(&mut packet, &bytes).unwrap();
+
+        let participants = packet.participants().as_ptr();
+        let name = packet.participants()[19].name().as_ptr();
+
+        bytes[24 + 19 * 54 + 5] = b'Q';
+        decode_into(&mut packet, &bytes).unwrap();
+
+        assert_eq!("Qlayer", packet.participants()[19].name());
+        assert_eq!(participants, packet.participants().as_ptr());
+        assert_eq!(name, packet.participants()[19].name().as_ptr());
+
+        let mut laps = LapPacket::default();
+        assert!(matches!(
+            decode_into(&mut laps, &bytes),
+            Err(F1Error::UnexpectedPacketType {
+                expected: PacketType::Lap,
+                actual: PacketType::Participants,
+            })
+        ));
+    }
+
     #[test]
     fn decode_short_name() {
         let mut bytes = BytesMut::with_capacity(48);
 
-        bytes.put_u8(b'N');
-        bytes.put_u8(b'a');
-        bytes.put_u8(b'm');
-        bytes.put_u8(b'e');
-        bytes.put_u8(0);
+        bytes.put(&b"Name"[..]);
+        bytes.put(&[0u8; 44][..]);
 
-        let mut cursor = Cursor::new(&mut bytes);
+        let mut cursor = Cursor::new(&bytes[..]);
 
-        let name = decode_name(&mut cursor);
+        let mut name = String::from("Previous name");
+        decode_name_into(&mut cursor, &mut name);
 
         assert_eq!(String::from("Name"), name);
         assert_eq!(48, cursor.position());
     }
+
+    #[test]
+    fn decode_utf8_name() {
+        let mut bytes = BytesMut::with_capacity(48);
+
+        bytes.put("Räikkönen".as_bytes());
+        bytes.put(&[0u8; 37][..]);
+
+        let mut name = String::new();
+        decode_name_into(&mut Cursor::new(&bytes[..]), &mut name);
+        assert_eq!("Räikkönen", name);
+
+        bytes[1] = 0xff;
+        assert_eq!("R\u{fffd}\u{fffd}ikkönen", decode_name(&bytes[..]));
+    }
 }
@@ -3,17 +3,21 @@
 //! The session packets by F1 2018 and F1 2019 differ only in their packet headers, the rest of the
 //! packet format is identical.
 
-use std::io::{Cursor, Error, ErrorKind};
+use std::io::Cursor;
 use std::time::Duration;
 
-use bytes::{Buf, BytesMut};
+use bytes::Buf;
 
+use crate::error::F1Error;
 use crate::nineteen::flag::decode_flag;
 use crate::nineteen::header::decode_header;
+use crate::nineteen::vehicle::decode_optional_vehicle_index;
 use crate::packet::ensure_packet_size;
+use crate::packet::header::GameVersion;
 use crate::packet::session::{
     Formula, MarshalZone, SafetyCar, Session, SessionPacket, Track, Weather,
 };
+use crate::types::{Speed, Temperature};
 
 /// Size of the session packet in F1 2019
 pub const PACKET_SIZE: usize = 149;
@@ -22,35 +26,35 @@ pub const PACKET_SIZE: usize = 149;
 ///
 /// The session packets by F1 2018 and F1 2019 differ only in their packet headers, the rest of the
 /// packet format is identical.
-pub fn decode_session(cursor: &mut Cursor<&mut BytesMut>) -> Result<SessionPacket, Error> {
+pub fn decode_session(cursor: &mut Cursor<&[u8]>) -> Result<SessionPacket, F1Error> {
     ensure_packet_size(PACKET_SIZE, cursor)?;
 
     let header = decode_header(cursor)?;
 
-    let weather = decode_weather(cursor)?;
-    let track_temperature = cursor.get_i8();
-    let air_temperature = cursor.get_i8();
+    let weather = decode_weather(cursor);
+    let track_temperature = Temperature::from(cursor.get_i8());
+    let air_temperature = Temperature::from(cursor.get_i8());
     let total_laps = cursor.get_u8();
     let track_length = cursor.get_u16_le();
-    let session_type = decode_session_type(cursor)?;
-    let track = decode_track(cursor)?;
-    let formula = decode_formula(cursor)?;
+    let session_type = decode_session_type(cursor);
+    let track = decode_track(cursor, *header.game_version());
+    let formula = decode_formula(cursor);
     let time_left = Duration::from_secs(cursor.get_u16_le() as u64);
     let duration = Duration::from_secs(cursor.get_u16_le() as u64);
-    let pit_speed_limit = cursor.get_u8();
+    let pit_speed_limit = Speed::from_kph(cursor.get_u8() as u16);
     let game_paused = cursor.get_u8() > 0;
     let is_spectating = cursor.get_u8() > 0;
-    let spectator_car_index = cursor.get_u8();
+    let spectator_car_index = decode_optional_vehicle_index(cursor);
     let sli_pro_support = cursor.get_u8() > 0;
 
     let marshal_zone_count = cursor.get_u8();
     let mut marshal_zones = Vec::with_capacity(marshal_zone_count as usize);
 
     for _ in 0..marshal_zone_count {
-        marshal_zones.push(MarshalZone::new(cursor.get_f32_le(), decode_flag(cursor)?));
+        marshal_zones.push(MarshalZone::new(cursor.get_f32_le(), decode_flag(cursor)));
     }
 
-    let safety_car = decode_safety_car(cursor)?;
+    let safety_car = decode_safety_car(cursor);
     let network_session = cursor.get_u8() > 0;
 
     Ok(SessionPacket::new(
@@ -76,110 +80,107 @@ pub fn decode_session(cursor: &mut Cursor<&mut BytesMut>) -> Result<SessionPacke
     ))
 }
 
-fn decode_weather(cursor: &mut Cursor<&mut BytesMut>) -> Result<Weather, Error> {
+fn decode_weather(cursor: &mut Cursor<&[u8]>) -> Weather {
     let value = cursor.get_u8();
 
     match value {
-        0 => Ok(Weather::Clear),
-        1 => Ok(Weather::LightCloud),
-        2 => Ok(Weather::Overcast),
-        3 => Ok(Weather::LightRain),
-        4 => Ok(Weather::HeavyRain),
-        5 => Ok(Weather::Storm),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode weather.",
-        )),
+        0 => Weather::Clear,
+        1 => Weather::LightCloud,
+        2 => Weather::Overcast,
+        3 => Weather::LightRain,
+        4 => Weather::HeavyRain,
+        5 => Weather::Storm,
+        value => Weather::Unknown(value),
     }
 }
 
-fn decode_session_type(cursor: &mut Cursor<&mut BytesMut>) -> Result<Session, Error> {
+fn decode_session_type(cursor: &mut Cursor<&[u8]>) -> Session {
     let value = cursor.get_u8();
 
     match value {
-        0 => Ok(Session::Unknown),
-        1 => Ok(Session::P1),
-        2 => Ok(Session::P2),
-        3 => Ok(Session::P3),
-        4 => Ok(Session::ShortPractice),
-        5 => Ok(Session::Q1),
-        6 => Ok(Session::Q2),
-        7 => Ok(Session::Q3),
-        8 => Ok(Session::ShortQualifying),
-        9 => Ok(Session::OneShotQualifying),
-        10 => Ok(Session::Race),
-        11 => Ok(Session::Race2),
-        12 => Ok(Session::TimeTrial),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode session.",
-        )),
+        1 => Session::P1,
+        2 => Session::P2,
+        3 => Session::P3,
+        4 => Session::ShortPractice,
+        5 => Session::Q1,
+        6 => Session::Q2,
+        7 => Session::Q3,
+        8 => Session::ShortQualifying,
+        9 => Session::OneShotQualifying,
+        10 => Session::Race,
+        11 => Session::Race2,
+        12 => Session::TimeTrial,
+        value => Session::Unknown(value),
     }
 }
 
-fn decode_track(cursor: &mut Cursor<&mut BytesMut>) -> Result<Track, Error> {
+/// Identifiers of the tracks in F1 2019, with the version of the game that added each track
+///
+/// Patches can add tracks to a game with new identifiers. An identifier that a version of the game
+/// sends before its track was added is decoded as an unknown track, so that packets from older
+/// versions are not mistaken for the new track. The version is only checked if the header carries
+/// it. All tracks of F1 2019 have been available since its release.
+const TRACKS: [(i8, Track, (u8, u8)); 25] = [
+    (0, Track::Melbourne, (1, 0)),
+    (1, Track::PaulRicard, (1, 0)),
+    (2, Track::Shanghai, (1, 0)),
+    (3, Track::Bahrain, (1, 0)),
+    (4, Track::Catalunya, (1, 0)),
+    (5, Track::Monaco, (1, 0)),
+    (6, Track::Montreal, (1, 0)),
+    (7, Track::Silverstone, (1, 0)),
+    (8, Track::Hockenheim, (1, 0)),
+    (9, Track::Hungaroring, (1, 0)),
+    (10, Track::Spa, (1, 0)),
+    (11, Track::Monza, (1, 0)),
+    (12, Track::Singapore, (1, 0)),
+    (13, Track::Suzuka, (1, 0)),
+    (14, Track::AbuDhabi, (1, 0)),
+    (15, Track::Texas, (1, 0)),
+    (16, Track::Brazil, (1, 0)),
+    (17, Track::Austria, (1, 0)),
+    (18, Track::Sochi, (1, 0)),
+    (19, Track::Mexico, (1, 0)),
+    (20, Track::Azerbaijan, (1, 0)),
+    (21, Track::BahrainShort, (1, 0)),
+    (22, Track::SilverstoneShort, (1, 0)),
+    (23, Track::TexasShort, (1, 0)),
+    (24, Track::SuzukaShort, (1, 0)),
+];
+
+fn decode_track(cursor: &mut Cursor<&[u8]>, game_version: Option<GameVersion>) -> Track {
     let value = cursor.get_i8();
 
-    match value {
-        -1 => Ok(Track::Unknown),
-        0 => Ok(Track::Melbourne),
-        1 => Ok(Track::PaulRicard),
-        2 => Ok(Track::Shanghai),
-        3 => Ok(Track::Bahrain),
-        4 => Ok(Track::Catalunya),
-        5 => Ok(Track::Monaco),
-        6 => Ok(Track::Montreal),
-        7 => Ok(Track::Silverstone),
-        8 => Ok(Track::Hockenheim),
-        9 => Ok(Track::Hungaroring),
-        10 => Ok(Track::Spa),
-        11 => Ok(Track::Monza),
-        12 => Ok(Track::Singapore),
-        13 => Ok(Track::Suzuka),
-        14 => Ok(Track::AbuDhabi),
-        15 => Ok(Track::Texas),
-        16 => Ok(Track::Brazil),
-        17 => Ok(Track::Austria),
-        18 => Ok(Track::Sochi),
-        19 => Ok(Track::Mexico),
-        20 => Ok(Track::Azerbaijan),
-        21 => Ok(Track::BahrainShort),
-        22 => Ok(Track::SilverstoneShort),
-        23 => Ok(Track::TexasShort),
-        24 => Ok(Track::SuzukaShort),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode track.",
-        )),
-    }
+    TRACKS
+        .iter()
+        .find(|(id, _, since)| {
+            *id == value
+                && game_version.is_none_or(|version| (version.major(), version.minor()) >= *since)
+        })
+        .map(|(_, track, _)| *track)
+        .unwrap_or(Track::Unknown(value))
 }
 
-fn decode_formula(cursor: &mut Cursor<&mut BytesMut>) -> Result<Formula, Error> {
+fn decode_formula(cursor: &mut Cursor<&[u8]>) -> Formula {
     let value = cursor.get_u8();
 
     match value {
-        0 => Ok(Formula::ModernF1),
-        1 => Ok(Formula::ClassicF1),
-        2 => Ok(Formula::F2),
-        3 => Ok(Formula::GenericF1),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode formula.",
-        )),
+        0 => Formula::ModernF1,
+        1 => Formula::ClassicF1,
+        2 => Formula::F2,
+        3 => Formula::GenericF1,
+        value => Formula::Unknown(value),
     }
 }
 
-fn decode_safety_car(cursor: &mut Cursor<&mut BytesMut>) -> Result<SafetyCar, Error> {
+fn decode_safety_car(cursor: &mut Cursor<&[u8]>) -> SafetyCar {
     let value = cursor.get_u8();
 
     match value {
-        0 => Ok(SafetyCar::None),
-        1 => Ok(SafetyCar::Full),
-        2 => Ok(SafetyCar::Virtual),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode safety car.",
-        )),
+        0 => SafetyCar::None,
+        1 => SafetyCar::Full,
+        2 => SafetyCar::Virtual,
+        value => SafetyCar::Unknown(value),
     }
 }
 
@@ -189,8 +190,11 @@ mod tests {
 
     use bytes::{BufMut, BytesMut};
 
-    use crate::nineteen::session::{decode_session, PACKET_SIZE};
+    use crate::nineteen::session::{decode_session, decode_track, PACKET_SIZE};
+    use crate::packet::header::GameVersion;
     use crate::packet::session::{Formula, SafetyCar, Session, Track, Weather};
+    use crate::packet::status::VisualTyreCompound;
+    use crate::types::Flag;
 
     fn put_packet_header(mut bytes: BytesMut) -> BytesMut {
         bytes.put_u16_le(2019);
@@ -198,9 +202,9 @@ mod tests {
         bytes.put_u8(2);
         bytes.put_u8(3);
         bytes.put_u8(1);
-        bytes.put_u64_le(u64::max_value());
+        bytes.put_u64_le(u64::MAX);
         bytes.put_f32_le(1.0);
-        bytes.put_u32_le(u32::max_value());
+        bytes.put_u32_le(u32::MAX);
         bytes.put_u8(0);
 
         bytes
@@ -208,8 +212,8 @@ mod tests {
 
     #[test]
     fn decode_session_with_error() {
-        let mut bytes = BytesMut::with_capacity(0);
-        let mut cursor = Cursor::new(&mut bytes);
+        let bytes = BytesMut::with_capacity(0);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_session(&mut cursor);
         assert!(packet.is_err());
@@ -238,34 +242,65 @@ mod tests {
         bytes.put_u8(21);
 
         for i in 0..21 {
-            bytes.put_f32_le(i as f32);
+            bytes.put_f32_le(i as f32 / 21.0);
             bytes.put_i8((i % 6) - 1);
         }
 
         bytes.put_u8(1);
         bytes.put_u8(1);
 
-        let mut cursor = Cursor::new(&mut bytes);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_session(&mut cursor).unwrap();
 
         assert_eq!(Weather::LightCloud, packet.weather());
-        assert_eq!(2, packet.track_temperature());
-        assert_eq!(3, packet.air_temperature());
+        assert_eq!(2, packet.track_temperature().as_celsius());
+        assert_eq!(3, packet.air_temperature().as_celsius());
         assert_eq!(4, packet.total_laps());
         assert_eq!(5, packet.track_length());
         assert_eq!(Session::Q2, packet.session_type());
+        assert!(packet.session_type().is_qualifying());
         assert_eq!(Track::Silverstone, packet.track());
+        assert_eq!(Some(5891), packet.track().info().length());
         assert_eq!(Formula::F2, packet.formula());
+        assert!(packet
+            .formula()
+            .tyre_compounds()
+            .contains(&VisualTyreCompound::F2Wet));
         assert_eq!(9, packet.time_left().as_secs());
         assert_eq!(10, packet.duration().as_secs());
-        assert_eq!(11, packet.pit_speed_limit());
+        assert_eq!(11, packet.pit_speed_limit().as_kph());
         assert!(packet.game_paused());
         assert!(packet.is_spectating());
-        assert_eq!(14, packet.spectator_car_index());
+        assert_eq!(
+            Some(14),
+            packet.spectator_car_index().map(|index| index.as_u8())
+        );
         assert!(packet.sli_pro_support());
         assert_eq!(21, packet.marshal_zones().len());
+        assert_eq!(Flag::Yellow, packet.marshal_zone_at(2.5).unwrap().flag());
+        assert_eq!(Flag::Green, packet.marshal_zone_at(-0.1).unwrap().flag());
         assert_eq!(SafetyCar::Full, packet.safety_car());
         assert!(packet.network_session());
     }
+
+    #[test]
+    fn decode_track_with_game_version() {
+        let mut bytes = BytesMut::with_capacity(3);
+        bytes.put_i8(7);
+        bytes.put_i8(7);
+        bytes.put_i8(7);
+
+        let mut cursor = Cursor::new(&bytes[..]);
+
+        assert_eq!(Track::Silverstone, decode_track(&mut cursor, None));
+        assert_eq!(
+            Track::Silverstone,
+            decode_track(&mut cursor, Some(GameVersion::new(1, 22)))
+        );
+        assert_eq!(
+            Track::Unknown(7),
+            decode_track(&mut cursor, Some(GameVersion::new(0, 9)))
+        );
+    }
 }
@@ -3,53 +3,83 @@
 //! The car setup packets by F1 2018 and F1 2019 differ only in their packet headers, the rest of
 //! the packet format is identical.
 
-use std::io::{Cursor, Error};
+use std::io::Cursor;
 
-use bytes::{Buf, BytesMut};
+use bytes::Buf;
 
+use crate::error::F1Error;
 use crate::nineteen::header::decode_header;
 use crate::packet::ensure_packet_size;
 use crate::packet::setup::{CarSetup, CarSetupPacket};
+use crate::types::Pressure;
 
 /// Size of the car setups packet in bytes
 pub const PACKET_SIZE: usize = 843;
 
+/// Size of the setup of a single car in bytes
+pub(crate) const ENTRY_SIZE: usize = 41;
+
 /// Decode a car setup packet sent by F1 2019
 ///
 /// F1 2018 and F1 2019 publish the same data in their car setup packets, but with different packet
 /// headers. In multiplayer sessions, the setups of other players are redacted and appear empty.
-pub fn decode_setups(cursor: &mut Cursor<&mut BytesMut>) -> Result<CarSetupPacket, Error> {
+pub fn decode_setups(cursor: &mut Cursor<&[u8]>) -> Result<CarSetupPacket, F1Error> {
+    let mut packet = CarSetupPacket::default();
+    decode_setups_into(cursor, &mut packet)?;
+
+    Ok(packet)
+}
+
+/// Decode a car setup packet sent by F1 2019 into an existing packet
+///
+/// The packet is overwritten, and the vector that holds the data of the cars is reused, so that
+/// decoding into the same packet over and over again does not allocate. If decoding fails, the
+/// contents of the packet are unspecified.
+pub fn decode_setups_into(
+    cursor: &mut Cursor<&[u8]>,
+    packet: &mut CarSetupPacket,
+) -> Result<(), F1Error> {
     ensure_packet_size(PACKET_SIZE, cursor)?;
 
     let header = decode_header(cursor)?;
-    let mut setups = Vec::with_capacity(20);
+    let setups = packet.setups_mut();
+    setups.clear();
+    setups.reserve(20);
 
     for _ in 0..20 {
-        setups.push(CarSetup::new(
-            cursor.get_u8(),
-            cursor.get_u8(),
-            cursor.get_u8(),
-            cursor.get_u8(),
-            cursor.get_f32_le(),
-            cursor.get_f32_le(),
-            cursor.get_f32_le(),
-            cursor.get_f32_le(),
-            cursor.get_u8(),
-            cursor.get_u8(),
-            cursor.get_u8(),
-            cursor.get_u8(),
-            cursor.get_u8(),
-            cursor.get_u8(),
-            cursor.get_u8(),
-            cursor.get_u8(),
-            cursor.get_f32_le(),
-            cursor.get_f32_le(),
-            cursor.get_u8(),
-            cursor.get_f32_le(),
-        ))
+        setups.push(decode_setup(cursor)?);
     }
 
-    Ok(CarSetupPacket::new(header, setups))
+    let setups = std::mem::take(packet.setups_mut());
+    *packet = CarSetupPacket::new(header, setups);
+
+    Ok(())
+}
+
+/// Decode the setup of a single car.
+pub(crate) fn decode_setup(cursor: &mut Cursor<&[u8]>) -> Result<CarSetup, F1Error> {
+    Ok(CarSetup::new(
+        cursor.get_u8(),
+        cursor.get_u8(),
+        cursor.get_u8(),
+        cursor.get_u8(),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        cursor.get_u8(),
+        cursor.get_u8(),
+        cursor.get_u8(),
+        cursor.get_u8(),
+        cursor.get_u8(),
+        cursor.get_u8(),
+        cursor.get_u8(),
+        cursor.get_u8(),
+        Pressure::from_psi(cursor.get_f32_le()),
+        Pressure::from_psi(cursor.get_f32_le()),
+        cursor.get_u8(),
+        cursor.get_f32_le(),
+    ))
 }
 
 #[cfg(test)]
@@ -67,9 +97,9 @@ mod tests {
         bytes.put_u8(2);
         bytes.put_u8(3);
         bytes.put_u8(5);
-        bytes.put_u64_le(u64::max_value());
+        bytes.put_u64_le(u64::MAX);
         bytes.put_f32_le(1.0);
-        bytes.put_u32_le(u32::max_value());
+        bytes.put_u32_le(u32::MAX);
         bytes.put_u8(0);
 
         bytes
@@ -77,8 +107,8 @@ mod tests {
 
     #[test]
     fn decode_setups_with_error() {
-        let mut bytes = BytesMut::with_capacity(0);
-        let mut cursor = Cursor::new(&mut bytes);
+        let bytes = BytesMut::with_capacity(0);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_setups(&mut cursor);
         assert!(packet.is_err());
@@ -113,7 +143,7 @@ mod tests {
         let padding = vec![0u8; 779];
         bytes.put(padding.as_slice());
 
-        let mut cursor = Cursor::new(&mut bytes);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_setups(&mut cursor).unwrap();
         let setup = packet.setups()[0];
@@ -134,8 +164,8 @@ mod tests {
         assert_eq!(14, setup.rear_suspension_height());
         assert_eq!(15, setup.brake_pressure());
         assert_eq!(16, setup.brake_bias());
-        assert_approx_eq!(17.0, setup.front_tyre_pressure());
-        assert_approx_eq!(18.0, setup.rear_tyre_pressure());
+        assert_approx_eq!(17.0, setup.front_tyre_pressure().as_psi());
+        assert_approx_eq!(18.0, setup.rear_tyre_pressure().as_psi());
         assert_eq!(19, setup.ballast());
         assert_approx_eq!(20.0, setup.fuel_load());
     }
@@ -4,10 +4,12 @@
 //! visual tyre compound (e.g. hard). This makes it packet format and decoder incompatible with
 //! earlier F1 games.
 
-use std::io::{Cursor, Error, ErrorKind};
+use std::io::Cursor;
 
-use bytes::{Buf, BytesMut};
+use bytes::Buf;
 
+use crate::error::F1Error;
+use crate::nineteen::corner::decode_corners;
 use crate::nineteen::flag::decode_flag;
 use crate::nineteen::header::decode_header;
 use crate::packet::ensure_packet_size;
@@ -20,178 +22,173 @@ use crate::types::CornerProperty;
 /// Size of the car status packet in bytes
 pub const PACKET_SIZE: usize = 1143;
 
+/// Size of the status of a single car in bytes
+pub(crate) const ENTRY_SIZE: usize = 56;
+
 /// Decode the car status packet sent by F1 2019
 ///
 /// The car status packet by F1 2019 introduces the differentiation between a physical and a visual
 /// tyre compound.
-pub fn decode_statuses(cursor: &mut Cursor<&mut BytesMut>) -> Result<CarStatusPacket, Error> {
+pub fn decode_statuses(cursor: &mut Cursor<&[u8]>) -> Result<CarStatusPacket, F1Error> {
+    let mut packet = CarStatusPacket::default();
+    decode_statuses_into(cursor, &mut packet)?;
+
+    Ok(packet)
+}
+
+/// Decode a car status packet sent by F1 2019 into an existing packet
+///
+/// The packet is overwritten, and the vector that holds the data of the cars is reused, so that
+/// decoding into the same packet over and over again does not allocate. If decoding fails, the
+/// contents of the packet are unspecified.
+pub fn decode_statuses_into(
+    cursor: &mut Cursor<&[u8]>,
+    packet: &mut CarStatusPacket,
+) -> Result<(), F1Error> {
     ensure_packet_size(PACKET_SIZE, cursor)?;
 
     let header = decode_header(cursor)?;
-    let mut car_status = Vec::with_capacity(20);
+    let statuses = packet.statuses_mut();
+    statuses.clear();
+    statuses.reserve(20);
 
     for _ in 0..20 {
-        car_status.push(CarStatus::new(
-            decode_traction_control(cursor)?,
-            cursor.get_u8() > 0,
-            decode_fuel_mix(cursor)?,
-            cursor.get_u8(),
-            cursor.get_u8() > 0,
-            cursor.get_f32_le(),
-            cursor.get_f32_le(),
-            cursor.get_f32_le(),
-            cursor.get_u16_le(),
-            cursor.get_u16_le(),
-            cursor.get_u8(),
-            decode_drs(cursor)?,
-            decode_tyre_wear(cursor),
-            decode_physical_tyre_compound(cursor)?,
-            decode_visual_tyre_compound(cursor)?,
-            decode_tyre_damage(cursor),
-            cursor.get_u8(),
-            cursor.get_u8(),
-            cursor.get_u8(),
-            cursor.get_u8(),
-            cursor.get_u8(),
-            decode_flag(cursor)?,
-            cursor.get_f32_le(),
-            decode_ers_deploy_mode(cursor)?,
-            cursor.get_f32_le(),
-            cursor.get_f32_le(),
-            cursor.get_f32_le(),
-        ));
+        statuses.push(decode_status(cursor)?);
     }
 
-    Ok(CarStatusPacket::new(header, car_status))
+    let statuses = std::mem::take(packet.statuses_mut());
+    *packet = CarStatusPacket::new(header, statuses);
+
+    Ok(())
 }
 
-fn decode_traction_control(cursor: &mut Cursor<&mut BytesMut>) -> Result<TractionControl, Error> {
+/// Decode the status of a single car.
+pub(crate) fn decode_status(cursor: &mut Cursor<&[u8]>) -> Result<CarStatus, F1Error> {
+    Ok(CarStatus::new(
+        decode_traction_control(cursor),
+        cursor.get_u8() > 0,
+        decode_fuel_mix(cursor),
+        cursor.get_u8(),
+        cursor.get_u8() > 0,
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        cursor.get_u16_le(),
+        cursor.get_u16_le(),
+        cursor.get_u8(),
+        decode_drs(cursor),
+        decode_tyre_wear(cursor),
+        decode_physical_tyre_compound(cursor),
+        decode_visual_tyre_compound(cursor),
+        decode_tyre_damage(cursor),
+        cursor.get_u8(),
+        cursor.get_u8(),
+        cursor.get_u8(),
+        cursor.get_u8(),
+        cursor.get_u8(),
+        decode_flag(cursor),
+        cursor.get_f32_le(),
+        decode_ers_deploy_mode(cursor),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+    ))
+}
+
+fn decode_traction_control(cursor: &mut Cursor<&[u8]>) -> TractionControl {
     let value = cursor.get_u8();
 
     match value {
-        0 => Ok(TractionControl::Off),
-        1 => Ok(TractionControl::Low),
-        2 => Ok(TractionControl::High),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode transaction control.",
-        )),
+        0 => TractionControl::Off,
+        1 => TractionControl::Low,
+        2 => TractionControl::High,
+        value => TractionControl::Unknown(value),
     }
 }
 
-fn decode_fuel_mix(cursor: &mut Cursor<&mut BytesMut>) -> Result<FuelMix, Error> {
+fn decode_fuel_mix(cursor: &mut Cursor<&[u8]>) -> FuelMix {
     let value = cursor.get_u8();
 
     match value {
-        0 => Ok(FuelMix::Lean),
-        1 => Ok(FuelMix::Standard),
-        2 => Ok(FuelMix::Rich),
-        3 => Ok(FuelMix::Max),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode fuel mix.",
-        )),
+        0 => FuelMix::Lean,
+        1 => FuelMix::Standard,
+        2 => FuelMix::Rich,
+        3 => FuelMix::Max,
+        value => FuelMix::Unknown(value),
     }
 }
 
-fn decode_drs(cursor: &mut Cursor<&mut BytesMut>) -> Result<DrsSetting, Error> {
+fn decode_drs(cursor: &mut Cursor<&[u8]>) -> DrsSetting {
     let value = cursor.get_i8();
 
     match value {
-        -1 => Ok(DrsSetting::Unknown),
-        0 => Ok(DrsSetting::NotAllowed),
-        1 => Ok(DrsSetting::Allowed),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode DRS status.",
-        )),
+        0 => DrsSetting::NotAllowed,
+        1 => DrsSetting::Allowed,
+        value => DrsSetting::Unknown(value),
     }
 }
 
-fn decode_tyre_wear(cursor: &mut Cursor<&mut BytesMut>) -> CornerProperty<u8> {
-    CornerProperty::new(
-        cursor.get_u8(),
-        cursor.get_u8(),
-        cursor.get_u8(),
-        cursor.get_u8(),
-    )
+fn decode_tyre_wear(cursor: &mut Cursor<&[u8]>) -> CornerProperty<u8> {
+    decode_corners(cursor, |cursor| cursor.get_u8())
 }
 
-fn decode_physical_tyre_compound(
-    cursor: &mut Cursor<&mut BytesMut>,
-) -> Result<PhysicalTyreCompound, Error> {
+fn decode_physical_tyre_compound(cursor: &mut Cursor<&[u8]>) -> PhysicalTyreCompound {
     let value = cursor.get_u8();
 
     match value {
-        7 => Ok(PhysicalTyreCompound::F1Intermediate),
-        8 => Ok(PhysicalTyreCompound::F1Wet),
-        9 => Ok(PhysicalTyreCompound::ClassicDry),
-        10 => Ok(PhysicalTyreCompound::ClassicWet),
-        11 => Ok(PhysicalTyreCompound::F2SuperSoft),
-        12 => Ok(PhysicalTyreCompound::F2Soft),
-        13 => Ok(PhysicalTyreCompound::F2Medium),
-        14 => Ok(PhysicalTyreCompound::F2Hard),
-        15 => Ok(PhysicalTyreCompound::F2Wet),
-        16 => Ok(PhysicalTyreCompound::F1C5),
-        17 => Ok(PhysicalTyreCompound::F1C4),
-        18 => Ok(PhysicalTyreCompound::F1C3),
-        19 => Ok(PhysicalTyreCompound::F1C2),
-        20 => Ok(PhysicalTyreCompound::F1C1),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode physical tyre compound.",
-        )),
+        7 => PhysicalTyreCompound::F1Intermediate,
+        8 => PhysicalTyreCompound::F1Wet,
+        9 => PhysicalTyreCompound::ClassicDry,
+        10 => PhysicalTyreCompound::ClassicWet,
+        11 => PhysicalTyreCompound::F2SuperSoft,
+        12 => PhysicalTyreCompound::F2Soft,
+        13 => PhysicalTyreCompound::F2Medium,
+        14 => PhysicalTyreCompound::F2Hard,
+        15 => PhysicalTyreCompound::F2Wet,
+        16 => PhysicalTyreCompound::F1C5,
+        17 => PhysicalTyreCompound::F1C4,
+        18 => PhysicalTyreCompound::F1C3,
+        19 => PhysicalTyreCompound::F1C2,
+        20 => PhysicalTyreCompound::F1C1,
+        value => PhysicalTyreCompound::Unknown(value),
     }
 }
 
-fn decode_visual_tyre_compound(
-    cursor: &mut Cursor<&mut BytesMut>,
-) -> Result<VisualTyreCompound, Error> {
+fn decode_visual_tyre_compound(cursor: &mut Cursor<&[u8]>) -> VisualTyreCompound {
     let value = cursor.get_u8();
 
     match value {
-        7 => Ok(VisualTyreCompound::F1Intermediate),
-        8 => Ok(VisualTyreCompound::F1Wet),
-        9 => Ok(VisualTyreCompound::ClassicDry),
-        10 => Ok(VisualTyreCompound::ClassicWet),
-        11 => Ok(VisualTyreCompound::F2SuperSoft),
-        12 => Ok(VisualTyreCompound::F2Soft),
-        13 => Ok(VisualTyreCompound::F2Medium),
-        14 => Ok(VisualTyreCompound::F2Hard),
-        15 => Ok(VisualTyreCompound::F2Wet),
-        16 => Ok(VisualTyreCompound::F1Soft),
-        17 => Ok(VisualTyreCompound::F1Medium),
-        18 => Ok(VisualTyreCompound::F1Hard),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode visual tyre compound.",
-        )),
+        7 => VisualTyreCompound::F1Intermediate,
+        8 => VisualTyreCompound::F1Wet,
+        9 => VisualTyreCompound::ClassicDry,
+        10 => VisualTyreCompound::ClassicWet,
+        11 => VisualTyreCompound::F2SuperSoft,
+        12 => VisualTyreCompound::F2Soft,
+        13 => VisualTyreCompound::F2Medium,
+        14 => VisualTyreCompound::F2Hard,
+        15 => VisualTyreCompound::F2Wet,
+        16 => VisualTyreCompound::F1Soft,
+        17 => VisualTyreCompound::F1Medium,
+        18 => VisualTyreCompound::F1Hard,
+        value => VisualTyreCompound::Unknown(value),
     }
 }
 
-fn decode_tyre_damage(cursor: &mut Cursor<&mut BytesMut>) -> CornerProperty<u8> {
-    CornerProperty::new(
-        cursor.get_u8(),
-        cursor.get_u8(),
-        cursor.get_u8(),
-        cursor.get_u8(),
-    )
+fn decode_tyre_damage(cursor: &mut Cursor<&[u8]>) -> CornerProperty<u8> {
+    decode_corners(cursor, |cursor| cursor.get_u8())
 }
 
-fn decode_ers_deploy_mode(cursor: &mut Cursor<&mut BytesMut>) -> Result<ErsDeployMode, Error> {
+fn decode_ers_deploy_mode(cursor: &mut Cursor<&[u8]>) -> ErsDeployMode {
     let value = cursor.get_u8();
 
     match value {
-        0 => Ok(ErsDeployMode::None),
-        1 => Ok(ErsDeployMode::Low),
-        2 => Ok(ErsDeployMode::Medium),
-        3 => Ok(ErsDeployMode::High),
-        4 => Ok(ErsDeployMode::Overtake),
-        5 => Ok(ErsDeployMode::Hotlap),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode ERS deployment mode.",
-        )),
+        0 => ErsDeployMode::None,
+        1 => ErsDeployMode::Low,
+        2 => ErsDeployMode::Medium,
+        3 => ErsDeployMode::High,
+        4 => ErsDeployMode::Overtake,
+        5 => ErsDeployMode::Hotlap,
+        value => ErsDeployMode::Unknown(value),
     }
 }
 
@@ -215,9 +212,9 @@ mod tests {
         bytes.put_u8(2);
         bytes.put_u8(3);
         bytes.put_u8(7);
-        bytes.put_u64_le(u64::max_value());
+        bytes.put_u64_le(u64::MAX);
         bytes.put_f32_le(1.0);
-        bytes.put_u32_le(u32::max_value());
+        bytes.put_u32_le(u32::MAX);
         bytes.put_u8(0);
 
         bytes
@@ -225,8 +222,8 @@ mod tests {
 
     #[test]
     fn decode_statuses_with_error() {
-        let mut bytes = BytesMut::with_capacity(0);
-        let mut cursor = Cursor::new(&mut bytes);
+        let bytes = BytesMut::with_capacity(0);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_statuses(&mut cursor);
         assert!(packet.is_err());
@@ -274,7 +271,7 @@ mod tests {
             bytes.put_f32_le(33.0);
         }
 
-        let mut cursor = Cursor::new(&mut bytes);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_statuses(&mut cursor).unwrap();
         let status = packet.statuses()[0];
@@ -290,17 +287,21 @@ mod tests {
         assert_eq!(9, status.max_rpm());
         assert_eq!(10, status.idle_rpm());
         assert_eq!(11, status.gear_count());
-        assert_eq!(DrsSetting::Unknown, status.drs());
-        assert_eq!(13, status.tyre_wear().front_left());
+        assert_eq!(DrsSetting::Unknown(-1), status.drs());
+        assert_eq!(13, status.tyre_wear().rear_left());
+        assert_eq!(15, status.tyre_wear().front_left());
         assert_eq!(PhysicalTyreCompound::F1C4, status.physical_tyre_compound());
         assert_eq!(VisualTyreCompound::F1Hard, status.visual_tyre_compound());
-        assert_eq!(19, status.tyre_damage().front_left());
+        assert_eq!("C4", status.physical_tyre_compound().name());
+        assert!(!status.visual_tyre_compound().is_wet());
+        assert_eq!(19, status.tyre_damage().rear_left());
         assert_eq!(23, status.front_left_wing_damage());
         assert_eq!(24, status.front_right_wing_damage());
         assert_eq!(25, status.rear_wing_damage());
         assert_eq!(26, status.engine_damage());
         assert_eq!(27, status.gear_box_damage());
         assert_eq!(Flag::Invalid, status.vehicle_flags());
+        assert_eq!("Invalid flag", status.vehicle_flags().to_string());
         assert_approx_eq!(29.0, status.ers_energy());
         assert_eq!(ErsDeployMode::Hotlap, status.ers_deploy_mode());
         assert_approx_eq!(31.0, status.ers_harvest_mgu_k());
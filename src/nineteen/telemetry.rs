@@ -3,141 +3,131 @@
 //! The telemetry packets by F1 2018 and F1 2019 differ only in their packet headers, the rest of
 //! the packet format is identical.
 
-use std::io::{Cursor, Error, ErrorKind};
+use std::convert::TryFrom;
+use std::io::Cursor;
 
-use bytes::{Buf, BytesMut};
+use bytes::Buf;
 
+use crate::error::F1Error;
+use crate::nineteen::corner::{decode_corners, try_decode_corners};
 use crate::nineteen::header::decode_header;
+use crate::nineteen::TELEMETRY_PACKET_SIZE;
 use crate::packet::ensure_packet_size;
-use crate::packet::telemetry::{Button, Gear, Surface, Telemetry, TelemetryPacket};
-use crate::types::CornerProperty;
+use crate::packet::telemetry::{ButtonFlags, Gear, Surface, Telemetry, TelemetryPacket};
+use crate::types::{CornerProperty, Pressure, Speed, Temperature};
 
 /// Size of the telemetry packet in bytes
-pub const PACKET_SIZE: usize = 1347;
+pub const PACKET_SIZE: usize = TELEMETRY_PACKET_SIZE;
+
+/// Size of the telemetry of a single car in bytes
+pub(crate) const ENTRY_SIZE: usize = 66;
 
 /// Decode a telemetry packet sent by F1 2019
 ///
 /// F1 2018 and F1 2019 publish the same data in their telemetry packets, but with different packet
 /// headers.
-pub fn decode_telemetry(cursor: &mut Cursor<&mut BytesMut>) -> Result<TelemetryPacket, Error> {
+pub fn decode_telemetry(cursor: &mut Cursor<&[u8]>) -> Result<TelemetryPacket, F1Error> {
+    let mut packet = TelemetryPacket::default();
+    decode_telemetry_into(cursor, &mut packet)?;
+
+    Ok(packet)
+}
+
+/// Decode a telemetry packet sent by F1 2019 into an existing packet
+///
+/// The packet is overwritten, and the vector that holds the data of the cars is reused, so that
+/// decoding into the same packet over and over again does not allocate. If decoding fails, the
+/// contents of the packet are unspecified.
+pub fn decode_telemetry_into(
+    cursor: &mut Cursor<&[u8]>,
+    packet: &mut TelemetryPacket,
+) -> Result<(), F1Error> {
     ensure_packet_size(PACKET_SIZE, cursor)?;
 
     let header = decode_header(cursor)?;
-    let mut telemetry = Vec::with_capacity(20);
+    let telemetry = packet.telemetry_mut();
+    telemetry.clear();
+    telemetry.reserve(20);
 
     for _ in 0..20 {
-        telemetry.push(Telemetry::new(
-            cursor.get_u16_le(),
-            cursor.get_f32_le(),
-            cursor.get_f32_le(),
-            cursor.get_f32_le(),
-            cursor.get_u8(),
-            decode_gear(cursor)?,
-            cursor.get_u16_le(),
-            cursor.get_u8() > 0,
-            cursor.get_u8(),
-            decode_brake_temperature(cursor),
-            decode_tyre_surface_temperature(cursor),
-            decode_tyre_inner_temperature(cursor),
-            cursor.get_u16_le(),
-            decode_tyre_pressure(cursor),
-            decode_surface_type(cursor)?,
-        ));
+        telemetry.push(decode_car_telemetry(cursor)?);
     }
 
-    let button_status = match Button::from_bits(cursor.get_u32_le()) {
-        Some(button) => button,
-        None => Button::NONE,
-    };
+    let telemetry = std::mem::take(packet.telemetry_mut());
+    // Bits for buttons that are unknown to this crate are dropped.
+    let button_status = ButtonFlags::from_bits_truncate(cursor.get_u32_le());
 
-    Ok(TelemetryPacket::new(header, telemetry, button_status))
-}
-
-fn decode_gear(cursor: &mut Cursor<&mut BytesMut>) -> Result<Gear, Error> {
-    let value = cursor.get_i8();
+    *packet = TelemetryPacket::new(header, telemetry, button_status);
 
-    match value {
-        -1 => Ok(Gear::Reverse),
-        0 => Ok(Gear::Neutral),
-        1 => Ok(Gear::First),
-        2 => Ok(Gear::Second),
-        3 => Ok(Gear::Third),
-        4 => Ok(Gear::Fourth),
-        5 => Ok(Gear::Fifth),
-        6 => Ok(Gear::Sixth),
-        7 => Ok(Gear::Seventh),
-        8 => Ok(Gear::Eighth),
-        _ => Err(Error::new(ErrorKind::InvalidData, "Failed to decode gear.")),
-    }
+    Ok(())
 }
 
-fn decode_brake_temperature(cursor: &mut Cursor<&mut BytesMut>) -> CornerProperty<u16> {
-    CornerProperty::new(
-        cursor.get_u16_le(),
-        cursor.get_u16_le(),
-        cursor.get_u16_le(),
+/// Decode the telemetry of a single car.
+pub(crate) fn decode_car_telemetry(cursor: &mut Cursor<&[u8]>) -> Result<Telemetry, F1Error> {
+    Ok(Telemetry::new(
+        Speed::from_kph(cursor.get_u16_le()),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        cursor.get_u8(),
+        decode_gear(cursor),
         cursor.get_u16_le(),
-    )
+        cursor.get_u8() > 0,
+        cursor.get_u8(),
+        try_decode_corners(cursor, decode_temperature)?,
+        try_decode_corners(cursor, decode_temperature)?,
+        try_decode_corners(cursor, decode_temperature)?,
+        decode_temperature(cursor)?,
+        decode_tyre_pressure(cursor),
+        decode_corners(cursor, decode_surface),
+    ))
 }
 
-fn decode_tyre_surface_temperature(cursor: &mut Cursor<&mut BytesMut>) -> CornerProperty<u16> {
-    CornerProperty::new(
-        cursor.get_u16_le(),
-        cursor.get_u16_le(),
-        cursor.get_u16_le(),
-        cursor.get_u16_le(),
-    )
-}
+fn decode_gear(cursor: &mut Cursor<&[u8]>) -> Gear {
+    let value = cursor.get_i8();
 
-fn decode_tyre_inner_temperature(cursor: &mut Cursor<&mut BytesMut>) -> CornerProperty<u16> {
-    CornerProperty::new(
-        cursor.get_u16_le(),
-        cursor.get_u16_le(),
-        cursor.get_u16_le(),
-        cursor.get_u16_le(),
-    )
+    match value {
+        -1 => Gear::Reverse,
+        0 => Gear::Neutral,
+        1 => Gear::First,
+        2 => Gear::Second,
+        3 => Gear::Third,
+        4 => Gear::Fourth,
+        5 => Gear::Fifth,
+        6 => Gear::Sixth,
+        7 => Gear::Seventh,
+        8 => Gear::Eighth,
+        value => Gear::Unknown(value),
+    }
 }
 
-fn decode_tyre_pressure(cursor: &mut Cursor<&mut BytesMut>) -> CornerProperty<f32> {
-    CornerProperty::new(
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-        cursor.get_f32_le(),
-    )
+fn decode_temperature(cursor: &mut Cursor<&[u8]>) -> Result<Temperature, F1Error> {
+    let value = cursor.get_u16_le();
+
+    Temperature::try_from(value).map_err(|_| F1Error::invalid_field("temperature", value, cursor))
 }
 
-fn decode_surface_type(
-    cursor: &mut Cursor<&mut BytesMut>,
-) -> Result<CornerProperty<Surface>, Error> {
-    Ok(CornerProperty::new(
-        decode_surface(cursor)?,
-        decode_surface(cursor)?,
-        decode_surface(cursor)?,
-        decode_surface(cursor)?,
-    ))
+fn decode_tyre_pressure(cursor: &mut Cursor<&[u8]>) -> CornerProperty<Pressure> {
+    decode_corners(cursor, |cursor| Pressure::from_psi(cursor.get_f32_le()))
 }
 
-fn decode_surface(cursor: &mut Cursor<&mut BytesMut>) -> Result<Surface, Error> {
+fn decode_surface(cursor: &mut Cursor<&[u8]>) -> Surface {
     let value = cursor.get_u8();
 
     match value {
-        0 => Ok(Surface::Tarmac),
-        1 => Ok(Surface::RumbleStrip),
-        2 => Ok(Surface::Concrete),
-        3 => Ok(Surface::Rock),
-        4 => Ok(Surface::Gravel),
-        5 => Ok(Surface::Mud),
-        6 => Ok(Surface::Sand),
-        7 => Ok(Surface::Grass),
-        8 => Ok(Surface::Water),
-        9 => Ok(Surface::Cobblestone),
-        10 => Ok(Surface::Metal),
-        11 => Ok(Surface::Ridged),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to decode surface.",
-        )),
+        0 => Surface::Tarmac,
+        1 => Surface::RumbleStrip,
+        2 => Surface::Concrete,
+        3 => Surface::Rock,
+        4 => Surface::Gravel,
+        5 => Surface::Mud,
+        6 => Surface::Sand,
+        7 => Surface::Grass,
+        8 => Surface::Water,
+        9 => Surface::Cobblestone,
+        10 => Surface::Metal,
+        11 => Surface::Ridged,
+        value => Surface::Unknown(value),
     }
 }
 
@@ -149,7 +139,7 @@ mod tests {
     use bytes::{BufMut, BytesMut};
 
     use crate::nineteen::telemetry::{decode_telemetry, PACKET_SIZE};
-    use crate::packet::telemetry::{Button, Gear, Surface};
+    use crate::packet::telemetry::{ButtonFlags, Gear, Surface};
 
     fn put_packet_header(mut bytes: BytesMut) -> BytesMut {
         bytes.put_u16_le(2019);
@@ -157,9 +147,9 @@ mod tests {
         bytes.put_u8(2);
         bytes.put_u8(3);
         bytes.put_u8(0);
-        bytes.put_u64_le(u64::max_value());
+        bytes.put_u64_le(u64::MAX);
         bytes.put_f32_le(1.0);
-        bytes.put_u32_le(u32::max_value());
+        bytes.put_u32_le(u32::MAX);
         bytes.put_u8(0);
 
         bytes
@@ -167,8 +157,8 @@ mod tests {
 
     #[test]
     fn decode_telemetry_with_error() {
-        let mut bytes = BytesMut::with_capacity(0);
-        let mut cursor = Cursor::new(&mut bytes);
+        let bytes = BytesMut::with_capacity(0);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_telemetry(&mut cursor);
         assert!(packet.is_err());
@@ -214,12 +204,12 @@ mod tests {
 
         bytes.put_u32_le(0x0001);
 
-        let mut cursor = Cursor::new(&mut bytes);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_telemetry(&mut cursor).unwrap();
         let telemetry = packet.telemetry()[0];
 
-        assert_eq!(1, telemetry.speed());
+        assert_eq!(1, telemetry.speed().as_kph());
         assert_approx_eq!(2.0, telemetry.throttle());
         assert_approx_eq!(3.0, telemetry.steering());
         assert_approx_eq!(4.0, telemetry.brake());
@@ -228,12 +218,23 @@ mod tests {
         assert_eq!(7, telemetry.engine_rpm());
         assert!(telemetry.drs());
         assert_eq!(9, telemetry.rev_lights());
-        assert_eq!(10, telemetry.brake_temperature().front_left());
-        assert_eq!(14, telemetry.tyre_surface_temperature().front_left());
-        assert_eq!(18, telemetry.tyre_inner_temperature().front_left());
-        assert_eq!(22, telemetry.engine_temperature());
-        assert_approx_eq!(23.0, telemetry.tyre_pressure().front_left());
-        assert_eq!(Surface::Mud, telemetry.surface_type().front_left());
-        assert_eq!(Button::CROSS_OR_A, packet.button_status());
+        assert_eq!(10, telemetry.brake_temperature().rear_left().as_celsius());
+        assert_eq!(12, telemetry.brake_temperature().front_left().as_celsius());
+        assert_eq!(
+            14,
+            telemetry
+                .tyre_surface_temperature()
+                .rear_left()
+                .as_celsius()
+        );
+        assert_eq!(
+            18,
+            telemetry.tyre_inner_temperature().rear_left().as_celsius()
+        );
+        assert_eq!(22, telemetry.engine_temperature().as_celsius());
+        assert_approx_eq!(23.0, telemetry.tyre_pressure().rear_left().as_psi());
+        assert_eq!(Surface::Mud, telemetry.surface_type().rear_left());
+        assert_eq!(ButtonFlags::CROSS_OR_A, packet.button_status());
+        assert!(packet.button_status().contains(ButtonFlags::A));
     }
 }
@@ -0,0 +1,22 @@
+//! Decoder for references to vehicles
+
+use std::io::Cursor;
+
+use bytes::Buf;
+
+use crate::error::F1Error;
+use crate::packet::header::ApiSpec;
+use crate::types::VehicleIndex;
+
+/// Decode a reference to a vehicle that must be on the grid
+pub fn decode_vehicle_index(cursor: &mut Cursor<&[u8]>) -> Result<VehicleIndex, F1Error> {
+    let value = cursor.get_u8();
+
+    VehicleIndex::new(value, ApiSpec::Nineteen)
+        .ok_or_else(|| F1Error::invalid_field("vehicle index", value, cursor))
+}
+
+/// Decode a reference to a vehicle that is off the grid if no vehicle is referenced
+pub fn decode_optional_vehicle_index(cursor: &mut Cursor<&[u8]>) -> Option<VehicleIndex> {
+    VehicleIndex::new(cursor.get_u8(), ApiSpec::Nineteen)
+}
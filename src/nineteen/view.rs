@@ -0,0 +1,379 @@
+//! Borrowed views of packets sent by F1 2019
+//!
+//! Decoding a packet into its owned representation allocates a vector for the data of the cars,
+//! and a string for the name of each participant. Consumers that process every packet right away,
+//! for example to update a dashboard or to aggregate statistics, throw these allocations away
+//! after a few microseconds, up to 60 times per second for each packet type.
+//!
+//! The packet view borrows the receive buffer instead. It decodes the header eagerly, and the data
+//! of each car only when it is accessed, straight from the underlying bytes. The data of a car is
+//! decoded into its owned representation on every access, which does not allocate for any packet
+//! type but participants. Names of participants are decoded like the owned decoders do, and borrow
+//! the buffer unless they contain invalid UTF-8.
+
+use std::borrow::Cow;
+use std::io::Cursor;
+
+use crate::car::MAX_CARS;
+use crate::error::F1Error;
+use crate::nineteen::header::{decode_header, HEADER_SIZE};
+use crate::nineteen::lap::{self, decode_lap};
+#[cfg(feature = "motion")]
+use crate::nineteen::motion::{self, decode_car_motion};
+use crate::nineteen::participants::{self, decode_name, decode_participant};
+use crate::nineteen::setup::{self, decode_setup};
+use crate::nineteen::status::{self, decode_status};
+#[cfg(feature = "telemetry")]
+use crate::nineteen::telemetry::{self, decode_car_telemetry};
+use crate::nineteen::{decode_nineteen, packet_size};
+use crate::packet::ensure_packet_size;
+use crate::packet::header::{ApiSpec, Header, PacketType};
+use crate::packet::lap::Lap;
+#[cfg(feature = "motion")]
+use crate::packet::motion::Motion;
+use crate::packet::participants::Participant;
+use crate::packet::setup::CarSetup;
+use crate::packet::status::CarStatus;
+#[cfg(feature = "telemetry")]
+use crate::packet::telemetry::Telemetry;
+use crate::packet::Packet;
+use crate::types::VehicleIndex;
+
+/// Function that decodes the data of a single car
+type DecodeEntry<T> = fn(&mut Cursor<&[u8]>) -> Result<T, F1Error>;
+
+/// Packet sent by F1 2019 that borrows its data from the receive buffer
+///
+/// The view checks the header and the size of the packet when it is created. The data of the cars
+/// is decoded every time it is accessed, so that consumers only pay for the cars they look at.
+///
+/// # Examples
+///
+/// ```
+/// use f1_api::nineteen::view::PacketView;
+///
+/// fn fastest_lap(data: &[u8]) -> Option<f32> {
+///     let view = PacketView::new(data).ok()?;
+///
+///     view.laps()?
+///         .iter()
+///         .filter_map(|lap| lap.ok())
+///         .map(|lap| lap.best_lap_time().as_secs_f32())
+///         .filter(|time| *time > 0.0)
+///         .reduce(f32::min)
+/// }
+/// ```
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct PacketView<'a> {
+    data: &'a [u8],
+    header: Header,
+}
+
+impl<'a> PacketView<'a> {
+    /// Create a view of the packet in the data, and decode its header.
+    ///
+    /// Returns an error if the data does not start with a valid header, or if it is smaller than
+    /// the packet that the header announces.
+    pub fn new(data: &'a [u8]) -> Result<Self, F1Error> {
+        let mut cursor = Cursor::new(data);
+        let header = decode_header(&mut cursor)?;
+
+        cursor.set_position(0);
+        ensure_packet_size(packet_size(header.packet_type()), &mut cursor)?;
+
+        Ok(PacketView { data, header })
+    }
+
+    /// Returns the header of the packet.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Returns the raw data of the packet.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Decode the packet into its owned representation.
+    pub fn to_packet(&self) -> Result<Packet, F1Error> {
+        decode_nineteen(&mut Cursor::new(self.data))
+    }
+
+    /// Returns the lap data of the cars, if this is a lap data packet.
+    pub fn laps(&self) -> Option<CarEntries<'a, Lap>> {
+        self.entries(PacketType::Lap, HEADER_SIZE, lap::ENTRY_SIZE, decode_lap)
+    }
+
+    /// Returns the motion of the cars, if this is a motion packet.
+    #[cfg(feature = "motion")]
+    pub fn motion(&self) -> Option<CarEntries<'a, Motion>> {
+        self.entries(
+            PacketType::Motion,
+            HEADER_SIZE,
+            motion::ENTRY_SIZE,
+            decode_car_motion,
+        )
+    }
+
+    /// Returns the participants, if this is a participants packet.
+    pub fn participants(&self) -> Option<CarEntries<'a, Participant>> {
+        self.entries(
+            PacketType::Participants,
+            participants::ENTRIES_OFFSET,
+            participants::ENTRY_SIZE,
+            decode_participant,
+        )
+    }
+
+    /// Returns the number of active participants, if this is a participants packet.
+    pub fn active_participants_count(&self) -> Option<u8> {
+        self.is(PacketType::Participants)
+            .then(|| self.data[HEADER_SIZE])
+    }
+
+    /// Returns the name of a participant, if this is a participants packet.
+    ///
+    /// The name is decoded like the owned decoder does, and borrows the data of the packet unless
+    /// it contains invalid UTF-8, which is replaced with the replacement character.
+    pub fn participant_name(&self, vehicle_index: VehicleIndex) -> Option<Cow<'a, str>> {
+        let index = vehicle_index.as_usize();
+
+        if !self.is(PacketType::Participants) || index >= MAX_CARS {
+            return None;
+        }
+
+        let start = participants::ENTRIES_OFFSET
+            + index * participants::ENTRY_SIZE
+            + participants::NAME_OFFSET;
+
+        Some(decode_name(
+            &self.data[start..start + participants::NAME_SIZE],
+        ))
+    }
+
+    /// Returns the setups of the cars, if this is a car setup packet.
+    pub fn setups(&self) -> Option<CarEntries<'a, CarSetup>> {
+        self.entries(
+            PacketType::Setup,
+            HEADER_SIZE,
+            setup::ENTRY_SIZE,
+            decode_setup,
+        )
+    }
+
+    /// Returns the status of the cars, if this is a car status packet.
+    pub fn statuses(&self) -> Option<CarEntries<'a, CarStatus>> {
+        self.entries(
+            PacketType::Status,
+            HEADER_SIZE,
+            status::ENTRY_SIZE,
+            decode_status,
+        )
+    }
+
+    /// Returns the telemetry of the cars, if this is a telemetry packet.
+    #[cfg(feature = "telemetry")]
+    pub fn telemetry(&self) -> Option<CarEntries<'a, Telemetry>> {
+        self.entries(
+            PacketType::Telemetry,
+            HEADER_SIZE,
+            telemetry::ENTRY_SIZE,
+            decode_car_telemetry,
+        )
+    }
+
+    fn is(&self, packet_type: PacketType) -> bool {
+        self.header.packet_type() == packet_type
+    }
+
+    fn entries<T>(
+        &self,
+        packet_type: PacketType,
+        offset: usize,
+        size: usize,
+        decode: DecodeEntry<T>,
+    ) -> Option<CarEntries<'a, T>> {
+        self.is(packet_type).then_some(CarEntries {
+            data: self.data,
+            offset,
+            size,
+            decode,
+        })
+    }
+}
+
+/// Data of the cars in a packet that is decoded when it is accessed
+///
+/// Each access decodes the data of the car from the underlying bytes into an owned value, so that
+/// only the cars that are accessed are decoded. The values do not borrow the packet. Errors for
+/// invalid fields report the offset of the field from the start of the packet, like the owned
+/// decoders do.
+#[derive(Debug)]
+pub struct CarEntries<'a, T> {
+    data: &'a [u8],
+    offset: usize,
+    size: usize,
+    decode: DecodeEntry<T>,
+}
+
+impl<T> Clone for CarEntries<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for CarEntries<'_, T> {}
+
+impl<'a, T> CarEntries<'a, T> {
+    /// Returns the number of cars.
+    pub fn len(&self) -> usize {
+        MAX_CARS
+    }
+
+    /// Returns whether there are no cars.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Decode the data of the car with the given index, if the index is in range.
+    pub fn get(&self, vehicle_index: VehicleIndex) -> Option<Result<T, F1Error>> {
+        let index = vehicle_index.as_usize();
+
+        if index >= MAX_CARS {
+            return None;
+        }
+
+        let mut cursor = Cursor::new(self.data);
+        cursor.set_position((self.offset + index * self.size) as u64);
+
+        Some((self.decode)(&mut cursor))
+    }
+
+    /// Returns an iterator that decodes the data of each car in turn.
+    pub fn iter(&self) -> impl Iterator<Item = Result<T, F1Error>> + 'a
+    where
+        T: 'a,
+    {
+        let entries = *self;
+
+        VehicleIndex::all(ApiSpec::Nineteen).filter_map(move |index| entries.get(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::fixtures::vehicle;
+    use bytes::{BufMut, BytesMut};
+
+    use crate::error::F1Error;
+    use crate::nineteen::view::PacketView;
+    use crate::nineteen::{lap, participants};
+    use crate::packet::header::PacketType;
+    use crate::packet::lap::ResultStatus;
+    use crate::packet::Packet;
+
+    fn put_packet_header(mut bytes: BytesMut, packet_id: u8) -> BytesMut {
+        bytes.put_u16_le(2019);
+        bytes.put_u8(1);
+        bytes.put_u8(2);
+        bytes.put_u8(3);
+        bytes.put_u8(packet_id);
+        bytes.put_u64_le(u64::MAX);
+        bytes.put_f32_le(1.0);
+        bytes.put_u32_le(u32::MAX);
+        bytes.put_u8(0);
+
+        bytes
+    }
+
+    #[test]
+    fn view_lap_packet() {
+        let bytes = BytesMut::with_capacity(lap::PACKET_SIZE);
+        let mut bytes = put_packet_header(bytes, 2);
+
+        for position in 1..=20 {
+            bytes.put_f32_le(62.0);
+            bytes.put_f32_le(60.0);
+            bytes.put_f32_le(58.1);
+            bytes.put_f32_le(21.1);
+            bytes.put_f32_le(19.0);
+            bytes.put_f32_le(543.0);
+            bytes.put_f32_le(2048.4);
+            bytes.put_f32_le(0.0);
+            bytes.put_u8(position);
+            bytes.put_u8(4);
+            bytes.put_u8(0);
+            bytes.put_u8(2);
+            bytes.put_u8(0);
+            bytes.put_u8(0);
+            bytes.put_u8(position);
+            bytes.put_u8(1);
+            bytes.put_u8(2);
+        }
+
+        let view = PacketView::new(&bytes).unwrap();
+        assert_eq!(PacketType::Lap, view.header().packet_type());
+        assert!(view.participants().is_none());
+
+        let laps = view.laps().unwrap();
+        let lap = laps.get(vehicle(1)).unwrap().unwrap();
+        assert_eq!(2, lap.position());
+        assert_eq!(ResultStatus::Active, lap.result_status());
+        assert!(laps.get(vehicle(19)).unwrap().is_ok());
+        assert_eq!(20, laps.iter().filter(|lap| lap.is_ok()).count());
+
+        match view.to_packet().unwrap() {
+            Packet::Lap(packet) => assert_eq!(&lap, &packet.laps()[1]),
+            packet => panic!("Unexpected packet {:?}", packet),
+        }
+
+        bytes[23 + 41 + 35] = 9;
+        let view = PacketView::new(&bytes).unwrap();
+        assert!(matches!(
+            view.laps().unwrap().get(vehicle(1)),
+            Some(Err(F1Error::InvalidField { offset: 99, .. }))
+        ));
+
+        assert!(matches!(
+            PacketView::new(&bytes[..100]),
+            Err(F1Error::IncompleteData { .. })
+        ));
+    }
+
+    #[test]
+    fn view_participant_names() {
+        let bytes = BytesMut::with_capacity(participants::PACKET_SIZE);
+        let mut bytes = put_packet_header(bytes, 4);
+
+        bytes.put_u8(2);
+
+        for name in ["Pérez", "Player"].iter().chain([""; 18].iter()) {
+            bytes.put_u8(1);
+            bytes.put_u8(2);
+            bytes.put_u8(3);
+            bytes.put_u8(4);
+            bytes.put_u8(5);
+
+            let mut padded = [0u8; 48];
+            padded[..name.len()].copy_from_slice(name.as_bytes());
+            bytes.put(&padded[..]);
+
+            bytes.put_u8(0);
+        }
+
+        let view = PacketView::new(&bytes).unwrap();
+        assert_eq!(Some(2), view.active_participants_count());
+        assert_eq!(Some("Pérez"), view.participant_name(vehicle(0)).as_deref());
+        assert_eq!(Some("Player"), view.participant_name(vehicle(1)).as_deref());
+        assert_eq!(Some(""), view.participant_name(vehicle(2)).as_deref());
+        assert_eq!(Some(""), view.participant_name(vehicle(19)).as_deref());
+        assert_eq!(None, view.laps().map(|laps| laps.len()));
+
+        match view.to_packet().unwrap() {
+            Packet::Participants(packet) => {
+                assert_eq!("Pérez", packet.participants()[0].name());
+            }
+            packet => panic!("Unexpected packet {:?}", packet),
+        }
+    }
+}
@@ -3,10 +3,24 @@
 //! This library implements a single packet format for all the F1 games it supports. The API
 //! specification has been slowly evolving from game to game, but without such significant changes
 //! that it would require a different packet format.
+//!
+//! Tests and simulators that need to construct packets can start from their defaults, and set
+//! individual fields with the `with_` method of each field. The default header of a packet carries
+//! the type of the packet.
+//!
+//! ```
+//! use f1_api::packet::lap::{Lap, LapPacket};
+//!
+//! let packet = LapPacket::default().with_laps(vec![Lap::default().with_position(1)]);
+//! assert_eq!(1, packet.laps()[0].position());
+//! ```
 
-use std::io::{Cursor, Error, ErrorKind};
+use std::io::Cursor;
 
-use bytes::{Buf, BytesMut};
+use bytes::Buf;
+
+use crate::error::F1Error;
+use crate::packet::header::Header;
 
 pub mod event;
 pub mod header;
@@ -23,7 +37,12 @@ pub mod telemetry;
 /// The F1 games publish different packets with different data at different intervals. Each of these
 /// packets is decoded from UDP to their respective representation in this Rust crate. The `Packet`
 /// enum lists all packets that can be expected, and that a client should handle.
+///
+/// The decoders of each API specification convert their packets into these unified variants
+/// directly. There are no variants for the packets of a specific API specification, so a lap data
+/// packet is always a `Packet::Lap`, whichever game sent it.
 #[derive(Debug, PartialEq, Clone, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Packet {
     /// The F1 games send event packets whenever certain events occur in a session. Some event
     /// packets carry a payload with more information about the event.
@@ -56,6 +75,73 @@ pub enum Packet {
     Telemetry(telemetry::TelemetryPacket),
 }
 
+impl Packet {
+    /// Returns the header of the packet.
+    ///
+    /// Every packet is prefixed with a header, which carries the session, time, and frame the
+    /// packet belongs to. This method returns it without matching on the type of the packet.
+    pub fn header(&self) -> &Header {
+        match self {
+            Packet::Event(packet) => packet.header(),
+            Packet::Lap(packet) => packet.header(),
+            Packet::Motion(packet) => packet.header(),
+            Packet::Participants(packet) => packet.header(),
+            Packet::Session(packet) => packet.header(),
+            Packet::Setup(packet) => packet.header(),
+            Packet::Status(packet) => packet.header(),
+            Packet::Telemetry(packet) => packet.header(),
+        }
+    }
+}
+
+impl From<event::EventPacket> for Packet {
+    fn from(packet: event::EventPacket) -> Self {
+        Packet::Event(packet)
+    }
+}
+
+impl From<lap::LapPacket> for Packet {
+    fn from(packet: lap::LapPacket) -> Self {
+        Packet::Lap(packet)
+    }
+}
+
+impl From<motion::MotionPacket> for Packet {
+    fn from(packet: motion::MotionPacket) -> Self {
+        Packet::Motion(packet)
+    }
+}
+
+impl From<participants::ParticipantsPacket> for Packet {
+    fn from(packet: participants::ParticipantsPacket) -> Self {
+        Packet::Participants(packet)
+    }
+}
+
+impl From<session::SessionPacket> for Packet {
+    fn from(packet: session::SessionPacket) -> Self {
+        Packet::Session(packet)
+    }
+}
+
+impl From<setup::CarSetupPacket> for Packet {
+    fn from(packet: setup::CarSetupPacket) -> Self {
+        Packet::Setup(packet)
+    }
+}
+
+impl From<status::CarStatusPacket> for Packet {
+    fn from(packet: status::CarStatusPacket) -> Self {
+        Packet::Status(packet)
+    }
+}
+
+impl From<telemetry::TelemetryPacket> for Packet {
+    fn from(packet: telemetry::TelemetryPacket) -> Self {
+        Packet::Telemetry(packet)
+    }
+}
+
 /// Ensure a packet has the expected size
 ///
 /// Modern F1 games send their packets over UDP. Depending on their size, these packets might be
@@ -68,17 +154,13 @@ pub enum Packet {
 /// complete packet.
 pub(crate) fn ensure_packet_size(
     expected_size: usize,
-    cursor: &mut Cursor<&mut BytesMut>,
-) -> Result<(), Error> {
+    cursor: &mut Cursor<&[u8]>,
+) -> Result<(), F1Error> {
     if cursor.remaining() < expected_size {
-        Err(Error::new(
-            ErrorKind::UnexpectedEof,
-            format!(
-                "Packet is expected to have a size of {} bytes, but was {}.",
-                expected_size,
-                cursor.remaining()
-            ),
-        ))
+        Err(F1Error::IncompleteData {
+            expected: expected_size,
+            actual: cursor.remaining(),
+        })
     } else {
         Ok(())
     }
@@ -86,11 +168,17 @@ pub(crate) fn ensure_packet_size(
 
 #[cfg(test)]
 mod tests {
-    use std::io::{Cursor, Error};
+    use std::io::Cursor;
+    use std::time::Duration;
 
     use bytes::{Buf, BufMut, BytesMut};
 
+    use crate::analysis::fixtures::{event_packet, header, lap_packet, vehicle};
+    use crate::error::F1Error;
     use crate::packet::ensure_packet_size;
+    use crate::packet::event::{Event, EventPacket};
+    use crate::packet::header::PacketType;
+    use crate::packet::lap::{Lap, LapPacket};
 
     struct Packet {
         counter: u8,
@@ -98,7 +186,7 @@ mod tests {
 
     const PACKET_SIZE: usize = 1;
 
-    fn decode_packet(cursor: &mut Cursor<&mut BytesMut>) -> Result<Packet, Error> {
+    fn decode_packet(cursor: &mut Cursor<&[u8]>) -> Result<Packet, F1Error> {
         ensure_packet_size(PACKET_SIZE, cursor)?;
 
         Ok(Packet {
@@ -111,7 +199,7 @@ mod tests {
         let mut bytes = BytesMut::with_capacity(1);
         bytes.put_u8(0);
 
-        let mut cursor = Cursor::new(&mut bytes);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_packet(&mut cursor).unwrap();
         assert_eq!(0, packet.counter);
@@ -119,10 +207,40 @@ mod tests {
 
     #[test]
     fn ensure_packet_size_with_error() {
-        let mut bytes = BytesMut::with_capacity(0);
-        let mut cursor = Cursor::new(&mut bytes);
+        let bytes = BytesMut::with_capacity(0);
+        let mut cursor = Cursor::new(&bytes[..]);
 
         let packet = decode_packet(&mut cursor);
         assert!(packet.is_err());
     }
+
+    #[test]
+    fn header_of_every_packet() {
+        let lap = lap_packet(2.0, vec![]);
+        assert_eq!(PacketType::Lap, lap.header().packet_type());
+        assert_eq!(2, lap.header().session_time().as_secs());
+
+        let event = event_packet(3.0, Event::SessionStarted);
+        assert_eq!(PacketType::Event, event.header().packet_type());
+    }
+
+    #[test]
+    fn packet_from_event_packet() {
+        let event = EventPacket::new(header(PacketType::Event, 1.0), Event::SessionStarted);
+
+        let packet: crate::packet::Packet = event.into();
+        assert_eq!(crate::packet::Packet::Event(event), packet);
+    }
+
+    #[test]
+    fn build_packet_from_default() {
+        let lap = Lap::default()
+            .with_position(3)
+            .with_last_lap_time(Duration::from_secs(90));
+        let packet = LapPacket::default().with_laps(vec![lap]);
+
+        assert_eq!(PacketType::Lap, packet.header().packet_type());
+        assert_eq!(3, packet[vehicle(0)].position());
+        assert_eq!(&Duration::from_secs(90), packet[vehicle(0)].last_lap_time());
+    }
 }
@@ -9,9 +9,9 @@ use std::fmt::Display;
 use std::time::Duration;
 
 use derive_new::new;
-use getset::{CopyGetters, Getters};
+use getset::{CopyGetters, Getters, WithSetters};
 
-use crate::packet::header::Header;
+use crate::packet::header::{Header, PacketType};
 use crate::types::VehicleIndex;
 
 /// Payload for fastest lap event
@@ -24,9 +24,10 @@ use crate::types::VehicleIndex;
 ///
 /// ```
 /// # use f1_api::packet::event::{FastestLap, Event};
+/// # use f1_api::types::VehicleIndex;
 /// # use std::time::Duration;
 /// #
-/// # let fastest_lap = FastestLap::new(0, Duration::from_secs(62));
+/// # let fastest_lap = FastestLap::new(VehicleIndex::default(), Duration::from_secs(62));
 /// # let event = Event::FastestLap(fastest_lap);
 /// #
 /// // Simplified use in a match statement
@@ -39,15 +40,28 @@ use crate::types::VehicleIndex;
 /// }
 /// ```
 #[derive(
-    new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+    new,
+    Debug,
+    Getters,
+    CopyGetters,
+    WithSetters,
+    PartialEq,
+    Copy,
+    Clone,
+    Eq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Default,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FastestLap {
     /// Returns the index of the car achieving the fastest lap.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     vehicle_index: VehicleIndex,
 
     /// Returns the time of the fastest lap.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     time: Duration,
 }
 
@@ -70,8 +84,9 @@ impl Display for FastestLap {
 ///
 /// ```
 /// # use f1_api::packet::event::{Event, Retirement};
+/// # use f1_api::types::VehicleIndex;
 /// #
-/// # let retirement = Retirement::new(0);
+/// # let retirement = Retirement::new(VehicleIndex::default());
 /// # let event = Event::Retirement(retirement);
 /// #
 /// // Simplified use in a match statement
@@ -83,11 +98,24 @@ impl Display for FastestLap {
 /// }
 /// ```
 #[derive(
-    new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+    new,
+    Debug,
+    Getters,
+    CopyGetters,
+    WithSetters,
+    PartialEq,
+    Copy,
+    Clone,
+    Eq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Default,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Retirement {
     /// Returns the index of the car retiring.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     vehicle_index: VehicleIndex,
 }
 
@@ -106,8 +134,9 @@ impl Display for Retirement {
 ///
 /// ```
 /// # use f1_api::packet::event::{Event, TeammateInPits};
+/// # use f1_api::types::VehicleIndex;
 /// #
-/// # let teammate_in_pits = TeammateInPits::new(0);
+/// # let teammate_in_pits = TeammateInPits::new(VehicleIndex::default());
 /// # let event = Event::TeammatesInPits(teammate_in_pits);
 /// #
 /// // Simplified use in a match statement
@@ -119,11 +148,24 @@ impl Display for Retirement {
 /// }
 /// ```
 #[derive(
-    new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+    new,
+    Debug,
+    Getters,
+    CopyGetters,
+    WithSetters,
+    PartialEq,
+    Copy,
+    Clone,
+    Eq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Default,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeammateInPits {
     /// Returns the index of the teammate who has just entered the pits.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     vehicle_index: VehicleIndex,
 }
 
@@ -141,8 +183,9 @@ impl Display for TeammateInPits {
 ///
 /// ```
 /// # use f1_api::packet::event::{Event, RaceWinner};
+/// # use f1_api::types::VehicleIndex;
 /// #
-/// # let race_winner = RaceWinner::new(0);
+/// # let race_winner = RaceWinner::new(VehicleIndex::default());
 /// # let event = Event::RaceWinner(race_winner);
 /// #
 /// // Simplified use in a match statement
@@ -154,11 +197,24 @@ impl Display for TeammateInPits {
 /// }
 /// ```
 #[derive(
-    new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
+    new,
+    Debug,
+    Getters,
+    CopyGetters,
+    WithSetters,
+    PartialEq,
+    Copy,
+    Clone,
+    Eq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Default,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RaceWinner {
     /// Returns the index of the car that has won the race.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     vehicle_index: VehicleIndex,
 }
 
@@ -174,7 +230,8 @@ impl Display for RaceWinner {
 /// game, only a subset of the defined events may be published. Some events carry a payload that
 /// further describes the event. For example, the event declaring the race winner sends with it the
 /// vehicle index of said winner.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     /// The chequered flag signals the end of the race.
     ChequeredFlag,
@@ -202,18 +259,13 @@ pub enum Event {
     SessionEnded,
 
     /// The start of a session is announced in an event.
+    #[default]
     SessionStarted,
 
     /// When a teammate enters the pits, an event carrying their vehicle index is published.
     TeammatesInPits(TeammateInPits),
 }
 
-impl Default for Event {
-    fn default() -> Self {
-        Event::SessionStarted
-    }
-}
-
 impl Display for Event {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -242,17 +294,27 @@ impl Display for Event {
 /// The modern F1 games send event packets with details about events that occur in a session. The
 /// frequency with which these packets are sent is not fixed, but rather packets are sent whenever
 /// events occur.
-#[derive(new, Debug, Getters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[derive(new, Debug, Getters, WithSetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EventPacket {
     /// Returns the packet header prefixing the event packet.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     header: Header,
 
     /// Returns the event from the event packet.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     event: Event,
 }
 
+impl Default for EventPacket {
+    fn default() -> Self {
+        EventPacket {
+            header: Header::default().with_packet_type(PacketType::Event),
+            event: Default::default(),
+        }
+    }
+}
+
 impl Display for EventPacket {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
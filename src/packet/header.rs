@@ -5,7 +5,7 @@ use std::fmt::Display;
 use std::time::Duration;
 
 use derive_new::new;
-use getset::{CopyGetters, Getters};
+use getset::{CopyGetters, Getters, WithSetters};
 
 use crate::types::VehicleIndex;
 
@@ -14,17 +14,30 @@ use crate::types::VehicleIndex;
 /// The modern F1 games have their own API specifications, each an evolution of the previous one.
 /// Since the data published by each game is unique in one way or another, support for additional
 /// API specs has to be implemented manually.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ApiSpec {
+    #[default]
     Nineteen,
 }
 
+impl ApiSpec {
+    /// Returns the number of cars on the grid, which is the size of the per-car arrays in packets.
+    pub fn grid_size(&self) -> usize {
+        match self {
+            ApiSpec::Nineteen => 20,
+        }
+    }
+}
+
 /// Packets sent by F1 games
 ///
 /// The modern F1 games have divided their telemetry output into multiple packets, which can be sent
 /// at different intervals based on how quickly their data changes.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PacketType {
+    #[default]
     Event,
     Lap,
     Motion,
@@ -44,6 +57,7 @@ pub enum PacketType {
 #[derive(
     new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GameVersion {
     /// Returns the major version of the game.
     #[getset(get_copy = "pub")]
@@ -71,41 +85,65 @@ impl Display for GameVersion {
 /// the packet was created.
 ///
 /// TODO Verify that the session tie can be represented as a duration
-#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[derive(
+    new,
+    Debug,
+    Getters,
+    CopyGetters,
+    WithSetters,
+    PartialEq,
+    Copy,
+    Clone,
+    Eq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Default,
+)]
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Header {
     /// Returns the API specification that was used to decode the packet.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     api_spec: ApiSpec,
 
     /// Returns the version of the game.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     game_version: Option<GameVersion>,
 
     /// Returns the type of the packet.
     ///
     /// The packet type is only required to determine how to decode the packet. After decoding it,
     /// the packet type is represented by Rust's type system.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     packet_type: PacketType,
 
     /// Returns the unique session UID.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     session_uid: u64,
 
     /// Returns the session time at the time the packet was sent.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     session_time: Duration,
 
     /// Returns the frame identifier at the time the packet was sent.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     frame_identifier: u32,
 
     /// Returns the player's car index.
     ///
     /// The setups and status of cars are published as arrays. This field indicates which position
-    /// in these arrays the player's car has.
-    #[getset(get_copy = "pub")]
+    /// in these arrays the player's car has. When the player is spectating the session, for example
+    /// as a broadcaster, the index does not refer to a car that the player drives.
+    #[getset(get_copy = "pub", set_with = "pub")]
     player_car_index: VehicleIndex,
+
+    /// Returns the index of the secondary player's car in split-screen sessions.
+    ///
+    /// Only newer games publish the car of a secondary player. F1 2019 does not, and its headers
+    /// never have a secondary player.
+    #[getset(get_copy = "pub", set_with = "pub")]
+    secondary_player_car_index: Option<VehicleIndex>,
 }
 
 impl Display for Header {
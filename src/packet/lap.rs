@@ -4,17 +4,23 @@
 //! which the packets are sent can be configured in the game. F1 2018 and F1 2019 share the same
 //! packet format.
 
+use std::ops::Index;
 use std::time::Duration;
 
 use derive_new::new;
-use getset::{CopyGetters, Getters};
+use getset::{CopyGetters, Getters, MutGetters, WithSetters};
 
-use crate::packet::header::Header;
+use crate::car::cars_with;
+use crate::packet::header::{Header, PacketType};
+use crate::packet::participants::{Participant, ParticipantsPacket};
+use crate::types::{car_entry, VehicleIndex};
 
 /// Statuses a driver can have during a lap
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DriverStatus {
     /// The driver is still in the garage, and has not left it yet.
+    #[default]
     InGarage,
 
     /// The driver is on a flying lap, and cars that are on an in- or out-lap have to give room.
@@ -31,18 +37,17 @@ pub enum DriverStatus {
     /// The driver is on track, but not on a special lap. This is the case during a race or practice
     /// session, where drivers do many laps in a row.
     OnTrack,
-}
 
-impl Default for DriverStatus {
-    fn default() -> Self {
-        DriverStatus::InGarage
-    }
+    /// The status is unknown to this crate, and carries its raw identifier.
+    Unknown(u8),
 }
 
 /// Statuses used to signal the progression of a pit stop
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PitStatus {
     /// No pit stop is being performed, and the car is most likely on track or in the garage.
+    #[default]
     None,
 
     /// The car is pitting, which means it is on the pit lane but not stationary in the pit box.
@@ -50,18 +55,17 @@ pub enum PitStatus {
 
     /// The car is stationary in the pit box, and the pit stop is being performed.
     InPits,
-}
 
-impl Default for PitStatus {
-    fn default() -> Self {
-        PitStatus::None
-    }
+    /// The status is unknown to this crate, and carries its raw identifier.
+    Unknown(u8),
 }
 
 /// Statuses that classify the result
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ResultStatus {
     /// The results are invalid.
+    #[default]
     Invalid,
 
     /// The results are not being collected yet.
@@ -82,18 +86,17 @@ pub enum ResultStatus {
 
     /// The car has been retired.
     Retired,
-}
 
-impl Default for ResultStatus {
-    fn default() -> Self {
-        ResultStatus::Invalid
-    }
+    /// The status is unknown to this crate, and carries its raw identifier.
+    Unknown(u8),
 }
 
 /// The three sectors of a race track in F1
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Sector {
     /// The first sector
+    #[default]
     First,
 
     /// The second sector
@@ -103,66 +106,63 @@ pub enum Sector {
     Third,
 }
 
-impl Default for Sector {
-    fn default() -> Self {
-        Sector::First
-    }
-}
-
 /// Data about a car and its lap times
 ///
 /// For each car in the session, a set of lap data is published. It contains data on the current
 /// lap, e.g. the current lap time and the sector the car is currently in, but also the time of the
 /// last and best lap.
-#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+#[derive(
+    new, Debug, Getters, CopyGetters, WithSetters, PartialEq, Copy, Clone, PartialOrd, Default,
+)]
 #[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Lap {
     /// Returns the time of the last lap.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     last_lap_time: Duration,
 
     /// Returns the time of the current lap.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     current_lap_time: Duration,
 
     /// Returns the time of the best lap.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     best_lap_time: Duration,
 
     /// Returns the time spent in sector 1 during the current lap.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     sector1_time: Duration,
 
     /// Returns the time spent in sector 2 during the current lap.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     sector2_time: Duration,
 
     /// Returns the distance the car has travelled in the current lap in meters.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     lap_distance: f32,
 
     /// Returns the total distance the car has travelled in the session in meters.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     total_distance: f32,
 
     /// Returns the delta during a safety car in seconds.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     safety_car_delta: Duration,
 
     /// Returns a car's position in the race.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     position: u8,
 
     /// Returns the number of the current lap.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     current_lap_number: u8,
 
     /// Returns a car's pit stop status.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     pit_status: PitStatus,
 
     /// Returns the sector the car is currently in.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     sector: Sector,
 
     /// Returns whether the current lap is valid.
@@ -170,23 +170,23 @@ pub struct Lap {
     /// The F1 games apply different rules to determine if a lap is valid. Cutting the track, losing
     /// control, or hitting objects or opponents can all invalidate a lap. This is crucial for
     /// qualifying, where invalid laps might not count for the results.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     is_valid_lap: bool,
 
     /// Returns the accumulated penalties for a car in seconds.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     penalties: u8,
 
     /// Returns the grid position the car started the race in.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     grid_position: u8,
 
     /// Returns the status of the driver.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     driver_status: DriverStatus,
 
     /// Returns the status of the race results.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     result_status: ResultStatus,
 }
 
@@ -194,13 +194,59 @@ pub struct Lap {
 ///
 /// The F1 games publish a lap packet that contains data on all 20 cars in a session. The packet is
 /// sent at a fixed interval that can be configured in the game.
-#[derive(new, Debug, Getters, PartialEq, Clone, PartialOrd)]
+#[derive(new, Debug, Getters, MutGetters, WithSetters, PartialEq, Clone, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LapPacket {
     /// Returns the packet header prefixing the lap data packet.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     header: Header,
 
     /// Returns the laps for all 20 cars in a session.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", get_mut = "pub(crate)", set_with = "pub")]
     laps: Vec<Lap>,
 }
+
+impl Default for LapPacket {
+    fn default() -> Self {
+        LapPacket {
+            header: Header::default().with_packet_type(PacketType::Lap),
+            laps: Default::default(),
+        }
+    }
+}
+
+impl LapPacket {
+    /// Returns the lap of a car, if the vehicle index refers to a car in the packet.
+    pub fn get(&self, vehicle_index: VehicleIndex) -> Option<&Lap> {
+        car_entry(&self.laps, vehicle_index)
+    }
+
+    /// Returns the lap of the player's car, if the player is driving a car.
+    pub fn player(&self) -> Option<&Lap> {
+        self.get(self.header.player_car_index())
+    }
+
+    /// Returns the lap of the secondary player's car in split-screen sessions.
+    pub fn secondary_player(&self) -> Option<&Lap> {
+        self.header
+            .secondary_player_car_index()
+            .and_then(|vehicle_index| self.get(vehicle_index))
+    }
+
+    /// Returns the laps of the active cars, with the index and the participant of each car.
+    pub fn cars_with<'a>(
+        &'a self,
+        participants: &'a ParticipantsPacket,
+    ) -> impl Iterator<Item = (VehicleIndex, &'a Participant, &'a Lap)> {
+        cars_with(participants, &self.laps)
+    }
+}
+
+impl Index<VehicleIndex> for LapPacket {
+    type Output = Lap;
+
+    /// Returns the lap of a car, and panics if the vehicle index refers to no car in the packet.
+    fn index(&self, vehicle_index: VehicleIndex) -> &Lap {
+        &self.laps[vehicle_index.as_usize()]
+    }
+}
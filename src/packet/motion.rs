@@ -4,52 +4,59 @@
 //! motion packet. The rate with which these packets are sent can be configured in the game. F1 2018
 //! and F1 2019 publish the same motion data.
 
+use std::ops::Index;
+
 use derive_new::new;
-use getset::{CopyGetters, Getters};
+use getset::{CopyGetters, Getters, MutGetters, WithSetters};
 
-use crate::packet::header::Header;
-use crate::types::{CornerProperty, Property3D};
+use crate::car::cars_with;
+use crate::packet::header::{Header, PacketType};
+use crate::packet::participants::{Participant, ParticipantsPacket};
+use crate::types::{car_entry, CornerProperty, Property3D, VehicleIndex};
 
 /// Data about a car and its position and movement in space
 ///
 /// The position and movement of each car in a session is described in the motion packet.
-#[derive(new, Debug, CopyGetters, Getters, PartialEq, Copy, Clone, PartialOrd, Default)]
+#[derive(
+    new, Debug, CopyGetters, Getters, WithSetters, PartialEq, Copy, Clone, PartialOrd, Default,
+)]
 #[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Motion {
     /// Returns the position of the car in 3D space.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     position: Property3D<f32>,
 
     /// Returns the velocity of the car on three axis.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     velocity: Property3D<f32>,
 
     /// Returns the normalized forward motion of the car on three axis.
     ///
     /// Normalized values can be converted to float through division by 32767.0f.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     forward_direction: Property3D<i16>,
 
     /// Returns the normalized lateral motion of the car on three axis.
     ///
     /// Normalized values can be converted to float through division by 32767.0f.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     right_direction: Property3D<i16>,
 
     /// Returns the G force on the car on each of the three axis.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     g_force: Property3D<f32>,
 
     /// Returns the yaw angle of the car in radians.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     yaw: f32,
 
     /// Returns the pitch angle of the car in radians.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     pitch: f32,
 
     /// Returns the roll angle of the car in radians.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     roll: f32,
 }
 
@@ -59,50 +66,107 @@ pub struct Motion {
 /// publicly observable properties for most cars, e.g. the position and movement of a car. For the
 /// player's car, additional motion data is published, e.g. various physical forces on the car and
 /// its suspension.
-#[derive(new, Debug, CopyGetters, Getters, PartialEq, Clone, PartialOrd)]
+#[derive(
+    new, Debug, CopyGetters, Getters, MutGetters, WithSetters, PartialEq, Clone, PartialOrd,
+)]
 #[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MotionPacket {
     /// Returns the packet header prefixing the motion packet.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     header: Header,
 
     /// Returns the publicly observable motion data for all 20 cars in the session.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", get_mut = "pub(crate)", set_with = "pub")]
     cars: Vec<Motion>,
 
     /// Returns the position of the suspension at each corner of the car.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     suspension_position: CornerProperty<f32>,
 
     /// Returns the velocity of the suspension at each corner of the car.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     suspension_velocity: CornerProperty<f32>,
 
     /// Returns the acceleration of the suspension at each corner of the car.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     suspension_acceleration: CornerProperty<f32>,
 
     /// Returns the wheel speed at each corner of the car.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     wheel_speed: CornerProperty<f32>,
 
     /// Returns the wheel slip at each corner of the car.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     wheel_slip: CornerProperty<f32>,
 
     /// Returns the velocity in local space on each axis.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     local_velocity: Property3D<f32>,
 
     /// Returns the angular velocity on each axis.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     angular_velocity: Property3D<f32>,
 
     /// Returns the angular acceleration on each axis.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     angular_acceleration: Property3D<f32>,
 
     /// Returns the current angle of the front wheels in radians.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     front_wheels_angle: f32,
 }
+
+impl Default for MotionPacket {
+    fn default() -> Self {
+        MotionPacket {
+            header: Header::default().with_packet_type(PacketType::Motion),
+            cars: Default::default(),
+            suspension_position: Default::default(),
+            suspension_velocity: Default::default(),
+            suspension_acceleration: Default::default(),
+            wheel_speed: Default::default(),
+            wheel_slip: Default::default(),
+            local_velocity: Default::default(),
+            angular_velocity: Default::default(),
+            angular_acceleration: Default::default(),
+            front_wheels_angle: Default::default(),
+        }
+    }
+}
+
+impl MotionPacket {
+    /// Returns the motion data of a car, if the vehicle index refers to a car in the packet.
+    pub fn get(&self, vehicle_index: VehicleIndex) -> Option<&Motion> {
+        car_entry(&self.cars, vehicle_index)
+    }
+
+    /// Returns the motion data of the player's car, if the player is driving a car.
+    pub fn player(&self) -> Option<&Motion> {
+        self.get(self.header.player_car_index())
+    }
+
+    /// Returns the motion data of the secondary player's car in split-screen sessions.
+    pub fn secondary_player(&self) -> Option<&Motion> {
+        self.header
+            .secondary_player_car_index()
+            .and_then(|vehicle_index| self.get(vehicle_index))
+    }
+
+    /// Returns the motion data of the active cars, with the index and the participant of each car.
+    pub fn cars_with<'a>(
+        &'a self,
+        participants: &'a ParticipantsPacket,
+    ) -> impl Iterator<Item = (VehicleIndex, &'a Participant, &'a Motion)> {
+        cars_with(participants, &self.cars)
+    }
+}
+
+impl Index<VehicleIndex> for MotionPacket {
+    type Output = Motion;
+
+    /// Returns the motion data of a car, and panics if the vehicle index refers to no car in the packet.
+    fn index(&self, vehicle_index: VehicleIndex) -> &Motion {
+        &self.cars[vehicle_index.as_usize()]
+    }
+}
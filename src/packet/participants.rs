@@ -3,31 +3,34 @@
 //! The F1 games provide information about each participant in a session, for example their name,
 //! team, and nationality. The data is updated every 5 seconds.
 
-use crate::packet::header::Header;
 use derive_new::new;
-use getset::{CopyGetters, Getters};
+use getset::{CopyGetters, Getters, MutGetters, WithSetters};
+
+use crate::packet::header::{Header, PacketType};
+use crate::types::car_entry;
 
 /// Controller of a car
 ///
 /// Cars can either be controlled by a human player or the AI.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+///
+/// Controllers that are unknown to this crate are decoded as `Unknown` with their raw identifier.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Controller {
+    #[default]
     AI,
     Human,
-}
-
-impl Default for Controller {
-    fn default() -> Self {
-        Controller::AI
-    }
+    Unknown(u8),
 }
 
 /// Drivers that appear in the F1 games
 ///
 /// The F1 games feature a long list of drivers that appear in the games. Not every driver is
 /// available in every game, and some drivers might be in a F2 championship in one game, and in F1
-/// in the next.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+/// in the next. Drivers that are unknown to this crate, for example because they were added to the
+/// game in an update, are decoded as `Unknown` with the raw identifier of the driver.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Driver {
     AlainForest,
     AlessioLorandi,
@@ -80,6 +83,8 @@ pub enum Driver {
     MickSchumacher,
     NaotaIzum,
     NicholasLatifi,
+    // Open a PR to change this and I will block you!
+    #[default]
     NicoHulkenburg,
     NikitaMazepin,
     NikoKari,
@@ -106,20 +111,117 @@ pub enum Driver {
     ValtteriBottas,
     WilheimKaufmann,
     YasarAtiyeh,
+
+    /// A human player in an online or LAN session, who is not assigned a driver by the game.
+    Human,
+
+    /// A driver that is unknown to this crate, with the raw identifier of the driver.
+    Unknown(u8),
 }
 
-impl Default for Driver {
-    fn default() -> Self {
-        // Open a PR to change this and I will block you!
-        Driver::NicoHulkenburg
+impl Driver {
+    /// Returns the name of the driver as it is shown to users.
+    ///
+    /// Human players are not assigned a driver, and their name is only published in the
+    /// participants packet.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Driver::AlainForest => "Alain Forest",
+            Driver::AlessioLorandi => "Alessio Lorandi",
+            Driver::AlexMurray => "Alex Murray",
+            Driver::AlexanderAlbon => "Alexander Albon",
+            Driver::AnthoineHubert => "Anthoine Hubert",
+            Driver::AntonioFuoco => "Antonio Fuoco",
+            Driver::AntonioGiovinazzi => "Antonio Giovinazzi",
+            Driver::ArjunMaini => "Arjun Maini",
+            Driver::ArronBarnes => "Arron Barnes",
+            Driver::ArtemMarkelov => "Artem Markelov",
+            Driver::BenjaminCoppens => "Benjamin Coppens",
+            Driver::CallistoCalabresi => "Callisto Calabresi",
+            Driver::CallumIlott => "Callum Ilott",
+            Driver::CarlosSainz => "Carlos Sainz",
+            Driver::CharlesLeclerc => "Charles Leclerc",
+            Driver::DanielJones => "Daniel Jones",
+            Driver::DanielRicciardo => "Daniel Ricciardo",
+            Driver::DaniilKvyat => "Daniil Kvyat",
+            Driver::DorianBoccolacci => "Dorian Boccolacci",
+            Driver::EstoSaari => "Esto Saari",
+            Driver::FlavioNieves => "Flavio Nieves",
+            Driver::GeorgeRussell => "George Russell",
+            Driver::GertWaldmuller => "Gert Waldmüller",
+            Driver::GuanyaZhou => "Guanyu Zhou",
+            Driver::GuilianoAlesi => "Guiliano Alesi",
+            Driver::HowardClarke => "Howard Clarke",
+            Driver::IgorCorreia => "Igor Correia",
+            Driver::JackAitken => "Jack Aitken",
+            Driver::JackTremblay => "Jack Tremblay",
+            Driver::JayLetourneau => "Jay Letourneau",
+            Driver::JonasSchiffer => "Jonas Schiffer",
+            Driver::JordanKing => "Jordan King",
+            Driver::JuanManuelCorrea => "Juan Manuel Correa",
+            Driver::JulianQuesada => "Julian Quesada",
+            Driver::KevinMagnussen => "Kevin Magnussen",
+            Driver::KimiRaikkonen => "Kimi Räikkönen",
+            Driver::KlimekMichalski => "Klimek Michalski",
+            Driver::LanceStroll => "Lance Stroll",
+            Driver::LandoNorris => "Lando Norris",
+            Driver::LewisHamilton => "Lewis Hamilton",
+            Driver::LouisDeletraz => "Louis Delétraz",
+            Driver::LucaGhiotto => "Luca Ghiotto",
+            Driver::LucasRoth => "Lucas Roth",
+            Driver::MahaveerRaghunathan => "Mahaveer Raghunathan",
+            Driver::MarieLaursen => "Marie Laursen",
+            Driver::MartinGiles => "Martin Giles",
+            Driver::MaxVerstappen => "Max Verstappen",
+            Driver::MaximilianGunther => "Maximilian Günther",
+            Driver::MickSchumacher => "Mick Schumacher",
+            Driver::NaotaIzum => "Naota Izumi",
+            Driver::NicholasLatifi => "Nicholas Latifi",
+            Driver::NicoHulkenburg => "Nico Hülkenberg",
+            Driver::NikitaMazepin => "Nikita Mazepin",
+            Driver::NikoKari => "Niko Kari",
+            Driver::NireiFukuzumi => "Nirei Fukuzumi",
+            Driver::NoahVisser => "Noah Visser",
+            Driver::NobuharuMatsushita => "Nobuharu Matsushita",
+            Driver::NyckDeVries => "Nyck de Vries",
+            Driver::PeterBelousov => "Peter Belousov",
+            Driver::PierreGasly => "Pierre Gasly",
+            Driver::RalphBoschung => "Ralph Boschung",
+            Driver::RashidNair => "Rashid Nair",
+            Driver::RobertKubica => "Robert Kubica",
+            Driver::RobertoMerhi => "Roberto Merhi",
+            Driver::RomainGrosjean => "Romain Grosjean",
+            Driver::RubenMeijer => "Ruben Meijer",
+            Driver::SantiagoMoreno => "Santiago Moreno",
+            Driver::SeanGelael => "Sean Gelael",
+            Driver::SebastianVettel => "Sebastian Vettel",
+            Driver::SergioPerez => "Sergio Pérez",
+            Driver::SergioSetteCamara => "Sérgio Sette Câmara",
+            Driver::SophieLevasseur => "Sophie Levasseur",
+            Driver::TadasukeMakino => "Tadasuke Makino",
+            Driver::TatianaCalderon => "Tatiana Calderon",
+            Driver::ValtteriBottas => "Valtteri Bottas",
+            Driver::WilheimKaufmann => "Wilheim Kaufmann",
+            Driver::YasarAtiyeh => "Yasar Atiyeh",
+            Driver::Human => "Human",
+            Driver::Unknown(_) => "Unknown driver",
+        }
+    }
+
+    /// Returns whether the driver is a human player in an online or LAN session.
+    pub fn is_human(&self) -> bool {
+        *self == Driver::Human
     }
 }
 
 /// Teams that appear in the F1 games
 ///
 /// The F1 games feature a long list of teams that appear in the games, with some teams only being
-/// available in certain games.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+/// available in certain games. The identifiers of the teams change between games, and are mapped to
+/// this enum by the decoder for each API specification. Teams without a mapping are decoded as
+/// `Unknown` with the raw identifier of the team.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Team {
     ARTGrandPrix,
     AlfaRomeo,
@@ -156,6 +258,8 @@ pub enum Team {
     McLaren1991,
     McLaren1998,
     McLaren2010,
+    // We don't really have a choice in the hybrid area.
+    #[default]
     Mercedes,
     Pertamina,
     Prema2019,
@@ -174,20 +278,158 @@ pub enum Team {
     Williams1992,
     Williams1996,
     Williams2003,
+    Unknown(u8),
 }
 
-impl Default for Team {
-    fn default() -> Self {
-        // We don't really have a choice in the hybrid area.
-        Team::Mercedes
+impl Team {
+    /// Returns the name of the team as it is shown to users.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Team::ARTGrandPrix => "ART Grand Prix",
+            Team::AlfaRomeo => "Alfa Romeo Racing",
+            Team::Arden2019 => "BWT Arden 2019",
+            Team::ArtGP2019 => "ART Grand Prix 2019",
+            Team::BWTArden => "BWT Arden",
+            Team::Brawn2009 => "Brawn GP 2009",
+            Team::Campos2019 => "Campos Racing 2019",
+            Team::CamposVexatecRacing => "Campos Vexatec Racing",
+            Team::Carlin => "Carlin",
+            Team::Carlin2019 => "Carlin 2019",
+            Team::CharouzRacingSystem => "Charouz Racing System",
+            Team::DAMS => "DAMS",
+            Team::Dams2019 => "DAMS 2019",
+            Team::Ferrari => "Scuderia Ferrari",
+            Team::Ferrari1976 => "Ferrari 1976",
+            Team::Ferrari1979 => "Ferrari 1979",
+            Team::Ferrari1990 => "Ferrari 1990",
+            Team::Ferrari1995 => "Ferrari 1995",
+            Team::Ferrari2002 => "Ferrari 2002",
+            Team::Ferrari2004 => "Ferrari 2004",
+            Team::Ferrari2007 => "Ferrari 2007",
+            Team::Ferrari2010 => "Ferrari 2010",
+            Team::Haas => "Haas F1 Team",
+            Team::Lotus1972 => "Lotus 1972",
+            Team::Lotus1978 => "Lotus 1978",
+            Team::MPMotorsport => "MP Motorsport",
+            Team::MPMotorsport2019 => "MP Motorsport 2019",
+            Team::McLaren => "McLaren F1 Team",
+            Team::McLaren1976 => "McLaren 1976",
+            Team::McLaren1982 => "McLaren 1982",
+            Team::McLaren1988 => "McLaren 1988",
+            Team::McLaren1990 => "McLaren 1990",
+            Team::McLaren1991 => "McLaren 1991",
+            Team::McLaren1998 => "McLaren 1998",
+            Team::McLaren2010 => "McLaren 2010",
+            Team::Mercedes => "Mercedes-AMG Petronas",
+            Team::Pertamina => "Pertamina Arden",
+            Team::Prema2019 => "Prema Racing 2019",
+            Team::RacingPoint => "Racing Point",
+            Team::RedBull2010 => "Red Bull Racing 2010",
+            Team::RedBullRacing => "Red Bull Racing",
+            Team::Renault => "Renault F1 Team",
+            Team::Renault2006 => "Renault 2006",
+            Team::RussianTime => "Russian Time",
+            Team::SauberJuniorCharouz2019 => "Sauber Junior Team by Charouz 2019",
+            Team::ToroRosso => "Scuderia Toro Rosso",
+            Team::Trident => "Trident",
+            Team::Trident2019 => "Trident 2019",
+            Team::UniVirtuosi2019 => "UNI-Virtuosi 2019",
+            Team::Williams => "Williams Racing",
+            Team::Williams1992 => "Williams 1992",
+            Team::Williams1996 => "Williams 1996",
+            Team::Williams2003 => "Williams 2003",
+            Team::Unknown(_) => "Unknown team",
+        }
+    }
+
+    /// Returns the primary color of the team's livery.
+    pub fn color(&self) -> Color {
+        match self {
+            Team::ARTGrandPrix => Color::new(0x1E, 0x1E, 0x1E),
+            Team::AlfaRomeo => Color::new(0x9B, 0x00, 0x00),
+            Team::Arden2019 => Color::new(0xE2, 0x00, 0x7A),
+            Team::ArtGP2019 => Color::new(0x1E, 0x1E, 0x1E),
+            Team::BWTArden => Color::new(0xE2, 0x00, 0x7A),
+            Team::Brawn2009 => Color::new(0xFF, 0xFF, 0xFF),
+            Team::Campos2019 => Color::new(0x00, 0x2C, 0x5F),
+            Team::CamposVexatecRacing => Color::new(0x00, 0x2C, 0x5F),
+            Team::Carlin => Color::new(0x00, 0x3A, 0x70),
+            Team::Carlin2019 => Color::new(0x00, 0x3A, 0x70),
+            Team::CharouzRacingSystem => Color::new(0x00, 0x20, 0x4E),
+            Team::DAMS => Color::new(0x00, 0xA3, 0xE0),
+            Team::Dams2019 => Color::new(0x00, 0xA3, 0xE0),
+            Team::Ferrari => Color::new(0xDC, 0x00, 0x00),
+            Team::Ferrari1976 => Color::new(0xDC, 0x00, 0x00),
+            Team::Ferrari1979 => Color::new(0xDC, 0x00, 0x00),
+            Team::Ferrari1990 => Color::new(0xDC, 0x00, 0x00),
+            Team::Ferrari1995 => Color::new(0xDC, 0x00, 0x00),
+            Team::Ferrari2002 => Color::new(0xDC, 0x00, 0x00),
+            Team::Ferrari2004 => Color::new(0xDC, 0x00, 0x00),
+            Team::Ferrari2007 => Color::new(0xDC, 0x00, 0x00),
+            Team::Ferrari2010 => Color::new(0xDC, 0x00, 0x00),
+            Team::Haas => Color::new(0xBD, 0x9E, 0x57),
+            Team::Lotus1972 => Color::new(0x00, 0x00, 0x00),
+            Team::Lotus1978 => Color::new(0x00, 0x00, 0x00),
+            Team::MPMotorsport => Color::new(0xFF, 0x6A, 0x13),
+            Team::MPMotorsport2019 => Color::new(0xFF, 0x6A, 0x13),
+            Team::McLaren => Color::new(0xFF, 0x87, 0x00),
+            Team::McLaren1976 => Color::new(0xFF, 0x87, 0x00),
+            Team::McLaren1982 => Color::new(0xFF, 0xFF, 0xFF),
+            Team::McLaren1988 => Color::new(0xFF, 0xFF, 0xFF),
+            Team::McLaren1990 => Color::new(0xFF, 0xFF, 0xFF),
+            Team::McLaren1991 => Color::new(0xFF, 0xFF, 0xFF),
+            Team::McLaren1998 => Color::new(0xC0, 0xC0, 0xC0),
+            Team::McLaren2010 => Color::new(0xC0, 0xC0, 0xC0),
+            Team::Mercedes => Color::new(0x00, 0xD2, 0xBE),
+            Team::Pertamina => Color::new(0xE2, 0x00, 0x7A),
+            Team::Prema2019 => Color::new(0xD5, 0x00, 0x00),
+            Team::RacingPoint => Color::new(0xF5, 0x96, 0xC8),
+            Team::RedBull2010 => Color::new(0x1E, 0x41, 0xFF),
+            Team::RedBullRacing => Color::new(0x1E, 0x41, 0xFF),
+            Team::Renault => Color::new(0xFF, 0xF5, 0x00),
+            Team::Renault2006 => Color::new(0x00, 0x50, 0xA0),
+            Team::RussianTime => Color::new(0xC8, 0x10, 0x2E),
+            Team::SauberJuniorCharouz2019 => Color::new(0x00, 0x20, 0x4E),
+            Team::ToroRosso => Color::new(0x46, 0x9B, 0xFF),
+            Team::Trident => Color::new(0x00, 0x32, 0xA0),
+            Team::Trident2019 => Color::new(0x00, 0x32, 0xA0),
+            Team::UniVirtuosi2019 => Color::new(0xFF, 0xD7, 0x00),
+            Team::Williams => Color::new(0xFF, 0xFF, 0xFF),
+            Team::Williams1992 => Color::new(0x00, 0x3C, 0x8C),
+            Team::Williams1996 => Color::new(0x00, 0x3C, 0x8C),
+            Team::Williams2003 => Color::new(0x00, 0x3C, 0x8C),
+            Team::Unknown(_) => Color::new(0x80, 0x80, 0x80),
+        }
     }
 }
 
+/// Color in the RGB color space
+///
+/// Teams are commonly identified by the primary color of their livery, for example in timing
+/// screens and charts.
+#[derive(new, Debug, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Color {
+    /// Returns the red channel of the color.
+    #[getset(get_copy = "pub")]
+    red: u8,
+
+    /// Returns the green channel of the color.
+    #[getset(get_copy = "pub")]
+    green: u8,
+
+    /// Returns the blue channel of the color.
+    #[getset(get_copy = "pub")]
+    blue: u8,
+}
+
 /// Nationalities that appear in the F1 games
 ///
 /// The F1 games feature a long list of drivers and teams, all of which have different
-/// nationalities.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+/// nationalities. Nationalities that are unknown to this crate are decoded as `Unknown` with the
+/// raw identifier of the nationality.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Nationality {
     American,
     Argentinean,
@@ -217,6 +459,8 @@ pub enum Nationality {
     Estonian,
     Finnish,
     French,
+    // Greetings from the Nürburg!
+    #[default]
     German,
     Ghanaian,
     Greek,
@@ -275,12 +519,198 @@ pub enum Nationality {
     Uruguayan,
     Venezuelan,
     Welsh,
+    Unknown(u8),
 }
 
-impl Default for Nationality {
-    fn default() -> Self {
-        // Greetings from the Nürburg!
-        Nationality::German
+impl Nationality {
+    /// Returns the name of the nationality as it is shown to users.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Nationality::American => "American",
+            Nationality::Argentinean => "Argentinean",
+            Nationality::Australian => "Australian",
+            Nationality::Austrian => "Austrian",
+            Nationality::Azerbaijani => "Azerbaijani",
+            Nationality::Bahraini => "Bahraini",
+            Nationality::Belgian => "Belgian",
+            Nationality::Bolivian => "Bolivian",
+            Nationality::Brazilian => "Brazilian",
+            Nationality::British => "British",
+            Nationality::Bulgarian => "Bulgarian",
+            Nationality::Cameroonian => "Cameroonian",
+            Nationality::Canadian => "Canadian",
+            Nationality::Chilean => "Chilean",
+            Nationality::Chinese => "Chinese",
+            Nationality::Colombian => "Colombian",
+            Nationality::CostaRican => "Costa Rican",
+            Nationality::Croatian => "Croatian",
+            Nationality::Cypriot => "Cypriot",
+            Nationality::Czech => "Czech",
+            Nationality::Danish => "Danish",
+            Nationality::Dutch => "Dutch",
+            Nationality::Ecuadorian => "Ecuadorian",
+            Nationality::Emirian => "Emirati",
+            Nationality::English => "English",
+            Nationality::Estonian => "Estonian",
+            Nationality::Finnish => "Finnish",
+            Nationality::French => "French",
+            Nationality::German => "German",
+            Nationality::Ghanaian => "Ghanaian",
+            Nationality::Greek => "Greek",
+            Nationality::Guatemalan => "Guatemalan",
+            Nationality::Honduran => "Honduran",
+            Nationality::HongKonger => "Hong Konger",
+            Nationality::Hungarian => "Hungarian",
+            Nationality::Icelander => "Icelander",
+            Nationality::Indian => "Indian",
+            Nationality::Indonesian => "Indonesian",
+            Nationality::Irish => "Irish",
+            Nationality::Israeli => "Israeli",
+            Nationality::Italian => "Italian",
+            Nationality::Jamaican => "Jamaican",
+            Nationality::Japanese => "Japanese",
+            Nationality::Jordanian => "Jordanian",
+            Nationality::Kuwaiti => "Kuwaiti",
+            Nationality::Latvian => "Latvian",
+            Nationality::Lebanese => "Lebanese",
+            Nationality::Lithuanian => "Lithuanian",
+            Nationality::Luxembourger => "Luxembourger",
+            Nationality::Malaysian => "Malaysian",
+            Nationality::Maltese => "Maltese",
+            Nationality::Mexican => "Mexican",
+            Nationality::Monegasque => "Monegasque",
+            Nationality::NewZealander => "New Zealander",
+            Nationality::Nicaraguan => "Nicaraguan",
+            Nationality::NorthKorean => "North Korean",
+            Nationality::NorthernIrish => "Northern Irish",
+            Nationality::Norwegian => "Norwegian",
+            Nationality::Omani => "Omani",
+            Nationality::Pakistani => "Pakistani",
+            Nationality::Panamanian => "Panamanian",
+            Nationality::Paraguayan => "Paraguayan",
+            Nationality::Peruvian => "Peruvian",
+            Nationality::Polish => "Polish",
+            Nationality::Portuguese => "Portuguese",
+            Nationality::Qatari => "Qatari",
+            Nationality::Romanian => "Romanian",
+            Nationality::Russian => "Russian",
+            Nationality::Salvadoran => "Salvadoran",
+            Nationality::Saudi => "Saudi",
+            Nationality::Scottish => "Scottish",
+            Nationality::Serbian => "Serbian",
+            Nationality::Singaporean => "Singaporean",
+            Nationality::Slovakian => "Slovakian",
+            Nationality::Slovenian => "Slovenian",
+            Nationality::SouthAfrican => "South African",
+            Nationality::SouthKorean => "South Korean",
+            Nationality::Spanish => "Spanish",
+            Nationality::Swedish => "Swedish",
+            Nationality::Swiss => "Swiss",
+            Nationality::Thai => "Thai",
+            Nationality::Turkish => "Turkish",
+            Nationality::Ukrainian => "Ukrainian",
+            Nationality::Uruguayan => "Uruguayan",
+            Nationality::Venezuelan => "Venezuelan",
+            Nationality::Welsh => "Welsh",
+            Nationality::Unknown(_) => "Unknown",
+        }
+    }
+
+    /// Returns the ISO 3166-1 alpha-2 code of the country.
+    ///
+    /// The constituent countries of the United Kingdom use their ISO 3166-2 subdivision codes,
+    /// since the games distinguish them from British drivers. Unknown nationalities return the
+    /// user-assigned code `XX`.
+    pub fn country_code(&self) -> &'static str {
+        match self {
+            Nationality::American => "US",
+            Nationality::Argentinean => "AR",
+            Nationality::Australian => "AU",
+            Nationality::Austrian => "AT",
+            Nationality::Azerbaijani => "AZ",
+            Nationality::Bahraini => "BH",
+            Nationality::Belgian => "BE",
+            Nationality::Bolivian => "BO",
+            Nationality::Brazilian => "BR",
+            Nationality::British => "GB",
+            Nationality::Bulgarian => "BG",
+            Nationality::Cameroonian => "CM",
+            Nationality::Canadian => "CA",
+            Nationality::Chilean => "CL",
+            Nationality::Chinese => "CN",
+            Nationality::Colombian => "CO",
+            Nationality::CostaRican => "CR",
+            Nationality::Croatian => "HR",
+            Nationality::Cypriot => "CY",
+            Nationality::Czech => "CZ",
+            Nationality::Danish => "DK",
+            Nationality::Dutch => "NL",
+            Nationality::Ecuadorian => "EC",
+            Nationality::Emirian => "AE",
+            Nationality::English => "GB-ENG",
+            Nationality::Estonian => "EE",
+            Nationality::Finnish => "FI",
+            Nationality::French => "FR",
+            Nationality::German => "DE",
+            Nationality::Ghanaian => "GH",
+            Nationality::Greek => "GR",
+            Nationality::Guatemalan => "GT",
+            Nationality::Honduran => "HN",
+            Nationality::HongKonger => "HK",
+            Nationality::Hungarian => "HU",
+            Nationality::Icelander => "IS",
+            Nationality::Indian => "IN",
+            Nationality::Indonesian => "ID",
+            Nationality::Irish => "IE",
+            Nationality::Israeli => "IL",
+            Nationality::Italian => "IT",
+            Nationality::Jamaican => "JM",
+            Nationality::Japanese => "JP",
+            Nationality::Jordanian => "JO",
+            Nationality::Kuwaiti => "KW",
+            Nationality::Latvian => "LV",
+            Nationality::Lebanese => "LB",
+            Nationality::Lithuanian => "LT",
+            Nationality::Luxembourger => "LU",
+            Nationality::Malaysian => "MY",
+            Nationality::Maltese => "MT",
+            Nationality::Mexican => "MX",
+            Nationality::Monegasque => "MC",
+            Nationality::NewZealander => "NZ",
+            Nationality::Nicaraguan => "NI",
+            Nationality::NorthKorean => "KP",
+            Nationality::NorthernIrish => "GB-NIR",
+            Nationality::Norwegian => "NO",
+            Nationality::Omani => "OM",
+            Nationality::Pakistani => "PK",
+            Nationality::Panamanian => "PA",
+            Nationality::Paraguayan => "PY",
+            Nationality::Peruvian => "PE",
+            Nationality::Polish => "PL",
+            Nationality::Portuguese => "PT",
+            Nationality::Qatari => "QA",
+            Nationality::Romanian => "RO",
+            Nationality::Russian => "RU",
+            Nationality::Salvadoran => "SV",
+            Nationality::Saudi => "SA",
+            Nationality::Scottish => "GB-SCT",
+            Nationality::Serbian => "RS",
+            Nationality::Singaporean => "SG",
+            Nationality::Slovakian => "SK",
+            Nationality::Slovenian => "SI",
+            Nationality::SouthAfrican => "ZA",
+            Nationality::SouthKorean => "KR",
+            Nationality::Spanish => "ES",
+            Nationality::Swedish => "SE",
+            Nationality::Swiss => "CH",
+            Nationality::Thai => "TH",
+            Nationality::Turkish => "TR",
+            Nationality::Ukrainian => "UA",
+            Nationality::Uruguayan => "UY",
+            Nationality::Venezuelan => "VE",
+            Nationality::Welsh => "GB-WLS",
+            Nationality::Unknown(_) => "XX",
+        }
     }
 }
 
@@ -288,16 +718,15 @@ impl Default for Nationality {
 ///
 /// In multiplayer sessions, only the player's telemetry data is broadcast over UDP. Telemetry data
 /// of other cars is restricted to prevent players gaining an unfair advantage.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+///
+/// Settings that are unknown to this crate are decoded as `Unknown` with their raw identifier.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TelemetryPrivacy {
+    #[default]
     Public,
     Restricted,
-}
-
-impl Default for TelemetryPrivacy {
-    fn default() -> Self {
-        TelemetryPrivacy::Public
-    }
+    Unknown(u8),
 }
 
 /// Data about a participant in the session
@@ -305,27 +734,40 @@ impl Default for TelemetryPrivacy {
 /// The F1 games publish data for each participant in a session that identifies them. This data
 /// includes the participant's name, team, and nationality among others.
 #[derive(
-    new, Debug, CopyGetters, Getters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash, Default,
+    new,
+    Debug,
+    CopyGetters,
+    Getters,
+    MutGetters,
+    WithSetters,
+    PartialEq,
+    Clone,
+    Eq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Default,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Participant {
     /// Returns the type of controller.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     controller: Controller,
 
     /// Returns the driver.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     driver: Driver,
 
     /// Returns the participant's team.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     team: Team,
 
     /// Returns the number of the participant's car.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     race_number: u8,
 
     /// Returns the participant's nationality.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     nationality: Nationality,
 
     /// Returns the participant's name.
@@ -333,11 +775,11 @@ pub struct Participant {
     /// In single player sessions, the AI is always named after the driver. In multiplayer sessions
     /// on PC, a player's SteamID or LAN name is used. On PlayStation, the LAN name is used. On
     /// Xbox, the driver name is always used.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", get_mut = "pub(crate)", set_with = "pub")]
     name: String,
 
     /// Returns the privacy setting for the participant's telemetry data.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     telemetry_privacy: Option<TelemetryPrivacy>,
 }
 
@@ -345,23 +787,61 @@ pub struct Participant {
 ///
 /// The F1 games provide information about each participant in a session, for example their name,
 /// team, and nationality. The data is updated every 5 seconds.
-#[derive(new, Debug, CopyGetters, Getters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash)]
+#[derive(
+    new,
+    Debug,
+    CopyGetters,
+    Getters,
+    MutGetters,
+    WithSetters,
+    PartialEq,
+    Clone,
+    Eq,
+    Ord,
+    PartialOrd,
+    Hash,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParticipantsPacket {
     /// Returns the packet header prefixing the participants packet.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     header: Header,
 
     /// Returns the number of active participant in the session.
     ///
     /// The number of active participants in the packet should match the number of cars on the HUD
     /// in-game.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     active_participants_count: u8,
 
     /// Returns the participants in the session.
     ///
     /// As is the case in other packets, the participants packet always contain 20 entries. This is
     /// also the case when there are less then 20 active participants in the session.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", get_mut = "pub(crate)", set_with = "pub")]
     participants: Vec<Participant>,
 }
+
+impl Default for ParticipantsPacket {
+    fn default() -> Self {
+        ParticipantsPacket {
+            header: Header::default().with_packet_type(PacketType::Participants),
+            active_participants_count: Default::default(),
+            participants: Default::default(),
+        }
+    }
+}
+
+impl ParticipantsPacket {
+    /// Returns the participant driving the player's car, if the player is driving a car.
+    pub fn player(&self) -> Option<&Participant> {
+        car_entry(&self.participants, self.header.player_car_index())
+    }
+
+    /// Returns the participant driving the secondary player's car in split-screen sessions.
+    pub fn secondary_player(&self) -> Option<&Participant> {
+        self.header
+            .secondary_player_car_index()
+            .and_then(|vehicle_index| car_entry(&self.participants, vehicle_index))
+    }
+}
@@ -3,29 +3,69 @@
 //! The F1 games provide information about the current session, for example weather and temperature
 //! as well as settings like the type of safety car in use.
 
+use std::fmt;
+use std::fmt::Display;
 use std::time::Duration;
 
 use derive_new::new;
-use getset::{CopyGetters, Getters};
+use getset::{CopyGetters, Getters, WithSetters};
 
-use crate::packet::header::Header;
-use crate::types::{Flag, VehicleIndex};
+use crate::packet::header::{Header, PacketType};
+use crate::packet::status::VisualTyreCompound;
+use crate::types::{Flag, Speed, Temperature, VehicleIndex};
 
 /// Types of formula racing supported by the F1 games
 ///
 /// The F1 games support different types of formula racing, with newer games typically supporting
-/// more than older games.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+/// more than older games. Types that are unknown to this crate are decoded as `Unknown` with the raw
+/// identifier of the formula.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Formula {
     ClassicF1,
     GenericF1,
+    #[default]
     ModernF1,
     F2,
+    Unknown(u8),
 }
 
-impl Default for Formula {
-    fn default() -> Self {
-        Formula::ModernF1
+impl Formula {
+    /// Returns the visual tyre compounds that the cars of the formula can use.
+    pub fn tyre_compounds(&self) -> &'static [VisualTyreCompound] {
+        match self {
+            Formula::ClassicF1 => &[
+                VisualTyreCompound::ClassicDry,
+                VisualTyreCompound::ClassicWet,
+            ],
+            Formula::GenericF1 | Formula::ModernF1 => &[
+                VisualTyreCompound::F1Soft,
+                VisualTyreCompound::F1Medium,
+                VisualTyreCompound::F1Hard,
+                VisualTyreCompound::F1Intermediate,
+                VisualTyreCompound::F1Wet,
+            ],
+            Formula::F2 => &[
+                VisualTyreCompound::F2SuperSoft,
+                VisualTyreCompound::F2Soft,
+                VisualTyreCompound::F2Medium,
+                VisualTyreCompound::F2Hard,
+                VisualTyreCompound::F2Wet,
+            ],
+            Formula::Unknown(_) => &[],
+        }
+    }
+}
+
+impl Display for Formula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Formula::ClassicF1 => write!(f, "Classic F1"),
+            Formula::GenericF1 => write!(f, "Generic F1"),
+            Formula::ModernF1 => write!(f, "F1"),
+            Formula::F2 => write!(f, "F2"),
+            Formula::Unknown(_) => write!(f, "Unknown formula"),
+        }
     }
 }
 
@@ -33,16 +73,23 @@ impl Default for Formula {
 ///
 /// The F1 games allow different rules to be configured for the safety car. Sessions can have no
 /// safety car at all, a virtual safety car, or a full safety car.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+///
+/// States of the safety car that are unknown to this crate are decoded as `Unknown` with their
+/// raw identifier.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SafetyCar {
     None,
+    #[default]
     Full,
     Virtual,
+    Unknown(u8),
 }
 
-impl Default for SafetyCar {
-    fn default() -> Self {
-        SafetyCar::Full
+impl SafetyCar {
+    /// Returns whether the full or the virtual safety car is deployed.
+    pub fn is_deployed(&self) -> bool {
+        *self != SafetyCar::None
     }
 }
 
@@ -50,8 +97,10 @@ impl Default for SafetyCar {
 ///
 /// F1 knows many different types of sessions. A typical race weekend consists of free practice,
 /// qualifying and a race, each of which can be divided into multiple sessions (e.g. first or second
-/// free practice).
+/// free practice). Sessions that the game reports as unknown, or that are unknown to this crate,
+/// are decoded as `Unknown` with the raw identifier of the session.
 #[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Session {
     OneShotQualifying,
     P1,
@@ -65,20 +114,118 @@ pub enum Session {
     ShortPractice,
     ShortQualifying,
     TimeTrial,
-    Unknown,
+    Unknown(u8),
 }
 
 impl Default for Session {
     fn default() -> Self {
-        Session::Unknown
+        Session::Unknown(0)
+    }
+}
+
+impl Session {
+    /// Returns whether the session is a practice session.
+    pub fn is_practice(&self) -> bool {
+        matches!(
+            self,
+            Session::P1 | Session::P2 | Session::P3 | Session::ShortPractice
+        )
+    }
+
+    /// Returns whether the session is a qualifying session.
+    pub fn is_qualifying(&self) -> bool {
+        matches!(
+            self,
+            Session::Q1
+                | Session::Q2
+                | Session::Q3
+                | Session::ShortQualifying
+                | Session::OneShotQualifying
+        )
+    }
+
+    /// Returns whether the session is a race, including the second race of a F2 weekend.
+    pub fn is_race(&self) -> bool {
+        matches!(self, Session::Race | Session::Race2)
+    }
+}
+
+impl Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Session::OneShotQualifying => write!(f, "One-shot qualifying"),
+            Session::P1 => write!(f, "Practice 1"),
+            Session::P2 => write!(f, "Practice 2"),
+            Session::P3 => write!(f, "Practice 3"),
+            Session::Q1 => write!(f, "Qualifying 1"),
+            Session::Q2 => write!(f, "Qualifying 2"),
+            Session::Q3 => write!(f, "Qualifying 3"),
+            Session::Race => write!(f, "Race"),
+            Session::Race2 => write!(f, "Race 2"),
+            Session::ShortPractice => write!(f, "Short practice"),
+            Session::ShortQualifying => write!(f, "Short qualifying"),
+            Session::TimeTrial => write!(f, "Time trial"),
+            Session::Unknown(_) => write!(f, "Unknown session"),
+        }
     }
 }
 
+/// Game modes in which a session can be played
+///
+/// F1 2021 started to publish the game mode in the session packet, which tells apart single player
+/// sessions, online sessions, and the invitational events of the career. F1 2019 does not publish
+/// the game mode, and its session packet does not carry one.
+///
+/// Game modes that are unknown to this crate are decoded as `Unknown` with their raw identifier.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameMode {
+    Benchmark,
+    CareerInvitational,
+    Championship,
+    ChampionshipInvitational,
+    EventMode,
+    #[default]
+    GrandPrix,
+    OnlineChampionship,
+    OnlineCustom,
+    OnlineLeague,
+    OnlineWeeklyEvent,
+    Splitscreen,
+    TimeTrial,
+    Unknown(u8),
+}
+
+/// Rulesets that a session can be played with
+///
+/// F1 2021 started to publish the ruleset in the session packet, which sets the rules for regular
+/// sessions as well as the challenges of the career. F1 2019 does not publish the ruleset, and its
+/// session packet does not carry one.
+///
+/// Rulesets that are unknown to this crate are decoded as `Unknown` with their raw identifier.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Ruleset {
+    Autocross,
+    AverageSpeedZone,
+    CheckpointChallenge,
+    Drift,
+    PracticeAndQualifying,
+    #[default]
+    Race,
+    RivalDuel,
+    TimeAttack,
+    TimeTrial,
+    Unknown(u8),
+}
+
 /// Race tracks that are in the F1 games
 ///
 /// The F1 games feature a long list of race tracks that appear in the games. Not every track is
-/// available in every game.
+/// available in every game. Tracks that the game reports as unknown, or that are unknown to this
+/// crate, are decoded as `Unknown` with the raw identifier of the track.
 #[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Track {
     AbuDhabi,
     Austria,
@@ -105,32 +252,208 @@ pub enum Track {
     SuzukaShort,
     Texas,
     TexasShort,
-    Unknown,
+    Unknown(i8),
 }
 
 impl Default for Track {
     fn default() -> Self {
-        Track::Unknown
+        Track::Unknown(-1)
+    }
+}
+
+impl Track {
+    /// Returns the metadata of the track.
+    ///
+    /// The length and the number of corners are only known for the full layouts of the tracks. The
+    /// length of the track in the current session is also published in the session packet.
+    pub fn info(&self) -> TrackInfo {
+        match self {
+            Track::AbuDhabi => TrackInfo::new(
+                "Yas Marina Circuit",
+                Some("United Arab Emirates"),
+                Some(5554),
+                Some(21),
+            ),
+            Track::Austria => {
+                TrackInfo::new("Red Bull Ring", Some("Austria"), Some(4318), Some(10))
+            }
+            Track::Azerbaijan => TrackInfo::new(
+                "Baku City Circuit",
+                Some("Azerbaijan"),
+                Some(6003),
+                Some(20),
+            ),
+            Track::Bahrain => TrackInfo::new(
+                "Bahrain International Circuit",
+                Some("Bahrain"),
+                Some(5412),
+                Some(15),
+            ),
+            Track::BahrainShort => TrackInfo::new(
+                "Bahrain International Circuit (Short)",
+                Some("Bahrain"),
+                None,
+                None,
+            ),
+            Track::Brazil => TrackInfo::new(
+                "Autódromo José Carlos Pace",
+                Some("Brazil"),
+                Some(4309),
+                Some(15),
+            ),
+            Track::Catalunya => TrackInfo::new(
+                "Circuit de Barcelona-Catalunya",
+                Some("Spain"),
+                Some(4655),
+                Some(16),
+            ),
+            Track::Hockenheim => {
+                TrackInfo::new("Hockenheimring", Some("Germany"), Some(4574), Some(17))
+            }
+            Track::Hungaroring => {
+                TrackInfo::new("Hungaroring", Some("Hungary"), Some(4381), Some(14))
+            }
+            Track::Melbourne => TrackInfo::new(
+                "Albert Park Circuit",
+                Some("Australia"),
+                Some(5303),
+                Some(16),
+            ),
+            Track::Mexico => TrackInfo::new(
+                "Autódromo Hermanos Rodríguez",
+                Some("Mexico"),
+                Some(4304),
+                Some(17),
+            ),
+            Track::Monaco => {
+                TrackInfo::new("Circuit de Monaco", Some("Monaco"), Some(3337), Some(19))
+            }
+            Track::Montreal => TrackInfo::new(
+                "Circuit Gilles Villeneuve",
+                Some("Canada"),
+                Some(4361),
+                Some(14),
+            ),
+            Track::Monza => TrackInfo::new(
+                "Autodromo Nazionale Monza",
+                Some("Italy"),
+                Some(5793),
+                Some(11),
+            ),
+            Track::PaulRicard => {
+                TrackInfo::new("Circuit Paul Ricard", Some("France"), Some(5842), Some(15))
+            }
+            Track::Shanghai => TrackInfo::new(
+                "Shanghai International Circuit",
+                Some("China"),
+                Some(5451),
+                Some(16),
+            ),
+            Track::Silverstone => TrackInfo::new(
+                "Silverstone Circuit",
+                Some("United Kingdom"),
+                Some(5891),
+                Some(18),
+            ),
+            Track::SilverstoneShort => TrackInfo::new(
+                "Silverstone Circuit (Short)",
+                Some("United Kingdom"),
+                None,
+                None,
+            ),
+            Track::Singapore => TrackInfo::new(
+                "Marina Bay Street Circuit",
+                Some("Singapore"),
+                Some(5063),
+                Some(23),
+            ),
+            Track::Sochi => TrackInfo::new("Sochi Autodrom", Some("Russia"), Some(5848), Some(18)),
+            Track::Spa => TrackInfo::new(
+                "Circuit de Spa-Francorchamps",
+                Some("Belgium"),
+                Some(7004),
+                Some(19),
+            ),
+            Track::Suzuka => TrackInfo::new(
+                "Suzuka International Racing Course",
+                Some("Japan"),
+                Some(5807),
+                Some(18),
+            ),
+            Track::SuzukaShort => TrackInfo::new(
+                "Suzuka International Racing Course (Short)",
+                Some("Japan"),
+                None,
+                None,
+            ),
+            Track::Texas => TrackInfo::new(
+                "Circuit of the Americas",
+                Some("United States"),
+                Some(5513),
+                Some(20),
+            ),
+            Track::TexasShort => TrackInfo::new(
+                "Circuit of the Americas (Short)",
+                Some("United States"),
+                None,
+                None,
+            ),
+            Track::Unknown(_) => TrackInfo::new("Unknown", None, None, None),
+        }
     }
 }
 
+/// Metadata of a race track
+///
+/// The F1 games only publish an identifier for each track. The metadata of the tracks is maintained
+/// by this crate, so that users do not need to maintain their own tables.
+#[derive(new, Debug, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+pub struct TrackInfo {
+    /// Returns the name of the circuit.
+    #[getset(get_copy = "pub")]
+    name: &'static str,
+
+    /// Returns the country the circuit is located in, if the track is known.
+    #[getset(get_copy = "pub")]
+    country: Option<&'static str>,
+
+    /// Returns the length of the track in meters, if it is known.
+    #[getset(get_copy = "pub")]
+    length: Option<u16>,
+
+    /// Returns the number of corners of the track, if it is known.
+    #[getset(get_copy = "pub")]
+    corners: Option<u8>,
+}
+
 /// Weather conditions that can occur in a session
 ///
 /// The modern F1 games support changing weather conditions, though not every weather condition is
 /// supported by every game.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+///
+/// Weather conditions are ordered from dry to wet, so that comparing two conditions tells whether
+/// the weather is getting wetter or drier. Weather conditions that are unknown to this crate are
+/// decoded as `Unknown` with the raw identifier of the condition, and are ordered after all others.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Weather {
+    #[default]
     Clear,
     LightCloud,
     Overcast,
     LightRain,
     HeavyRain,
     Storm,
+    Unknown(u8),
 }
 
-impl Default for Weather {
-    fn default() -> Self {
-        Weather::Clear
+impl Weather {
+    /// Returns whether it is raining.
+    pub fn is_wet(&self) -> bool {
+        matches!(
+            self,
+            Weather::LightRain | Weather::HeavyRain | Weather::Storm
+        )
     }
 }
 
@@ -140,14 +463,15 @@ impl Default for Weather {
 /// drivers about hazards on track, faster cars approaching from behind, and other important status
 /// updates. Each zone is represented by a struct containing the fraction of the race track's length
 /// where the zone starts, and any flag that is currently being shown there.
-#[derive(new, Debug, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+#[derive(new, Debug, CopyGetters, WithSetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MarshalZone {
     /// Returns the start point of the marshal zone as a fraction of the race track's total length.
-    #[getset(get_copy = "pub")]
-    start: f32,
+    #[getset(get_copy = "pub", set_with = "pub")]
+    start_fraction: f32,
 
     /// Returns the flag that is currently being displayed in the marshal zone.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     flag: Flag,
 }
 
@@ -155,82 +479,138 @@ pub struct MarshalZone {
 ///
 /// The session packet provides information about the current session, for example weather and
 /// temperature as well as settings like the type of safety car in use.
-#[derive(new, Debug, CopyGetters, Getters, PartialEq, Clone, PartialOrd)]
+#[derive(new, Debug, CopyGetters, Getters, WithSetters, PartialEq, Clone, PartialOrd)]
 #[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SessionPacket {
     /// Returns the packet header prefixing the session packet.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     header: Header,
 
     /// Returns the current weather in the session.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     weather: Weather,
 
-    /// Returns the track temperature in degrees celsius.
-    #[getset(get_copy = "pub")]
-    track_temperature: i8,
+    /// Returns the track temperature.
+    #[getset(get_copy = "pub", set_with = "pub")]
+    track_temperature: Temperature,
 
-    /// Returns the air temperature in degrees celsius.
-    #[getset(get_copy = "pub")]
-    air_temperature: i8,
+    /// Returns the air temperature.
+    #[getset(get_copy = "pub", set_with = "pub")]
+    air_temperature: Temperature,
 
     /// Returns the total number of laps in this race.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     total_laps: u8,
 
     /// Returns the length of the race track in metres.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     track_length: u16,
 
     /// Returns the type of the current session.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     session_type: Session,
 
     /// Returns the race track of the session.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     track: Track,
 
     /// Returns the type of formula racing.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     formula: Formula,
 
     /// Returns the time that is left in the session.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     time_left: Duration,
 
     /// Returns the duration of the session in seconds.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     duration: Duration,
 
-    /// Returns the pit speed limit in kilometers per hour.
-    #[getset(get_copy = "pub")]
-    pit_speed_limit: u8,
+    /// Returns the pit speed limit.
+    #[getset(get_copy = "pub", set_with = "pub")]
+    pit_speed_limit: Speed,
 
     /// Returns whether the game is paused right now.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     game_paused: bool,
 
     /// Returns whether the player is spectating the session.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     is_spectating: bool,
 
-    /// Returns the index of the car being spectated.
-    #[getset(get_copy = "pub")]
-    spectator_car_index: VehicleIndex,
+    /// Returns the index of the car being spectated, or `None` if no car is spectated.
+    #[getset(get_copy = "pub", set_with = "pub")]
+    spectator_car_index: Option<VehicleIndex>,
 
     /// Returns whether the support for SLI Pro is active.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     sli_pro_support: bool,
 
     /// Returns the marshal zones around the track.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     marshal_zones: Vec<MarshalZone>,
 
     /// Returns the type of safety car that is used in the session.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     safety_car: SafetyCar,
 
     /// Returns whether the session is a multiplayer session.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     network_session: bool,
 }
+
+impl Default for SessionPacket {
+    fn default() -> Self {
+        SessionPacket {
+            header: Header::default().with_packet_type(PacketType::Session),
+            weather: Default::default(),
+            track_temperature: Default::default(),
+            air_temperature: Default::default(),
+            total_laps: Default::default(),
+            track_length: Default::default(),
+            session_type: Default::default(),
+            track: Default::default(),
+            formula: Default::default(),
+            time_left: Default::default(),
+            duration: Default::default(),
+            pit_speed_limit: Default::default(),
+            game_paused: Default::default(),
+            is_spectating: Default::default(),
+            spectator_car_index: Default::default(),
+            sli_pro_support: Default::default(),
+            marshal_zones: Default::default(),
+            safety_car: Default::default(),
+            network_session: Default::default(),
+        }
+    }
+}
+
+impl SessionPacket {
+    /// Returns the index of the car being spectated, if the player is spectating the session.
+    ///
+    /// The spectator car index is only meaningful while the player is spectating, and is ignored
+    /// otherwise.
+    pub fn spectated_car(&self) -> Option<VehicleIndex> {
+        self.spectator_car_index.filter(|_| self.is_spectating)
+    }
+
+    /// Returns the marshal zone that a lap distance in meters falls in.
+    ///
+    /// Marshal zones extend from their start to the start of the next zone, and the last zone
+    /// wraps around the start/finish line. No zone is returned if the packet has no marshal zones
+    /// or the length of the track is unknown.
+    pub fn marshal_zone_at(&self, lap_distance: f32) -> Option<&MarshalZone> {
+        if self.track_length == 0 {
+            return None;
+        }
+
+        let fraction = (lap_distance / self.track_length as f32).rem_euclid(1.0);
+
+        self.marshal_zones
+            .iter()
+            .rev()
+            .find(|zone| zone.start_fraction <= fraction)
+            .or_else(|| self.marshal_zones.last())
+    }
+}
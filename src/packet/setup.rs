@@ -3,96 +3,104 @@
 //! The F1 games publish data about the setups of all cars in a session. In multiplayer sessions,
 //! setups of other players are redacted to prevent anyone from gaining an unfair advantage.
 
+use std::ops::Index;
+
 use derive_new::new;
-use getset::{CopyGetters, Getters};
+use getset::{CopyGetters, Getters, MutGetters, WithSetters};
 
-use crate::packet::header::Header;
+use crate::car::cars_with;
+use crate::packet::header::{Header, PacketType};
+use crate::packet::participants::{Participant, ParticipantsPacket};
+use crate::types::{car_entry, Pressure, VehicleIndex};
 
 /// Setup of a car
 ///
 /// The setup of a car in the F1 games consists of a set of parameters that players can adjust
 /// before leaving the garage.
-#[derive(new, Debug, CopyGetters, Getters, PartialEq, Copy, Clone, PartialOrd, Default)]
+#[derive(
+    new, Debug, CopyGetters, Getters, WithSetters, PartialEq, Copy, Clone, PartialOrd, Default,
+)]
 #[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CarSetup {
     /// Returns the setting for the front wing aero.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     front_wing: u8,
 
     /// Returns the setting for the rear wing aero.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     rear_wing: u8,
 
     /// Returns the differential adjustment on throttle as a percentage.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     on_throttle: u8,
 
     /// Returns the differential adjustment off throttle as a percentage.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     off_throttle: u8,
 
     /// Returns the setting for the front camber angle.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     front_camber: f32,
 
     /// Returns the setting for the rear camber angle.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     rear_camber: f32,
 
     /// Returns the setting for the front toe angle.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     front_toe: f32,
 
     /// Returns the setting for the rear toe angle.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     rear_toe: f32,
 
     /// Returns the front suspension setting.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     front_suspension: u8,
 
     /// Returns the rear suspension setting.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     rear_suspension: u8,
 
     /// Returns the setting for the front anti-roll bar.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     front_anti_roll_bar: u8,
 
     /// Returns the setting for the rear anti-roll bar.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     rear_anti_roll_bar: u8,
 
     /// Returns the setting for the front ride height.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     front_suspension_height: u8,
 
     /// Returns the setting for the rear right height.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     rear_suspension_height: u8,
 
     /// Returns the setting for the brake pressure as a percentage.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     brake_pressure: u8,
 
     /// Returns the setting for the brake bias as a percentage.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     brake_bias: u8,
 
-    /// Returns the setting for the front tyre pressure in psi.
-    #[getset(get_copy = "pub")]
-    front_tyre_pressure: f32,
+    /// Returns the setting for the front tyre pressure.
+    #[getset(get_copy = "pub", set_with = "pub")]
+    front_tyre_pressure: Pressure,
 
-    /// Returns the setting for the rear tyre pressure in psi.
-    #[getset(get_copy = "pub")]
-    rear_tyre_pressure: f32,
+    /// Returns the setting for the rear tyre pressure.
+    #[getset(get_copy = "pub", set_with = "pub")]
+    rear_tyre_pressure: Pressure,
 
     /// Returns the setting for additional ballast.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     ballast: u8,
 
     /// Returns the setting for the fuel load.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     fuel_load: f32,
 }
 
@@ -101,13 +109,59 @@ pub struct CarSetup {
 /// The F1 games publish the setup of each car in the session in the car setup packet. In
 /// multiplayer sessions, the setups of other players are redacted to prevent anyone from gaining an
 /// unfair advantage.
-#[derive(new, Debug, Getters, PartialEq, Clone, PartialOrd)]
+#[derive(new, Debug, Getters, MutGetters, WithSetters, PartialEq, Clone, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CarSetupPacket {
     /// Returns the packet header prefixing the car setup packet.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     header: Header,
 
     /// Returns the setups of all 20 cars in the session.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", get_mut = "pub(crate)", set_with = "pub")]
     setups: Vec<CarSetup>,
 }
+
+impl Default for CarSetupPacket {
+    fn default() -> Self {
+        CarSetupPacket {
+            header: Header::default().with_packet_type(PacketType::Setup),
+            setups: Default::default(),
+        }
+    }
+}
+
+impl CarSetupPacket {
+    /// Returns the setup of a car, if the vehicle index refers to a car in the packet.
+    pub fn get(&self, vehicle_index: VehicleIndex) -> Option<&CarSetup> {
+        car_entry(&self.setups, vehicle_index)
+    }
+
+    /// Returns the setup of the player's car, if the player is driving a car.
+    pub fn player(&self) -> Option<&CarSetup> {
+        self.get(self.header.player_car_index())
+    }
+
+    /// Returns the setup of the secondary player's car in split-screen sessions.
+    pub fn secondary_player(&self) -> Option<&CarSetup> {
+        self.header
+            .secondary_player_car_index()
+            .and_then(|vehicle_index| self.get(vehicle_index))
+    }
+
+    /// Returns the setups of the active cars, with the index and the participant of each car.
+    pub fn cars_with<'a>(
+        &'a self,
+        participants: &'a ParticipantsPacket,
+    ) -> impl Iterator<Item = (VehicleIndex, &'a Participant, &'a CarSetup)> {
+        cars_with(participants, &self.setups)
+    }
+}
+
+impl Index<VehicleIndex> for CarSetupPacket {
+    type Output = CarSetup;
+
+    /// Returns the setup of a car, and panics if the vehicle index refers to no car in the packet.
+    fn index(&self, vehicle_index: VehicleIndex) -> &CarSetup {
+        &self.setups[vehicle_index.as_usize()]
+    }
+}
@@ -3,19 +3,25 @@
 //! The F1 games provide detailed information about the status of each car in the session. The rate
 //! with which the data is provided can be configured in the in-game settings.
 
+use std::ops::Index;
+
 use derive_new::new;
-use getset::{CopyGetters, Getters};
+use getset::{CopyGetters, Getters, MutGetters, WithSetters};
 
-use crate::packet::header::Header;
-use crate::types::{CornerProperty, Flag};
+use crate::car::cars_with;
+use crate::packet::header::{Header, PacketType};
+use crate::packet::participants::{Participant, ParticipantsPacket};
+use crate::types::{car_entry, CornerProperty, Flag, VehicleIndex};
 
 /// Traction control settings
 ///
 /// Traction control is a driver assist that does only exist in-game, and not on an actual F1 car.
 /// It can be turned off, or switched between a low and high setting.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TractionControl {
     /// Traction control is turned off.
+    #[default]
     Off,
 
     /// Traction control operates at a low setting, and offers only minor assists.
@@ -23,24 +29,23 @@ pub enum TractionControl {
 
     /// Traction control operates at a high setting, and offers maximum help.
     High,
-}
 
-impl Default for TractionControl {
-    fn default() -> Self {
-        TractionControl::Off
-    }
+    /// The setting is unknown to this crate, and carries its raw identifier.
+    Unknown(u8),
 }
 
 /// Fuel mix settings
 ///
 /// F1 cars can run on different fuel mixes, and drivers are often required to change the fuel mix
 /// during a race to save fuel or prevent the engine from overheating.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FuelMix {
     /// The engine runs on a lean fuel mix.
     Lean,
 
     /// The engine runs on the standard fuel mix.
+    #[default]
     Standard,
 
     /// The engine runs on a rich fuel mix.
@@ -48,12 +53,9 @@ pub enum FuelMix {
 
     /// The engine runs on the richest fuel mix.
     Max,
-}
 
-impl Default for FuelMix {
-    fn default() -> Self {
-        FuelMix::Standard
-    }
+    /// The fuel mix is unknown to this crate, and carries its raw identifier.
+    Unknown(u8),
 }
 
 /// Setting of the Drag Reduction System
@@ -61,20 +63,22 @@ impl Default for FuelMix {
 /// The Drag Reduction System, or DRS, can be disabled and enabled during a race. When it is
 /// disabled, drivers cannot activate it.
 #[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DrsSetting {
-    ///  The DRS setting is unknown, for example because the current formula does not support it.
-    Unknown,
-
     /// DRS is disabled, and cannot be used by drivers.
     NotAllowed,
 
     /// DRS is enabled, and can be used by drivers.
     Allowed,
+
+    /// The DRS setting is unknown, for example because the current formula does not support it,
+    /// and carries its raw value. The games report -1 for formulas without DRS.
+    Unknown(i8),
 }
 
 impl Default for DrsSetting {
     fn default() -> Self {
-        DrsSetting::Unknown
+        DrsSetting::Unknown(-1)
     }
 }
 
@@ -87,11 +91,14 @@ impl Default for DrsSetting {
 /// tyre.
 ///
 /// For older games that do not know this distinction yet, the tyre compound is duplicated in both
-/// fields.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+/// fields. Compounds that are unknown to this crate are decoded as `Unknown` with the raw identifier
+/// of the compound.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PhysicalTyreCompound {
     ClassicDry,
     ClassicWet,
+    #[default]
     F1C1,
     F1C2,
     F1C3,
@@ -111,11 +118,59 @@ pub enum PhysicalTyreCompound {
     F2Medium,
     F2Hard,
     F2Wet,
+    Unknown(u8),
 }
 
-impl Default for PhysicalTyreCompound {
-    fn default() -> Self {
-        PhysicalTyreCompound::F1C1
+impl PhysicalTyreCompound {
+    /// Returns the name of the tyre compound as it is shown to users.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PhysicalTyreCompound::ClassicDry => "Classic Dry",
+            PhysicalTyreCompound::ClassicWet => "Classic Wet",
+            PhysicalTyreCompound::F1C1 => "C1",
+            PhysicalTyreCompound::F1C2 => "C2",
+            PhysicalTyreCompound::F1C3 => "C3",
+            PhysicalTyreCompound::F1C4 => "C4",
+            PhysicalTyreCompound::F1C5 => "C5",
+            PhysicalTyreCompound::F1HyperSoft => "Hyper Soft",
+            PhysicalTyreCompound::F1UltraSoft => "Ultra Soft",
+            PhysicalTyreCompound::F1SuperSoft => "Super Soft",
+            PhysicalTyreCompound::F1Soft => "Soft",
+            PhysicalTyreCompound::F1Medium => "Medium",
+            PhysicalTyreCompound::F1Hard => "Hard",
+            PhysicalTyreCompound::F1SuperHard => "Super Hard",
+            PhysicalTyreCompound::F1Intermediate => "Intermediate",
+            PhysicalTyreCompound::F1Wet => "Wet",
+            PhysicalTyreCompound::F2SuperSoft => "F2 Super Soft",
+            PhysicalTyreCompound::F2Soft => "F2 Soft",
+            PhysicalTyreCompound::F2Medium => "F2 Medium",
+            PhysicalTyreCompound::F2Hard => "F2 Hard",
+            PhysicalTyreCompound::F2Wet => "F2 Wet",
+            PhysicalTyreCompound::Unknown(_) => "Unknown",
+        }
+    }
+
+    /// Returns whether the tyre compound is made for wet conditions.
+    pub fn is_wet(&self) -> bool {
+        matches!(
+            self,
+            PhysicalTyreCompound::ClassicWet
+                | PhysicalTyreCompound::F1Intermediate
+                | PhysicalTyreCompound::F1Wet
+                | PhysicalTyreCompound::F2Wet
+        )
+    }
+
+    /// Returns whether the tyre compound is used in the F2 championship.
+    pub fn is_f2(&self) -> bool {
+        matches!(
+            self,
+            PhysicalTyreCompound::F2SuperSoft
+                | PhysicalTyreCompound::F2Soft
+                | PhysicalTyreCompound::F2Medium
+                | PhysicalTyreCompound::F2Hard
+                | PhysicalTyreCompound::F2Wet
+        )
     }
 }
 
@@ -128,14 +183,17 @@ impl Default for PhysicalTyreCompound {
 /// tyre.
 ///
 /// For older games that do not know this distinction yet, the tyre compound is duplicated in both
-/// fields.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+/// fields. Compounds that are unknown to this crate are decoded as `Unknown` with the raw identifier
+/// of the compound.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VisualTyreCompound {
     ClassicDry,
     ClassicWet,
     F1HyperSoft,
     F1UltraSoft,
     F1SuperSoft,
+    #[default]
     F1Soft,
     F1Medium,
     F1Hard,
@@ -147,11 +205,54 @@ pub enum VisualTyreCompound {
     F2Medium,
     F2Hard,
     F2Wet,
+    Unknown(u8),
 }
 
-impl Default for VisualTyreCompound {
-    fn default() -> Self {
-        VisualTyreCompound::F1Soft
+impl VisualTyreCompound {
+    /// Returns the name of the tyre compound as it is shown to users.
+    pub fn name(&self) -> &'static str {
+        match self {
+            VisualTyreCompound::ClassicDry => "Classic Dry",
+            VisualTyreCompound::ClassicWet => "Classic Wet",
+            VisualTyreCompound::F1HyperSoft => "Hyper Soft",
+            VisualTyreCompound::F1UltraSoft => "Ultra Soft",
+            VisualTyreCompound::F1SuperSoft => "Super Soft",
+            VisualTyreCompound::F1Soft => "Soft",
+            VisualTyreCompound::F1Medium => "Medium",
+            VisualTyreCompound::F1Hard => "Hard",
+            VisualTyreCompound::F1SuperHard => "Super Hard",
+            VisualTyreCompound::F1Intermediate => "Intermediate",
+            VisualTyreCompound::F1Wet => "Wet",
+            VisualTyreCompound::F2SuperSoft => "F2 Super Soft",
+            VisualTyreCompound::F2Soft => "F2 Soft",
+            VisualTyreCompound::F2Medium => "F2 Medium",
+            VisualTyreCompound::F2Hard => "F2 Hard",
+            VisualTyreCompound::F2Wet => "F2 Wet",
+            VisualTyreCompound::Unknown(_) => "Unknown",
+        }
+    }
+
+    /// Returns whether the tyre compound is made for wet conditions.
+    pub fn is_wet(&self) -> bool {
+        matches!(
+            self,
+            VisualTyreCompound::ClassicWet
+                | VisualTyreCompound::F1Intermediate
+                | VisualTyreCompound::F1Wet
+                | VisualTyreCompound::F2Wet
+        )
+    }
+
+    /// Returns whether the tyre compound is used in the F2 championship.
+    pub fn is_f2(&self) -> bool {
+        matches!(
+            self,
+            VisualTyreCompound::F2SuperSoft
+                | VisualTyreCompound::F2Soft
+                | VisualTyreCompound::F2Medium
+                | VisualTyreCompound::F2Hard
+                | VisualTyreCompound::F2Wet
+        )
     }
 }
 
@@ -159,13 +260,15 @@ impl Default for VisualTyreCompound {
 ///
 /// The Energy Recovery System, or ERS, can be operated in different modes that determine how much
 /// energy is harvested under braking, and how much is used to accelerate the car.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ErsDeployMode {
     /// The Energy Recovery System is disabled or does not exist in the current car.
     None,
 
     /// The Energy Recovery System operates at a low setting, and harvest more energy than it
     /// consumes.
+    #[default]
     Low,
 
     /// The Energy Recovery System operates at a medium setting, harvesting and deploying at a
@@ -183,12 +286,9 @@ pub enum ErsDeployMode {
     /// The Energy Recovery System operates at a level optimized for qualifying laps, which aims to
     /// deplete the batteries by the end of the lap.
     Hotlap,
-}
 
-impl Default for ErsDeployMode {
-    fn default() -> Self {
-        ErsDeployMode::Low
-    }
+    /// The deployment mode is unknown to this crate, and carries its raw identifier.
+    Unknown(u8),
 }
 
 /// Data describing the status of a car
@@ -197,115 +297,118 @@ impl Default for ErsDeployMode {
 /// about the fuel, the engine, the various assistance systems like ABS, DRS, and ERS, and the
 /// damage the car has sustained. In multiplayer sessions, some of this data is restricted and only
 /// shown for the player's own car.
-#[derive(new, Debug, CopyGetters, Getters, PartialEq, Copy, Clone, PartialOrd, Default)]
+#[derive(
+    new, Debug, CopyGetters, Getters, WithSetters, PartialEq, Copy, Clone, PartialOrd, Default,
+)]
 #[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CarStatus {
     /// Returns the traction control setting.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     traction_control: TractionControl,
 
     /// Returns whether ABS is enabled.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     abs: bool,
 
     /// Returns the fuel mix setting.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     fuel_mix: FuelMix,
 
     /// Returns the front brake bias (percentage).
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     brake_bias: u8,
 
     /// Returns whether the pit speed limiter is engaged.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     pit_limiter: bool,
 
     /// Returns the remaining fuel mass in tank.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     fuel_remaining: f32,
 
     /// Returns the fuel capacity.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     fuel_capacity: f32,
 
     /// Returns the remaining fuel in terms of laps.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     fuel_remaining_laps: f32,
 
     /// Returns the car's maximum RPM where the rev limiter kicks in.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     max_rpm: u16,
 
     /// Returns the car's idle RPM.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     idle_rpm: u16,
 
     /// Returns the car's number of gears.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     gear_count: u8,
 
     /// Returns the status of DRS.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     drs: DrsSetting,
 
     /// Returns the tyre wear at each corner of the car in percent.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     tyre_wear: CornerProperty<u8>,
 
     /// Returns the physical compound of the tyres.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     physical_tyre_compound: PhysicalTyreCompound,
 
     /// Returns the visual compound of the tyres.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     visual_tyre_compound: VisualTyreCompound,
 
     /// Returns the tyre damage at each corner of the car in percent.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     tyre_damage: CornerProperty<u8>,
 
     /// Returns the damage to the left front wing in percent.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     front_left_wing_damage: u8,
 
     /// Returns the damage to the right front wing in percent.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     front_right_wing_damage: u8,
 
     /// Returns the damage to the rear wing in percent.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     rear_wing_damage: u8,
 
     /// Returns the damage to the engine in percent.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     engine_damage: u8,
 
     /// Returns the damage to the gear box in percent.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     gear_box_damage: u8,
 
     /// Returns the flags that are being shown to the current car.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     vehicle_flags: Flag,
 
     /// Returns the ERS energy store in Joules.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     ers_energy: f32,
 
     /// Returns the ERS deploy mode.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     ers_deploy_mode: ErsDeployMode,
 
     /// Returns the ERS energy harvested this lap by the MGU-K.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     ers_harvest_mgu_k: f32,
 
     /// Returns the ERS energy harvested this lap by the MGU-H.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     ers_harvest_mgu_h: f32,
 
     /// Returns the ERS energy deployed this lap.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     ers_deployed: f32,
 }
 
@@ -313,13 +416,59 @@ pub struct CarStatus {
 ///
 /// The F1 games publish data on the status of each car in the session at a rate that can be
 /// configured in the in-game settings.
-#[derive(new, Debug, Getters, PartialEq, Clone, PartialOrd)]
+#[derive(new, Debug, Getters, MutGetters, WithSetters, PartialEq, Clone, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CarStatusPacket {
     /// Returns the packet header prefixing the car status packet.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     header: Header,
 
     /// Returns the status of each car in the session.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", get_mut = "pub(crate)", set_with = "pub")]
     statuses: Vec<CarStatus>,
 }
+
+impl Default for CarStatusPacket {
+    fn default() -> Self {
+        CarStatusPacket {
+            header: Header::default().with_packet_type(PacketType::Status),
+            statuses: Default::default(),
+        }
+    }
+}
+
+impl CarStatusPacket {
+    /// Returns the status of a car, if the vehicle index refers to a car in the packet.
+    pub fn get(&self, vehicle_index: VehicleIndex) -> Option<&CarStatus> {
+        car_entry(&self.statuses, vehicle_index)
+    }
+
+    /// Returns the status of the player's car, if the player is driving a car.
+    pub fn player(&self) -> Option<&CarStatus> {
+        self.get(self.header.player_car_index())
+    }
+
+    /// Returns the status of the secondary player's car in split-screen sessions.
+    pub fn secondary_player(&self) -> Option<&CarStatus> {
+        self.header
+            .secondary_player_car_index()
+            .and_then(|vehicle_index| self.get(vehicle_index))
+    }
+
+    /// Returns the statuses of the active cars, with the index and the participant of each car.
+    pub fn cars_with<'a>(
+        &'a self,
+        participants: &'a ParticipantsPacket,
+    ) -> impl Iterator<Item = (VehicleIndex, &'a Participant, &'a CarStatus)> {
+        cars_with(participants, &self.statuses)
+    }
+}
+
+impl Index<VehicleIndex> for CarStatusPacket {
+    type Output = CarStatus;
+
+    /// Returns the status of a car, and panics if the vehicle index refers to no car in the packet.
+    fn index(&self, vehicle_index: VehicleIndex) -> &CarStatus {
+        &self.statuses[vehicle_index.as_usize()]
+    }
+}
@@ -4,85 +4,96 @@
 //! includes physical properties of the car, e.g. its speed, but also information about the controls
 //! that are applied, e.g. which buttons are being pressed.
 
-use bitflags::bitflags;
-use derive_new::new;
-use getset::{CopyGetters, Getters};
-
-use crate::packet::header::Header;
-use crate::types::CornerProperty;
+use std::ops::Index;
 
-bitflags! {
-    /// A bit field with currently pressed buttons.
-    ///
-    /// The F1 games publish which buttons are currently being pressed by the user. This information
-    /// is encoded in a bit field, where each bit represents a different button.
-    pub struct Button: u32 {
-        const NONE = 0x0;
-        const CROSS_OR_A = 0x0001;
-        const TRIANGLE_OR_Y = 0x0002;
-        const CIRCLE_OR_B = 0x0004;
-        const SQUARE_OR_X = 0x0008;
-        const DPAD_LEFT = 0x0010;
-        const DPAD_RIGHT = 0x0020;
-        const DPAD_UP = 0x0040;
-        const DPAD_DOWN = 0x0080;
-        const OPTIONS_OR_MENU = 0x0100;
-        const L1_OR_LB = 0x0200;
-        const R1_OR_RB = 0x0400;
-        const L2_OR_LT = 0x0800;
-        const R2_OR_RT = 0x1000;
-        const LEFT_STICK_CLICK = 0x2000;
-        const RIGHT_STICK_CLICK =0x4000;
-    }
-}
+use derive_new::new;
+use getset::{CopyGetters, Getters, MutGetters, WithSetters};
 
-impl Default for Button {
-    fn default() -> Self {
-        Button::NONE
-    }
-}
+use crate::car::cars_with;
+use crate::packet::header::{Header, PacketType};
+use crate::packet::participants::{Participant, ParticipantsPacket};
+use crate::types::{car_entry, CornerProperty, Pressure, Speed, Temperature, VehicleIndex};
+pub use crate::types::{Button, ButtonFlags};
 
 /// Gears of a Formula One car
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+///
+/// Gears that are unknown to this crate are decoded as `Unknown` with the raw value of the gear.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Gear {
-    Reverse = -1,
-    Neutral = 0,
-    First = 1,
-    Second = 2,
-    Third = 3,
-    Fourth = 4,
-    Fifth = 5,
-    Sixth = 6,
-    Seventh = 7,
-    Eighth = 8,
+    Reverse,
+    #[default]
+    Neutral,
+    First,
+    Second,
+    Third,
+    Fourth,
+    Fifth,
+    Sixth,
+    Seventh,
+    Eighth,
+    Unknown(i8),
 }
 
-impl Default for Gear {
-    fn default() -> Self {
-        Gear::Neutral
+impl From<Gear> for i8 {
+    /// Returns the number of the gear, with -1 for reverse and 0 for neutral.
+    fn from(gear: Gear) -> Self {
+        match gear {
+            Gear::Reverse => -1,
+            Gear::Neutral => 0,
+            Gear::First => 1,
+            Gear::Second => 2,
+            Gear::Third => 3,
+            Gear::Fourth => 4,
+            Gear::Fifth => 5,
+            Gear::Sixth => 6,
+            Gear::Seventh => 7,
+            Gear::Eighth => 8,
+            Gear::Unknown(value) => value,
+        }
     }
 }
 
 /// Surfaces that a tyre can come in contact with in the F1 games
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+///
+/// F1 2019 publishes the surface under each wheel of a car in the telemetry packet. Surfaces that are
+/// unknown to this crate are decoded as `Unknown` with the raw identifier of the surface.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Surface {
-    Tarmac = 0,
-    RumbleStrip = 1,
-    Concrete = 2,
-    Rock = 3,
-    Gravel = 4,
-    Mud = 5,
-    Sand = 6,
-    Grass = 7,
-    Water = 8,
-    Cobblestone = 9,
-    Metal = 10,
-    Ridged = 11,
+    #[default]
+    Tarmac,
+    RumbleStrip,
+    Concrete,
+    Rock,
+    Gravel,
+    Mud,
+    Sand,
+    Grass,
+    Water,
+    Cobblestone,
+    Metal,
+    Ridged,
+    Unknown(u8),
 }
 
-impl Default for Surface {
-    fn default() -> Self {
-        Surface::Tarmac
+impl Surface {
+    /// Returns whether the surface is next to the track, for example grass or gravel.
+    pub fn is_off_track(&self) -> bool {
+        matches!(
+            self,
+            Surface::Rock
+                | Surface::Gravel
+                | Surface::Mud
+                | Surface::Sand
+                | Surface::Grass
+                | Surface::Water
+        )
+    }
+
+    /// Returns whether the surface is a kerb.
+    pub fn is_kerb(&self) -> bool {
+        *self == Surface::RumbleStrip
     }
 }
 
@@ -90,69 +101,72 @@ impl Default for Surface {
 ///
 /// The telemetry data provided from the F1 games contains detailed, and quickly changing data on
 /// the inner mechanics of each car, e.g. its speed, engine RPMs, and temperatures.
-#[derive(new, Debug, CopyGetters, Getters, PartialEq, Copy, Clone, PartialOrd, Default)]
+#[derive(
+    new, Debug, CopyGetters, Getters, WithSetters, PartialEq, Copy, Clone, PartialOrd, Default,
+)]
 #[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Telemetry {
-    /// Returns the speed of the car in kilometers per hour.
-    #[getset(get_copy = "pub")]
-    speed: u16,
+    /// Returns the speed of the car.
+    #[getset(get_copy = "pub", set_with = "pub")]
+    speed: Speed,
 
     /// Returns the ratio of the applied throttle.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     throttle: f32,
 
     /// Returns the ratio of steering input.
     ///
     /// The values range from -1.0 for a full lock left to 1.0 for a full lock right.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     steering: f32,
 
     /// Returns the ratio of brake applied.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     brake: f32,
 
     /// Returns the percentage that the clutch has been applied.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     clutch: u8,
 
     /// Returns the gear the car is in.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     gear: Gear,
 
     /// Returns the engine RPM.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     engine_rpm: u16,
 
     /// Returns whether the DRS is deployed.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     drs: bool,
 
     /// Returns the percentage of how far the rev lights indicator is engaged.
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set_with = "pub")]
     rev_lights: u8,
 
-    /// Returns the brake temperature at each corner of the in degrees celsius.
-    #[getset(get = "pub")]
-    brake_temperature: CornerProperty<u16>,
+    /// Returns the brake temperature at each corner of the car.
+    #[getset(get = "pub", set_with = "pub")]
+    brake_temperature: CornerProperty<Temperature>,
 
-    /// Returns the tyre surface temperature at each corner of the car in degrees celsius.
-    #[getset(get = "pub")]
-    tyre_surface_temperature: CornerProperty<u16>,
+    /// Returns the tyre surface temperature at each corner of the car.
+    #[getset(get = "pub", set_with = "pub")]
+    tyre_surface_temperature: CornerProperty<Temperature>,
 
-    /// Returns the tyre inner temperature at each corner of the car in degrees celsius.
-    #[getset(get = "pub")]
-    tyre_inner_temperature: CornerProperty<u16>,
+    /// Returns the tyre inner temperature at each corner of the car.
+    #[getset(get = "pub", set_with = "pub")]
+    tyre_inner_temperature: CornerProperty<Temperature>,
 
-    /// Returns the engine temperature in degrees celsius.
-    #[getset(get_copy = "pub")]
-    engine_temperature: u16,
+    /// Returns the engine temperature.
+    #[getset(get_copy = "pub", set_with = "pub")]
+    engine_temperature: Temperature,
 
-    /// Returns the tyre pressure at each corner of the car in psi.
-    #[getset(get = "pub")]
-    tyre_pressure: CornerProperty<f32>,
+    /// Returns the tyre pressure at each corner of the car.
+    #[getset(get = "pub", set_with = "pub")]
+    tyre_pressure: CornerProperty<Pressure>,
 
     /// Returns the type of the surface each tyre fo the car has contact with.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     surface_type: CornerProperty<Surface>,
 }
 
@@ -160,17 +174,66 @@ pub struct Telemetry {
 ///
 /// The F1 games publish telemetry data for each car in the session. The telemetry data includes
 /// parameters such as the car's speed, as well as information in controller inputs from the user.
-#[derive(new, Debug, CopyGetters, Getters, PartialEq, Clone, PartialOrd)]
+#[derive(
+    new, Debug, CopyGetters, Getters, MutGetters, WithSetters, PartialEq, Clone, PartialOrd,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TelemetryPacket {
     /// Returns the packet header prefixing the telemetry packet.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set_with = "pub")]
     header: Header,
 
     /// Returns the telemetry data for each car in the session.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", get_mut = "pub(crate)", set_with = "pub")]
     telemetry: Vec<Telemetry>,
 
     /// Returns a bit flag indicating which buttons are currently pressed.
-    #[getset(get_copy = "pub")]
-    button_status: Button,
+    #[getset(get_copy = "pub", set_with = "pub")]
+    button_status: ButtonFlags,
+}
+
+impl Default for TelemetryPacket {
+    fn default() -> Self {
+        TelemetryPacket {
+            header: Header::default().with_packet_type(PacketType::Telemetry),
+            telemetry: Default::default(),
+            button_status: Default::default(),
+        }
+    }
+}
+
+impl TelemetryPacket {
+    /// Returns the telemetry of a car, if the vehicle index refers to a car in the packet.
+    pub fn get(&self, vehicle_index: VehicleIndex) -> Option<&Telemetry> {
+        car_entry(&self.telemetry, vehicle_index)
+    }
+
+    /// Returns the telemetry of the player's car, if the player is driving a car.
+    pub fn player(&self) -> Option<&Telemetry> {
+        self.get(self.header.player_car_index())
+    }
+
+    /// Returns the telemetry of the secondary player's car in split-screen sessions.
+    pub fn secondary_player(&self) -> Option<&Telemetry> {
+        self.header
+            .secondary_player_car_index()
+            .and_then(|vehicle_index| self.get(vehicle_index))
+    }
+
+    /// Returns the telemetry of the active cars, with the index and the participant of each car.
+    pub fn cars_with<'a>(
+        &'a self,
+        participants: &'a ParticipantsPacket,
+    ) -> impl Iterator<Item = (VehicleIndex, &'a Participant, &'a Telemetry)> {
+        cars_with(participants, &self.telemetry)
+    }
+}
+
+impl Index<VehicleIndex> for TelemetryPacket {
+    type Output = Telemetry;
+
+    /// Returns the telemetry of a car, and panics if the vehicle index refers to no car in the packet.
+    fn index(&self, vehicle_index: VehicleIndex) -> &Telemetry {
+        &self.telemetry[vehicle_index.as_usize()]
+    }
 }
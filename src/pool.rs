@@ -0,0 +1,229 @@
+//! Pool of reusable receive buffers
+//!
+//! The F1 games send dozens of packets per second, and a buffer is needed to receive each of them.
+//! Allocating a new buffer for every datagram puts pressure on the allocator for no benefit, since
+//! the datagram is decoded right away and its buffer is dropped again. The buffer pool keeps the
+//! buffers that have been returned to it, and hands them out again, so that a stream that has warmed
+//! up receives its packets without allocating any buffers.
+//!
+//! The pool counts the buffers it allocates and reuses. Its metrics can be used to verify that a
+//! stream runs without allocations, or to size the pool for consumers that hold on to buffers.
+
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use bytes::BytesMut;
+use derive_new::new;
+use getset::CopyGetters;
+
+/// Size of the buffers in the default pool, which fits the largest packet sent by the F1 games
+pub const DEFAULT_BUFFER_SIZE: usize = 2048;
+
+/// Number of idle buffers that the default pool keeps
+pub const DEFAULT_POOL_CAPACITY: usize = 4;
+
+/// Metrics that describe how a pool has been used
+#[derive(new, Debug, CopyGetters, PartialEq, Eq, Copy, Clone, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoolMetrics {
+    /// Returns the number of buffers that have been allocated by the pool.
+    #[getset(get_copy = "pub")]
+    allocations: u64,
+
+    /// Returns the number of times that an idle buffer has been reused.
+    #[getset(get_copy = "pub")]
+    reuses: u64,
+
+    /// Returns the number of returned buffers that have been dropped because the pool was full.
+    #[getset(get_copy = "pub")]
+    discards: u64,
+
+    /// Returns the number of idle buffers in the pool.
+    #[getset(get_copy = "pub")]
+    available: usize,
+}
+
+/// Shared state of a pool and its buffers
+#[derive(Debug, Default)]
+struct Inner {
+    buffer_size: usize,
+    capacity: usize,
+    buffers: Mutex<Vec<BytesMut>>,
+    allocations: AtomicU64,
+    reuses: AtomicU64,
+    discards: AtomicU64,
+}
+
+/// Pool of buffers that are reused to receive datagrams
+///
+/// Cloning the pool creates another handle to the same buffers, which can be used to read the
+/// metrics of a pool that has been passed to a stream. Pools are equal if they share their buffers.
+///
+/// # Examples
+///
+/// ```
+/// use f1_api::pool::BufferPool;
+///
+/// let pool = BufferPool::default();
+/// drop(pool.get());
+/// drop(pool.get());
+///
+/// assert_eq!(1, pool.metrics().allocations());
+/// assert_eq!(1, pool.metrics().reuses());
+/// ```
+#[derive(Debug, Clone)]
+pub struct BufferPool {
+    inner: Arc<Inner>,
+}
+
+impl BufferPool {
+    /// Create a new pool of buffers with the given size, which keeps up to `capacity` idle buffers.
+    pub fn new(buffer_size: usize, capacity: usize) -> Self {
+        BufferPool {
+            inner: Arc::new(Inner {
+                buffer_size,
+                capacity,
+                buffers: Mutex::new(Vec::with_capacity(capacity)),
+                ..Inner::default()
+            }),
+        }
+    }
+
+    /// Returns the size of the buffers in bytes.
+    pub fn buffer_size(&self) -> usize {
+        self.inner.buffer_size
+    }
+
+    /// Take a buffer from the pool, or allocate a new one if no idle buffer is available.
+    ///
+    /// The buffer is filled with zeros up to the size of the buffers in the pool, and returns to the
+    /// pool when it is dropped.
+    pub fn get(&self) -> PooledBuffer {
+        // Buffers whose bytes have been frozen into a packet can only be reused once the packet has
+        // been dropped. Until then, a new buffer is allocated in their place.
+        let idle = self
+            .buffers()
+            .pop()
+            .and_then(|mut buffer| buffer.try_reclaim(self.inner.buffer_size).then_some(buffer));
+
+        let mut buffer = match idle {
+            Some(buffer) => {
+                self.inner.reuses.fetch_add(1, Ordering::Relaxed);
+                buffer
+            }
+            None => {
+                self.inner.allocations.fetch_add(1, Ordering::Relaxed);
+                BytesMut::with_capacity(self.inner.buffer_size)
+            }
+        };
+        buffer.resize(self.inner.buffer_size, 0);
+
+        PooledBuffer {
+            buffer: Some(buffer),
+            pool: self.clone(),
+        }
+    }
+
+    /// Returns the metrics of the pool.
+    pub fn metrics(&self) -> PoolMetrics {
+        PoolMetrics::new(
+            self.inner.allocations.load(Ordering::Relaxed),
+            self.inner.reuses.load(Ordering::Relaxed),
+            self.inner.discards.load(Ordering::Relaxed),
+            self.buffers().len(),
+        )
+    }
+
+    fn put(&self, mut buffer: BytesMut) {
+        let mut buffers = self.buffers();
+
+        // Buffers that have been split keep their allocation, and are reclaimed when they are taken.
+        if buffers.len() < self.inner.capacity {
+            buffer.clear();
+            buffers.push(buffer);
+        } else {
+            self.inner.discards.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn buffers(&self) -> std::sync::MutexGuard<'_, Vec<BytesMut>> {
+        // The buffers are only pushed and popped, so they are still valid if a thread panicked.
+        self.inner
+            .buffers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        BufferPool::new(DEFAULT_BUFFER_SIZE, DEFAULT_POOL_CAPACITY)
+    }
+}
+
+impl PartialEq for BufferPool {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+/// Buffer that returns to its pool when it is dropped
+#[derive(Debug)]
+pub struct PooledBuffer {
+    buffer: Option<BytesMut>,
+    pool: BufferPool,
+}
+
+impl Deref for PooledBuffer {
+    type Target = BytesMut;
+
+    fn deref(&self) -> &Self::Target {
+        // The buffer is only taken when it is dropped.
+        self.buffer.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledBuffer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.buffer.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        if let Some(buffer) = self.buffer.take() {
+            self.pool.put(buffer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pool::BufferPool;
+
+    #[test]
+    fn reuse_returned_buffers() {
+        let pool = BufferPool::new(64, 1);
+
+        let mut first = pool.get();
+        assert_eq!(64, first.len());
+        first.truncate(10);
+        first[0] = 1;
+
+        let second = pool.get();
+        drop(first);
+        drop(second);
+
+        let metrics = pool.metrics();
+        assert_eq!(2, metrics.allocations());
+        assert_eq!(1, metrics.discards());
+        assert_eq!(1, metrics.available());
+
+        let third = pool.get();
+        assert_eq!(64, third.len());
+        assert_eq!(0, third[0]);
+        assert_eq!(1, pool.metrics().reuses());
+        assert_eq!(0, pool.metrics().available());
+    }
+}
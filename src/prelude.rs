@@ -0,0 +1,50 @@
+//! Items that most consumers of this crate need
+//!
+//! The prelude re-exports the stream entry points, the packet enum and the packets it wraps, and
+//! the types that appear throughout the packets. Consumers that only read packets from a stream can
+//! import everything they need with a single glob import.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::net::{IpAddr, SocketAddr};
+//!
+//! use f1_api::prelude::*;
+//! use tokio_stream::StreamExt;
+//!
+//! async fn example() {
+//!     let socket = SocketAddr::new(IpAddr::from([0, 0, 0, 0]), 20777);
+//!     let mut stream = F1::stream(socket).unwrap();
+//!
+//!     while let Some(packet) = stream.next().await {
+//!         if let Ok(Packet::Telemetry(telemetry)) = packet {
+//!             if let Some(player) = telemetry.player() {
+//!                 println!("{}", player.speed());
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+
+pub use crate::codec::{DecodeMode, DecoderBuilder, F1Codec};
+pub use crate::error::F1Error;
+pub use crate::handler::{dispatch, PacketHandler};
+pub use crate::lazy::{LazyCodec, LazyPacket};
+pub use crate::nineteen::view::{CarEntries, PacketView};
+pub use crate::nineteen::{decode_into, DecodeInto};
+pub use crate::packet::event::{Event, EventPacket};
+pub use crate::packet::header::{ApiSpec, GameVersion, Header, PacketType};
+pub use crate::packet::lap::{Lap, LapPacket};
+pub use crate::packet::motion::{Motion, MotionPacket};
+pub use crate::packet::participants::{Participant, ParticipantsPacket};
+pub use crate::packet::session::SessionPacket;
+pub use crate::packet::setup::{CarSetup, CarSetupPacket};
+pub use crate::packet::status::{CarStatus, CarStatusPacket};
+pub use crate::packet::telemetry::{Telemetry, TelemetryPacket};
+pub use crate::packet::Packet;
+pub use crate::pool::{BufferPool, PoolMetrics};
+pub use crate::state::SessionState;
+pub use crate::types::{
+    CornerProperty, Flag, Pressure, Property3D, Speed, Temperature, VehicleIndex,
+};
+pub use crate::{ok_packets, F1};
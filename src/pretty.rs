@@ -0,0 +1,375 @@
+//! Human-readable formatting of packets
+//!
+//! The derived `Debug` output of the packets lists every field of every car in one long line, which
+//! is hard to read when looking at live data. The pretty printer renders a packet as a short summary
+//! of its header, followed by an aligned table with a row for each car and a column for the most
+//! important fields of the packet. Session packets are rendered as a list of their fields, and
+//! event packets as a single line.
+//!
+//! The pretty printer is meant for humans, and its output may change between versions. Programs
+//! that need a stable format should serialize the packets with the `serde` feature instead.
+
+use std::fmt;
+use std::fmt::Display;
+use std::time::Duration;
+
+use crate::packet::header::Header;
+use crate::packet::lap::LapPacket;
+use crate::packet::motion::MotionPacket;
+use crate::packet::participants::ParticipantsPacket;
+use crate::packet::session::SessionPacket;
+use crate::packet::setup::CarSetupPacket;
+use crate::packet::status::CarStatusPacket;
+use crate::packet::telemetry::{Gear, TelemetryPacket};
+use crate::packet::Packet;
+
+/// Packet that is formatted for humans
+///
+/// This struct is returned by `Packet::pretty`, and renders the packet when it is displayed.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Pretty<'a> {
+    packet: &'a Packet,
+}
+
+impl Packet {
+    /// Returns the packet in a form that is formatted for humans when it is displayed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use f1_api::packet::Packet;
+    ///
+    /// fn print(packet: &Packet) {
+    ///     println!("{}", packet.pretty());
+    /// }
+    /// ```
+    pub fn pretty(&self) -> Pretty<'_> {
+        Pretty { packet: self }
+    }
+}
+
+impl Display for Pretty<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = self.packet.header();
+
+        writeln!(
+            f,
+            "{:?} packet, session {}, time {:.3} s, frame {}",
+            header.packet_type(),
+            header.session_uid(),
+            header.session_time().as_secs_f32(),
+            header.frame_identifier()
+        )?;
+
+        match self.packet {
+            Packet::Event(packet) => writeln!(f, "{}", packet.event()),
+            Packet::Lap(packet) => write!(f, "{}", lap_table(packet)),
+            Packet::Motion(packet) => write!(f, "{}", motion_table(packet)),
+            Packet::Participants(packet) => write!(f, "{}", participants_table(packet)),
+            Packet::Session(packet) => write!(f, "{}", session_table(packet)),
+            Packet::Setup(packet) => write!(f, "{}", setup_table(packet)),
+            Packet::Status(packet) => write!(f, "{}", status_table(packet)),
+            Packet::Telemetry(packet) => write!(f, "{}", telemetry_table(packet)),
+        }
+    }
+}
+
+/// Table with named columns whose values are aligned when it is displayed
+#[derive(Debug, PartialEq, Clone, Default)]
+struct Table {
+    columns: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn new(columns: &[&'static str]) -> Self {
+        Table {
+            columns: columns.to_vec(),
+            rows: Vec::new(),
+        }
+    }
+
+    fn row(&mut self, values: Vec<String>) {
+        self.rows.push(values);
+    }
+}
+
+impl Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                self.rows
+                    .iter()
+                    .filter_map(|row| row.get(index))
+                    .map(|value| value.chars().count())
+                    .chain(std::iter::once(column.chars().count()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let columns: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| column.to_string())
+            .collect();
+        for row in std::iter::once(&columns).chain(self.rows.iter()) {
+            let line: Vec<String> = row
+                .iter()
+                .zip(widths.iter())
+                .map(|(value, width)| format!("{:<width$}", value, width = width))
+                .collect();
+
+            writeln!(f, "{}", line.join("  ").trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the index of a car, marked with an asterisk if it is the player's car.
+fn car(header: &Header, index: usize) -> String {
+    if index == header.player_car_index().as_usize() {
+        format!("{}*", index)
+    } else {
+        index.to_string()
+    }
+}
+
+/// Format a lap time as minutes, seconds, and milliseconds, or a dash if it is not set.
+fn lap_time(time: &Duration) -> String {
+    if time.is_zero() {
+        return String::from("-");
+    }
+
+    let millis = time.as_millis();
+    format!(
+        "{}:{:02}.{:03}",
+        millis / 60_000,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+/// Format a gear as it is shown on the steering wheel.
+fn gear(gear: Gear) -> String {
+    match gear {
+        Gear::Reverse => String::from("R"),
+        Gear::Neutral => String::from("N"),
+        gear => i8::from(gear).to_string(),
+    }
+}
+
+fn lap_table(packet: &LapPacket) -> Table {
+    let mut table = Table::new(&[
+        "Car", "Pos", "Lap", "Current", "Last", "Best", "Sector", "Distance", "Pit", "Status",
+    ]);
+
+    for (index, lap) in packet.laps().iter().enumerate() {
+        table.row(vec![
+            car(packet.header(), index),
+            lap.position().to_string(),
+            lap.current_lap_number().to_string(),
+            lap_time(lap.current_lap_time()),
+            lap_time(lap.last_lap_time()),
+            lap_time(lap.best_lap_time()),
+            format!("{:?}", lap.sector()),
+            format!("{:.1} m", lap.lap_distance()),
+            format!("{:?}", lap.pit_status()),
+            format!("{:?}", lap.result_status()),
+        ]);
+    }
+
+    table
+}
+
+fn motion_table(packet: &MotionPacket) -> Table {
+    let mut table = Table::new(&["Car", "X", "Y", "Z", "Speed", "Yaw", "Pitch", "Roll"]);
+
+    for (index, motion) in packet.cars().iter().enumerate() {
+        let (position, velocity) = (motion.position(), motion.velocity());
+        let speed =
+            (velocity.x().powi(2) + velocity.y().powi(2) + velocity.z().powi(2)).sqrt() * 3.6;
+
+        table.row(vec![
+            car(packet.header(), index),
+            format!("{:.1}", position.x()),
+            format!("{:.1}", position.y()),
+            format!("{:.1}", position.z()),
+            format!("{:.0} km/h", speed),
+            format!("{:.3}", motion.yaw()),
+            format!("{:.3}", motion.pitch()),
+            format!("{:.3}", motion.roll()),
+        ]);
+    }
+
+    table
+}
+
+fn participants_table(packet: &ParticipantsPacket) -> Table {
+    let mut table = Table::new(&["Car", "Name", "Number", "Team", "Nationality", "Controller"]);
+
+    for (index, participant) in packet
+        .participants()
+        .iter()
+        .take(packet.active_participants_count() as usize)
+        .enumerate()
+    {
+        table.row(vec![
+            car(packet.header(), index),
+            participant.name().clone(),
+            participant.race_number().to_string(),
+            participant.team().name().to_string(),
+            participant.nationality().name().to_string(),
+            format!("{:?}", participant.controller()),
+        ]);
+    }
+
+    table
+}
+
+fn session_table(packet: &SessionPacket) -> Table {
+    let mut table = Table::new(&["Field", "Value"]);
+    let mut field = |name: &'static str, value: String| table.row(vec![name.to_string(), value]);
+
+    field("Track", packet.track().info().name().to_string());
+    field("Session", packet.session_type().to_string());
+    field("Formula", packet.formula().to_string());
+    field("Weather", format!("{:?}", packet.weather()));
+    field("Air temperature", packet.air_temperature().to_string());
+    field("Track temperature", packet.track_temperature().to_string());
+    field("Laps", packet.total_laps().to_string());
+    field("Time left", format!("{} s", packet.time_left().as_secs()));
+    field("Safety car", format!("{:?}", packet.safety_car()));
+
+    for (index, zone) in packet.marshal_zones().iter().enumerate() {
+        table.row(vec![
+            format!("Marshal zone {}", index),
+            format!("{:.3} {}", zone.start_fraction(), zone.flag()),
+        ]);
+    }
+
+    table
+}
+
+fn setup_table(packet: &CarSetupPacket) -> Table {
+    let mut table = Table::new(&[
+        "Car",
+        "Wings",
+        "Differential",
+        "Suspension",
+        "Anti-roll bars",
+        "Brake bias",
+        "Fuel",
+    ]);
+
+    for (index, setup) in packet.setups().iter().enumerate() {
+        table.row(vec![
+            car(packet.header(), index),
+            format!("{}/{}", setup.front_wing(), setup.rear_wing()),
+            format!("{}/{}", setup.on_throttle(), setup.off_throttle()),
+            format!("{}/{}", setup.front_suspension(), setup.rear_suspension()),
+            format!(
+                "{}/{}",
+                setup.front_anti_roll_bar(),
+                setup.rear_anti_roll_bar()
+            ),
+            format!("{} %", setup.brake_bias()),
+            format!("{:.1} kg", setup.fuel_load()),
+        ]);
+    }
+
+    table
+}
+
+fn status_table(packet: &CarStatusPacket) -> Table {
+    let mut table = Table::new(&["Car", "Tyres", "Wear", "Fuel", "Fuel laps", "ERS", "Flag"]);
+
+    for (index, status) in packet.statuses().iter().enumerate() {
+        let wear = status.tyre_wear();
+
+        table.row(vec![
+            car(packet.header(), index),
+            status.visual_tyre_compound().name().to_string(),
+            format!(
+                "{}/{}/{}/{} %",
+                wear.front_left(),
+                wear.front_right(),
+                wear.rear_left(),
+                wear.rear_right()
+            ),
+            format!("{:.1} kg", status.fuel_remaining()),
+            format!("{:.1}", status.fuel_remaining_laps()),
+            format!("{:.0} kJ", status.ers_energy() / 1000.0),
+            status.vehicle_flags().to_string(),
+        ]);
+    }
+
+    table
+}
+
+fn telemetry_table(packet: &TelemetryPacket) -> Table {
+    let mut table = Table::new(&[
+        "Car", "Speed", "Throttle", "Brake", "Steering", "Gear", "RPM", "DRS",
+    ]);
+
+    for (index, telemetry) in packet.telemetry().iter().enumerate() {
+        table.row(vec![
+            car(packet.header(), index),
+            telemetry.speed().to_string(),
+            format!("{:.0} %", telemetry.throttle() * 100.0),
+            format!("{:.0} %", telemetry.brake() * 100.0),
+            format!("{:.2}", telemetry.steering()),
+            gear(telemetry.gear()),
+            telemetry.engine_rpm().to_string(),
+            if telemetry.drs() { "Open" } else { "Closed" }.to_string(),
+        ]);
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::fixtures::{lap_packet, participants_packet, LapFixture};
+
+    #[test]
+    fn pretty_lap_packet() {
+        let packet = lap_packet(
+            1.5,
+            vec![
+                LapFixture {
+                    position: 2,
+                    last_lap_time: 83.456,
+                    ..LapFixture::default()
+                },
+                LapFixture {
+                    position: 1,
+                    ..LapFixture::default()
+                },
+            ],
+        );
+
+        let pretty = packet.pretty().to_string();
+        let lines: Vec<&str> = pretty.lines().collect();
+
+        assert_eq!(4, lines.len());
+        assert!(lines[0].starts_with("Lap packet"));
+        assert!(lines[1].starts_with("Car  Pos  Lap  Current  Last      Best"));
+        assert!(lines[2].starts_with("0*   2    "));
+        assert!(lines[2].contains("1:23.456"));
+        assert!(lines[3].starts_with("1    1    "));
+    }
+
+    #[test]
+    fn pretty_participants_packet() {
+        let packet = participants_packet(0.0, vec!["Alice", "Bob"]);
+
+        let pretty = packet.pretty().to_string();
+        assert_eq!(4, pretty.lines().count());
+        assert!(pretty.contains("Alice"));
+    }
+}
@@ -0,0 +1,644 @@
+//! Live state of a session
+//!
+//! Every packet sent by the F1 games describes only one aspect of a session, for example the lap
+//! data or the telemetry of the cars. Most applications need to combine these packets to get the
+//! full picture, and show the name of a driver next to their position and their tyres. The session
+//! state consumes every packet, and keeps the latest data of the session and of each car in one
+//! place.
+//!
+//! While it consumes the packets, the session state also derives events that are not published by
+//! the games, for example when a car enters the pit lane or overtakes another car. These events can
+//! be published to many consumers with the event bus. Overtakes are detected by a position tracker
+//! with its default debounce, so that positions that flicker during a pit cycle or at a timing line
+//! do not publish a burst of overtakes.
+//!
+//! In multiplayer sessions, players can restrict the telemetry of their car. The games then set the
+//! private values of these cars to zero, for example their fuel, tyre wear, and setup. The session
+//! state flags cars with restricted telemetry, so that these values can be shown as unavailable
+//! instead of as zeros. The setups of restricted cars are not stored at all.
+//!
+//! When a packet of a new session is received, the state of the previous session is discarded.
+//!
+//! Applications that read the state from other threads, for example to serve it over HTTP or to
+//! render it, can take a snapshot of the state at any time. Snapshots are immutable, and share the
+//! data of the session, the cars, and the position tracker with the live state until it is replaced
+//! by newer packets. This makes snapshots cheap to take, even at the rate at which packets are
+//! received.
+//!
+//! With the `serde` feature enabled, the session state can be serialized to disk and restored
+//! later, so that an application that was restarted can resume in the middle of a session. The
+//! shared data is serialized like any other value, and a restored state allocates its own copy of
+//! the session, of each car, and of the position tracker. It does not share any data with the
+//! snapshots of the state that was serialized, and serializing many snapshots stores the same data
+//! many times.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use getset::{CopyGetters, Getters};
+
+use crate::analysis::position::PositionTracker;
+use crate::analysis::privacy::is_restricted;
+use crate::packet::event::Event;
+use crate::packet::header::Header;
+use crate::packet::lap::{Lap, LapPacket, PitStatus, ResultStatus};
+use crate::packet::motion::Motion;
+use crate::packet::participants::Participant;
+use crate::packet::session::SessionPacket;
+use crate::packet::setup::CarSetup;
+use crate::packet::status::{CarStatus, CarStatusPacket};
+use crate::packet::telemetry::Telemetry;
+use crate::packet::Packet;
+use crate::state::event::{SessionEvent, SessionEventKind};
+use crate::types::{car_entry, enumerate_cars, VehicleIndex};
+
+pub mod diff;
+pub mod drivers;
+pub mod event;
+pub mod history;
+pub mod manager;
+pub mod subscription;
+
+/// Latest data of a car in the session
+#[derive(Debug, Getters, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CarState {
+    /// Returns the participant driving the car, if it is known.
+    #[getset(get = "pub")]
+    participant: Option<Participant>,
+
+    /// Returns the latest lap data of the car, if it is known.
+    #[getset(get = "pub")]
+    lap: Option<Lap>,
+
+    /// Returns the latest status of the car, if it is known.
+    ///
+    /// The private values of the status, for example the fuel, tyre wear, and ERS, are zero if the
+    /// telemetry of the car is restricted.
+    #[getset(get = "pub")]
+    status: Option<CarStatus>,
+
+    /// Returns the latest telemetry of the car, if it is known.
+    #[getset(get = "pub")]
+    telemetry: Option<Telemetry>,
+
+    /// Returns the latest motion data of the car, if it is known.
+    #[getset(get = "pub")]
+    motion: Option<Motion>,
+
+    /// Returns the latest setup of the car, if it is known and the telemetry is not restricted.
+    #[getset(get = "pub")]
+    setup: Option<CarSetup>,
+}
+
+impl CarState {
+    /// Returns whether the participant has restricted the telemetry of the car.
+    pub fn is_restricted(&self) -> bool {
+        self.participant.as_ref().is_some_and(is_restricted)
+    }
+
+    /// Returns the latest status of the car, if it is known and the telemetry is not restricted.
+    pub fn unrestricted_status(&self) -> Option<&CarStatus> {
+        self.status.as_ref().filter(|_| !self.is_restricted())
+    }
+
+    /// Returns the position of the car, if it is known.
+    pub fn position(&self) -> Option<u8> {
+        self.lap
+            .map(|lap| lap.position())
+            .filter(|position| *position > 0)
+    }
+}
+
+/// Live state of a session
+///
+/// The session state consumes every packet, and replaces the data of the session and the cars with
+/// the latest values. Cars are added to the state as soon as they appear in any packet, and stay in
+/// the state until a new session starts.
+#[derive(Debug, Getters, CopyGetters, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SessionState {
+    /// Returns the unique identifier of the session, if a packet has been received.
+    #[getset(get_copy = "pub")]
+    session_uid: Option<u64>,
+
+    /// Returns the session time of the latest packet.
+    #[getset(get = "pub")]
+    session_time: Duration,
+
+    /// Returns the frame identifier of the latest packet.
+    #[getset(get_copy = "pub")]
+    frame_identifier: u32,
+
+    /// Returns the index of the player's car, as reported by the latest packet.
+    ///
+    /// The index does not refer to a car of the player while they are spectating the session.
+    #[getset(get_copy = "pub")]
+    player_car_index: VehicleIndex,
+
+    /// Returns the index of the secondary player's car in split-screen sessions, as reported by the
+    /// latest packet.
+    #[getset(get_copy = "pub")]
+    secondary_player_car_index: Option<VehicleIndex>,
+
+    session: Option<Arc<SessionPacket>>,
+
+    /// Returns the number of active participants in the session, if it is known.
+    #[getset(get_copy = "pub")]
+    active_participants_count: Option<u8>,
+
+    cars: BTreeMap<VehicleIndex, Arc<CarState>>,
+
+    positions: Arc<PositionTracker>,
+}
+
+impl SessionState {
+    /// Create a new, empty session state.
+    pub fn new() -> Self {
+        SessionState::default()
+    }
+
+    /// Update the state with the data from the packet, and return the derived events.
+    pub fn update(&mut self, packet: &Packet) -> Vec<SessionEvent> {
+        self.update_header(packet.header());
+
+        match packet {
+            Packet::Event(packet) => {
+                if let Event::FastestLap(fastest_lap) = packet.event() {
+                    return vec![SessionEvent::new(
+                        self.session_time,
+                        fastest_lap.vehicle_index(),
+                        SessionEventKind::FastestLap(*fastest_lap.time()),
+                    )];
+                }
+            }
+            Packet::Lap(packet) => return self.update_laps(packet),
+            Packet::Motion(packet) => {
+                for (index, motion) in enumerate_cars(packet.cars()) {
+                    self.car_mut(index).motion = Some(*motion);
+                }
+            }
+            Packet::Participants(packet) => {
+                self.active_participants_count = Some(packet.active_participants_count());
+
+                for (index, participant) in enumerate_cars(packet.participants()) {
+                    let car = self.car_mut(index);
+                    car.participant = Some(participant.clone());
+
+                    if car.is_restricted() {
+                        car.setup = None;
+                    }
+                }
+            }
+            Packet::Session(packet) => self.session = Some(Arc::new(packet.clone())),
+            Packet::Setup(packet) => {
+                for (index, setup) in enumerate_cars(packet.setups()) {
+                    let car = self.car_mut(index);
+
+                    if !car.is_restricted() {
+                        car.setup = Some(*setup);
+                    }
+                }
+            }
+            Packet::Status(packet) => return self.update_statuses(packet),
+            Packet::Telemetry(packet) => {
+                for (index, telemetry) in enumerate_cars(packet.telemetry()) {
+                    self.car_mut(index).telemetry = Some(*telemetry);
+                }
+            }
+        }
+
+        Vec::new()
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) -> Vec<SessionEvent> {
+        let mut events = Vec::new();
+        let previous: Vec<Option<Lap>> = enumerate_cars(packet.laps())
+            .map(|(index, _)| self.car(index).and_then(|car| car.lap))
+            .collect();
+
+        for (index, lap) in enumerate_cars(packet.laps()) {
+            self.car_mut(index).lap = Some(*lap);
+
+            let previous_lap = match previous[index.as_usize()] {
+                Some(previous_lap) => previous_lap,
+                None => continue,
+            };
+
+            let kind = |kind| SessionEvent::new(self.session_time, index, kind);
+
+            let was_in_pit_lane = previous_lap.pit_status() != PitStatus::None;
+            let is_in_pit_lane = lap.pit_status() != PitStatus::None;
+
+            if !was_in_pit_lane && is_in_pit_lane {
+                events.push(kind(SessionEventKind::PitEntry));
+            } else if was_in_pit_lane && !is_in_pit_lane {
+                events.push(kind(SessionEventKind::PitExit));
+            }
+
+            if !is_out(previous_lap.result_status()) && is_out(lap.result_status()) {
+                events.push(kind(SessionEventKind::Retirement(lap.result_status())));
+            }
+        }
+
+        for change in Arc::make_mut(&mut self.positions).update_laps(packet) {
+            let (gainer, loser) = match (
+                car_entry(packet.laps(), change.gainer()),
+                car_entry(packet.laps(), change.loser()),
+            ) {
+                (Some(gainer), Some(loser)) => (gainer, loser),
+                _ => continue,
+            };
+
+            // Cars that lose their position because they are in the pit lane are not overtaken.
+            if loser.pit_status() != PitStatus::None {
+                continue;
+            }
+
+            events.push(SessionEvent::new(
+                self.session_time,
+                change.gainer(),
+                SessionEventKind::Overtake {
+                    overtaken: change.loser(),
+                    position: gainer.position(),
+                },
+            ));
+        }
+
+        events
+    }
+
+    fn update_statuses(&mut self, packet: &CarStatusPacket) -> Vec<SessionEvent> {
+        let mut events = Vec::new();
+
+        for (index, status) in enumerate_cars(packet.statuses()) {
+            let session_time = self.session_time;
+            let car = self.car_mut(index);
+            let kind = |kind| SessionEvent::new(session_time, index, kind);
+
+            if let Some(previous) = car.status {
+                if previous.vehicle_flags() != status.vehicle_flags() {
+                    events.push(kind(SessionEventKind::FlagChange(status.vehicle_flags())));
+                }
+
+                if previous.visual_tyre_compound() != status.visual_tyre_compound() {
+                    events.push(kind(SessionEventKind::TyreChange(
+                        status.visual_tyre_compound(),
+                    )));
+                }
+            }
+
+            car.status = Some(*status);
+        }
+
+        events
+    }
+
+    fn update_header(&mut self, header: &Header) {
+        if self
+            .session_uid
+            .is_some_and(|session_uid| session_uid != header.session_uid())
+        {
+            *self = SessionState::default();
+        }
+
+        self.session_uid = Some(header.session_uid());
+        self.session_time = *header.session_time();
+        self.frame_identifier = header.frame_identifier();
+        self.player_car_index = header.player_car_index();
+        self.secondary_player_car_index = header.secondary_player_car_index();
+    }
+
+    fn car_mut(&mut self, vehicle_index: VehicleIndex) -> &mut CarState {
+        Arc::make_mut(self.cars.entry(vehicle_index).or_default())
+    }
+
+    /// Take an immutable snapshot of the current state.
+    ///
+    /// The snapshot shares the data that has not changed since with the live state, and is not
+    /// affected by any packets that are received after it was taken.
+    pub fn snapshot(&self) -> Arc<SessionState> {
+        Arc::new(self.clone())
+    }
+
+    /// Returns the latest session packet, if one has been received.
+    pub fn session(&self) -> Option<&SessionPacket> {
+        self.session.as_deref()
+    }
+
+    /// Returns the state of a car.
+    pub fn car(&self, vehicle_index: VehicleIndex) -> Option<&CarState> {
+        self.cars.get(&vehicle_index).map(|car| car.as_ref())
+    }
+
+    /// Returns whether the player is spectating the session, if a session packet has been received.
+    pub fn is_spectating(&self) -> bool {
+        self.session()
+            .is_some_and(|session| session.is_spectating())
+    }
+
+    /// Returns the state of the player's car, unless the player is spectating the session.
+    pub fn player(&self) -> Option<&CarState> {
+        if self.is_spectating() {
+            None
+        } else {
+            self.car(self.player_car_index)
+        }
+    }
+
+    /// Returns the state of the secondary player's car in split-screen sessions.
+    pub fn secondary_player(&self) -> Option<&CarState> {
+        self.secondary_player_car_index
+            .and_then(|vehicle_index| self.car(vehicle_index))
+    }
+
+    /// Returns the index of the car the player is looking at.
+    ///
+    /// This is the car being spectated while the player is spectating, and the player's own car
+    /// otherwise.
+    pub fn focused_car_index(&self) -> VehicleIndex {
+        self.session()
+            .and_then(|session| session.spectated_car())
+            .unwrap_or(self.player_car_index)
+    }
+
+    /// Returns the state of the car the player is looking at.
+    pub fn focused_car(&self) -> Option<&CarState> {
+        self.car(self.focused_car_index())
+    }
+
+    /// Returns the states of all cars, ordered by their index.
+    pub fn cars(&self) -> impl Iterator<Item = (VehicleIndex, &CarState)> {
+        self.cars.iter().map(|(index, car)| (*index, car.as_ref()))
+    }
+
+    /// Returns the states of the cars with a position, ordered by their position.
+    pub fn classification(&self) -> Vec<(VehicleIndex, &CarState)> {
+        let mut cars: Vec<(VehicleIndex, &CarState)> = self
+            .cars()
+            .filter(|(_, car)| car.position().is_some())
+            .collect();
+
+        cars.sort_by_key(|(_, car)| car.position());
+        cars
+    }
+}
+
+/// Returns whether the result status ends the session of a car before the finish.
+fn is_out(status: ResultStatus) -> bool {
+    matches!(
+        status,
+        ResultStatus::Disqualified | ResultStatus::NotClassified | ResultStatus::Retired
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::analysis::fixtures::{
+        header, lap_packet, participants_packet, session_packet, status_packet, vehicle,
+        LapFixture, SessionFixture, StatusFixture,
+    };
+    use crate::packet::header::{Header, PacketType};
+    use crate::packet::lap::{LapPacket, ResultStatus};
+    use crate::packet::participants::{
+        Controller, Driver, Nationality, Participant, ParticipantsPacket, Team, TelemetryPrivacy,
+    };
+    use crate::packet::session::Session;
+    use crate::packet::Packet;
+    use crate::state::event::SessionEventKind;
+    use crate::state::SessionState;
+
+    fn lap(position: u8) -> LapFixture {
+        LapFixture {
+            position,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_packets() {
+        let mut state = SessionState::new();
+
+        state.update(&session_packet(
+            1.0,
+            SessionFixture {
+                session_type: Session::Race,
+                ..SessionFixture::default()
+            },
+        ));
+        state.update(&participants_packet(1.0, vec!["Alice", "Bob"]));
+        state.update(&lap_packet(2.0, vec![lap(2), lap(1)]));
+
+        assert_eq!(Some(0), state.session_uid());
+        assert_eq!(2.0, state.session_time().as_secs_f32());
+        assert_eq!(Session::Race, state.session().unwrap().session_type());
+
+        let classification = state.classification();
+        assert_eq!(1, classification[0].0);
+        assert_eq!(
+            "Bob",
+            classification[0].1.participant().as_ref().unwrap().name()
+        );
+        assert_eq!(Some(2), state.player().unwrap().position());
+    }
+
+    #[test]
+    fn snapshot_with_later_update() {
+        let mut state = SessionState::new();
+
+        state.update(&lap_packet(1.0, vec![lap(1), lap(2)]));
+        let snapshot = state.snapshot();
+        state.update(&lap_packet(2.0, vec![lap(2), lap(1)]));
+
+        assert_eq!(1.0, snapshot.session_time().as_secs_f32());
+        assert_eq!(Some(1), snapshot.car(vehicle(0)).unwrap().position());
+        assert_eq!(Some(2), state.car(vehicle(0)).unwrap().position());
+    }
+
+    #[test]
+    fn snapshot_shares_position_tracker() {
+        let mut state = SessionState::new();
+
+        state.update(&lap_packet(1.0, vec![lap(1), lap(2)]));
+        let snapshot = state.snapshot();
+        assert!(Arc::ptr_eq(&state.positions, &snapshot.positions));
+
+        state.update(&lap_packet(2.0, vec![lap(2), lap(1)]));
+        assert!(!Arc::ptr_eq(&state.positions, &snapshot.positions));
+        assert_ne!(state.positions, snapshot.positions);
+    }
+
+    #[test]
+    fn update_with_restricted_telemetry() {
+        let mut state = SessionState::new();
+        let participant = Participant::new(
+            Controller::Human,
+            Driver::default(),
+            Team::default(),
+            0,
+            Nationality::default(),
+            "Alice".to_string(),
+            Some(TelemetryPrivacy::Restricted),
+        );
+
+        state.update(&Packet::Participants(ParticipantsPacket::new(
+            header(PacketType::Participants, 1.0),
+            1,
+            vec![participant],
+        )));
+        state.update(&status_packet(1.0, vec![StatusFixture::default()]));
+
+        let car = state.car(vehicle(0)).unwrap();
+        assert!(car.is_restricted());
+        assert!(car.status().is_some());
+        assert_eq!(None, car.unrestricted_status());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn restore_serialized_state() {
+        let mut state = SessionState::new();
+
+        state.update(&session_packet(
+            1.0,
+            SessionFixture {
+                session_type: Session::Race,
+                ..SessionFixture::default()
+            },
+        ));
+        state.update(&participants_packet(1.0, vec!["Alice", "Bob"]));
+        state.update(&lap_packet(2.0, vec![lap(2), lap(1)]));
+        state.update(&status_packet(2.0, vec![StatusFixture::default()]));
+
+        let json = serde_json::to_string(&state.snapshot()).unwrap();
+        let restored: SessionState = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(state, restored);
+        assert_eq!(Some(1), restored.car(vehicle(1)).unwrap().position());
+    }
+
+    #[test]
+    fn retire_disqualified_and_unclassified_cars() {
+        let mut state = SessionState::new();
+        let result = |result_status| LapFixture {
+            result_status,
+            ..LapFixture::default()
+        };
+
+        state.update(&lap_packet(0.0, vec![result(ResultStatus::Active); 3]));
+
+        let events = state.update(&lap_packet(
+            1.0,
+            vec![
+                result(ResultStatus::Disqualified),
+                result(ResultStatus::Retired),
+                result(ResultStatus::Finished),
+            ],
+        ));
+        let retirements: Vec<_> = events
+            .iter()
+            .map(|event| (event.vehicle_index(), event.kind()))
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    vehicle(0),
+                    SessionEventKind::Retirement(ResultStatus::Disqualified)
+                ),
+                (
+                    vehicle(1),
+                    SessionEventKind::Retirement(ResultStatus::Retired)
+                ),
+            ],
+            retirements
+        );
+
+        let events = state.update(&lap_packet(
+            2.0,
+            vec![
+                result(ResultStatus::Disqualified),
+                result(ResultStatus::NotClassified),
+                result(ResultStatus::Finished),
+            ],
+        ));
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn debounce_overtakes() {
+        let mut state = SessionState::new();
+        let mut overtakes = Vec::new();
+
+        // The positions of two cars flap while the timing catches up, and then settle swapped.
+        for (time, positions) in [
+            (0.0, (1, 2)),
+            (0.2, (2, 1)),
+            (0.4, (1, 2)),
+            (0.6, (2, 1)),
+            (0.8, (1, 2)),
+            (1.0, (2, 1)),
+            (2.0, (2, 1)),
+        ]
+        .iter()
+        {
+            let events = state.update(&lap_packet(*time, vec![lap(positions.0), lap(positions.1)]));
+            overtakes.extend(
+                events
+                    .into_iter()
+                    .filter(|event| matches!(event.kind(), SessionEventKind::Overtake { .. })),
+            );
+        }
+
+        assert_eq!(1, overtakes.len());
+        assert_eq!(vehicle(1), overtakes[0].vehicle_index());
+        assert_eq!(2.0, overtakes[0].session_time().as_secs_f32());
+    }
+
+    #[test]
+    fn player_while_spectating() {
+        let mut state = SessionState::new();
+
+        state.update(&session_packet(
+            1.0,
+            SessionFixture {
+                is_spectating: true,
+                spectator_car_index: Some(vehicle(1)),
+                ..SessionFixture::default()
+            },
+        ));
+        state.update(&lap_packet(1.0, vec![lap(1), lap(2)]));
+
+        assert!(state.is_spectating());
+        assert!(state.player().is_none());
+        assert_eq!(1, state.focused_car_index());
+        assert_eq!(Some(2), state.focused_car().unwrap().position());
+    }
+
+    #[test]
+    fn secondary_player_in_split_screen() {
+        let mut state = SessionState::new();
+        let header = header(PacketType::Lap, 1.0);
+        let header = Header::new(
+            header.api_spec(),
+            *header.game_version(),
+            header.packet_type(),
+            header.session_uid(),
+            *header.session_time(),
+            header.frame_identifier(),
+            vehicle(0),
+            Some(vehicle(1)),
+        );
+
+        state.update(&Packet::Lap(LapPacket::new(
+            header,
+            vec![lap(1).into(), lap(2).into()],
+        )));
+
+        assert_eq!(Some(vehicle(1)), state.secondary_player_car_index());
+        assert_eq!(Some(1), state.player().unwrap().position());
+        assert_eq!(Some(2), state.secondary_player().unwrap().position());
+    }
+}
@@ -0,0 +1,239 @@
+//! Differences between session states
+//!
+//! User interfaces and network services that mirror the session state only need to update the data
+//! that has changed. The diff between a snapshot and the current state lists exactly which values
+//! of the session and the cars have changed, so that consumers can send or render the changes
+//! instead of the full state.
+//!
+//! Cars whose data has not been touched since the snapshot share their data with the snapshot, and
+//! are skipped without comparing their values.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use getset::{CopyGetters, Getters};
+
+use crate::packet::lap::{PitStatus, ResultStatus};
+use crate::packet::session::{SafetyCar, Weather};
+use crate::packet::status::VisualTyreCompound;
+use crate::state::{CarState, SessionState};
+use crate::types::{CornerProperty, Flag, VehicleIndex};
+
+/// Change to the data of the session, with the new value
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+pub enum SessionChange {
+    /// The weather has changed.
+    Weather(Weather),
+
+    /// The safety car has been deployed or called in.
+    SafetyCar(SafetyCar),
+
+    /// The flag in the marshal zone with the given index has changed.
+    MarshalZoneFlag(usize, Flag),
+}
+
+/// Change to the data of a car, with the new value
+#[derive(Debug, PartialEq, Clone, Eq, Ord, PartialOrd, Hash)]
+pub enum CarChange {
+    /// The participant driving the car has changed, for example their name.
+    Participant,
+
+    /// The position of the car has changed.
+    Position(Option<u8>),
+
+    /// The car has started a new lap.
+    Lap(u8),
+
+    /// The car has entered or left the pit lane or its pit box.
+    PitStatus(PitStatus),
+
+    /// The result status of the car has changed, for example because it retired.
+    ResultStatus(ResultStatus),
+
+    /// The car has changed to tyres of another compound.
+    TyreCompound(VisualTyreCompound),
+
+    /// The wear of the tyres has changed.
+    TyreWear(CornerProperty<u8>),
+
+    /// The flag that is shown to the car has changed.
+    Flag(Flag),
+}
+
+/// Changes between two session states
+#[derive(Debug, Getters, CopyGetters, PartialEq, Clone, Default)]
+pub struct StateDiff {
+    /// Returns whether the states belong to different sessions, in which case the changes are
+    /// relative to an empty state.
+    #[getset(get_copy = "pub")]
+    new_session: bool,
+
+    /// Returns the changes to the data of the session.
+    #[getset(get = "pub")]
+    session: Vec<SessionChange>,
+
+    /// Returns the changes to the data of each car that has changed.
+    #[getset(get = "pub")]
+    cars: BTreeMap<VehicleIndex, Vec<CarChange>>,
+}
+
+impl StateDiff {
+    /// Returns whether nothing has changed.
+    pub fn is_empty(&self) -> bool {
+        !self.new_session && self.session.is_empty() && self.cars.is_empty()
+    }
+}
+
+impl SessionState {
+    /// Returns the changes from an earlier snapshot to the current state.
+    pub fn diff(&self, previous: &SessionState) -> StateDiff {
+        let new_session = previous.session_uid != self.session_uid;
+        let empty = SessionState::default();
+        let previous = if new_session { &empty } else { previous };
+
+        let mut diff = StateDiff {
+            new_session,
+            ..StateDiff::default()
+        };
+
+        if let Some(session) = self.session() {
+            let before = previous.session();
+
+            if before.is_none_or(|before| before.weather() != session.weather()) {
+                diff.session.push(SessionChange::Weather(session.weather()));
+            }
+
+            if before.is_none_or(|before| before.safety_car() != session.safety_car()) {
+                diff.session
+                    .push(SessionChange::SafetyCar(session.safety_car()));
+            }
+
+            for (index, zone) in session.marshal_zones().iter().enumerate() {
+                let flag_before = before
+                    .and_then(|before| before.marshal_zones().get(index))
+                    .map(|zone| zone.flag());
+
+                if flag_before != Some(zone.flag()) {
+                    diff.session
+                        .push(SessionChange::MarshalZoneFlag(index, zone.flag()));
+                }
+            }
+        }
+
+        let default = CarState::default();
+        for (vehicle_index, car) in self.cars.iter() {
+            let before = previous.cars.get(vehicle_index);
+
+            if before.is_some_and(|before| Arc::ptr_eq(before, car)) {
+                continue;
+            }
+
+            let changes = diff_car(before.map_or(&default, |before| before.as_ref()), car);
+            if !changes.is_empty() {
+                diff.cars.insert(*vehicle_index, changes);
+            }
+        }
+
+        diff
+    }
+}
+
+/// Returns the changes between two states of a car.
+fn diff_car(before: &CarState, after: &CarState) -> Vec<CarChange> {
+    let mut changes = Vec::new();
+
+    if after.participant.is_some() && before.participant != after.participant {
+        changes.push(CarChange::Participant);
+    }
+
+    if let Some(lap) = after.lap {
+        let lap_before = before.lap;
+
+        if before.position() != after.position() {
+            changes.push(CarChange::Position(after.position()));
+        }
+
+        if lap_before.is_none_or(|before| before.current_lap_number() != lap.current_lap_number()) {
+            changes.push(CarChange::Lap(lap.current_lap_number()));
+        }
+
+        if lap_before.is_none_or(|before| before.pit_status() != lap.pit_status()) {
+            changes.push(CarChange::PitStatus(lap.pit_status()));
+        }
+
+        if lap_before.is_none_or(|before| before.result_status() != lap.result_status()) {
+            changes.push(CarChange::ResultStatus(lap.result_status()));
+        }
+    }
+
+    if let Some(status) = after.status {
+        let status_before = before.status;
+
+        if status_before
+            .is_none_or(|before| before.visual_tyre_compound() != status.visual_tyre_compound())
+        {
+            changes.push(CarChange::TyreCompound(status.visual_tyre_compound()));
+        }
+
+        if !after.is_restricted()
+            && status_before.is_none_or(|before| before.tyre_wear() != status.tyre_wear())
+        {
+            changes.push(CarChange::TyreWear(*status.tyre_wear()));
+        }
+
+        if status_before.is_none_or(|before| before.vehicle_flags() != status.vehicle_flags()) {
+            changes.push(CarChange::Flag(status.vehicle_flags()));
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::fixtures::{
+        lap_packet, status_packet, vehicle, LapFixture, StatusFixture,
+    };
+    use crate::state::diff::CarChange;
+    use crate::state::SessionState;
+    use crate::types::Flag;
+
+    fn lap(position: u8) -> LapFixture {
+        LapFixture {
+            position,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn diff_with_snapshot() {
+        let mut state = SessionState::new();
+
+        state.update(&lap_packet(1.0, vec![lap(1), lap(2)]));
+        state.update(&status_packet(1.0, vec![StatusFixture::default()]));
+        let snapshot = state.snapshot();
+
+        assert!(state.diff(&snapshot).is_empty());
+
+        state.update(&lap_packet(2.0, vec![lap(1), lap(2)]));
+        state.update(&status_packet(
+            2.0,
+            vec![StatusFixture {
+                vehicle_flags: Flag::Blue,
+                ..StatusFixture::default()
+            }],
+        ));
+        state.update(&lap_packet(3.0, vec![lap(2), lap(1)]));
+
+        let diff = state.diff(&snapshot);
+        assert!(!diff.new_session());
+        assert_eq!(
+            &vec![CarChange::Position(Some(2)), CarChange::Flag(Flag::Blue)],
+            diff.cars().get(&vehicle(0)).unwrap()
+        );
+        assert_eq!(
+            &vec![CarChange::Position(Some(1))],
+            diff.cars().get(&vehicle(1)).unwrap()
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Resolution of driver names
+//!
+//! The F1 games do not always report the names that a league wants to show. Depending on the
+//! platform, multiplayer sessions report Steam names, LAN names, or the generic name of the driver
+//! the player has picked, and AI drivers are named after the real drivers. The driver database maps
+//! the names and race numbers reported by the games to the real names of the drivers, and can
+//! override the team a driver is shown in.
+//!
+//! With the `serde` feature enabled, the database can be loaded from a file.
+
+use std::collections::BTreeMap;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::participants::{Participant, Team};
+use crate::state::CarState;
+
+/// Overrides for the data of a driver
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DriverOverride {
+    /// Returns the name that is shown for the driver, if it is overridden.
+    #[getset(get = "pub")]
+    name: Option<String>,
+
+    /// Returns the team that is shown for the driver, if it is overridden.
+    #[getset(get_copy = "pub")]
+    team: Option<Team>,
+}
+
+/// A driver with their resolved name and team
+#[derive(
+    new, Debug, Getters, CopyGetters, PartialEq, Clone, Eq, Ord, PartialOrd, Hash, Default,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResolvedDriver {
+    /// Returns the name of the driver.
+    #[getset(get = "pub")]
+    name: String,
+
+    /// Returns the team of the driver.
+    #[getset(get_copy = "pub")]
+    team: Team,
+
+    /// Returns whether any data of the driver was taken from the database.
+    #[getset(get_copy = "pub")]
+    overridden: bool,
+}
+
+/// Database of overrides for the names and teams of drivers
+///
+/// Overrides are looked up by the name the game reports for a participant first, and by the race
+/// number of the participant's car second. Data that is not overridden is taken from the
+/// participant.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DriverDatabase {
+    names: BTreeMap<String, DriverOverride>,
+    race_numbers: BTreeMap<u8, DriverOverride>,
+}
+
+impl DriverDatabase {
+    /// Create a new, empty driver database.
+    pub fn new() -> Self {
+        DriverDatabase::default()
+    }
+
+    /// Add an override for the participant with the given name, for example a gamertag.
+    pub fn insert_name(&mut self, game_name: &str, driver: DriverOverride) {
+        self.names.insert(game_name.to_string(), driver);
+    }
+
+    /// Add an override for the participant driving the car with the given race number.
+    pub fn insert_race_number(&mut self, race_number: u8, driver: DriverOverride) {
+        self.race_numbers.insert(race_number, driver);
+    }
+
+    /// Returns the override for a participant, if the database has one.
+    pub fn get(&self, participant: &Participant) -> Option<&DriverOverride> {
+        self.names
+            .get(participant.name())
+            .or_else(|| self.race_numbers.get(&participant.race_number()))
+    }
+
+    /// Resolve the name and team of a participant.
+    pub fn resolve(&self, participant: &Participant) -> ResolvedDriver {
+        match self.get(participant) {
+            Some(driver) => ResolvedDriver::new(
+                driver
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| participant.name().clone()),
+                driver.team.unwrap_or_else(|| participant.team()),
+                true,
+            ),
+            None => ResolvedDriver::new(participant.name().clone(), participant.team(), false),
+        }
+    }
+
+    /// Resolve the name and team of the driver of a car, if the participant is known.
+    pub fn resolve_car(&self, car: &CarState) -> Option<ResolvedDriver> {
+        car.participant()
+            .as_ref()
+            .map(|participant| self.resolve(participant))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::packet::participants::{
+        Controller, Driver, Nationality, Participant, Team, TelemetryPrivacy,
+    };
+    use crate::state::drivers::{DriverDatabase, DriverOverride};
+
+    fn participant(name: &str, race_number: u8) -> Participant {
+        Participant::new(
+            Controller::Human,
+            Driver::default(),
+            Team::Mercedes,
+            race_number,
+            Nationality::German,
+            name.to_string(),
+            Some(TelemetryPrivacy::Public),
+        )
+    }
+
+    #[test]
+    fn resolve_with_overrides() {
+        let mut database = DriverDatabase::new();
+        database.insert_name(
+            "xX_Alice_Xx",
+            DriverOverride::new(Some("Alice Smith".to_string()), None),
+        );
+        database.insert_race_number(7, DriverOverride::new(None, Some(Team::Ferrari)));
+
+        let alice = database.resolve(&participant("xX_Alice_Xx", 3));
+        assert_eq!("Alice Smith", alice.name());
+        assert_eq!(Team::Mercedes, alice.team());
+        assert!(alice.overridden());
+
+        let player = database.resolve(&participant("Player", 7));
+        assert_eq!("Player", player.name());
+        assert_eq!(Team::Ferrari, player.team());
+
+        assert!(!database.resolve(&participant("Bob", 9)).overridden());
+    }
+}
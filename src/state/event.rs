@@ -0,0 +1,212 @@
+//! Derived events of a session
+//!
+//! The F1 games only send event packets for a few occasions, for example when a driver sets the
+//! fastest lap. Many other important moments of a race can only be derived by comparing packets,
+//! for example when a car enters the pit lane or overtakes another car. The session state derives
+//! these events while it consumes the packets, and the event bus publishes them on a broadcast
+//! channel that any number of consumers can subscribe to.
+
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+use tokio::sync::broadcast::{self, Receiver, Sender};
+
+use crate::packet::lap::ResultStatus;
+use crate::packet::status::VisualTyreCompound;
+use crate::packet::Packet;
+use crate::state::SessionState;
+use crate::types::{Flag, VehicleIndex};
+
+/// Kinds of events that are derived from the packets
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+pub enum SessionEventKind {
+    /// The car has entered the pit lane.
+    #[default]
+    PitEntry,
+
+    /// The car has left the pit lane.
+    PitExit,
+
+    /// The car has overtaken another car, and is now in the given position.
+    Overtake {
+        overtaken: VehicleIndex,
+        position: u8,
+    },
+
+    /// The flag that is shown to the car has changed.
+    FlagChange(Flag),
+
+    /// The car has changed to tyres of another compound.
+    TyreChange(VisualTyreCompound),
+
+    /// The car has set the fastest lap of the session, with the given lap time.
+    FastestLap(Duration),
+
+    /// The car has retired, been disqualified, or not been classified, with the result status that
+    /// ended its session.
+    Retirement(ResultStatus),
+}
+
+/// Kinds of derived events, without the data they carry
+///
+/// Consumers that only want to receive a certain kind of event can select it with this enum,
+/// without having to construct the data of the event.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+pub enum EventKind {
+    #[default]
+    PitEntry,
+    PitExit,
+    Overtake,
+    FlagChange,
+    TyreChange,
+    FastestLap,
+    Retirement,
+}
+
+impl From<SessionEventKind> for EventKind {
+    fn from(kind: SessionEventKind) -> Self {
+        match kind {
+            SessionEventKind::PitEntry => EventKind::PitEntry,
+            SessionEventKind::PitExit => EventKind::PitExit,
+            SessionEventKind::Overtake { .. } => EventKind::Overtake,
+            SessionEventKind::FlagChange(_) => EventKind::FlagChange,
+            SessionEventKind::TyreChange(_) => EventKind::TyreChange,
+            SessionEventKind::FastestLap(_) => EventKind::FastestLap,
+            SessionEventKind::Retirement(_) => EventKind::Retirement,
+        }
+    }
+}
+
+/// An event that has been derived from the packets
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+pub struct SessionEvent {
+    /// Returns the session time at which the event occurred.
+    #[getset(get = "pub")]
+    session_time: Duration,
+
+    /// Returns the index of the car the event is about.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the kind of the event.
+    #[getset(get_copy = "pub")]
+    kind: SessionEventKind,
+}
+
+/// Bus that publishes the events of a session
+///
+/// The event bus feeds the packets into a session state, and publishes the derived events on a
+/// broadcast channel. Subscribers that fall behind by more than the capacity of the channel miss the
+/// oldest events.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    state: SessionState,
+    sender: Sender<SessionEvent>,
+}
+
+impl EventBus {
+    /// Create a new event bus whose channel holds the given number of events.
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+
+        EventBus {
+            state: SessionState::new(),
+            sender,
+        }
+    }
+
+    /// Subscribe to the events that are published after this call.
+    pub fn subscribe(&self) -> Receiver<SessionEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Update the session state with the packet, and publish the derived events.
+    pub fn update(&mut self, packet: &Packet) {
+        for event in self.state.update(packet) {
+            // Sending only fails when there are no subscribers, in which case the event is dropped.
+            self.sender.send(event).ok();
+        }
+    }
+
+    /// Returns the session state that the events are derived from.
+    pub fn state(&self) -> &SessionState {
+        &self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::fixtures::{
+        lap_packet, status_packet, vehicle, LapFixture, StatusFixture,
+    };
+    use crate::packet::lap::{PitStatus, ResultStatus};
+    use crate::packet::status::VisualTyreCompound;
+    use crate::state::event::{EventBus, SessionEventKind};
+
+    fn lap(position: u8, pit_status: PitStatus) -> LapFixture {
+        LapFixture {
+            position,
+            pit_status,
+            result_status: ResultStatus::Active,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_subscriber() {
+        let mut bus = EventBus::new(16);
+        let mut receiver = bus.subscribe();
+
+        bus.update(&lap_packet(
+            1.0,
+            vec![lap(1, PitStatus::None), lap(2, PitStatus::None)],
+        ));
+        bus.update(&lap_packet(
+            2.0,
+            vec![lap(2, PitStatus::None), lap(1, PitStatus::None)],
+        ));
+        bus.update(&lap_packet(
+            3.0,
+            vec![lap(2, PitStatus::None), lap(1, PitStatus::None)],
+        ));
+        bus.update(&lap_packet(
+            3.0,
+            vec![lap(2, PitStatus::None), lap(1, PitStatus::Pitting)],
+        ));
+        bus.update(&status_packet(
+            3.0,
+            vec![StatusFixture {
+                visual_tyre_compound: VisualTyreCompound::F1Soft,
+                ..StatusFixture::default()
+            }],
+        ));
+        bus.update(&status_packet(
+            4.0,
+            vec![StatusFixture {
+                visual_tyre_compound: VisualTyreCompound::F1Hard,
+                ..StatusFixture::default()
+            }],
+        ));
+
+        let overtake = receiver.try_recv().unwrap();
+        assert_eq!(1, overtake.vehicle_index());
+        assert_eq!(
+            SessionEventKind::Overtake {
+                overtaken: vehicle(0),
+                position: 1
+            },
+            overtake.kind()
+        );
+
+        let pit_entry = receiver.try_recv().unwrap();
+        assert_eq!(1, pit_entry.vehicle_index());
+        assert_eq!(SessionEventKind::PitEntry, pit_entry.kind());
+
+        assert_eq!(
+            SessionEventKind::TyreChange(VisualTyreCompound::F1Hard),
+            receiver.try_recv().unwrap().kind()
+        );
+        assert!(receiver.try_recv().is_err());
+    }
+}
@@ -0,0 +1,323 @@
+//! History of completed laps
+//!
+//! The session state only keeps the latest data of every car. Many questions about a session need
+//! its full history, for example the times of all laps of a driver, the sector times of every driver
+//! on a given lap, or the telemetry of a car during one of its stints. The lap history records every
+//! completed lap of every car, and answers these queries during and after the session.
+//!
+//! Recording the telemetry of every lap takes a lot of memory, and can be turned off. With the
+//! `serde` feature enabled, the history can be written to disk and restored later.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use derive_new::new;
+use getset::{CopyGetters, Getters};
+
+use crate::analysis::ranking::RankedTime;
+use crate::packet::lap::{LapPacket, PitStatus};
+use crate::packet::participants::ParticipantsPacket;
+use crate::packet::status::{CarStatusPacket, VisualTyreCompound};
+use crate::packet::telemetry::{Telemetry, TelemetryPacket};
+use crate::packet::Packet;
+use crate::types::{enumerate_cars, VehicleIndex};
+
+/// Telemetry of a car at a point of a lap
+#[derive(new, Debug, Getters, CopyGetters, PartialEq, Copy, Clone, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TelemetrySample {
+    /// Returns the session time of the sample.
+    #[getset(get = "pub")]
+    session_time: Duration,
+
+    /// Returns the distance of the car along the lap in meters.
+    #[getset(get_copy = "pub")]
+    lap_distance: f32,
+
+    /// Returns the telemetry of the car.
+    #[getset(get = "pub")]
+    telemetry: Telemetry,
+}
+
+/// A lap that a car has completed
+#[derive(Debug, Getters, CopyGetters, PartialEq, Clone, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LapRecord {
+    /// Returns the index of the car.
+    #[getset(get_copy = "pub")]
+    vehicle_index: VehicleIndex,
+
+    /// Returns the number of the lap.
+    #[getset(get_copy = "pub")]
+    lap: u8,
+
+    /// Returns the stint in which the lap was completed, starting with the first stint at 1.
+    #[getset(get_copy = "pub")]
+    stint: u8,
+
+    /// Returns the lap time.
+    #[getset(get = "pub")]
+    lap_time: Duration,
+
+    /// Returns the times of the three sectors, if they are known.
+    #[getset(get = "pub")]
+    sector_times: [Option<Duration>; 3],
+
+    /// Returns whether the lap was valid.
+    #[getset(get_copy = "pub")]
+    is_valid: bool,
+
+    /// Returns the position of the car at the end of the lap.
+    #[getset(get_copy = "pub")]
+    position: u8,
+
+    /// Returns the tyre compound the lap was driven on, if it is known.
+    #[getset(get_copy = "pub")]
+    tyre_compound: Option<VisualTyreCompound>,
+
+    /// Returns the telemetry of the lap, if it was recorded.
+    #[getset(get = "pub")]
+    telemetry: Vec<TelemetrySample>,
+}
+
+impl LapRecord {
+    /// Returns the lap time ranked for a leaderboard, with invalidated laps after valid laps.
+    pub fn ranked_time(&self) -> RankedTime {
+        RankedTime::new(self.lap_time, self.is_valid)
+    }
+}
+
+/// Internal state that is tracked for each car
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct CarHistory {
+    name: Option<String>,
+    current: Option<LapRecord>,
+    lap_distance: f32,
+    pit_status: PitStatus,
+    stint: u8,
+    laps: Vec<LapRecord>,
+}
+
+/// History of the completed laps of all cars
+///
+/// The lap history consumes lap data, car status, participants, and telemetry packets. Sector times
+/// are taken from the lap data, and the third sector is calculated from the lap time. A new stint
+/// starts whenever a car leaves the pit lane.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LapHistory {
+    record_telemetry: bool,
+    cars: BTreeMap<VehicleIndex, CarHistory>,
+}
+
+impl LapHistory {
+    /// Create a new, empty lap history that optionally records the telemetry of every lap.
+    pub fn new(record_telemetry: bool) -> Self {
+        LapHistory {
+            record_telemetry,
+            cars: BTreeMap::new(),
+        }
+    }
+
+    /// Update the history with the data from the packet.
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Lap(packet) => self.update_laps(packet),
+            Packet::Participants(packet) => self.update_participants(packet),
+            Packet::Status(packet) => self.update_statuses(packet),
+            Packet::Telemetry(packet) if self.record_telemetry => self.update_telemetry(packet),
+            _ => {}
+        }
+    }
+
+    fn update_laps(&mut self, packet: &LapPacket) {
+        for (vehicle_index, lap) in enumerate_cars(packet.laps()) {
+            let car = self.car_mut(vehicle_index);
+
+            if car.pit_status != PitStatus::None && lap.pit_status() == PitStatus::None {
+                car.stint += 1;
+            }
+            car.pit_status = lap.pit_status();
+            car.lap_distance = lap.lap_distance();
+
+            let current = car.current.get_or_insert_with(|| LapRecord {
+                vehicle_index,
+                lap: lap.current_lap_number(),
+                ..LapRecord::default()
+            });
+
+            if lap.current_lap_number() > current.lap {
+                let mut completed = std::mem::take(current);
+
+                completed.stint = car.stint;
+                completed.lap_time = *lap.last_lap_time();
+                completed.position = lap.position();
+                completed.sector_times[2] = completed.sector_times[0]
+                    .zip(completed.sector_times[1])
+                    .and_then(|(first, second)| lap.last_lap_time().checked_sub(first + second));
+
+                *current = LapRecord {
+                    vehicle_index,
+                    lap: lap.current_lap_number(),
+                    tyre_compound: completed.tyre_compound,
+                    ..LapRecord::default()
+                };
+                car.laps.push(completed);
+            }
+
+            if !lap.sector1_time().is_zero() {
+                current.sector_times[0] = Some(*lap.sector1_time());
+            }
+            if !lap.sector2_time().is_zero() {
+                current.sector_times[1] = Some(*lap.sector2_time());
+            }
+            current.is_valid = lap.is_valid_lap();
+        }
+    }
+
+    fn car_mut(&mut self, vehicle_index: VehicleIndex) -> &mut CarHistory {
+        self.cars
+            .entry(vehicle_index)
+            .or_insert_with(|| CarHistory {
+                stint: 1,
+                ..CarHistory::default()
+            })
+    }
+
+    fn update_participants(&mut self, packet: &ParticipantsPacket) {
+        for (index, participant) in enumerate_cars(packet.participants()) {
+            self.car_mut(index).name = Some(participant.name().clone());
+        }
+    }
+
+    fn update_statuses(&mut self, packet: &CarStatusPacket) {
+        for (index, status) in enumerate_cars(packet.statuses()) {
+            let current = self
+                .cars
+                .get_mut(&index)
+                .and_then(|car| car.current.as_mut());
+
+            if let Some(current) = current {
+                current.tyre_compound = Some(status.visual_tyre_compound());
+            }
+        }
+    }
+
+    fn update_telemetry(&mut self, packet: &TelemetryPacket) {
+        let session_time = *packet.header().session_time();
+
+        for (index, telemetry) in enumerate_cars(packet.telemetry()) {
+            let car = match self.cars.get_mut(&index) {
+                Some(car) => car,
+                None => continue,
+            };
+
+            let lap_distance = car.lap_distance;
+            if let Some(current) = car.current.as_mut() {
+                current.telemetry.push(TelemetrySample::new(
+                    session_time,
+                    lap_distance,
+                    *telemetry,
+                ));
+            }
+        }
+    }
+
+    /// Returns the completed laps of a car.
+    pub fn laps(&self, vehicle_index: VehicleIndex) -> &[LapRecord] {
+        self.cars
+            .get(&vehicle_index)
+            .map(|car| car.laps.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns the completed laps of the cars driven by the driver with the given name.
+    pub fn driver_laps(&self, name: &str) -> Vec<&LapRecord> {
+        self.cars
+            .values()
+            .filter(|car| car.name.as_deref() == Some(name))
+            .flat_map(|car| car.laps.iter())
+            .collect()
+    }
+
+    /// Returns the records of all cars for a lap, ordered by the position at the end of the lap.
+    pub fn lap(&self, lap: u8) -> Vec<&LapRecord> {
+        let mut records: Vec<&LapRecord> = self
+            .cars
+            .values()
+            .flat_map(|car| car.laps.iter())
+            .filter(|record| record.lap == lap)
+            .collect();
+
+        records.sort_by_key(|record| record.position);
+        records
+    }
+
+    /// Returns the completed laps of a car in a stint, starting with the first stint at 1.
+    pub fn stint(&self, vehicle_index: VehicleIndex, stint: u8) -> Vec<&LapRecord> {
+        self.laps(vehicle_index)
+            .iter()
+            .filter(|record| record.stint == stint)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::analysis::fixtures::{
+        lap_packet, participants_packet, telemetry_packet, vehicle, LapFixture, TelemetryFixture,
+    };
+    use crate::packet::lap::PitStatus;
+    use crate::state::history::LapHistory;
+
+    fn lap(current_lap_number: u8, sector1_time: f32, sector2_time: f32) -> LapFixture {
+        LapFixture {
+            position: 1,
+            current_lap_number,
+            sector1_time,
+            sector2_time,
+            is_valid_lap: true,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_laps_and_stints() {
+        let mut history = LapHistory::new(true);
+
+        history.update(&lap_packet(0.0, vec![lap(1, 0.0, 0.0)]));
+        history.update(&participants_packet(0.0, vec!["Alice"]));
+        history.update(&lap_packet(30.0, vec![lap(1, 30.0, 0.0)]));
+        history.update(&telemetry_packet(40.0, vec![TelemetryFixture::default()]));
+        history.update(&lap_packet(
+            90.0,
+            vec![LapFixture {
+                last_lap_time: 90.0,
+                pit_status: PitStatus::Pitting,
+                ..lap(2, 0.0, 0.0)
+            }],
+        ));
+        history.update(&lap_packet(100.0, vec![lap(2, 0.0, 0.0)]));
+        history.update(&lap_packet(
+            200.0,
+            vec![LapFixture {
+                last_lap_time: 100.0,
+                ..lap(3, 0.0, 0.0)
+            }],
+        ));
+
+        let laps = history.driver_laps("Alice");
+        assert_eq!(2, laps.len());
+        assert_eq!(&Duration::from_secs(90), laps[0].lap_time());
+        assert_eq!(Some(Duration::from_secs(30)), laps[0].sector_times()[0]);
+        assert_eq!(None, laps[0].sector_times()[2]);
+        assert_eq!(1, laps[0].telemetry().len());
+
+        assert_eq!(1, history.stint(vehicle(0), 1).len());
+        assert_eq!(2, history.stint(vehicle(0), 2)[0].lap());
+        assert_eq!(1, history.lap(2).len());
+    }
+}
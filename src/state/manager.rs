@@ -0,0 +1,168 @@
+//! Management of multiple sessions
+//!
+//! A single process can receive the packets of more than one session, either one after the other
+//! when a weekend moves from practice into qualifying, or at the same time when several rigs send
+//! their telemetry to the same relay. The session manager keeps a separate session state for each
+//! session, routes every packet to the state of its session by the unique identifier of the
+//! session, and expires sessions that have not received any packets for some time.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::packet::Packet;
+use crate::state::event::SessionEvent;
+use crate::state::SessionState;
+
+/// Internal state that is tracked for each session
+#[derive(Debug, Clone, PartialEq)]
+struct ManagedSession {
+    state: SessionState,
+    last_packet: Instant,
+}
+
+/// Manager for the states of multiple sessions
+///
+/// Sessions are created when their first packet is received. A session expires when it has not
+/// received a packet for longer than the configured expiry, which is measured in wall-clock time
+/// since the session time of a session stops when it ends.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionManager {
+    expiry: Duration,
+    sessions: BTreeMap<u64, ManagedSession>,
+}
+
+impl SessionManager {
+    /// Create a new session manager that expires sessions after the given time without packets.
+    pub fn new(expiry: Duration) -> Self {
+        SessionManager {
+            expiry,
+            sessions: BTreeMap::new(),
+        }
+    }
+
+    /// Route the packet to the state of its session, and return the derived events.
+    pub fn update(&mut self, packet: &Packet) -> Vec<SessionEvent> {
+        self.update_at(packet, Instant::now())
+    }
+
+    fn update_at(&mut self, packet: &Packet, now: Instant) -> Vec<SessionEvent> {
+        let session_uid = packet.header().session_uid();
+
+        let session = self
+            .sessions
+            .entry(session_uid)
+            .or_insert_with(|| ManagedSession {
+                state: SessionState::new(),
+                last_packet: now,
+            });
+
+        session.last_packet = now;
+        session.state.update(packet)
+    }
+
+    /// Remove the sessions that have expired, and return their final states.
+    pub fn expire(&mut self) -> Vec<SessionState> {
+        self.expire_at(Instant::now())
+    }
+
+    fn expire_at(&mut self, now: Instant) -> Vec<SessionState> {
+        let expiry = self.expiry;
+        let expired: Vec<u64> = self
+            .sessions
+            .iter()
+            .filter(|(_, session)| now.saturating_duration_since(session.last_packet) > expiry)
+            .map(|(session_uid, _)| *session_uid)
+            .collect();
+
+        expired
+            .into_iter()
+            .filter_map(|session_uid| self.sessions.remove(&session_uid))
+            .map(|session| session.state)
+            .collect()
+    }
+
+    /// Returns the state of a session.
+    pub fn session(&self, session_uid: u64) -> Option<&SessionState> {
+        self.sessions
+            .get(&session_uid)
+            .map(|session| &session.state)
+    }
+
+    /// Returns the states of all sessions, ordered by their unique identifier.
+    pub fn sessions(&self) -> impl Iterator<Item = (u64, &SessionState)> {
+        self.sessions
+            .iter()
+            .map(|(session_uid, session)| (*session_uid, &session.state))
+    }
+
+    /// Returns the state of the session that received a packet most recently.
+    pub fn latest(&self) -> Option<&SessionState> {
+        self.sessions
+            .values()
+            .max_by_key(|session| session.last_packet)
+            .map(|session| &session.state)
+    }
+
+    /// Returns the number of sessions.
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Returns whether there are no sessions.
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+}
+
+impl Default for SessionManager {
+    fn default() -> Self {
+        SessionManager::new(Duration::from_secs(60))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::analysis::fixtures::{vehicle, LapFixture};
+    use crate::packet::header::{ApiSpec, Header, PacketType};
+    use crate::packet::lap::LapPacket;
+    use crate::packet::Packet;
+    use crate::state::manager::SessionManager;
+
+    fn lap_packet(session_uid: u64, session_time: u64) -> Packet {
+        let header = Header::new(
+            ApiSpec::Nineteen,
+            None,
+            PacketType::Lap,
+            session_uid,
+            Duration::from_secs(session_time),
+            0,
+            vehicle(0),
+            None,
+        );
+
+        Packet::Lap(LapPacket::new(header, vec![LapFixture::default().into()]))
+    }
+
+    #[test]
+    fn update_with_multiple_sessions() {
+        let mut manager = SessionManager::new(Duration::from_secs(10));
+        let start = Instant::now();
+
+        manager.update_at(&lap_packet(1, 1), start);
+        manager.update_at(&lap_packet(2, 5), start + Duration::from_secs(5));
+
+        assert_eq!(2, manager.len());
+        assert_eq!(5.0, manager.latest().unwrap().session_time().as_secs_f32());
+        assert_eq!(
+            1.0,
+            manager.session(1).unwrap().session_time().as_secs_f32()
+        );
+
+        let expired = manager.expire_at(start + Duration::from_secs(12));
+        assert_eq!(1, expired.len());
+        assert_eq!(Some(1), expired[0].session_uid());
+        assert!(manager.session(1).is_none());
+    }
+}
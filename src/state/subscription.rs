@@ -0,0 +1,263 @@
+//! Callbacks for packets and derived events
+//!
+//! Streams and the event bus are the recommended way to consume packets. Applications that are not
+//! built around an asynchronous runtime, or that prefer callbacks, can register closures for the
+//! packets and derived events they care about instead. The subscription registry feeds every packet
+//! into a session state, and calls the closures that have been registered for the packet and for
+//! the events that are derived from it.
+
+use std::fmt;
+
+use crate::packet::event::EventPacket;
+use crate::packet::lap::LapPacket;
+use crate::packet::motion::MotionPacket;
+use crate::packet::participants::ParticipantsPacket;
+use crate::packet::session::SessionPacket;
+use crate::packet::setup::CarSetupPacket;
+use crate::packet::status::CarStatusPacket;
+use crate::packet::telemetry::TelemetryPacket;
+use crate::packet::Packet;
+use crate::state::event::{EventKind, SessionEvent};
+use crate::state::SessionState;
+
+/// Closure that is called with a packet or event
+type Callback<T> = Box<dyn FnMut(&T) + Send>;
+
+/// Registry of callbacks for packets and derived events
+///
+/// Callbacks are called in the order in which they were registered. Callbacks for a packet are
+/// called before the callbacks for the events that are derived from it.
+///
+/// # Examples
+///
+/// ```
+/// use f1_api::state::event::EventKind;
+/// use f1_api::state::subscription::Subscriptions;
+///
+/// let mut subscriptions = Subscriptions::new();
+///
+/// subscriptions
+///     .on_telemetry(|telemetry| println!("{:?}", telemetry.player()))
+///     .on_event(EventKind::FastestLap, |event| println!("{:?}", event));
+/// ```
+#[derive(Default)]
+pub struct Subscriptions {
+    state: SessionState,
+    packets: Vec<Callback<Packet>>,
+    event_packets: Vec<Callback<EventPacket>>,
+    laps: Vec<Callback<LapPacket>>,
+    motions: Vec<Callback<MotionPacket>>,
+    participants: Vec<Callback<ParticipantsPacket>>,
+    sessions: Vec<Callback<SessionPacket>>,
+    setups: Vec<Callback<CarSetupPacket>>,
+    statuses: Vec<Callback<CarStatusPacket>>,
+    telemetry: Vec<Callback<TelemetryPacket>>,
+    events: Vec<(Option<EventKind>, Callback<SessionEvent>)>,
+}
+
+impl Subscriptions {
+    /// Create a new registry without any callbacks.
+    pub fn new() -> Self {
+        Subscriptions::default()
+    }
+
+    /// Register a callback for every packet.
+    pub fn on_packet<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&Packet) + Send + 'static,
+    {
+        self.packets.push(Box::new(callback));
+        self
+    }
+
+    /// Register a callback for event packets.
+    pub fn on_event_packet<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&EventPacket) + Send + 'static,
+    {
+        self.event_packets.push(Box::new(callback));
+        self
+    }
+
+    /// Register a callback for lap data packets.
+    pub fn on_lap<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&LapPacket) + Send + 'static,
+    {
+        self.laps.push(Box::new(callback));
+        self
+    }
+
+    /// Register a callback for motion packets.
+    pub fn on_motion<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&MotionPacket) + Send + 'static,
+    {
+        self.motions.push(Box::new(callback));
+        self
+    }
+
+    /// Register a callback for participants packets.
+    pub fn on_participants<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&ParticipantsPacket) + Send + 'static,
+    {
+        self.participants.push(Box::new(callback));
+        self
+    }
+
+    /// Register a callback for session packets.
+    pub fn on_session<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&SessionPacket) + Send + 'static,
+    {
+        self.sessions.push(Box::new(callback));
+        self
+    }
+
+    /// Register a callback for car setup packets.
+    pub fn on_setup<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&CarSetupPacket) + Send + 'static,
+    {
+        self.setups.push(Box::new(callback));
+        self
+    }
+
+    /// Register a callback for car status packets.
+    pub fn on_status<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&CarStatusPacket) + Send + 'static,
+    {
+        self.statuses.push(Box::new(callback));
+        self
+    }
+
+    /// Register a callback for telemetry packets.
+    pub fn on_telemetry<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&TelemetryPacket) + Send + 'static,
+    {
+        self.telemetry.push(Box::new(callback));
+        self
+    }
+
+    /// Register a callback for derived events of the given kind.
+    pub fn on_event<F>(&mut self, kind: EventKind, callback: F) -> &mut Self
+    where
+        F: FnMut(&SessionEvent) + Send + 'static,
+    {
+        self.events.push((Some(kind), Box::new(callback)));
+        self
+    }
+
+    /// Register a callback for derived events of every kind.
+    pub fn on_events<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&SessionEvent) + Send + 'static,
+    {
+        self.events.push((None, Box::new(callback)));
+        self
+    }
+
+    /// Update the session state with the packet, and call the callbacks for the packet and for the
+    /// derived events.
+    pub fn update(&mut self, packet: &Packet) {
+        for callback in self.packets.iter_mut() {
+            callback(packet);
+        }
+
+        match packet {
+            Packet::Event(packet) => call(&mut self.event_packets, packet),
+            Packet::Lap(packet) => call(&mut self.laps, packet),
+            Packet::Motion(packet) => call(&mut self.motions, packet),
+            Packet::Participants(packet) => call(&mut self.participants, packet),
+            Packet::Session(packet) => call(&mut self.sessions, packet),
+            Packet::Setup(packet) => call(&mut self.setups, packet),
+            Packet::Status(packet) => call(&mut self.statuses, packet),
+            Packet::Telemetry(packet) => call(&mut self.telemetry, packet),
+        }
+
+        for event in self.state.update(packet) {
+            let kind = EventKind::from(event.kind());
+
+            for (_, callback) in self
+                .events
+                .iter_mut()
+                .filter(|(selected, _)| selected.is_none_or(|selected| selected == kind))
+            {
+                callback(&event);
+            }
+        }
+    }
+
+    /// Returns the session state that the events are derived from.
+    pub fn state(&self) -> &SessionState {
+        &self.state
+    }
+}
+
+impl fmt::Debug for Subscriptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscriptions")
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Call each callback with the packet.
+fn call<T>(callbacks: &mut [Callback<T>], packet: &T) {
+    for callback in callbacks.iter_mut() {
+        callback(packet);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::analysis::fixtures::{lap_packet, LapFixture};
+    use crate::packet::lap::{PitStatus, ResultStatus};
+    use crate::state::event::{EventKind, SessionEventKind};
+    use crate::state::subscription::Subscriptions;
+
+    fn lap(position: u8, pit_status: PitStatus) -> LapFixture {
+        LapFixture {
+            position,
+            pit_status,
+            result_status: ResultStatus::Active,
+            ..LapFixture::default()
+        }
+    }
+
+    #[test]
+    fn update_with_callbacks() {
+        let laps = Arc::new(Mutex::new(0));
+        let events = Arc::new(Mutex::new(Vec::new()));
+
+        let mut subscriptions = Subscriptions::new();
+        let counter = laps.clone();
+        let recorder = events.clone();
+        subscriptions
+            .on_lap(move |_| *counter.lock().unwrap() += 1)
+            .on_event(EventKind::PitEntry, move |event| {
+                recorder.lock().unwrap().push(event.kind())
+            });
+
+        subscriptions.update(&lap_packet(
+            1.0,
+            vec![lap(1, PitStatus::None), lap(2, PitStatus::None)],
+        ));
+        subscriptions.update(&lap_packet(
+            2.0,
+            vec![lap(2, PitStatus::None), lap(1, PitStatus::None)],
+        ));
+        subscriptions.update(&lap_packet(
+            3.0,
+            vec![lap(2, PitStatus::Pitting), lap(1, PitStatus::None)],
+        ));
+
+        assert_eq!(3, *laps.lock().unwrap());
+        assert_eq!(vec![SessionEventKind::PitEntry], *events.lock().unwrap());
+    }
+}
@@ -0,0 +1,212 @@
+//! Throttling of high-rate packets
+//!
+//! The F1 games can send the motion and telemetry data at up to 60 Hz. Dashboards that only render
+//! a few times per second, or network sinks with limited bandwidth, cannot keep up with this rate.
+//! The throttle passes at most one packet of each throttled type per interval, and drops the packets
+//! in between. Since every packet contains the data of all cars, this limits the rate for each car.
+//!
+//! The interval is measured in session time, so that recorded sessions that are replayed faster or
+//! slower than real time are throttled in the same way.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use tokio_stream::{Stream, StreamExt};
+
+use crate::packet::header::PacketType;
+use crate::packet::Packet;
+
+/// Throttle that limits the rate of packets of certain types
+///
+/// Packets of types that are not throttled are always passed. When a packet of a new session is
+/// received, or the session time jumps back, the next packet of each type is passed.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Throttle {
+    interval: Duration,
+    packet_types: BTreeSet<PacketType>,
+    session_uid: Option<u64>,
+    passed: BTreeMap<PacketType, Duration>,
+}
+
+impl Throttle {
+    /// Create a new throttle that passes at most one motion and one telemetry packet per interval.
+    ///
+    /// An interval of 100 milliseconds limits the packets to 10 Hz.
+    pub fn new(interval: Duration) -> Self {
+        Throttle::with_packet_types(interval, &[PacketType::Motion, PacketType::Telemetry])
+    }
+
+    /// Create a new throttle that passes at most one packet of each of the given types per interval.
+    pub fn with_packet_types(interval: Duration, packet_types: &[PacketType]) -> Self {
+        Throttle {
+            interval,
+            packet_types: packet_types.iter().copied().collect(),
+            session_uid: None,
+            passed: BTreeMap::new(),
+        }
+    }
+
+    /// Returns whether the packet is passed, and records it if it is.
+    pub fn update(&mut self, packet: &Packet) -> bool {
+        let header = packet.header();
+        let packet_type = header.packet_type();
+
+        if !self.packet_types.contains(&packet_type) {
+            return true;
+        }
+
+        if self.session_uid != Some(header.session_uid()) {
+            self.session_uid = Some(header.session_uid());
+            self.passed.clear();
+        }
+
+        let session_time = *header.session_time();
+        let interval = self.interval;
+        let passed = self
+            .passed
+            .get(&packet_type)
+            .is_none_or(|passed| session_time < *passed || session_time - *passed >= interval);
+
+        if passed {
+            self.passed.insert(packet_type, session_time);
+        }
+
+        passed
+    }
+}
+
+/// Throttle a stream of packets, and drop the packets that the throttle does not pass.
+///
+/// # Examples
+///
+/// ```
+/// use std::net::{IpAddr, SocketAddr};
+/// use std::time::Duration;
+///
+/// use f1_api::throttle::{throttle_stream, Throttle};
+/// use f1_api::{ok_packets, F1};
+/// use tokio_stream::StreamExt;
+///
+/// async fn example() {
+///     let socket = SocketAddr::new(IpAddr::from([0, 0, 0, 0]), 20777);
+///     let stream = ok_packets(F1::stream(socket).unwrap());
+///
+///     let throttle = Throttle::new(Duration::from_millis(100));
+///     let mut stream = throttle_stream(stream, throttle);
+///
+///     while let Some(packet) = stream.next().await {
+///         println!("Received {:?} packet", packet.header().packet_type());
+///     }
+/// }
+/// ```
+pub fn throttle_stream<S>(stream: S, throttle: Throttle) -> impl Stream<Item = Packet>
+where
+    S: Stream<Item = Packet>,
+{
+    let mut throttle = throttle;
+
+    stream.filter(move |packet| throttle.update(packet))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio_stream::StreamExt;
+
+    use crate::analysis::fixtures::{
+        invalid_datagram, lap_datagram, lap_packet, telemetry_packet, udp_stream, LapFixture,
+        TelemetryFixture,
+    };
+    use crate::ok_packets;
+    use crate::packet::header::PacketType;
+    use crate::packet::Packet;
+    use crate::throttle::{throttle_stream, Throttle};
+
+    fn telemetry(session_time: f32) -> Packet {
+        telemetry_packet(session_time, vec![TelemetryFixture::default()])
+    }
+
+    fn in_session(packet: Packet, session_uid: u64) -> Packet {
+        match packet {
+            Packet::Telemetry(packet) => {
+                let header = packet.header().with_session_uid(session_uid);
+                Packet::from(packet.with_header(header))
+            }
+            packet => panic!("Unexpected packet {:?}", packet),
+        }
+    }
+
+    #[test]
+    fn limit_rate_to_interval() {
+        let mut throttle = Throttle::new(Duration::from_millis(100));
+
+        let passed = (0..60)
+            .filter(|frame| throttle.update(&telemetry(*frame as f32 / 60.0)))
+            .count();
+
+        assert_eq!(10, passed);
+    }
+
+    #[test]
+    fn pass_after_new_session_or_rewind() {
+        let mut throttle = Throttle::new(Duration::from_secs(1));
+
+        assert!(throttle.update(&telemetry(10.0)));
+        assert!(!throttle.update(&telemetry(10.5)));
+
+        // The session time jumps back, for example when a session is restarted.
+        assert!(throttle.update(&telemetry(2.0)));
+        assert!(!throttle.update(&telemetry(2.5)));
+
+        assert!(throttle.update(&in_session(telemetry(2.6), 1)));
+        assert!(!throttle.update(&in_session(telemetry(2.7), 1)));
+    }
+
+    #[test]
+    fn throttle_selected_packet_types() {
+        let mut throttle = Throttle::with_packet_types(Duration::from_secs(1), &[PacketType::Lap]);
+
+        assert!(throttle.update(&lap_packet(0.0, vec![LapFixture::default()])));
+        assert!(!throttle.update(&lap_packet(0.5, vec![LapFixture::default()])));
+        assert!(throttle.update(&telemetry(0.5)));
+        assert!(throttle.update(&telemetry(0.6)));
+    }
+
+    #[tokio::test]
+    async fn throttle_telemetry() {
+        let mut packets: Vec<Packet> = (0..12)
+            .map(|index| telemetry_packet(index as f32 / 20.0, vec![TelemetryFixture::default()]))
+            .collect();
+        packets.insert(3, lap_packet(0.1, vec![LapFixture::default()]));
+
+        let throttle = Throttle::new(Duration::from_millis(200));
+        let passed: Vec<Packet> = throttle_stream(tokio_stream::iter(packets), throttle)
+            .collect()
+            .await;
+
+        let session_times: Vec<f32> = passed
+            .iter()
+            .map(|packet| packet.header().session_time().as_secs_f32())
+            .collect();
+        assert_eq!(vec![0.0, 0.1, 0.2, 0.4], session_times);
+    }
+
+    #[tokio::test]
+    async fn throttle_udp_stream() {
+        let (sender, stream) = udp_stream().await;
+        sender.send(&invalid_datagram()).await.unwrap();
+        for session_time in &[0.0, 0.5, 1.0] {
+            sender.send(&lap_datagram(*session_time)).await.unwrap();
+        }
+
+        let throttle = Throttle::with_packet_types(Duration::from_secs(1), &[PacketType::Lap]);
+        let passed: Vec<_> = throttle_stream(ok_packets(stream), throttle)
+            .take(2)
+            .map(|packet| packet.header().session_time().as_secs_f32())
+            .collect()
+            .await;
+
+        assert_eq!(vec![0.0, 1.0], passed);
+    }
+}
@@ -1,7 +1,110 @@
 //! Collection of auxiliary types that are used throughout the crate
 
+use std::convert::TryFrom;
+use std::fmt;
+use std::fmt::Display;
+use std::num::TryFromIntError;
+
+use bitflags::bitflags;
 use derive_new::new;
 use getset::CopyGetters;
+use thiserror::Error;
+
+use crate::car::MAX_CARS;
+use crate::packet::header::ApiSpec;
+
+bitflags! {
+    /// A bit field with currently pressed buttons.
+    ///
+    /// The F1 games publish which buttons are currently being pressed by the user. This information
+    /// is encoded in a bit field, where each bit represents a different button. The names of the
+    /// flags combine the names of the buttons on PlayStation and Xbox controllers, and the buttons
+    /// are also available under their name on each platform.
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct ButtonFlags: u32 {
+        const NONE = 0x0;
+        const CROSS_OR_A = 0x0001;
+        const TRIANGLE_OR_Y = 0x0002;
+        const CIRCLE_OR_B = 0x0004;
+        const SQUARE_OR_X = 0x0008;
+        const DPAD_LEFT = 0x0010;
+        const DPAD_RIGHT = 0x0020;
+        const DPAD_UP = 0x0040;
+        const DPAD_DOWN = 0x0080;
+        const OPTIONS_OR_MENU = 0x0100;
+        const L1_OR_LB = 0x0200;
+        const R1_OR_RB = 0x0400;
+        const L2_OR_LT = 0x0800;
+        const R2_OR_RT = 0x1000;
+        const LEFT_STICK_CLICK = 0x2000;
+        const RIGHT_STICK_CLICK = 0x4000;
+    }
+}
+
+impl Default for ButtonFlags {
+    fn default() -> Self {
+        ButtonFlags::NONE
+    }
+}
+
+impl ButtonFlags {
+    /// The cross button on a PlayStation controller.
+    pub const CROSS: ButtonFlags = ButtonFlags::CROSS_OR_A;
+
+    /// The triangle button on a PlayStation controller.
+    pub const TRIANGLE: ButtonFlags = ButtonFlags::TRIANGLE_OR_Y;
+
+    /// The circle button on a PlayStation controller.
+    pub const CIRCLE: ButtonFlags = ButtonFlags::CIRCLE_OR_B;
+
+    /// The square button on a PlayStation controller.
+    pub const SQUARE: ButtonFlags = ButtonFlags::SQUARE_OR_X;
+
+    /// The options button on a PlayStation controller.
+    pub const OPTIONS: ButtonFlags = ButtonFlags::OPTIONS_OR_MENU;
+
+    /// The L1 button on a PlayStation controller.
+    pub const L1: ButtonFlags = ButtonFlags::L1_OR_LB;
+
+    /// The R1 button on a PlayStation controller.
+    pub const R1: ButtonFlags = ButtonFlags::R1_OR_RB;
+
+    /// The L2 trigger on a PlayStation controller.
+    pub const L2: ButtonFlags = ButtonFlags::L2_OR_LT;
+
+    /// The R2 trigger on a PlayStation controller.
+    pub const R2: ButtonFlags = ButtonFlags::R2_OR_RT;
+
+    /// The A button on an Xbox controller.
+    pub const A: ButtonFlags = ButtonFlags::CROSS_OR_A;
+
+    /// The Y button on an Xbox controller.
+    pub const Y: ButtonFlags = ButtonFlags::TRIANGLE_OR_Y;
+
+    /// The B button on an Xbox controller.
+    pub const B: ButtonFlags = ButtonFlags::CIRCLE_OR_B;
+
+    /// The X button on an Xbox controller.
+    pub const X: ButtonFlags = ButtonFlags::SQUARE_OR_X;
+
+    /// The menu button on an Xbox controller.
+    pub const MENU: ButtonFlags = ButtonFlags::OPTIONS_OR_MENU;
+
+    /// The left bumper on an Xbox controller.
+    pub const LB: ButtonFlags = ButtonFlags::L1_OR_LB;
+
+    /// The right bumper on an Xbox controller.
+    pub const RB: ButtonFlags = ButtonFlags::R1_OR_RB;
+
+    /// The left trigger on an Xbox controller.
+    pub const LT: ButtonFlags = ButtonFlags::L2_OR_LT;
+
+    /// The right trigger on an Xbox controller.
+    pub const RT: ButtonFlags = ButtonFlags::R2_OR_RT;
+}
+
+/// The former name of `ButtonFlags`, which is kept for compatibility
+pub type Button = ButtonFlags;
 
 /// Flags shown in formula racing
 ///
@@ -9,19 +112,361 @@ use getset::CopyGetters;
 /// flag signals the race start or restart, while a yellow flag warns of hazards on track. The red
 /// flag aborts a race or session. The blue flag signals that a faster car is approaching from
 /// behind.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+///
+/// The same flags are shown to individual cars in the car status packet, and in the marshal zones
+/// of the session packet.
+///
+/// Flags that are unknown to this crate are decoded as `Unknown` with their raw identifier.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Flag {
     Invalid,
+    #[default]
     None,
     Green,
     Blue,
     Yellow,
     Red,
+    Unknown(i8),
 }
 
-impl Default for Flag {
-    fn default() -> Self {
-        Flag::None
+impl Display for Flag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Flag::Invalid => write!(f, "Invalid flag"),
+            Flag::None => write!(f, "No flag"),
+            Flag::Green => write!(f, "Green flag"),
+            Flag::Blue => write!(f, "Blue flag"),
+            Flag::Yellow => write!(f, "Yellow flag"),
+            Flag::Red => write!(f, "Red flag"),
+            Flag::Unknown(_) => write!(f, "Unknown flag"),
+        }
+    }
+}
+
+/// Penalties that stewards can hand out
+///
+/// Stewards punish infringements with penalties that range from warnings and invalidated laps to
+/// time penalties, drive-throughs, and disqualifications. The names of the penalties match the text
+/// the games show when a penalty is given.
+///
+/// F1 2019 does not publish individual penalties, and only reports the accumulated time penalties
+/// of each driver in the lap data. Newer games publish penalties in events, with an identifier from
+/// the API specification of F1 2020 that is converted with `From<u8>`. Penalties that are unknown
+/// to this crate are converted to `Unknown` with their raw identifier.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PenaltyType {
+    DriveThrough,
+    StopGo,
+    GridPenalty,
+    PenaltyReminder,
+    TimePenalty,
+    Warning,
+    Disqualified,
+    RemovedFromFormationLap,
+    ParkedTooLongTimer,
+    TyreRegulations,
+    ThisLapInvalidated,
+    ThisAndNextLapInvalidated,
+    ThisLapInvalidatedWithoutReason,
+    ThisAndNextLapInvalidatedWithoutReason,
+    ThisAndPreviousLapInvalidated,
+    ThisAndPreviousLapInvalidatedWithoutReason,
+    Retired,
+    BlackFlagTimer,
+    Unknown(u8),
+}
+
+impl From<u8> for PenaltyType {
+    fn from(id: u8) -> Self {
+        match id {
+            0 => PenaltyType::DriveThrough,
+            1 => PenaltyType::StopGo,
+            2 => PenaltyType::GridPenalty,
+            3 => PenaltyType::PenaltyReminder,
+            4 => PenaltyType::TimePenalty,
+            5 => PenaltyType::Warning,
+            6 => PenaltyType::Disqualified,
+            7 => PenaltyType::RemovedFromFormationLap,
+            8 => PenaltyType::ParkedTooLongTimer,
+            9 => PenaltyType::TyreRegulations,
+            10 => PenaltyType::ThisLapInvalidated,
+            11 => PenaltyType::ThisAndNextLapInvalidated,
+            12 => PenaltyType::ThisLapInvalidatedWithoutReason,
+            13 => PenaltyType::ThisAndNextLapInvalidatedWithoutReason,
+            14 => PenaltyType::ThisAndPreviousLapInvalidated,
+            15 => PenaltyType::ThisAndPreviousLapInvalidatedWithoutReason,
+            16 => PenaltyType::Retired,
+            17 => PenaltyType::BlackFlagTimer,
+            id => PenaltyType::Unknown(id),
+        }
+    }
+}
+
+impl Display for PenaltyType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PenaltyType::DriveThrough => write!(f, "Drive through"),
+            PenaltyType::StopGo => write!(f, "Stop go"),
+            PenaltyType::GridPenalty => write!(f, "Grid penalty"),
+            PenaltyType::PenaltyReminder => write!(f, "Penalty reminder"),
+            PenaltyType::TimePenalty => write!(f, "Time penalty"),
+            PenaltyType::Warning => write!(f, "Warning"),
+            PenaltyType::Disqualified => write!(f, "Disqualified"),
+            PenaltyType::RemovedFromFormationLap => write!(f, "Removed from formation lap"),
+            PenaltyType::ParkedTooLongTimer => write!(f, "Parked too long timer"),
+            PenaltyType::TyreRegulations => write!(f, "Tyre regulations"),
+            PenaltyType::ThisLapInvalidated => write!(f, "This lap invalidated"),
+            PenaltyType::ThisAndNextLapInvalidated => write!(f, "This and next lap invalidated"),
+            PenaltyType::ThisLapInvalidatedWithoutReason => {
+                write!(f, "This lap invalidated without reason")
+            }
+            PenaltyType::ThisAndNextLapInvalidatedWithoutReason => {
+                write!(f, "This and next lap invalidated without reason")
+            }
+            PenaltyType::ThisAndPreviousLapInvalidated => {
+                write!(f, "This and previous lap invalidated")
+            }
+            PenaltyType::ThisAndPreviousLapInvalidatedWithoutReason => {
+                write!(f, "This and previous lap invalidated without reason")
+            }
+            PenaltyType::Retired => write!(f, "Retired"),
+            PenaltyType::BlackFlagTimer => write!(f, "Black flag timer"),
+            PenaltyType::Unknown(_) => write!(f, "Unknown penalty"),
+        }
+    }
+}
+
+/// Infringements of the rules that stewards can punish
+///
+/// Every penalty is given for an infringement of the sporting regulations, for example cutting a
+/// corner to gain time or speeding in the pit lane. The names of the infringements match the text
+/// the games show when a penalty is given.
+///
+/// F1 2019 does not publish the infringements for which penalties are given. Newer games publish
+/// them together with the penalties, with an identifier from the API specification of F1 2020 that
+/// is converted with `From<u8>`. Infringements that are unknown to this crate are converted to
+/// `Unknown` with their raw identifier.
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InfringementType {
+    BlockingBySlowDriving,
+    BlockingByWrongWayDriving,
+    ReversingOffTheStartLine,
+    BigCollision,
+    SmallCollision,
+    CollisionFailedToHandBackPositionSingle,
+    CollisionFailedToHandBackPositionMultiple,
+    CornerCuttingGainedTime,
+    CornerCuttingOvertakeSingle,
+    CornerCuttingOvertakeMultiple,
+    CrossedPitExitLane,
+    IgnoringBlueFlags,
+    IgnoringYellowFlags,
+    IgnoringDriveThrough,
+    TooManyDriveThroughs,
+    DriveThroughReminderServeWithinNLaps,
+    DriveThroughReminderServeThisLap,
+    PitLaneSpeeding,
+    ParkedForTooLong,
+    IgnoringTyreRegulations,
+    TooManyPenalties,
+    MultipleWarnings,
+    ApproachingDisqualification,
+    TyreRegulationsSelectSingle,
+    TyreRegulationsSelectMultiple,
+    LapInvalidatedCornerCutting,
+    LapInvalidatedRunningWide,
+    CornerCuttingRanWideGainedTimeMinor,
+    CornerCuttingRanWideGainedTimeSignificant,
+    CornerCuttingRanWideGainedTimeExtreme,
+    LapInvalidatedWallRiding,
+    LapInvalidatedFlashbackUsed,
+    LapInvalidatedResetToTrack,
+    BlockingThePitlane,
+    JumpStart,
+    SafetyCarToCarCollision,
+    SafetyCarIllegalOvertake,
+    SafetyCarExceedingAllowedPace,
+    VirtualSafetyCarExceedingAllowedPace,
+    FormationLapBelowAllowedSpeed,
+    RetiredMechanicalFailure,
+    RetiredTerminallyDamaged,
+    SafetyCarFallingTooFarBack,
+    BlackFlagTimer,
+    UnservedStopGoPenalty,
+    UnservedDriveThroughPenalty,
+    EngineComponentChange,
+    GearboxChange,
+    LeagueGridPenalty,
+    RetryPenalty,
+    IllegalTimeGain,
+    MandatoryPitstop,
+    Unknown(u8),
+}
+
+impl From<u8> for InfringementType {
+    fn from(id: u8) -> Self {
+        match id {
+            0 => InfringementType::BlockingBySlowDriving,
+            1 => InfringementType::BlockingByWrongWayDriving,
+            2 => InfringementType::ReversingOffTheStartLine,
+            3 => InfringementType::BigCollision,
+            4 => InfringementType::SmallCollision,
+            5 => InfringementType::CollisionFailedToHandBackPositionSingle,
+            6 => InfringementType::CollisionFailedToHandBackPositionMultiple,
+            7 => InfringementType::CornerCuttingGainedTime,
+            8 => InfringementType::CornerCuttingOvertakeSingle,
+            9 => InfringementType::CornerCuttingOvertakeMultiple,
+            10 => InfringementType::CrossedPitExitLane,
+            11 => InfringementType::IgnoringBlueFlags,
+            12 => InfringementType::IgnoringYellowFlags,
+            13 => InfringementType::IgnoringDriveThrough,
+            14 => InfringementType::TooManyDriveThroughs,
+            15 => InfringementType::DriveThroughReminderServeWithinNLaps,
+            16 => InfringementType::DriveThroughReminderServeThisLap,
+            17 => InfringementType::PitLaneSpeeding,
+            18 => InfringementType::ParkedForTooLong,
+            19 => InfringementType::IgnoringTyreRegulations,
+            20 => InfringementType::TooManyPenalties,
+            21 => InfringementType::MultipleWarnings,
+            22 => InfringementType::ApproachingDisqualification,
+            23 => InfringementType::TyreRegulationsSelectSingle,
+            24 => InfringementType::TyreRegulationsSelectMultiple,
+            25 => InfringementType::LapInvalidatedCornerCutting,
+            26 => InfringementType::LapInvalidatedRunningWide,
+            27 => InfringementType::CornerCuttingRanWideGainedTimeMinor,
+            28 => InfringementType::CornerCuttingRanWideGainedTimeSignificant,
+            29 => InfringementType::CornerCuttingRanWideGainedTimeExtreme,
+            30 => InfringementType::LapInvalidatedWallRiding,
+            31 => InfringementType::LapInvalidatedFlashbackUsed,
+            32 => InfringementType::LapInvalidatedResetToTrack,
+            33 => InfringementType::BlockingThePitlane,
+            34 => InfringementType::JumpStart,
+            35 => InfringementType::SafetyCarToCarCollision,
+            36 => InfringementType::SafetyCarIllegalOvertake,
+            37 => InfringementType::SafetyCarExceedingAllowedPace,
+            38 => InfringementType::VirtualSafetyCarExceedingAllowedPace,
+            39 => InfringementType::FormationLapBelowAllowedSpeed,
+            40 => InfringementType::RetiredMechanicalFailure,
+            41 => InfringementType::RetiredTerminallyDamaged,
+            42 => InfringementType::SafetyCarFallingTooFarBack,
+            43 => InfringementType::BlackFlagTimer,
+            44 => InfringementType::UnservedStopGoPenalty,
+            45 => InfringementType::UnservedDriveThroughPenalty,
+            46 => InfringementType::EngineComponentChange,
+            47 => InfringementType::GearboxChange,
+            48 => InfringementType::LeagueGridPenalty,
+            49 => InfringementType::RetryPenalty,
+            50 => InfringementType::IllegalTimeGain,
+            51 => InfringementType::MandatoryPitstop,
+            id => InfringementType::Unknown(id),
+        }
+    }
+}
+
+impl Display for InfringementType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InfringementType::BlockingBySlowDriving => write!(f, "Blocking by slow driving"),
+            InfringementType::BlockingByWrongWayDriving => {
+                write!(f, "Blocking by wrong way driving")
+            }
+            InfringementType::ReversingOffTheStartLine => write!(f, "Reversing off the start line"),
+            InfringementType::BigCollision => write!(f, "Big collision"),
+            InfringementType::SmallCollision => write!(f, "Small collision"),
+            InfringementType::CollisionFailedToHandBackPositionSingle => {
+                write!(f, "Collision failed to hand back position single")
+            }
+            InfringementType::CollisionFailedToHandBackPositionMultiple => {
+                write!(f, "Collision failed to hand back position multiple")
+            }
+            InfringementType::CornerCuttingGainedTime => write!(f, "Corner cutting gained time"),
+            InfringementType::CornerCuttingOvertakeSingle => {
+                write!(f, "Corner cutting overtake single")
+            }
+            InfringementType::CornerCuttingOvertakeMultiple => {
+                write!(f, "Corner cutting overtake multiple")
+            }
+            InfringementType::CrossedPitExitLane => write!(f, "Crossed pit exit lane"),
+            InfringementType::IgnoringBlueFlags => write!(f, "Ignoring blue flags"),
+            InfringementType::IgnoringYellowFlags => write!(f, "Ignoring yellow flags"),
+            InfringementType::IgnoringDriveThrough => write!(f, "Ignoring drive through"),
+            InfringementType::TooManyDriveThroughs => write!(f, "Too many drive throughs"),
+            InfringementType::DriveThroughReminderServeWithinNLaps => {
+                write!(f, "Drive through reminder serve within n laps")
+            }
+            InfringementType::DriveThroughReminderServeThisLap => {
+                write!(f, "Drive through reminder serve this lap")
+            }
+            InfringementType::PitLaneSpeeding => write!(f, "Pit lane speeding"),
+            InfringementType::ParkedForTooLong => write!(f, "Parked for too long"),
+            InfringementType::IgnoringTyreRegulations => write!(f, "Ignoring tyre regulations"),
+            InfringementType::TooManyPenalties => write!(f, "Too many penalties"),
+            InfringementType::MultipleWarnings => write!(f, "Multiple warnings"),
+            InfringementType::ApproachingDisqualification => {
+                write!(f, "Approaching disqualification")
+            }
+            InfringementType::TyreRegulationsSelectSingle => {
+                write!(f, "Tyre regulations select single")
+            }
+            InfringementType::TyreRegulationsSelectMultiple => {
+                write!(f, "Tyre regulations select multiple")
+            }
+            InfringementType::LapInvalidatedCornerCutting => {
+                write!(f, "Lap invalidated corner cutting")
+            }
+            InfringementType::LapInvalidatedRunningWide => {
+                write!(f, "Lap invalidated running wide")
+            }
+            InfringementType::CornerCuttingRanWideGainedTimeMinor => {
+                write!(f, "Corner cutting ran wide gained time minor")
+            }
+            InfringementType::CornerCuttingRanWideGainedTimeSignificant => {
+                write!(f, "Corner cutting ran wide gained time significant")
+            }
+            InfringementType::CornerCuttingRanWideGainedTimeExtreme => {
+                write!(f, "Corner cutting ran wide gained time extreme")
+            }
+            InfringementType::LapInvalidatedWallRiding => write!(f, "Lap invalidated wall riding"),
+            InfringementType::LapInvalidatedFlashbackUsed => {
+                write!(f, "Lap invalidated flashback used")
+            }
+            InfringementType::LapInvalidatedResetToTrack => {
+                write!(f, "Lap invalidated reset to track")
+            }
+            InfringementType::BlockingThePitlane => write!(f, "Blocking the pitlane"),
+            InfringementType::JumpStart => write!(f, "Jump start"),
+            InfringementType::SafetyCarToCarCollision => write!(f, "Safety car to car collision"),
+            InfringementType::SafetyCarIllegalOvertake => write!(f, "Safety car illegal overtake"),
+            InfringementType::SafetyCarExceedingAllowedPace => {
+                write!(f, "Safety car exceeding allowed pace")
+            }
+            InfringementType::VirtualSafetyCarExceedingAllowedPace => {
+                write!(f, "Virtual safety car exceeding allowed pace")
+            }
+            InfringementType::FormationLapBelowAllowedSpeed => {
+                write!(f, "Formation lap below allowed speed")
+            }
+            InfringementType::RetiredMechanicalFailure => write!(f, "Retired mechanical failure"),
+            InfringementType::RetiredTerminallyDamaged => write!(f, "Retired terminally damaged"),
+            InfringementType::SafetyCarFallingTooFarBack => {
+                write!(f, "Safety car falling too far back")
+            }
+            InfringementType::BlackFlagTimer => write!(f, "Black flag timer"),
+            InfringementType::UnservedStopGoPenalty => write!(f, "Unserved stop go penalty"),
+            InfringementType::UnservedDriveThroughPenalty => {
+                write!(f, "Unserved drive through penalty")
+            }
+            InfringementType::EngineComponentChange => write!(f, "Engine component change"),
+            InfringementType::GearboxChange => write!(f, "Gearbox change"),
+            InfringementType::LeagueGridPenalty => write!(f, "League grid penalty"),
+            InfringementType::RetryPenalty => write!(f, "Retry penalty"),
+            InfringementType::IllegalTimeGain => write!(f, "Illegal time gain"),
+            InfringementType::MandatoryPitstop => write!(f, "Mandatory pitstop"),
+            InfringementType::Unknown(_) => write!(f, "Unknown infringement"),
+        }
     }
 }
 
@@ -29,15 +474,143 @@ impl Default for Flag {
 ///
 /// In Formula 1, a maximum of 20 cars can participate in any session. The modern F1 games use this
 /// rule to use arrays with a static size of 20 whenever they publish data about all vehicles in a
-/// session. Data in those arrays is referenced using an unsigned byte. By defining a type alias for
-/// the indices, their usage can be checked by the Rust compiler.
-pub type VehicleIndex = u8;
+/// session. Data in those arrays is referenced using an unsigned byte, which is wrapped in the
+/// vehicle index so that only indices of cars on the grid can be constructed.
+///
+/// Not every byte is a valid index. F1 2019 uses 255 in some fields to signal that no car is
+/// referenced, for example the spectated car when no car is spectated. These fields are decoded as
+/// `Option<VehicleIndex>`. Indices can be checked against the grid of an API specification with
+/// `new`, or against the largest grid of any supported API specification with `try_from`.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+///
+/// use f1_api::packet::header::ApiSpec;
+/// use f1_api::types::VehicleIndex;
+///
+/// assert_eq!(Some(19), VehicleIndex::new(19, ApiSpec::Nineteen).map(|index| index.as_u8()));
+/// assert_eq!(None, VehicleIndex::new(20, ApiSpec::Nineteen));
+/// assert!(VehicleIndex::try_from(255).is_err());
+/// ```
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "u8", into = "u8")
+)]
+pub struct VehicleIndex(u8);
+
+impl VehicleIndex {
+    /// Create a vehicle index, if the index refers to a car on the grid of the API specification.
+    pub fn new(index: u8, api_spec: ApiSpec) -> Option<Self> {
+        if (index as usize) < api_spec.grid_size() {
+            Some(VehicleIndex(index))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the vehicle indices of every car on the grid of the API specification.
+    pub fn all(api_spec: ApiSpec) -> impl Iterator<Item = VehicleIndex> {
+        (0..api_spec.grid_size()).map(|index| VehicleIndex(index as u8))
+    }
+
+    /// Returns the index as a byte, like it is published by the games.
+    pub fn as_u8(&self) -> u8 {
+        self.0
+    }
+
+    /// Returns the index as a position in the per-car arrays of a packet.
+    pub fn as_usize(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl TryFrom<u8> for VehicleIndex {
+    type Error = InvalidVehicleIndex;
+
+    fn try_from(index: u8) -> Result<Self, Self::Error> {
+        if (index as usize) < MAX_CARS {
+            Ok(VehicleIndex(index))
+        } else {
+            Err(InvalidVehicleIndex(index))
+        }
+    }
+}
+
+impl From<VehicleIndex> for u8 {
+    fn from(vehicle_index: VehicleIndex) -> Self {
+        vehicle_index.0
+    }
+}
+
+impl From<VehicleIndex> for usize {
+    fn from(vehicle_index: VehicleIndex) -> Self {
+        vehicle_index.0 as usize
+    }
+}
+
+impl PartialEq<u8> for VehicleIndex {
+    fn eq(&self, other: &u8) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<VehicleIndex> for u8 {
+    fn eq(&self, other: &VehicleIndex) -> bool {
+        *self == other.0
+    }
+}
+
+impl Display for VehicleIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Error for a byte that does not refer to a car on the grid
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Hash, Error)]
+#[error("Vehicle index {0} does not refer to a car on the grid.")]
+pub struct InvalidVehicleIndex(pub u8);
+
+/// Returns the entries of a per-car array together with their vehicle indices.
+///
+/// Entries beyond the largest grid of any supported API specification are skipped.
+pub fn enumerate_cars<T>(entries: &[T]) -> impl Iterator<Item = (VehicleIndex, &T)> {
+    (0..MAX_CARS)
+        .map(|index| VehicleIndex(index as u8))
+        .zip(entries)
+}
+
+/// Returns the entry of a car in a per-car array.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+///
+/// use f1_api::types::{car_entry, VehicleIndex};
+///
+/// let positions = vec![2, 1];
+///
+/// assert_eq!(Some(&1), car_entry(&positions, VehicleIndex::try_from(1).unwrap()));
+/// assert_eq!(None, car_entry(&positions, VehicleIndex::try_from(2).unwrap()));
+/// ```
+pub fn car_entry<T>(entries: &[T], vehicle_index: VehicleIndex) -> Option<&T> {
+    entries.get(vehicle_index.as_usize())
+}
 
 /// Property on each corner of a car
 ///
 /// The F1 games publish telemetry data and setup parameters that describe each corner of a car. For
 /// example, the suspension settings or tyre pressures are a set of four numbers, one for each
-/// corner of the car. These properties can be expressed by the `Corner` type.
+/// corner of the car. These properties can be expressed by the `CornerProperty` type.
+///
+/// The games publish these properties as arrays in the order rear left, rear right, front left,
+/// and front right. The decoders map each value to its named corner, so that users never need to
+/// remember the order of the arrays.
 ///
 /// # Examples
 ///
@@ -45,8 +618,12 @@ pub type VehicleIndex = u8;
 /// use f1_api::types::CornerProperty;
 ///
 /// let suspension_position = CornerProperty::new(1.0, 0.9, 1.1, 1.0);
+///
+/// assert_eq!(0.9, suspension_position.front_right());
+/// assert_eq!(Some(1.1), suspension_position.iter().reduce(f32::max));
 /// ```
 #[derive(new, Debug, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CornerProperty<T>
 where
     T: Copy,
@@ -68,6 +645,36 @@ where
     rear_right: T,
 }
 
+impl<T> CornerProperty<T>
+where
+    T: Copy,
+{
+    /// Returns an iterator over the values at the front left, front right, rear left, and rear
+    /// right.
+    pub fn iter(&self) -> impl Iterator<Item = T> {
+        IntoIterator::into_iter([
+            self.front_left,
+            self.front_right,
+            self.rear_left,
+            self.rear_right,
+        ])
+    }
+
+    /// Apply a function to the value at each corner.
+    pub fn map<U, F>(&self, f: F) -> CornerProperty<U>
+    where
+        U: Copy,
+        F: Fn(T) -> U,
+    {
+        CornerProperty::new(
+            f(self.front_left),
+            f(self.front_right),
+            f(self.rear_left),
+            f(self.rear_right),
+        )
+    }
+}
+
 /// Property in a three-dimensional world
 ///
 /// The F1 games publish data that places objects in a three dimensional world. Examples include the
@@ -81,6 +688,7 @@ where
 /// let g_forces = Property3D::new(1.0, 1.3, 0.9);
 /// ```
 #[derive(new, Debug, CopyGetters, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Property3D<T>
 where
     T: Copy,
@@ -97,3 +705,266 @@ where
     #[getset(get_copy = "pub")]
     z: T,
 }
+
+/// Speed of a car or a speed limit
+///
+/// The F1 games publish speeds in different units, with most speeds in kilometers per hour. The
+/// speed type stores kilometers per hour, and converts to other units explicitly so that speeds
+/// are not confused in different units.
+///
+/// # Examples
+///
+/// ```
+/// use f1_api::types::Speed;
+///
+/// let pit_speed_limit = Speed::from_kph(80);
+/// assert_eq!("80 km/h", pit_speed_limit.to_string());
+/// ```
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Speed(u16);
+
+impl Speed {
+    /// Create a speed from kilometers per hour.
+    pub fn from_kph(kph: u16) -> Self {
+        Speed(kph)
+    }
+
+    /// Returns the speed in kilometers per hour.
+    pub fn as_kph(&self) -> u16 {
+        self.0
+    }
+
+    /// Returns the speed in miles per hour.
+    pub fn as_mph(&self) -> f32 {
+        self.0 as f32 / 1.609_344
+    }
+
+    /// Returns the speed in meters per second.
+    pub fn as_mps(&self) -> f32 {
+        self.0 as f32 / 3.6
+    }
+}
+
+impl Display for Speed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} km/h", self.0)
+    }
+}
+
+/// Temperature of the weather or a part of a car
+///
+/// The F1 games publish temperatures in degrees celsius, using different integer types for
+/// different temperatures. The temperature type stores degrees celsius, and converts to degrees
+/// fahrenheit explicitly.
+///
+/// # Examples
+///
+/// ```
+/// use f1_api::types::Temperature;
+///
+/// let track_temperature = Temperature::from_celsius(30);
+/// assert_eq!(86.0, track_temperature.as_fahrenheit());
+/// ```
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Temperature(i16);
+
+impl Temperature {
+    /// Create a temperature from degrees celsius.
+    pub fn from_celsius(celsius: i16) -> Self {
+        Temperature(celsius)
+    }
+
+    /// Returns the temperature in degrees celsius.
+    pub fn as_celsius(&self) -> i16 {
+        self.0
+    }
+
+    /// Returns the temperature in degrees fahrenheit.
+    pub fn as_fahrenheit(&self) -> f32 {
+        self.0 as f32 * 1.8 + 32.0
+    }
+}
+
+impl From<i8> for Temperature {
+    fn from(celsius: i8) -> Self {
+        Temperature(celsius as i16)
+    }
+}
+
+impl TryFrom<u16> for Temperature {
+    type Error = TryFromIntError;
+
+    fn try_from(celsius: u16) -> Result<Self, Self::Error> {
+        i16::try_from(celsius).map(Temperature)
+    }
+}
+
+impl Display for Temperature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} °C", self.0)
+    }
+}
+
+/// Pressure of a tyre
+///
+/// The F1 games publish tyre pressures in pounds per square inch, or psi. The pressure type stores
+/// psi, and converts to bar and kilopascal explicitly.
+///
+/// # Examples
+///
+/// ```
+/// use f1_api::types::Pressure;
+///
+/// let tyre_pressure = Pressure::from_psi(23.0);
+/// assert_eq!("23.0 psi", tyre_pressure.to_string());
+/// ```
+#[derive(Debug, PartialEq, Copy, Clone, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pressure(f32);
+
+impl Pressure {
+    /// Create a pressure from pounds per square inch.
+    pub fn from_psi(psi: f32) -> Self {
+        Pressure(psi)
+    }
+
+    /// Returns the pressure in pounds per square inch.
+    pub fn as_psi(&self) -> f32 {
+        self.0
+    }
+
+    /// Returns the pressure in bar.
+    pub fn as_bar(&self) -> f32 {
+        self.as_kpa() / 100.0
+    }
+
+    /// Returns the pressure in kilopascal.
+    pub fn as_kpa(&self) -> f32 {
+        self.0 * 6.894_757
+    }
+}
+
+impl Display for Pressure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1} psi", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::packet::header::ApiSpec;
+    use crate::types::{
+        car_entry, enumerate_cars, InfringementType, InvalidVehicleIndex, PenaltyType, Pressure,
+        Speed, Temperature, VehicleIndex,
+    };
+
+    #[test]
+    fn vehicle_index_bounds() {
+        assert_eq!(
+            Some(19),
+            VehicleIndex::new(19, ApiSpec::Nineteen).map(|index| index.as_u8())
+        );
+        assert_eq!(None, VehicleIndex::new(20, ApiSpec::Nineteen));
+        assert_eq!(None, VehicleIndex::new(255, ApiSpec::Nineteen));
+
+        assert_eq!(0, VehicleIndex::try_from(0).unwrap());
+        assert_eq!(Err(InvalidVehicleIndex(20)), VehicleIndex::try_from(20));
+        assert_eq!(Err(InvalidVehicleIndex(255)), VehicleIndex::try_from(255));
+
+        assert_eq!(20, VehicleIndex::all(ApiSpec::Nineteen).count());
+    }
+
+    #[test]
+    fn index_car_arrays() {
+        let entries = vec![0u8; 22];
+
+        let indices: Vec<u8> = enumerate_cars(&entries)
+            .map(|(index, _)| index.as_u8())
+            .collect();
+        assert_eq!((0..20).collect::<Vec<u8>>(), indices);
+
+        let short = [7u8, 8];
+        assert_eq!(
+            Some(&8),
+            car_entry(&short, VehicleIndex::try_from(1).unwrap())
+        );
+        assert_eq!(None, car_entry(&short, VehicleIndex::try_from(19).unwrap()));
+    }
+
+    #[test]
+    fn convert_speed() {
+        assert_approx_eq!(0.0, Speed::from_kph(0).as_mph());
+        assert_approx_eq!(62.137_12, Speed::from_kph(100).as_mph(), 1e-4);
+        assert_approx_eq!(200.0, Speed::from_kph(322).as_mph(), 0.1);
+        assert_approx_eq!(25.0, Speed::from_kph(90).as_mps());
+
+        assert_eq!(322, Speed::from_kph(322).as_kph());
+        assert_eq!("322 km/h", Speed::from_kph(322).to_string());
+    }
+
+    #[test]
+    fn convert_temperature() {
+        assert_approx_eq!(32.0, Temperature::from_celsius(0).as_fahrenheit());
+        assert_approx_eq!(212.0, Temperature::from_celsius(100).as_fahrenheit());
+        assert_approx_eq!(-40.0, Temperature::from_celsius(-40).as_fahrenheit());
+        assert_approx_eq!(98.6, Temperature::from_celsius(37).as_fahrenheit(), 1e-4);
+
+        assert_eq!(-5, Temperature::from(-5i8).as_celsius());
+        assert_eq!(105, Temperature::try_from(105u16).unwrap().as_celsius());
+        assert!(Temperature::try_from(u16::MAX).is_err());
+        assert_eq!("-5 °C", Temperature::from_celsius(-5).to_string());
+    }
+
+    #[test]
+    fn convert_pressure() {
+        assert_approx_eq!(1.0, Pressure::from_psi(14.503_774).as_bar());
+        assert_approx_eq!(1.585_794, Pressure::from_psi(23.0).as_bar());
+        assert_approx_eq!(158.579_4, Pressure::from_psi(23.0).as_kpa(), 1e-3);
+        assert_approx_eq!(0.0, Pressure::from_psi(0.0).as_bar());
+
+        assert_approx_eq!(21.5, Pressure::from_psi(21.5).as_psi());
+        assert_eq!("21.5 psi", Pressure::from_psi(21.5).to_string());
+    }
+
+    #[test]
+    fn convert_penalty_identifiers() {
+        assert_eq!(PenaltyType::DriveThrough, PenaltyType::from(0));
+        assert_eq!(PenaltyType::TimePenalty, PenaltyType::from(4));
+        assert_eq!(PenaltyType::Disqualified, PenaltyType::from(6));
+        assert_eq!(PenaltyType::BlackFlagTimer, PenaltyType::from(17));
+        assert_eq!(PenaltyType::Unknown(18), PenaltyType::from(18));
+        assert_eq!("Unknown penalty", PenaltyType::from(255).to_string());
+    }
+
+    #[test]
+    fn convert_infringement_identifiers() {
+        assert_eq!(
+            InfringementType::BlockingBySlowDriving,
+            InfringementType::from(0)
+        );
+        assert_eq!(
+            InfringementType::CornerCuttingGainedTime,
+            InfringementType::from(7)
+        );
+        assert_eq!(
+            InfringementType::PitLaneSpeeding,
+            InfringementType::from(17)
+        );
+        assert_eq!(InfringementType::JumpStart, InfringementType::from(34));
+        assert_eq!(
+            InfringementType::MandatoryPitstop,
+            InfringementType::from(51)
+        );
+        assert_eq!(InfringementType::Unknown(52), InfringementType::from(52));
+        assert_eq!(
+            "Unknown infringement",
+            InfringementType::from(255).to_string()
+        );
+    }
+}